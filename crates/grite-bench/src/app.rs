@@ -1,6 +1,7 @@
 //! Application state and event handling
 
 use std::io;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -12,7 +13,9 @@ use crossterm::ExecutableCommand;
 use ratatui::prelude::*;
 use ratatui::Terminal;
 
-use crate::bench::{BenchmarkConfig, BenchmarkRunner, MetricsCollector, MetricsSnapshot};
+use crate::bench::{
+    read_only, BenchmarkConfig, BenchmarkRunner, MetricsCollector, MetricsSnapshot,
+};
 use crate::error::Result;
 use crate::ui::{self, UiState};
 
@@ -236,6 +239,45 @@ impl App {
     }
 }
 
+/// Benchmark reads against the existing repository at `repo_path` without
+/// writing to it (no TUI)
+pub fn run_read_only(
+    repo_path: &Path,
+    operations: usize,
+    json_report_path: Option<&Path>,
+) -> Result<MetricsSnapshot> {
+    let metrics = MetricsCollector::new(0);
+
+    println!("Running read-only benchmark: {} reads...", operations);
+    read_only::run(&repo_path.join(".git"), operations, &metrics)?;
+    metrics.update_throughput_sample();
+    let snapshot = metrics.snapshot();
+
+    if let Some(path) = json_report_path {
+        let report = serde_json::to_string_pretty(&snapshot)?;
+        std::fs::write(path, report)?;
+        println!("Report saved to {}", path.display());
+    }
+
+    println!("=== RESULTS ===");
+    println!(
+        "Reads: {} ok, {} failed, Writes: {}",
+        snapshot.read_operations,
+        snapshot.failed_operations,
+        snapshot.write_operations()
+    );
+    println!(
+        "Latency: P50={:.2}ms P95={:.2}ms P99={:.2}ms Max={:.2}ms",
+        snapshot.latencies.p50_ms(),
+        snapshot.latencies.p95_ms(),
+        snapshot.latencies.p99_ms(),
+        snapshot.latencies.max_ms()
+    );
+    println!("Elapsed: {:.2}s", snapshot.elapsed.as_secs_f64());
+
+    Ok(snapshot)
+}
+
 /// Run benchmark in headless mode (no TUI)
 pub fn run_headless(config: BenchmarkConfig) -> Result<MetricsSnapshot> {
     let metrics = Arc::new(MetricsCollector::new(config.scenario.agent_count));
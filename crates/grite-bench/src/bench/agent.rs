@@ -143,6 +143,7 @@ impl SimulatedAgent {
 
         let kind = EventKind::CommentAdded {
             body: self.random_comment(),
+            parent_event: None,
         };
 
         let event_id = compute_event_id(&issue_id, &self.actor_id, ts, None, &kind);
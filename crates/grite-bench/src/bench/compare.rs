@@ -0,0 +1,228 @@
+//! Comparison of two saved benchmark reports
+
+use std::path::Path;
+
+use super::metrics::MetricsSnapshot;
+use crate::error::Result;
+
+/// Extracts an aggregate value from a report
+type MetricFn = fn(&MetricsSnapshot) -> f64;
+
+/// Default regression threshold, in percent
+pub const DEFAULT_REGRESSION_THRESHOLD_PCT: f64 = 10.0;
+
+/// Delta for a single aggregate metric
+#[derive(Debug, Clone)]
+pub struct MetricDelta {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    /// Relative change in percent (positive means the value went up)
+    pub delta_pct: f64,
+    /// Whether the change exceeds the threshold in the bad direction
+    pub regressed: bool,
+}
+
+/// Result of comparing two reports
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub baseline_agents: usize,
+    pub current_agents: usize,
+    pub threshold_pct: f64,
+    pub deltas: Vec<MetricDelta>,
+}
+
+impl Comparison {
+    /// Whether any metric regressed beyond the threshold
+    pub fn has_regression(&self) -> bool {
+        self.deltas.iter().any(|d| d.regressed)
+    }
+}
+
+/// Load a JSON report written by `--json-report`
+pub fn load_report(path: &Path) -> Result<MetricsSnapshot> {
+    let data = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+/// Compare aggregate metrics of two reports.
+///
+/// Only run-wide aggregates are compared, so reports produced with
+/// different agent counts remain comparable.
+pub fn compare(
+    baseline: &MetricsSnapshot,
+    current: &MetricsSnapshot,
+    threshold_pct: f64,
+) -> Comparison {
+    let metrics: [(&'static str, &'static str, MetricFn, bool); 4] = [
+        ("throughput", "ops/sec", throughput, true),
+        ("p50 latency", "ms", |s| s.latencies.p50_ms(), false),
+        ("p99 latency", "ms", |s| s.latencies.p99_ms(), false),
+        ("error rate", "%", error_rate, false),
+    ];
+
+    let deltas = metrics
+        .iter()
+        .map(|&(name, unit, value, higher_is_better)| {
+            let baseline = value(baseline);
+            let current = value(current);
+            let delta_pct = relative_change(baseline, current);
+            let worse_pct = if higher_is_better {
+                -delta_pct
+            } else {
+                delta_pct
+            };
+            MetricDelta {
+                name,
+                unit,
+                baseline,
+                current,
+                delta_pct,
+                regressed: worse_pct > threshold_pct,
+            }
+        })
+        .collect();
+
+    Comparison {
+        baseline_agents: baseline.agent_metrics.len(),
+        current_agents: current.agent_metrics.len(),
+        threshold_pct,
+        deltas,
+    }
+}
+
+/// Print a comparison table to stdout
+pub fn print_comparison(cmp: &Comparison) {
+    if cmp.baseline_agents != cmp.current_agents {
+        println!(
+            "Note: agent counts differ (baseline {}, current {}); comparing aggregates only",
+            cmp.baseline_agents, cmp.current_agents
+        );
+    }
+
+    println!(
+        "{:<14} {:>14} {:>14} {:>10}",
+        "METRIC", "BASELINE", "CURRENT", "DELTA"
+    );
+    for d in &cmp.deltas {
+        println!(
+            "{:<14} {:>14} {:>14} {:>+9.1}%{}",
+            d.name,
+            format!("{:.2} {}", d.baseline, d.unit),
+            format!("{:.2} {}", d.current, d.unit),
+            d.delta_pct,
+            if d.regressed { "  REGRESSION" } else { "" }
+        );
+    }
+
+    if cmp.has_regression() {
+        println!(
+            "\nRegression detected (threshold {:.1}%)",
+            cmp.threshold_pct
+        );
+    } else {
+        println!("\nNo regressions (threshold {:.1}%)", cmp.threshold_pct);
+    }
+}
+
+fn throughput(s: &MetricsSnapshot) -> f64 {
+    let secs = s.elapsed.as_secs_f64();
+    if secs == 0.0 {
+        0.0
+    } else {
+        s.total_operations as f64 / secs
+    }
+}
+
+fn error_rate(s: &MetricsSnapshot) -> f64 {
+    if s.total_operations == 0 {
+        0.0
+    } else {
+        (s.failed_operations as f64 / s.total_operations as f64) * 100.0
+    }
+}
+
+fn relative_change(baseline: f64, current: f64) -> f64 {
+    if baseline == current {
+        0.0
+    } else if baseline == 0.0 {
+        // Any change from zero counts as a full swing
+        100.0_f64.copysign(current)
+    } else {
+        (current - baseline) / baseline * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::metrics::{AgentMetrics, LatencyPercentiles};
+    use std::time::Duration;
+
+    fn make_report(
+        agents: usize,
+        total: u64,
+        failed: u64,
+        p50_us: u64,
+        p99_us: u64,
+    ) -> MetricsSnapshot {
+        MetricsSnapshot {
+            total_operations: total,
+            successful_operations: total - failed,
+            failed_operations: failed,
+            wal_contentions: 0,
+            db_lock_waits: 0,
+            crdt_conflicts: 0,
+            issues_created: 0,
+            comments_added: 0,
+            labels_added: 0,
+            labels_removed: 0,
+            issues_updated: 0,
+            issues_closed: 0,
+            read_operations: 0,
+            latencies: LatencyPercentiles {
+                p50_us,
+                p95_us: p99_us,
+                p99_us,
+                max_us: p99_us,
+            },
+            throughput_history: vec![],
+            current_throughput: 0.0,
+            peak_throughput: 0.0,
+            agent_metrics: vec![AgentMetrics::default(); agents],
+            event_log: vec![],
+            elapsed: Duration::from_secs(10),
+        }
+    }
+
+    #[test]
+    fn test_compare_self_has_zero_deltas() {
+        let report = make_report(8, 800, 4, 1_500, 9_000);
+        let cmp = compare(&report, &report, DEFAULT_REGRESSION_THRESHOLD_PCT);
+
+        assert_eq!(cmp.deltas.len(), 4);
+        for d in &cmp.deltas {
+            assert_eq!(d.delta_pct, 0.0, "{} should not change", d.name);
+            assert!(!d.regressed);
+        }
+        assert!(!cmp.has_regression());
+    }
+
+    #[test]
+    fn test_compare_flags_regression_across_agent_counts() {
+        let baseline = make_report(8, 800, 0, 1_000, 5_000);
+        let current = make_report(4, 400, 0, 2_000, 5_000);
+        let cmp = compare(&baseline, &current, DEFAULT_REGRESSION_THRESHOLD_PCT);
+
+        assert_eq!(cmp.baseline_agents, 8);
+        assert_eq!(cmp.current_agents, 4);
+        let regressed: Vec<&str> = cmp
+            .deltas
+            .iter()
+            .filter(|d| d.regressed)
+            .map(|d| d.name)
+            .collect();
+        assert_eq!(regressed, vec!["throughput", "p50 latency"]);
+    }
+}
@@ -29,6 +29,9 @@ pub struct MetricsCollector {
     pub issues_updated: AtomicU64,
     pub issues_closed: AtomicU64,
 
+    // Successful reads (read-only benchmark)
+    pub read_operations: AtomicU64,
+
     // Latency histogram (requires lock for HDR updates)
     latency_histogram: RwLock<Histogram<u64>>,
 
@@ -75,6 +78,8 @@ impl MetricsCollector {
             issues_updated: AtomicU64::new(0),
             issues_closed: AtomicU64::new(0),
 
+            read_operations: AtomicU64::new(0),
+
             // 1 microsecond to 60 seconds, 3 significant figures
             latency_histogram: RwLock::new(Histogram::new_with_bounds(1, 60_000_000, 3).unwrap()),
 
@@ -122,6 +127,22 @@ impl MetricsCollector {
         }
     }
 
+    /// Record a completed read; reads never count towards the write totals
+    pub fn record_read(&self, success: bool, latency: Duration) {
+        self.total_operations.fetch_add(1, Ordering::Relaxed);
+
+        if success {
+            self.successful_operations.fetch_add(1, Ordering::Relaxed);
+            self.read_operations.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed_operations.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if let Ok(mut hist) = self.latency_histogram.write() {
+            let _ = hist.record(latency.as_micros() as u64);
+        }
+    }
+
     /// Record a WAL contention event
     pub fn record_wal_contention(&self) {
         self.wal_contentions.fetch_add(1, Ordering::Relaxed);
@@ -248,6 +269,8 @@ impl MetricsCollector {
             issues_updated: self.issues_updated.load(Ordering::Relaxed),
             issues_closed: self.issues_closed.load(Ordering::Relaxed),
 
+            read_operations: self.read_operations.load(Ordering::Relaxed),
+
             latencies: self.get_latency_percentiles(),
             throughput_history: throughput_data,
             current_throughput,
@@ -418,6 +441,9 @@ pub struct MetricsSnapshot {
     pub issues_updated: u64,
     pub issues_closed: u64,
 
+    #[serde(default)]
+    pub read_operations: u64,
+
     pub latencies: LatencyPercentiles,
     pub throughput_history: Vec<u64>,
     pub current_throughput: f64,
@@ -459,6 +485,16 @@ impl MetricsSnapshot {
         }
     }
 
+    /// Successful writes of every kind
+    pub fn write_operations(&self) -> u64 {
+        self.issues_created
+            + self.comments_added
+            + self.labels_added
+            + self.labels_removed
+            + self.issues_updated
+            + self.issues_closed
+    }
+
     pub fn contention_rate(&self) -> f64 {
         if self.total_operations == 0 {
             0.0
@@ -1,8 +1,10 @@
 //! Benchmark module
 
 pub mod agent;
+pub mod compare;
 pub mod config;
 pub mod metrics;
+pub mod read_only;
 pub mod runner;
 pub mod scenario;
 
@@ -0,0 +1,231 @@
+//! Read-only benchmark against an existing repository
+//!
+//! The repository's store is never opened in place. Its sled directory is
+//! copied to a temp directory while a shared lock on the store's lock file
+//! keeps writers out, and every read runs against the copy. Nothing in the
+//! repository is written and its write lock is never taken.
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use fs2::FileExt;
+use libgrite_core::config::repo_sled_path;
+use libgrite_core::store::{GriteStore, IssueFilter};
+use libgrite_core::types::issue::IssueSummary;
+use rand::Rng;
+
+use super::metrics::MetricsCollector;
+use crate::error::{BenchError, Result};
+
+/// Read operations, run in rotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadOp {
+    ListIssues,
+    ShowIssue,
+    SearchIssues,
+}
+
+impl ReadOp {
+    const ALL: [ReadOp; 3] = [ReadOp::ListIssues, ReadOp::ShowIssue, ReadOp::SearchIssues];
+}
+
+/// A private copy of a repository's store, removed on drop
+pub struct ReadOnlyStore {
+    store: Option<GriteStore>,
+    copy_dir: PathBuf,
+}
+
+impl ReadOnlyStore {
+    /// Copy the store of the repository at `git_dir` and open the copy.
+    ///
+    /// Refuses when a writer holds the store's lock, since copying a store
+    /// mid-write could capture a torn database.
+    pub fn open(git_dir: &Path) -> Result<Self> {
+        let sled_path = repo_sled_path(git_dir);
+        if !sled_path.exists() {
+            return Err(BenchError::Config(format!(
+                "No grite store at {}",
+                sled_path.display()
+            )));
+        }
+
+        // A missing lock file means no writer has ever opened the store
+        let lock_path = sled_path.with_extension("lock");
+        let lock_file = match File::open(&lock_path) {
+            Ok(file) => {
+                // fs2's lock, not the std method of the same name (Rust 1.89+)
+                FileExt::try_lock_shared(&file).map_err(|_| {
+                    BenchError::Bench(format!(
+                        "Store at {} is locked by a writer (is the daemon running?); \
+                         refusing to open it read-only",
+                        sled_path.display()
+                    ))
+                })?;
+                Some(file)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let copy_dir =
+            std::env::temp_dir().join(format!("grite-bench-ro-{}-{}", std::process::id(), nanos));
+        let copied = copy_dir_all(&sled_path, &copy_dir);
+        drop(lock_file);
+
+        // From here on, dropping `ro` removes a partial copy
+        let mut ro = Self {
+            store: None,
+            copy_dir,
+        };
+        copied?;
+        ro.store = Some(GriteStore::open(&ro.copy_dir)?);
+        Ok(ro)
+    }
+
+    fn store(&self) -> &GriteStore {
+        self.store.as_ref().expect("store is open")
+    }
+}
+
+impl Drop for ReadOnlyStore {
+    fn drop(&mut self) {
+        // Close sled before deleting its files
+        self.store = None;
+        let _ = std::fs::remove_dir_all(&self.copy_dir);
+    }
+}
+
+/// Recursively copy a directory
+fn copy_dir_all(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir_all(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Run `operations` reads against the store of the repository at `git_dir`,
+/// recording each one in `metrics`
+pub fn run(git_dir: &Path, operations: usize, metrics: &MetricsCollector) -> Result<()> {
+    let ro = ReadOnlyStore::open(git_dir)?;
+    let store = ro.store();
+    let issues = store.list_issues(&IssueFilter::default())?;
+    metrics.log_event(format!("Read-only benchmark over {} issues", issues.len()));
+
+    for (i, op) in ReadOp::ALL.iter().cycle().take(operations).enumerate() {
+        let start = Instant::now();
+        let result = run_op(store, *op, &issues);
+        metrics.record_read(result.is_ok(), start.elapsed());
+        if let Err(e) = result {
+            metrics.log_event(format!("Read #{} ({:?}) failed: {}", i, op, e));
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a single read; `issues` supplies targets for show and search
+fn run_op(store: &GriteStore, op: ReadOp, issues: &[IssueSummary]) -> Result<()> {
+    let random_issue = || {
+        if issues.is_empty() {
+            None
+        } else {
+            Some(&issues[rand::thread_rng().gen_range(0..issues.len())])
+        }
+    };
+
+    match op {
+        ReadOp::ListIssues => {
+            store.list_issues(&IssueFilter::default())?;
+        }
+        ReadOp::ShowIssue => {
+            if let Some(issue) = random_issue() {
+                store.get_issue(&issue.issue_id)?;
+                store.get_issue_events(&issue.issue_id)?;
+            }
+        }
+        ReadOp::SearchIssues => {
+            // Search for a word from a random title, as a user would
+            let term = random_issue()
+                .and_then(|issue| issue.title.split_whitespace().next())
+                .unwrap_or("")
+                .to_lowercase();
+            let _matches: Vec<IssueSummary> = store
+                .list_issues(&IssueFilter::default())?
+                .into_iter()
+                .filter(|issue| issue.title.to_lowercase().contains(&term))
+                .collect();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libgrite_core::hash::compute_event_id;
+    use libgrite_core::types::event::{Event, EventKind};
+    use libgrite_core::types::ids::generate_issue_id;
+    use tempfile::tempdir;
+
+    fn populate(git_dir: &Path, issues: usize) {
+        std::fs::create_dir_all(git_dir.join("grite")).unwrap();
+        let store = GriteStore::open_locked(&repo_sled_path(git_dir)).unwrap();
+        let actor = [1u8; 16];
+        for i in 0..issues {
+            let issue_id = generate_issue_id();
+            let kind = EventKind::IssueCreated {
+                title: format!("Issue number {}", i),
+                body: String::new(),
+                labels: vec![],
+            };
+            let ts = 1000 + i as u64;
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            store
+                .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_read_only_bench_never_writes() {
+        let dir = tempdir().unwrap();
+        let git_dir = dir.path().join(".git");
+        populate(&git_dir, 5);
+
+        let metrics = MetricsCollector::new(0);
+        run(&git_dir, 30, &metrics).unwrap();
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.read_operations, 30);
+        assert_eq!(snapshot.failed_operations, 0);
+        assert_eq!(snapshot.write_operations(), 0);
+
+        // The repository's store is untouched and can still be locked
+        let store = GriteStore::open_locked(&repo_sled_path(&git_dir)).unwrap();
+        assert_eq!(store.get_all_events().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_read_only_bench_refuses_locked_store() {
+        let dir = tempdir().unwrap();
+        let git_dir = dir.path().join(".git");
+        populate(&git_dir, 1);
+
+        let _writer = GriteStore::open_locked(&repo_sled_path(&git_dir)).unwrap();
+        let metrics = MetricsCollector::new(0);
+        let err = run(&git_dir, 3, &metrics).unwrap_err();
+        assert!(err.to_string().contains("refusing"), "{}", err);
+        assert_eq!(metrics.snapshot().total_operations, 0);
+    }
+}
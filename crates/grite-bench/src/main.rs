@@ -7,7 +7,7 @@ mod ui;
 
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 use app::App;
 use bench::{BenchmarkConfig, BenchmarkScenario};
@@ -18,6 +18,9 @@ use error::Result;
 #[command(about = "TUI benchmark for testing concurrent agent writes to grite")]
 #[command(version)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<BenchCommand>,
+
     /// Number of concurrent agents
     #[arg(short = 'n', long, default_value = "8")]
     agents: usize,
@@ -41,11 +44,55 @@ struct Cli {
     /// Non-interactive mode (no TUI)
     #[arg(long)]
     headless: bool,
+
+    /// Benchmark list/show/search reads against an existing repository
+    /// (requires --repo); runs `--operations` reads on a private copy of its
+    /// store and never writes
+    #[arg(long, requires = "repo")]
+    read_only: bool,
+}
+
+#[derive(Subcommand)]
+enum BenchCommand {
+    /// Compare two JSON reports and flag regressions
+    Compare {
+        /// Baseline report
+        baseline: PathBuf,
+
+        /// Current report
+        current: PathBuf,
+
+        /// Regression threshold in percent
+        #[arg(short = 't', long, default_value_t = bench::compare::DEFAULT_REGRESSION_THRESHOLD_PCT)]
+        threshold: f64,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    if let Some(BenchCommand::Compare {
+        baseline,
+        current,
+        threshold,
+    }) = cli.command
+    {
+        let baseline = bench::compare::load_report(&baseline)?;
+        let current = bench::compare::load_report(&current)?;
+        let cmp = bench::compare::compare(&baseline, &current, threshold);
+        bench::compare::print_comparison(&cmp);
+        if cmp.has_regression() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if cli.read_only {
+        let repo = cli.repo.as_deref().expect("--read-only requires --repo");
+        app::run_read_only(repo, cli.operations, cli.json_report.as_deref())?;
+        return Ok(());
+    }
+
     // Parse scenario
     let scenario = BenchmarkScenario::from_name(&cli.scenario, cli.agents, cli.operations)
         .ok_or_else(|| {
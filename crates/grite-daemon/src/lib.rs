@@ -21,6 +21,7 @@
 //! ```
 
 pub mod error;
+pub mod rate_limit;
 pub mod signals;
 pub mod state;
 pub mod supervisor;
@@ -10,6 +10,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use clap::Parser;
+use daemonize::Daemonize;
 use grite_daemon::supervisor::Supervisor;
 use tracing::{error, info};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
@@ -21,7 +22,7 @@ struct Cli {
     #[arg(long)]
     endpoint: Option<String>,
 
-    /// Daemonize (run in background)
+    /// Daemonize (fork into the background and detach from the terminal)
     #[arg(long, short)]
     daemon: bool,
 
@@ -29,6 +30,10 @@ struct Cli {
     #[arg(long)]
     pid_file: Option<PathBuf>,
 
+    /// Write the socket path to this file once the daemon is accepting connections
+    #[arg(long)]
+    ready_file: Option<PathBuf>,
+
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, default_value = "info")]
     log_level: String,
@@ -36,12 +41,50 @@ struct Cli {
     /// Idle timeout in seconds (daemon auto-stops after this period of inactivity, 0 = no timeout)
     #[arg(long, default_value = "0")]
     idle_timeout: u64,
+
+    /// Maximum requests per second from each actor (unlimited if unset)
+    #[arg(long, value_name = "OPS_PER_SEC", value_parser = parse_rate_limit)]
+    rate_limit: Option<f64>,
+}
+
+fn parse_rate_limit(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(rate),
+        _ => Err(format!("expected a positive number, got '{}'", s)),
+    }
 }
 
-#[tokio::main]
-async fn main() {
+fn main() {
     let cli = Cli::parse();
 
+    // Fork before the runtime starts; the parent exits once the child detaches.
+    // The child keeps the working directory so relative paths still resolve.
+    if cli.daemon {
+        let daemonized = std::env::current_dir()
+            .map_err(|e| e.to_string())
+            .and_then(|cwd| {
+                Daemonize::new()
+                    .working_directory(cwd)
+                    .start()
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = daemonized {
+            eprintln!("Failed to daemonize: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start runtime: {}", e);
+            std::process::exit(1);
+        }
+    };
+    runtime.block_on(run(cli));
+}
+
+async fn run(cli: Cli) {
     // Initialize logging
     let filter = EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new(&cli.log_level))
@@ -54,13 +97,6 @@ async fn main() {
 
     info!("grite-daemon starting");
 
-    // Daemonize if requested
-    if cli.daemon {
-        info!("Daemonizing...");
-        // For full daemonization, we'd use the daemonize crate
-        // For now, we just run in foreground
-    }
-
     // Write PID file if specified
     if let Some(ref pid_file) = cli.pid_file {
         let pid = std::process::id();
@@ -81,7 +117,13 @@ async fn main() {
     let endpoint = cli
         .endpoint
         .unwrap_or_else(libgrite_ipc::default_socket_path);
-    let supervisor = Supervisor::new(endpoint, idle_timeout);
+    let mut supervisor = Supervisor::new(endpoint, idle_timeout);
+    if let Some(ready_file) = cli.ready_file {
+        supervisor = supervisor.with_ready_file(ready_file);
+    }
+    if let Some(rate_limit) = cli.rate_limit {
+        supervisor = supervisor.with_rate_limit(rate_limit);
+    }
 
     if let Err(e) = supervisor.run(shutdown).await {
         error!("Supervisor error: {}", e);
@@ -0,0 +1,59 @@
+//! Per-actor rate limiting
+//!
+//! Each actor gets its own token bucket, so an actor that exhausts its budget
+//! is rejected without slowing down requests from anyone else.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Token bucket for a single actor
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token-bucket rate limiter keyed by actor ID
+pub struct RateLimiter {
+    ops_per_sec: f64,
+    /// Bucket capacity: one second's worth of requests, and at least one
+    burst: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Allow each actor `ops_per_sec` requests per second on average
+    pub fn new(ops_per_sec: f64) -> Self {
+        Self {
+            ops_per_sec,
+            burst: ops_per_sec.max(1.0),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Requests per second allowed for each actor
+    pub fn ops_per_sec(&self) -> f64 {
+        self.ops_per_sec
+    }
+
+    /// Take a token for `actor_id`, returning false if its bucket is empty
+    pub fn try_acquire(&self, actor_id: &str) -> bool {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(actor_id.to_string()).or_insert(Bucket {
+            tokens: self.burst,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.ops_per_sec).min(self.burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
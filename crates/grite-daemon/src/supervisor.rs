@@ -14,6 +14,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use libgrite_ipc::{
+    error::codes,
     framing::{read_framed_async, write_framed_async},
     messages::{ArchivedIpcRequest, IpcRequest, IpcResponse},
     IpcCommand, Notification, IPC_SCHEMA_VERSION,
@@ -23,6 +24,7 @@ use tokio::sync::{mpsc, Mutex, Semaphore};
 use tracing::{debug, info, warn};
 
 use crate::error::DaemonError;
+use crate::rate_limit::RateLimiter;
 use crate::state::{AtomicSupervisorState, SupervisorState};
 use crate::worker::{Worker, WorkerMessage};
 
@@ -62,6 +64,7 @@ struct DaemonState {
     start_instant: Instant,
     idle_timeout: Option<Duration>,
     supervisor_state: AtomicSupervisorState,
+    rate_limiter: Option<RateLimiter>,
 }
 
 impl DaemonState {
@@ -75,6 +78,7 @@ impl DaemonState {
 pub struct Supervisor {
     state: Arc<DaemonState>,
     notify_rx: mpsc::Receiver<Notification>,
+    ready_file: Option<PathBuf>,
 }
 
 impl Supervisor {
@@ -103,9 +107,35 @@ impl Supervisor {
             start_instant,
             idle_timeout,
             supervisor_state: AtomicSupervisorState::new(SupervisorState::Starting),
+            rate_limiter: None,
         });
 
-        Self { state, notify_rx }
+        Self {
+            state,
+            notify_rx,
+            ready_file: None,
+        }
+    }
+
+    /// Write the socket path to `path` once the socket is accepting connections.
+    ///
+    /// Lets scripts and process supervisors wait for readiness instead of
+    /// polling the socket. The file is removed on shutdown.
+    pub fn with_ready_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.ready_file = Some(path.into());
+        self
+    }
+
+    /// Limit each actor to `ops_per_sec` requests per second.
+    ///
+    /// Requests over the limit are rejected with a `rate_limited` error.
+    /// Daemon status and stop requests are never limited.
+    pub fn with_rate_limit(mut self, ops_per_sec: f64) -> Self {
+        // The state is not shared until `run` spawns connection tasks
+        if let Some(state) = Arc::get_mut(&mut self.state) {
+            state.rate_limiter = Some(RateLimiter::new(ops_per_sec));
+        }
+        self
     }
 
     /// Run the supervisor until shutdown.
@@ -155,6 +185,16 @@ impl Supervisor {
         })?;
 
         info!("Listening on {}", self.state.socket_path);
+
+        // Readiness is announced only after bind, so the socket is connectable
+        if let Some(ref ready_file) = self.ready_file {
+            if let Err(e) = write_ready_file(ready_file, &self.state.socket_path) {
+                drop(listener);
+                let _ = std::fs::remove_file(&self.state.socket_path);
+                return Err(e.into());
+            }
+            debug!("Wrote ready file {}", ready_file.display());
+        }
         self.state
             .supervisor_state
             .transition(SupervisorState::Running, Ordering::SeqCst)
@@ -265,8 +305,11 @@ impl Supervisor {
         // the broadcast — the second send simply has no receivers.
         let _ = self.state.shutdown_tx.send(());
 
-        // Clean up socket file
+        // Clean up socket and ready files
         let _ = std::fs::remove_file(&self.state.socket_path);
+        if let Some(ref ready_file) = self.ready_file {
+            let _ = std::fs::remove_file(ready_file);
+        }
 
         // Stop accepting new connections so no new tasks can spawn.
         drop(listener);
@@ -295,6 +338,13 @@ impl Supervisor {
     }
 }
 
+/// Write via a temp file and rename so watchers never see a partial path
+fn write_ready_file(path: &Path, socket_path: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("tmp.{}", std::process::id()));
+    std::fs::write(&tmp, socket_path)?;
+    std::fs::rename(&tmp, path)
+}
+
 /// Drain workers from the map and shut them down.
 ///
 /// The mutex is released before sending shutdown messages or awaiting
@@ -397,6 +447,11 @@ async fn process_request(raw: &[u8], state: &DaemonState) -> IpcResponse {
         }
     };
 
+    dispatch_request(request, state).await
+}
+
+/// Handle a deserialized request, at the supervisor or in a worker
+async fn dispatch_request(request: IpcRequest, state: &DaemonState) -> IpcResponse {
     debug!(
         request_id = %request.request_id,
         repo = %request.repo_root,
@@ -408,10 +463,7 @@ async fn process_request(raw: &[u8], state: &DaemonState) -> IpcResponse {
     match &request.command {
         IpcCommand::DaemonStop => {
             let _ = state.shutdown_tx.send(());
-            return IpcResponse::success(
-                request.request_id,
-                Some(serde_json::json!({"stopping": true}).to_string()),
-            );
+            return daemon_response(&request, serde_json::json!({"stopping": true}));
         }
         IpcCommand::DaemonStatus => {
             let workers_guard = state.workers.lock().await;
@@ -419,30 +471,58 @@ async fn process_request(raw: &[u8], state: &DaemonState) -> IpcResponse {
             drop(workers_guard);
 
             let supervisor_state = format!("{:?}", state.supervisor_state.load(Ordering::SeqCst));
-            return IpcResponse::success(
+            return daemon_response(
+                &request,
+                serde_json::json!({
+                    "running": true,
+                    "daemon_id": state.daemon_id,
+                    "pid": state.pid,
+                    "host_id": state.host_id,
+                    "ipc_endpoint": state.socket_path,
+                    "started_ts": state.started_ts,
+                    "worker_count": worker_count,
+                    "state": supervisor_state,
+                }),
+            );
+        }
+        _ => {}
+    }
+
+    if let Some(limiter) = &state.rate_limiter {
+        if !limiter.try_acquire(&request.actor_id) {
+            debug!(actor = %request.actor_id, "Request rate limited");
+            return IpcResponse::error(
                 request.request_id,
-                Some(
-                    serde_json::json!({
-                        "running": true,
-                        "daemon_id": state.daemon_id,
-                        "pid": state.pid,
-                        "host_id": state.host_id,
-                        "ipc_endpoint": state.socket_path,
-                        "started_ts": state.started_ts,
-                        "worker_count": worker_count,
-                        "state": supervisor_state,
-                    })
-                    .to_string(),
+                codes::RATE_LIMITED.to_string(),
+                format!(
+                    "Actor {} exceeded the rate limit of {} requests per second",
+                    request.actor_id,
+                    limiter.ops_per_sec()
                 ),
             );
         }
-        _ => {}
     }
 
     // Route to worker
     route_to_worker(request, state).await
 }
 
+/// A successful response to a daemon-level command, in the requested encoding
+fn daemon_response(request: &IpcRequest, data: serde_json::Value) -> IpcResponse {
+    IpcResponse::success_encoded(
+        request.request_id.clone(),
+        Some(&data),
+        request.accept_encoding,
+    )
+    .unwrap_or_else(|e| {
+        IpcResponse::error(
+            request.request_id.clone(),
+            codes::IPC_ERROR.to_string(),
+            e.to_string(),
+        )
+    })
+}
+
 /// Route a request to the appropriate worker, creating one if needed.
 ///
 /// If the worker's channel is dead (task panicked or exited), the stale
@@ -558,7 +638,9 @@ async fn send_to_worker(request: &IpcRequest, tx: mpsc::Sender<WorkerMessage>) -
     let msg = WorkerMessage::Command {
         request_id: request.request_id.clone(),
         actor_id: request.actor_id.clone(),
+        data_dir: request.data_dir.clone(),
         command: request.command.clone(),
+        encoding: request.accept_encoding,
         response_tx,
     };
 
@@ -2,36 +2,63 @@
 //!
 //! Each worker owns exclusive access to the shared sled database for its
 //! repository. Commands are processed concurrently using tokio tasks, with
-//! sled's internal MVCC handling concurrent access safely.
+//! sled's internal MVCC handling concurrent access safely. At most the repo
+//! config's `max_concurrent_commands` run at once; the rest wait for a permit.
 //!
 //! Actor ID is supplied per-command rather than being fixed at worker
 //! creation time, reflecting the shared-sled model where actor identity
 //! is authorship metadata rather than a storage partition.
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock, TryLockError};
 use std::time::Duration;
 
-use libgrite_core::config::repo_sled_path;
-use libgrite_core::store::IssueFilter;
+use libgrite_core::audit::AuditLog;
+use libgrite_core::config::{
+    actor_dir, list_actors, load_actor_config, load_repo_config, repo_sled_path,
+};
+use libgrite_core::store::{IssueFilter, IssueSort};
 use libgrite_core::types::ids::{hex_to_id, ActorId};
 use libgrite_core::{GriteError, GriteStore, LockedStore};
-use libgrite_ipc::{DaemonLock, IpcCommand, IpcResponse, Notification};
-use tokio::sync::mpsc;
+use libgrite_ipc::{
+    DaemonLock, DaemonRegistry, DataEncoding, IpcCommand, IpcError, IpcResponse, Notification,
+    RegistryEntry,
+};
+use tokio::sync::{mpsc, Semaphore};
 use tracing::{debug, error, info, warn};
 
 use crate::error::DaemonError;
 use crate::state::{AtomicWorkerState, WorkerState};
 
+/// Commands a worker runs at once when the repo config does not say
+pub const DEFAULT_MAX_CONCURRENT_COMMANDS: usize = 16;
+
+/// Attempts to take the daemon lock when the repo config does not say
+pub const DEFAULT_LOCK_ACQUIRE_ATTEMPTS: u32 = 10;
+
+/// First wait between daemon lock attempts; doubles up to the maximum
+const LOCK_RETRY_INITIAL_DELAY: Duration = Duration::from_millis(200);
+const LOCK_RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Extra wait past a lock's expiry so the retry finds it expired
+const LOCK_EXPIRY_SLACK: Duration = Duration::from_millis(50);
+
 /// Message sent to a worker
+#[allow(clippy::large_enum_variant)]
 pub enum WorkerMessage {
     /// Execute a command
     Command {
         request_id: String,
         /// Actor ID (hex) for event authorship
         actor_id: String,
+        /// The client's `--data-dir` / `GRITE_HOME`, where an actor outside
+        /// the repo keeps its config (empty if none)
+        data_dir: String,
         command: IpcCommand,
+        /// Encoding the client asked for response data
+        encoding: DataEncoding,
         response_tx: tokio::sync::oneshot::Sender<IpcResponse>,
     },
     /// Refresh the heartbeat
@@ -62,6 +89,26 @@ pub struct Worker {
     ipc_endpoint: String,
     /// Owner actor ID used when acquiring the daemon lock
     owner_actor_id: String,
+    /// Attempts to take the daemon lock before giving up (repo config)
+    lock_attempts: u32,
+    /// Rebuild automatically when a rebuild is recommended (repo config)
+    auto_rebuild: bool,
+    /// Number of sync commands currently running
+    syncs_in_flight: Arc<AtomicUsize>,
+    /// Set while an automatic rebuild is running
+    rebuilding: Arc<AtomicBool>,
+    /// Held shared by every running command and exclusively by an automatic
+    /// rebuild, so a rebuild never overlaps a command; commands that find it
+    /// held exclusively reply `db_busy` instead of waiting
+    store_access: Arc<RwLock<()>>,
+    /// Snapshot and prune the WAL past this many commits (repo config)
+    max_wal_commits: Option<usize>,
+    /// Set while the WAL is being compacted
+    compacting: Arc<AtomicBool>,
+    /// Permits for running commands, bounding concurrency (repo config)
+    command_permits: Arc<Semaphore>,
+    /// Number of commands currently running
+    pub in_flight: Arc<AtomicUsize>,
     /// Current lifecycle state
     pub state: Arc<AtomicWorkerState>,
 }
@@ -79,14 +126,30 @@ impl Worker {
         let git_dir = repo_root.join(".git");
         let grite_dir = git_dir.join("grite");
         let sled_path = repo_sled_path(&git_dir);
+        let repo_config = load_repo_config(&git_dir)?.unwrap_or_default();
+        let auto_rebuild = repo_config.auto_rebuild;
+        let store_options = repo_config.store_options();
+        let max_wal_commits = repo_config
+            .snapshot
+            .and_then(|s| s.max_wal_commits)
+            .map(|n| n as usize);
+        let max_concurrent_commands = repo_config
+            .max_concurrent_commands
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_COMMANDS)
+            .max(1);
+        let lock_attempts = repo_config
+            .lock_acquire_attempts
+            .unwrap_or(DEFAULT_LOCK_ACQUIRE_ATTEMPTS)
+            .max(1);
 
         // Open store with filesystem lock (blocking with timeout)
         // This ensures exclusive process-level access to the sled database
         let state = Arc::new(AtomicWorkerState::new(WorkerState::Initializing));
 
-        let store = Arc::new(GriteStore::open_locked_blocking(
+        let store = Arc::new(GriteStore::open_locked_blocking_with_options(
             &sled_path,
             Duration::from_secs(5),
+            &store_options,
         )?);
 
         state.store(WorkerState::Idle, Ordering::SeqCst);
@@ -102,20 +165,82 @@ impl Worker {
             host_id,
             ipc_endpoint,
             owner_actor_id,
+            lock_attempts,
+            auto_rebuild,
+            syncs_in_flight: Arc::new(AtomicUsize::new(0)),
+            rebuilding: Arc::new(AtomicBool::new(false)),
+            store_access: Arc::new(RwLock::new(())),
+            max_wal_commits,
+            compacting: Arc::new(AtomicBool::new(false)),
+            command_permits: Arc::new(Semaphore::new(max_concurrent_commands)),
+            in_flight: Arc::new(AtomicUsize::new(0)),
             state,
         })
     }
 
     /// Acquire the daemon lock
-    pub fn acquire_lock(&self) -> Result<DaemonLock, DaemonError> {
-        DaemonLock::acquire(
-            &self.grite_dir,
-            self.repo_root.to_string_lossy().to_string(),
-            self.owner_actor_id.clone(),
-            self.host_id.clone(),
-            self.ipc_endpoint.clone(),
-        )
-        .map_err(|e| DaemonError::LockFailed(e.to_string()))
+    ///
+    /// A lock left by a daemon that is shutting down is waited for, with
+    /// backoff, until its lease expires. A lock held by another running
+    /// process fails at once.
+    pub async fn acquire_lock(&self) -> Result<DaemonLock, DaemonError> {
+        let mut delay = LOCK_RETRY_INITIAL_DELAY;
+        let mut attempt = 1;
+        loop {
+            let err = match DaemonLock::acquire(
+                &self.grite_dir,
+                self.repo_root.to_string_lossy().to_string(),
+                self.owner_actor_id.clone(),
+                self.host_id.clone(),
+                self.ipc_endpoint.clone(),
+            ) {
+                Ok(lock) => return Ok(lock),
+                Err(e) => e,
+            };
+
+            // Time left on the current lock, or None if retrying cannot help
+            let remaining = match &err {
+                IpcError::LockHeld { .. } => match DaemonLock::read(&self.grite_dir)? {
+                    Some(held) if held.is_held_by_other_live_process(&self.host_id) => {
+                        return Err(DaemonError::LockFailed(format!(
+                            "another daemon (pid {}) is running for this repository",
+                            held.pid
+                        )));
+                    }
+                    Some(held) => Some(Duration::from_millis(held.time_remaining_ms())),
+                    None => Some(Duration::ZERO),
+                },
+                IpcError::LockRace => Some(Duration::ZERO),
+                _ => None,
+            };
+            match remaining {
+                Some(remaining) if attempt < self.lock_attempts => {
+                    let wait = delay.min(remaining + LOCK_EXPIRY_SLACK);
+                    debug!(
+                        attempt,
+                        wait_ms = wait.as_millis() as u64,
+                        "Daemon lock busy, retrying"
+                    );
+                    tokio::time::sleep(wait).await;
+                    delay = (delay * 2).min(LOCK_RETRY_MAX_DELAY);
+                    attempt += 1;
+                }
+                _ => return Err(DaemonError::LockFailed(err.to_string())),
+            }
+        }
+    }
+
+    /// Record this worker's endpoint in the per-user discovery registry
+    fn register_endpoint(&self) {
+        let entry = RegistryEntry {
+            repo_root: self.repo_root.to_string_lossy().to_string(),
+            actor_id: self.owner_actor_id.clone(),
+            endpoint: self.ipc_endpoint.clone(),
+            pid: std::process::id(),
+        };
+        if let Err(e) = DaemonRegistry::default().register(entry) {
+            warn!("Failed to register daemon endpoint: {}", e);
+        }
     }
 
     /// Refresh the daemon lock heartbeat
@@ -137,9 +262,10 @@ impl Worker {
         );
 
         // Acquire lock
-        match self.acquire_lock() {
+        match self.acquire_lock().await {
             Ok(_lock) => {
                 debug!("Daemon lock acquired");
+                self.register_endpoint();
             }
             Err(e) => {
                 error!("Failed to acquire lock: {}", e);
@@ -157,7 +283,7 @@ impl Worker {
             .await;
 
         // Track in-flight commands so we can wait for them on shutdown
-        let in_flight = Arc::new(AtomicUsize::new(0));
+        let in_flight = Arc::clone(&self.in_flight);
         let worker_state = Arc::clone(&self.state);
 
         // Event loop - commands are spawned as concurrent tasks
@@ -166,7 +292,9 @@ impl Worker {
                 WorkerMessage::Command {
                     request_id,
                     actor_id,
+                    data_dir,
                     command,
+                    encoding,
                     response_tx,
                 } => {
                     // Parse actor ID bytes for event authorship
@@ -183,45 +311,139 @@ impl Worker {
                         }
                     };
 
+                    // Writes are attributed to the request's actor, which must exist
+                    if is_write_command(&command)
+                        && !actor_is_known(&self.git_dir, &actor_id, &data_dir)
+                    {
+                        let resp = IpcResponse::error(
+                            request_id,
+                            libgrite_ipc::error::codes::INVALID_INPUT.to_string(),
+                            format!(
+                                "Unknown actor {}: not registered in this repository",
+                                actor_id
+                            ),
+                        );
+                        let _ = response_tx.send(resp);
+                        continue;
+                    }
+
                     // Clone data needed for the spawned task
                     let store = Arc::clone(&self.store);
                     let sled_path = self.sled_path.clone();
                     let git_dir = self.git_dir.clone();
                     let in_flight = Arc::clone(&in_flight);
                     let state = Arc::clone(&worker_state);
-
-                    let was_idle = in_flight.load(Ordering::SeqCst) == 0;
-                    in_flight.fetch_add(1, Ordering::SeqCst);
-                    if was_idle {
-                        state.store(WorkerState::Busy, Ordering::SeqCst);
-                    }
-
-                    // Run on the blocking thread pool — sled and git2 do
-                    // synchronous I/O that must not starve the async runtime.
-                    tokio::task::spawn_blocking(move || {
-                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                            execute_command(
-                                &store,
-                                actor_id_bytes,
-                                &sled_path,
-                                &git_dir,
-                                &request_id,
-                                &command,
-                            )
-                        }));
-                        let response = match result {
-                            Ok(resp) => resp,
-                            Err(_) => IpcResponse::error(
-                                request_id,
-                                "panic".to_string(),
-                                "Command handler panicked".to_string(),
-                            ),
+                    let auto_rebuild = self.auto_rebuild && is_write_command(&command);
+                    let max_wal_commits =
+                        self.max_wal_commits.filter(|_| is_write_command(&command));
+                    let is_sync = matches!(command, IpcCommand::Sync { .. });
+                    let syncs_in_flight = Arc::clone(&self.syncs_in_flight);
+                    let rebuilding = Arc::clone(&self.rebuilding);
+                    let store_access = Arc::clone(&self.store_access);
+                    let compacting = Arc::clone(&self.compacting);
+                    let notify_tx = self.notify_tx.clone();
+
+                    let command_permits = Arc::clone(&self.command_permits);
+
+                    // Wait for a free slot off the event loop, so heartbeats
+                    // and shutdown are still handled while commands queue. The
+                    // permit moves into the blocking task and is released when
+                    // it finishes, even if the handler panics.
+                    tokio::spawn(async move {
+                        let permit = match command_permits.acquire_owned().await {
+                            Ok(permit) => permit,
+                            Err(_) => {
+                                let resp = IpcResponse::error(
+                                    request_id,
+                                    "worker_unavailable".to_string(),
+                                    "Worker is shutting down".to_string(),
+                                );
+                                let _ = response_tx.send(resp);
+                                return;
+                            }
                         };
-                        let _ = response_tx.send(response);
-                        let remaining = in_flight.fetch_sub(1, Ordering::SeqCst);
-                        if remaining == 1 {
-                            state.store(WorkerState::Idle, Ordering::SeqCst);
+
+                        let was_idle = in_flight.load(Ordering::SeqCst) == 0;
+                        in_flight.fetch_add(1, Ordering::SeqCst);
+                        if was_idle {
+                            state.store(WorkerState::Busy, Ordering::SeqCst);
                         }
+
+                        // Run on the blocking thread pool — sled and git2 do
+                        // synchronous I/O that must not starve the async runtime.
+                        tokio::task::spawn_blocking(move || {
+                            let _permit = permit;
+                            if is_sync {
+                                syncs_in_flight.fetch_add(1, Ordering::SeqCst);
+                            }
+                            // An automatic rebuild holds the store exclusively;
+                            // reply busy at once rather than wait it out.
+                            let access = match store_access.try_read() {
+                                Ok(access) => Some(access),
+                                Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+                                Err(TryLockError::WouldBlock) => None,
+                            };
+                            let ran = access.is_some();
+                            let response = match access {
+                                Some(access) => {
+                                    let result = std::panic::catch_unwind(
+                                        std::panic::AssertUnwindSafe(|| {
+                                            execute_command(
+                                                &store,
+                                                actor_id_bytes,
+                                                &sled_path,
+                                                &git_dir,
+                                                &request_id,
+                                                &command,
+                                                encoding,
+                                                &notify_tx,
+                                            )
+                                        }),
+                                    );
+                                    drop(access);
+                                    match result {
+                                        Ok(resp) => resp,
+                                        Err(_) => IpcResponse::error(
+                                            request_id,
+                                            "panic".to_string(),
+                                            "Command handler panicked".to_string(),
+                                        ),
+                                    }
+                                }
+                                None => IpcResponse::error(
+                                    request_id,
+                                    libgrite_ipc::error::codes::DB_BUSY.to_string(),
+                                    "Database is being rebuilt, retry shortly".to_string(),
+                                ),
+                            };
+                            let _ = response_tx.send(response);
+                            if is_sync {
+                                syncs_in_flight.fetch_sub(1, Ordering::SeqCst);
+                            }
+                            let remaining = in_flight.fetch_sub(1, Ordering::SeqCst);
+                            if remaining == 1 {
+                                state.store(WorkerState::Idle, Ordering::SeqCst);
+                                // The response has already been sent, so a rebuild
+                                // here never delays the command that triggered it.
+                                if auto_rebuild && ran {
+                                    maybe_auto_rebuild(
+                                        &store,
+                                        &sled_path,
+                                        &store_access,
+                                        &rebuilding,
+                                        &notify_tx,
+                                    );
+                                }
+                                if let Some(max_commits) = max_wal_commits.filter(|_| ran) {
+                                    maybe_compact_wal(
+                                        &git_dir,
+                                        max_commits,
+                                        &syncs_in_flight,
+                                        &compacting,
+                                    );
+                                }
+                            }
+                        });
                     });
                 }
                 WorkerMessage::Heartbeat => {
@@ -231,15 +453,20 @@ impl Worker {
                 }
                 WorkerMessage::Shutdown => {
                     worker_state.store(WorkerState::ShuttingDown, Ordering::SeqCst);
+                    // Commands still waiting for a slot are turned away
+                    self.command_permits.close();
                     info!("Worker shutdown requested");
                     break;
                 }
             }
         }
 
-        // Wait for in-flight commands to complete (with timeout)
+        // Wait for in-flight commands and any maintenance to complete (with timeout)
         let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
-        while in_flight.load(Ordering::SeqCst) > 0 {
+        while in_flight.load(Ordering::SeqCst) > 0
+            || self.rebuilding.load(Ordering::SeqCst)
+            || self.compacting.load(Ordering::SeqCst)
+        {
             if tokio::time::Instant::now() >= deadline {
                 warn!(
                     "Timed out waiting for {} in-flight commands",
@@ -261,6 +488,11 @@ impl Worker {
         if let Err(e) = DaemonLock::release(&self.grite_dir) {
             warn!("Failed to release lock: {}", e);
         }
+        if let Err(e) = DaemonRegistry::default()
+            .unregister(&self.repo_root.to_string_lossy(), &self.owner_actor_id)
+        {
+            warn!("Failed to unregister daemon endpoint: {}", e);
+        }
 
         // Flush store
         if let Err(e) = self.store.flush() {
@@ -274,9 +506,129 @@ impl Worker {
     }
 }
 
+/// Whether `actor_id` is registered in the repo, or is the actor whose config
+/// lives in the request's data directory
+fn actor_is_known(git_dir: &Path, actor_id: &str, data_dir: &str) -> bool {
+    let matches = |dir: &Path| load_actor_config(dir).is_ok_and(|c| c.actor_id == actor_id);
+    matches(&actor_dir(git_dir, actor_id)) || (!data_dir.is_empty() && matches(Path::new(data_dir)))
+}
+
+/// Whether a command appends events to the store
+fn is_write_command(command: &IpcCommand) -> bool {
+    write_command_name(command).is_some()
+}
+
+/// CLI name of a command that appends events, as recorded in the audit log
+fn write_command_name(command: &IpcCommand) -> Option<&'static str> {
+    let name = match command {
+        IpcCommand::IssueCreate { .. } => "issue create",
+        IpcCommand::IssueUpdate { .. } => "issue update",
+        IpcCommand::IssueComment { .. } => "issue comment",
+        IpcCommand::IssueClose { .. } => "issue close",
+        IpcCommand::IssueReopen { .. } => "issue reopen",
+        IpcCommand::IssueLabel { .. } => "issue label",
+        IpcCommand::IssueAssign { .. } => "issue assign",
+        IpcCommand::IssueLink { .. } => "issue link add",
+        IpcCommand::IssueLinkIssue { .. } => "issue link-issue",
+        IpcCommand::IssueDup { .. } => "issue dup",
+        IpcCommand::IssueSubscribe { .. } => "issue subscribe",
+        IpcCommand::IssueEstimate { .. } => "issue estimate",
+        IpcCommand::IssueLogTime { .. } => "issue log-time",
+        IpcCommand::IssueMilestone { .. } => "issue milestone",
+        IpcCommand::IssueSeverity { .. } => "issue severity",
+        IpcCommand::IssueDelete { .. } => "issue delete",
+        IpcCommand::IssueUndelete { .. } => "issue undelete",
+        IpcCommand::IssueAttach { .. } => "issue attachment add",
+        IpcCommand::IssueField { .. } => "issue field",
+        IpcCommand::IssueDepAdd { .. } => "issue dep add",
+        IpcCommand::IssueDepRemove { .. } => "issue dep remove",
+        IpcCommand::Sync { .. } => "sync",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Rebuild the store if a rebuild is recommended.
+///
+/// Skipped if another rebuild is already running. The rebuild holds
+/// `store_access` exclusively: it waits for running commands, syncs
+/// included, to finish, and commands that arrive meanwhile wait for it.
+/// Emits a `Rebuilt` notification on success.
+fn maybe_auto_rebuild(
+    store: &LockedStore,
+    sled_path: &Path,
+    store_access: &RwLock<()>,
+    rebuilding: &AtomicBool,
+    notify_tx: &mpsc::Sender<Notification>,
+) {
+    match store.stats(sled_path) {
+        Ok(stats) if stats.rebuild_recommended => {}
+        Ok(_) => return,
+        Err(e) => {
+            warn!("Failed to read stats for auto-rebuild: {}", e);
+            return;
+        }
+    }
+
+    if rebuilding
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return;
+    }
+
+    let exclusive = store_access.write().unwrap_or_else(|e| e.into_inner());
+    info!("Rebuild recommended, running auto-rebuild");
+    match store.rebuild() {
+        Ok(stats) => {
+            let _ = notify_tx.try_send(Notification::rebuilt(
+                stats.event_count as u64,
+                stats.issue_count as u64,
+            ));
+        }
+        Err(e) => warn!("Auto-rebuild failed: {}", e),
+    }
+    drop(exclusive);
+    rebuilding.store(false, Ordering::SeqCst);
+}
+
+/// Snapshot and prune the WAL once it grows past `max_commits`.
+fn maybe_compact_wal(
+    git_dir: &Path,
+    max_commits: usize,
+    syncs_in_flight: &AtomicUsize,
+    compacting: &AtomicBool,
+) {
+    use libgrite_git::WalManager;
+
+    // A sync may be rewriting the WAL ref concurrently
+    if syncs_in_flight.load(Ordering::SeqCst) > 0 {
+        debug!("Skipping WAL compaction: sync in progress");
+        return;
+    }
+
+    if compacting
+        .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return;
+    }
+
+    match WalManager::open(git_dir).and_then(|wal| wal.compact(max_commits)) {
+        Ok(Some(stats)) => info!(
+            "Compacted WAL: pruned {} commits, kept {}",
+            stats.pruned, stats.kept
+        ),
+        Ok(None) => {}
+        Err(e) => warn!("WAL compaction failed: {}", e),
+    }
+    compacting.store(false, Ordering::SeqCst);
+}
+
 /// Execute a command with the given context.
 ///
 /// This is a standalone function to enable concurrent execution via tokio::spawn.
+#[allow(clippy::too_many_arguments)]
 fn execute_command(
     store: &LockedStore,
     actor_id_bytes: ActorId,
@@ -284,11 +636,30 @@ fn execute_command(
     git_dir: &Path,
     request_id: &str,
     command: &IpcCommand,
+    encoding: DataEncoding,
+    notify_tx: &mpsc::Sender<Notification>,
 ) -> IpcResponse {
-    let result = execute_command_inner(store, actor_id_bytes, sled_path, git_dir, command);
+    let _span = tracing::debug_span!("ipc_command", request_id).entered();
+    let result = execute_command_inner(
+        store,
+        actor_id_bytes,
+        sled_path,
+        git_dir,
+        command,
+        notify_tx,
+    );
+
+    let response = result.and_then(|data| match data {
+        // Plain text (a markdown export) is passed through as-is
+        Some(serde_json::Value::String(text)) => {
+            Ok(IpcResponse::success(request_id.to_string(), Some(text)))
+        }
+        data => IpcResponse::success_encoded(request_id.to_string(), data.as_ref(), encoding)
+            .map_err(DaemonError::Ipc),
+    });
 
-    match result {
-        Ok(data) => IpcResponse::success(request_id.to_string(), data),
+    match response {
+        Ok(response) => response,
         Err(e) => {
             let (code, message) = error_to_code_message(&e);
             IpcResponse::error(request_id.to_string(), code, message)
@@ -303,13 +674,17 @@ fn execute_command_inner(
     sled_path: &Path,
     git_dir: &Path,
     command: &IpcCommand,
-) -> Result<Option<String>, DaemonError> {
-    use libgrite_core::export::{export_json, export_markdown, ExportSince};
+    notify_tx: &mpsc::Sender<Notification>,
+) -> Result<Option<serde_json::Value>, DaemonError> {
+    use libgrite_core::export::{
+        export_counts, export_json, export_json_to, export_markdown, export_markdown_to,
+        parse_kinds, write_streamed, EventJson, ExportOptions, ExportOrder, ExportSince,
+    };
     use libgrite_core::hash::compute_event_id;
-    use libgrite_core::types::event::{Event, EventKind, IssueState};
+    use libgrite_core::types::event::{Event, EventKind, IssueState, Severity};
     use libgrite_core::types::ids::{generate_issue_id, id_to_hex};
     use libgrite_core::types::issue::IssueProjection;
-    use libgrite_git::{SyncManager, WalManager};
+    use libgrite_git::{SyncManager, SyncReport, WalManager};
 
     // Open WAL (best-effort — sled operations work without it)
     let wal = match WalManager::open(git_dir) {
@@ -320,13 +695,27 @@ fn execute_command_inner(
         }
     };
 
-    /// Persist events to both sled store and WAL.
-    /// WAL append is best-effort — failures are logged but don't fail the operation.
+    // Audit log (best-effort, like the WAL)
+    let audit = match write_command_name(command) {
+        Some(name) => AuditLog::for_repo(git_dir, name).unwrap_or_else(|e| {
+            warn!("Failed to read audit setting: {}", e);
+            None
+        }),
+        None => None,
+    };
+
+    /// Persist events to both sled store and WAL, then announce each one.
+    /// WAL and audit log appends and notifications are best-effort —
+    /// failures are logged but don't fail the operation.
+    /// Notifications are dropped rather than delaying the response if the
+    /// channel is full.
     fn persist_events(
         store: &LockedStore,
         wal: Option<&WalManager>,
+        audit: Option<&AuditLog>,
         actor_id: &ActorId,
         events: &[Event],
+        notify_tx: &mpsc::Sender<Notification>,
     ) -> Result<(), DaemonError> {
         for event in events {
             store.insert_event(event)?;
@@ -339,11 +728,148 @@ fn execute_command_inner(
             }
         }
 
+        if let Some(log) = audit {
+            if let Err(e) = log.record(events) {
+                warn!("Failed to write audit log: {}", e);
+            }
+        }
+
+        for event in events {
+            let issue_id = id_to_hex(&event.issue_id);
+            let event_id = id_to_hex(&event.event_id);
+            debug!(
+                issue_id = %issue_id,
+                event_id = %event_id,
+                kind = event.kind.kind_name(),
+                "Applied event"
+            );
+            // The events are already durable, so a failed lookup only costs
+            // the subscriber list of this notification
+            let subscribers = match store.get_issue(&event.issue_id) {
+                Ok(issue) => issue
+                    .map(|p| p.subscribers.into_iter().collect())
+                    .unwrap_or_default(),
+                Err(e) => {
+                    warn!("Failed to load subscribers for {}: {}", issue_id, e);
+                    Vec::new()
+                }
+            };
+            let notification =
+                Notification::event_applied(issue_id, event_id, event.ts_unix_ms, subscribers);
+            if notify_tx.try_send(notification).is_err() {
+                debug!("Notification channel full or closed, dropping EventApplied");
+            }
+        }
+
         Ok(())
     }
 
+    /// Record a single event on an existing issue
+    fn record_event(
+        store: &LockedStore,
+        wal: Option<&WalManager>,
+        audit: Option<&AuditLog>,
+        actor_id: ActorId,
+        issue_id: &str,
+        kind: EventKind,
+        notify_tx: &mpsc::Sender<Notification>,
+    ) -> Result<Option<serde_json::Value>, DaemonError> {
+        let id = store.resolve_issue_id(issue_id)?;
+        if !store.issue_exists(&id)? {
+            return Err(DaemonError::Core(GriteError::NotFound(format!(
+                "Issue {} not found",
+                issue_id
+            ))));
+        }
+
+        let ts = current_time_ms();
+        let event_id = compute_event_id(&id, &actor_id, ts, None, &kind);
+        let event = Event::new(event_id, id, actor_id, ts, None, kind);
+        persist_events(
+            store,
+            wal,
+            audit,
+            &actor_id,
+            std::slice::from_ref(&event),
+            notify_tx,
+        )?;
+
+        let json = serde_json::json!({
+            "issue_id": id_to_hex(&id),
+            "event_id": id_to_hex(&event_id),
+        });
+        Ok(Some(json))
+    }
+
+    /// Record every event kind on every issue as one batch
+    ///
+    /// All IDs are resolved first, so an unknown ID fails the batch before
+    /// anything is written.
+    fn apply_to_issues(
+        store: &LockedStore,
+        wal: Option<&WalManager>,
+        audit: Option<&AuditLog>,
+        actor_id: ActorId,
+        issue_ids: &[String],
+        kinds: Vec<EventKind>,
+        notify_tx: &mpsc::Sender<Notification>,
+    ) -> Result<Option<serde_json::Value>, DaemonError> {
+        let ids = store.resolve_existing_issues(issue_ids)?;
+
+        let ts = current_time_ms();
+        let mut events = Vec::with_capacity(ids.len() * kinds.len());
+        for id in &ids {
+            for kind in &kinds {
+                let event_id = compute_event_id(id, &actor_id, ts, None, kind);
+                events.push(Event::new(event_id, *id, actor_id, ts, None, kind.clone()));
+            }
+        }
+        persist_events(store, wal, audit, &actor_id, &events, notify_tx)?;
+
+        let event_ids: Vec<String> = events.iter().map(|e| id_to_hex(&e.event_id)).collect();
+        let json = match issue_ids {
+            [issue_id] => serde_json::json!({
+                "issue_id": issue_id,
+                "event_ids": event_ids,
+            }),
+            _ => serde_json::json!({
+                "issue_ids": ids.iter().map(id_to_hex).collect::<Vec<_>>(),
+                "event_ids": event_ids,
+            }),
+        };
+        Ok(Some(serde_json::to_value(&json)?))
+    }
+
     match command {
-        IpcCommand::IssueList { state, label } => {
+        IpcCommand::IssueList {
+            state,
+            label,
+            assignee,
+            unassigned,
+            field,
+            milestone,
+            severity,
+            include_deleted,
+            sort,
+        } => {
+            let field = field.as_deref().map(IssueFilter::parse_field).transpose()?;
+            let sort = IssueSort::from_str(sort).ok_or_else(|| {
+                DaemonError::Core(GriteError::InvalidArgs(format!(
+                    "Unknown issue sort: {}",
+                    sort
+                )))
+            })?;
+            let severity = severity
+                .as_deref()
+                .map(|s| {
+                    Severity::from_str(s).ok_or_else(|| {
+                        DaemonError::Core(GriteError::InvalidArgs(format!(
+                            "Invalid severity: {}",
+                            s
+                        )))
+                    })
+                })
+                .transpose()?;
             let filter = IssueFilter {
                 state: state.as_ref().map(|s| match s.as_str() {
                     "open" => IssueState::Open,
@@ -351,8 +877,18 @@ fn execute_command_inner(
                     _ => IssueState::Open,
                 }),
                 label: label.clone(),
+                assignee: assignee.clone(),
+                unassigned: *unassigned,
+                field,
+                milestone: milestone.clone(),
+                severity,
+                include_deleted: *include_deleted,
             };
-            let issues = store.list_issues(&filter)?;
+            let issues = store.list_issues_sorted(&filter, sort)?;
+            let names: HashMap<String, String> = list_actors(git_dir)?
+                .into_iter()
+                .filter_map(|a| Some((a.actor_id, a.label?)))
+                .collect();
             let summaries: Vec<serde_json::Value> = issues
                 .iter()
                 .map(|s| {
@@ -363,12 +899,18 @@ fn execute_command_inner(
                         "labels": s.labels,
                         "assignees": s.assignees,
                         "created_ts": s.created_ts,
+                        "created_by": s.created_by.map(|actor| id_to_hex(&actor)),
+                        "created_by_name": s
+                            .created_by
+                            .and_then(|actor| names.get(&id_to_hex(&actor))),
                         "updated_ts": s.updated_ts,
                         "comment_count": s.comment_count,
+                        "blocks_count": s.blocks_count,
+                        "deleted": s.deleted,
                     })
                 })
                 .collect();
-            let json = serde_json::to_string(&serde_json::json!({ "issues": summaries }))?;
+            let json = serde_json::json!({ "issues": summaries });
             Ok(Some(json))
         }
 
@@ -383,7 +925,39 @@ fn execute_command_inner(
                 )))
             })?;
 
-            let json = serde_json::to_string(&projection_to_json(&p))?;
+            let mut json_val = projection_to_json(&p);
+            json_val["dependencies"] =
+                dependency_refs_to_json(&store.get_dependency_refs(&id, false)?);
+            json_val["dependents"] =
+                dependency_refs_to_json(&store.get_dependency_refs(&id, true)?);
+            json_val["digest"] = serde_json::json!(id_to_hex(&store.issue_digest(&id)?));
+            let json = serde_json::to_value(&json_val)?;
+            Ok(Some(json))
+        }
+
+        IpcCommand::IssueEvents {
+            issue_id,
+            offset,
+            limit,
+        } => {
+            let id = store
+                .resolve_issue_id(issue_id)
+                .map_err(DaemonError::Core)?;
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
+                    "Issue {} not found",
+                    issue_id
+                ))));
+            }
+
+            let (events, total) =
+                store.get_issue_events_page(&id, *offset as usize, *limit as usize)?;
+            let events: Vec<EventJson> = events.iter().map(EventJson::from).collect();
+            let json = serde_json::json!({
+                "events": events,
+                "offset": offset,
+                "total": total,
+            });
             Ok(Some(json))
         }
 
@@ -392,12 +966,15 @@ fn execute_command_inner(
             body,
             labels,
         } => {
+            let labels = load_repo_config(git_dir)?
+                .unwrap_or_default()
+                .issue_labels(labels)?;
             let issue_id = generate_issue_id();
             let ts = current_time_ms();
             let kind = EventKind::IssueCreated {
                 title: title.clone(),
                 body: body.clone(),
-                labels: labels.clone(),
+                labels,
             };
             let event_id = compute_event_id(&issue_id, &actor_id_bytes, ts, None, &kind);
             let event = Event::new(event_id, issue_id, actor_id_bytes, ts, None, kind);
@@ -405,8 +982,10 @@ fn execute_command_inner(
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
             let projection = IssueProjection::from_event(&event)?;
@@ -414,7 +993,7 @@ fn execute_command_inner(
             json_val["event_id"] = serde_json::Value::String(id_to_hex(&event_id));
             json_val["action"] =
                 serde_json::Value::String(libgrite_ipc::issue_action::CREATED.to_string());
-            let json = serde_json::to_string(&json_val)?;
+            let json = serde_json::to_value(&json_val)?;
             Ok(Some(json))
         }
 
@@ -432,12 +1011,12 @@ fn execute_command_inner(
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
+                ))));
+            }
 
             let ts = current_time_ms();
             let kind = EventKind::IssueUpdated {
@@ -450,44 +1029,60 @@ fn execute_command_inner(
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
                 "event_id": id_to_hex(&event_id),
-            }))?;
+            });
             Ok(Some(json))
         }
 
-        IpcCommand::IssueComment { issue_id, body } => {
+        IpcCommand::IssueComment {
+            issue_id,
+            body,
+            reply_to,
+        } => {
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
+            let issue = store.get_issue(&id)?.ok_or_else(|| {
                 DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
                 )))
             })?;
+            let parent_event = reply_to
+                .as_deref()
+                .map(|prefix| issue.resolve_comment(prefix))
+                .transpose()
+                .map_err(DaemonError::Core)?;
 
             let ts = current_time_ms();
-            let kind = EventKind::CommentAdded { body: body.clone() };
+            let kind = EventKind::CommentAdded {
+                body: body.clone(),
+                parent_event,
+            };
             let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
             let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
 
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
                 "event_id": id_to_hex(&event_id),
-            }))?;
+            });
             Ok(Some(json))
         }
 
@@ -495,12 +1090,12 @@ fn execute_command_inner(
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
+                ))));
+            }
 
             let ts = current_time_ms();
             let kind = EventKind::StateChanged {
@@ -512,16 +1107,18 @@ fn execute_command_inner(
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
                 "event_id": id_to_hex(&event_id),
                 "state": "closed",
                 "action": libgrite_ipc::issue_action::CLOSED,
-            }))?;
+            });
             Ok(Some(json))
         }
 
@@ -529,12 +1126,12 @@ fn execute_command_inner(
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
+                ))));
+            }
 
             let ts = current_time_ms();
             let kind = EventKind::StateChanged {
@@ -546,148 +1143,372 @@ fn execute_command_inner(
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
                 "event_id": id_to_hex(&event_id),
                 "state": "open",
                 "action": libgrite_ipc::issue_action::REOPENED,
-            }))?;
+            });
             Ok(Some(json))
         }
 
         IpcCommand::IssueLabel {
-            issue_id,
+            issue_ids,
+            add,
+            remove,
+        } => {
+            if !add.is_empty() {
+                let config = load_repo_config(git_dir)?.unwrap_or_default();
+                for id in store.resolve_existing_issues(issue_ids)? {
+                    if let Some(proj) = store.get_issue(&id)? {
+                        config.check_label_limit(&proj, add, remove)?;
+                    }
+                }
+            }
+            let kinds = add
+                .iter()
+                .map(|label| EventKind::LabelAdded {
+                    label: label.clone(),
+                })
+                .chain(remove.iter().map(|label| EventKind::LabelRemoved {
+                    label: label.clone(),
+                }))
+                .collect();
+            apply_to_issues(
+                store,
+                wal.as_ref(),
+                audit.as_ref(),
+                actor_id_bytes,
+                issue_ids,
+                kinds,
+                notify_tx,
+            )
+        }
+
+        IpcCommand::IssueAssign {
+            issue_ids,
             add,
             remove,
+        } => {
+            let kinds = add
+                .iter()
+                .map(|user| EventKind::AssigneeAdded { user: user.clone() })
+                .chain(
+                    remove
+                        .iter()
+                        .map(|user| EventKind::AssigneeRemoved { user: user.clone() }),
+                )
+                .collect();
+            apply_to_issues(
+                store,
+                wal.as_ref(),
+                audit.as_ref(),
+                actor_id_bytes,
+                issue_ids,
+                kinds,
+                notify_tx,
+            )
+        }
+
+        IpcCommand::IssueLink {
+            issue_id,
+            url,
+            note,
         } => {
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
-
-            let mut event_ids = Vec::new();
-            let mut events = Vec::new();
-            let ts = current_time_ms();
-
-            for label in add {
-                let kind = EventKind::LabelAdded {
-                    label: label.clone(),
-                };
-                let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
-                let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
-                event_ids.push(id_to_hex(&event_id));
-                events.push(event);
+                ))));
             }
 
-            for label in remove {
-                let kind = EventKind::LabelRemoved {
-                    label: label.clone(),
-                };
-                let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
-                let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
-                event_ids.push(id_to_hex(&event_id));
-                events.push(event);
-            }
+            let ts = current_time_ms();
+            let kind = EventKind::LinkAdded {
+                url: url.clone(),
+                note: note.clone(),
+            };
+            let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
+            let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
 
-            persist_events(store, wal.as_ref(), &actor_id_bytes, &events)?;
+            persist_events(
+                store,
+                wal.as_ref(),
+                audit.as_ref(),
+                &actor_id_bytes,
+                std::slice::from_ref(&event),
+                notify_tx,
+            )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
-                "event_ids": event_ids,
-            }))?;
+                "event_id": id_to_hex(&event_id),
+            });
             Ok(Some(json))
         }
 
-        IpcCommand::IssueAssign {
+        IpcCommand::IssueLinkIssue {
             issue_id,
-            add,
+            target_id,
+            relation,
             remove,
         } => {
+            use libgrite_core::types::issue::IssueLink;
+
+            let relation = IssueLink::normalize_relation(relation).ok_or_else(|| {
+                DaemonError::Core(GriteError::InvalidArgs(format!(
+                    "Invalid relation: '{}'",
+                    relation
+                )))
+            })?;
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            let target = store
+                .resolve_issue_id(target_id)
+                .map_err(DaemonError::Core)?;
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
+                ))));
+            }
+            if !store.issue_exists(&target)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
+                    "Target {} not found",
+                    target_id
+                ))));
+            }
+            if id == target {
+                return Err(DaemonError::Core(GriteError::InvalidArgs(
+                    "Cannot link an issue to itself".to_string(),
+                )));
+            }
 
-            let mut event_ids = Vec::new();
-            let mut events = Vec::new();
             let ts = current_time_ms();
+            let kind = if *remove {
+                EventKind::IssueUnlinked {
+                    target,
+                    relation: relation.clone(),
+                }
+            } else {
+                EventKind::IssueLinked {
+                    target,
+                    relation: relation.clone(),
+                }
+            };
+            let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
+            let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
+            persist_events(
+                store,
+                wal.as_ref(),
+                audit.as_ref(),
+                &actor_id_bytes,
+                std::slice::from_ref(&event),
+                notify_tx,
+            )?;
 
-            for user in add {
-                let kind = EventKind::AssigneeAdded { user: user.clone() };
-                let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
-                let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
-                event_ids.push(id_to_hex(&event_id));
-                events.push(event);
-            }
+            let json = serde_json::json!({
+                "issue_id": issue_id,
+                "event_id": id_to_hex(&event_id),
+                "target": target_id,
+                "relation": relation,
+                "action": if *remove { "removed" } else { "added" },
+            });
+            Ok(Some(json))
+        }
 
-            for user in remove {
-                let kind = EventKind::AssigneeRemoved { user: user.clone() };
-                let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
-                let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
-                event_ids.push(id_to_hex(&event_id));
-                events.push(event);
+        IpcCommand::IssueDup {
+            issue_id,
+            target_id,
+        } => {
+            let id = store
+                .resolve_issue_id(issue_id)
+                .map_err(DaemonError::Core)?;
+            let target = store
+                .resolve_issue_id(target_id)
+                .map_err(DaemonError::Core)?;
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
+                    "Issue {} not found",
+                    issue_id
+                ))));
+            }
+            if !store.issue_exists(&target)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
+                    "Target {} not found",
+                    target_id
+                ))));
+            }
+            if id == target {
+                return Err(DaemonError::Core(GriteError::InvalidArgs(
+                    "Cannot mark an issue a duplicate of itself".to_string(),
+                )));
             }
 
-            persist_events(store, wal.as_ref(), &actor_id_bytes, &events)?;
+            let ts = current_time_ms();
+            let events: Vec<Event> = [
+                EventKind::IssueLinked {
+                    target,
+                    relation: "duplicate-of".to_string(),
+                },
+                EventKind::StateChanged {
+                    state: IssueState::Closed,
+                },
+            ]
+            .into_iter()
+            .map(|kind| {
+                let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
+                Event::new(event_id, id, actor_id_bytes, ts, None, kind)
+            })
+            .collect();
+            persist_events(
+                store,
+                wal.as_ref(),
+                audit.as_ref(),
+                &actor_id_bytes,
+                &events,
+                notify_tx,
+            )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
-                "event_ids": event_ids,
-            }))?;
+                "target": target_id,
+                "event_ids": events.iter().map(|e| id_to_hex(&e.event_id)).collect::<Vec<_>>(),
+                "state": "closed",
+                "action": libgrite_ipc::issue_action::CLOSED,
+            });
             Ok(Some(json))
         }
 
-        IpcCommand::IssueLink {
+        IpcCommand::IssueSubscribe {
             issue_id,
-            url,
-            note,
+            user,
+            remove,
         } => {
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
+                ))));
+            }
 
+            let user = user.clone().unwrap_or_else(|| id_to_hex(&actor_id_bytes));
             let ts = current_time_ms();
-            let kind = EventKind::LinkAdded {
-                url: url.clone(),
-                note: note.clone(),
+            let kind = if *remove {
+                EventKind::Unsubscribed { user }
+            } else {
+                EventKind::Subscribed { user }
             };
             let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
             let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
-
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
                 "event_id": id_to_hex(&event_id),
-            }))?;
+            });
             Ok(Some(json))
         }
 
+        IpcCommand::IssueEstimate { issue_id, minutes } => record_event(
+            store,
+            wal.as_ref(),
+            audit.as_ref(),
+            actor_id_bytes,
+            issue_id,
+            EventKind::TimeEstimated { minutes: *minutes },
+            notify_tx,
+        ),
+
+        IpcCommand::IssueLogTime {
+            issue_id,
+            minutes,
+            note,
+        } => record_event(
+            store,
+            wal.as_ref(),
+            audit.as_ref(),
+            actor_id_bytes,
+            issue_id,
+            EventKind::TimeLogged {
+                minutes: *minutes,
+                note: note.clone(),
+            },
+            notify_tx,
+        ),
+
+        IpcCommand::IssueMilestone {
+            issue_id,
+            milestone,
+        } => record_event(
+            store,
+            wal.as_ref(),
+            audit.as_ref(),
+            actor_id_bytes,
+            issue_id,
+            EventKind::MilestoneSet {
+                milestone: milestone.clone(),
+            },
+            notify_tx,
+        ),
+
+        IpcCommand::IssueSeverity { issue_id, severity } => {
+            let severity = Severity::from_str(severity).ok_or_else(|| {
+                DaemonError::Core(GriteError::InvalidArgs(format!(
+                    "Invalid severity: {}",
+                    severity
+                )))
+            })?;
+            record_event(
+                store,
+                wal.as_ref(),
+                audit.as_ref(),
+                actor_id_bytes,
+                issue_id,
+                EventKind::SeveritySet { severity },
+                notify_tx,
+            )
+        }
+
+        IpcCommand::IssueDelete { issue_id } => record_event(
+            store,
+            wal.as_ref(),
+            audit.as_ref(),
+            actor_id_bytes,
+            issue_id,
+            EventKind::IssueDeleted {},
+            notify_tx,
+        ),
+
+        IpcCommand::IssueUndelete { issue_id } => record_event(
+            store,
+            wal.as_ref(),
+            audit.as_ref(),
+            actor_id_bytes,
+            issue_id,
+            EventKind::IssueUndeleted {},
+            notify_tx,
+        ),
+
         IpcCommand::IssueAttach {
             issue_id,
             file_path,
@@ -695,12 +1516,12 @@ fn execute_command_inner(
             let id = store
                 .resolve_issue_id(issue_id)
                 .map_err(DaemonError::Core)?;
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
+                ))));
+            }
 
             let parts: Vec<&str> = file_path.splitn(3, ':').collect();
             if parts.len() != 3 {
@@ -722,50 +1543,182 @@ fn execute_command_inner(
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
+                &actor_id_bytes,
+                std::slice::from_ref(&event),
+                notify_tx,
+            )?;
+
+            let json = serde_json::json!({
+                "issue_id": issue_id,
+                "event_id": id_to_hex(&event_id),
+            });
+            Ok(Some(json))
+        }
+
+        IpcCommand::IssueField {
+            issue_id,
+            key,
+            value,
+        } => {
+            let id = store
+                .resolve_issue_id(issue_id)
+                .map_err(DaemonError::Core)?;
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
+                    "Issue {} not found",
+                    issue_id
+                ))));
+            }
+
+            let ts = current_time_ms();
+            let kind = EventKind::FieldSet {
+                key: key.clone(),
+                value: value.clone(),
+            };
+            let event_id = compute_event_id(&id, &actor_id_bytes, ts, None, &kind);
+            let event = Event::new(event_id, id, actor_id_bytes, ts, None, kind);
+
+            persist_events(
+                store,
+                wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
                 "event_id": id_to_hex(&event_id),
-            }))?;
+            });
             Ok(Some(json))
         }
 
         IpcCommand::DbStats => {
             let stats = store.stats(sled_path)?;
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "path": stats.path,
                 "size_bytes": stats.size_bytes,
                 "event_count": stats.event_count,
                 "issue_count": stats.issue_count,
                 "last_rebuild_ts": stats.last_rebuild_ts,
-            }))?;
+                "context_file_count": stats.context_file_count,
+                "context_symbol_count": stats.context_symbol_count,
+                "last_context_update_ts": stats.last_context_update_ts,
+            });
             Ok(Some(json))
         }
 
-        IpcCommand::Rebuild => {
-            let stats = store.rebuild()?;
-            let json = serde_json::to_string(&serde_json::json!({
+        IpcCommand::Rebuild { skip_errors } => {
+            let stats = if *skip_errors {
+                store.rebuild_skipping_errors()?
+            } else {
+                store.rebuild()?
+            };
+            let skipped: Vec<_> = stats
+                .skipped
+                .iter()
+                .map(|(event_id, error)| {
+                    serde_json::json!({
+                        "event_id": libgrite_core::types::ids::id_to_hex(event_id),
+                        "error": error,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
                 "event_count": stats.event_count,
                 "issue_count": stats.issue_count,
-            }))?;
+                "skipped": skipped,
+            });
+            Ok(Some(json))
+        }
+
+        IpcCommand::RebuildPlan => {
+            let plan = store.rebuild_plan()?;
+            let json = serde_json::json!({
+                "dry_run": true,
+                "event_count": plan.event_count,
+                "issue_count": plan.issue_count,
+                "kinds": plan.kinds,
+            });
             Ok(Some(json))
         }
 
-        IpcCommand::Export { format, since } => {
+        IpcCommand::Export {
+            format,
+            since,
+            order,
+            issue,
+            kinds,
+            count_only,
+            include_attachments,
+            anonymize,
+            out,
+        } => {
+            let order = ExportOrder::from_str(order).ok_or_else(|| {
+                DaemonError::Core(GriteError::InvalidArgs(format!(
+                    "Unknown export order: {}",
+                    order
+                )))
+            })?;
             let since_opt = since
                 .as_ref()
                 .and_then(|s| s.parse::<u64>().ok())
                 .map(ExportSince::Timestamp);
+            let issue = issue
+                .as_deref()
+                .map(|id| store.resolve_issue_id(id))
+                .transpose()?;
+            let kinds = kinds.as_deref().map(parse_kinds).transpose()?;
+            let options = ExportOptions {
+                order,
+                issue,
+                kinds,
+                include_attachments: *include_attachments,
+                anonymize: *anonymize,
+            };
+
+            if *count_only {
+                let counts = export_counts(store, since_opt, &options)?;
+                return Ok(Some(serde_json::to_value(counts)?));
+            }
+
+            if let Some(out) = out {
+                let markdown = match format.as_str() {
+                    "json" => false,
+                    "md" | "markdown" => true,
+                    _ => {
+                        return Err(DaemonError::Core(GriteError::InvalidArgs(format!(
+                            "Unknown format: {}",
+                            format
+                        ))))
+                    }
+                };
+                // Markdown exports report the number of issues written
+                let event_count = write_streamed(Path::new(out), |w| {
+                    if markdown {
+                        export_markdown_to(store, since_opt, &options, w)
+                    } else {
+                        export_json_to(store, since_opt, &options, w)
+                    }
+                })?;
+                return Ok(Some(serde_json::json!({
+                    "format": if markdown { "md" } else { "json" },
+                    "output_path": out,
+                    "wal_head": null,
+                    "event_count": event_count,
+                })));
+            }
 
             let output = match format.as_str() {
                 "json" => {
-                    let export = export_json(store, since_opt)?;
-                    serde_json::to_string(&export)?
+                    let export = export_json(store, since_opt, &options)?;
+                    serde_json::to_value(&export)?
+                }
+                "md" | "markdown" => {
+                    serde_json::Value::String(export_markdown(store, since_opt, &options)?)
                 }
-                "md" | "markdown" => export_markdown(store, since_opt)?,
                 _ => {
                     return Err(DaemonError::Core(GriteError::InvalidArgs(format!(
                         "Unknown format: {}",
@@ -798,18 +1751,18 @@ fn execute_command_inner(
                 )))
             })?;
 
-            store.get_issue(&id)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Issue {} not found",
                     issue_id
-                )))
-            })?;
-            store.get_issue(&target)?.ok_or_else(|| {
-                DaemonError::Core(GriteError::NotFound(format!(
+                ))));
+            }
+            if !store.issue_exists(&target)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
                     "Target {} not found",
                     target_id
-                )))
-            })?;
+                ))));
+            }
 
             if store.would_create_cycle(&id, &target, &dep)? {
                 return Err(DaemonError::Core(GriteError::InvalidArgs(format!(
@@ -828,17 +1781,19 @@ fn execute_command_inner(
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "event_id": id_to_hex(&event_id),
                 "issue_id": issue_id,
                 "target": target_id,
                 "dep_type": dep_type,
                 "action": "added",
-            }))?;
+            });
             Ok(Some(json))
         }
 
@@ -874,17 +1829,19 @@ fn execute_command_inner(
             persist_events(
                 store,
                 wal.as_ref(),
+                audit.as_ref(),
                 &actor_id_bytes,
                 std::slice::from_ref(&event),
+                notify_tx,
             )?;
 
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "event_id": id_to_hex(&event_id),
                 "issue_id": issue_id,
                 "target": target_id,
                 "dep_type": dep_type,
                 "action": "removed",
-            }))?;
+            });
             Ok(Some(json))
         }
 
@@ -914,11 +1871,11 @@ fn execute_command_inner(
                     }))
                 })
                 .collect::<Result<Vec<_>, DaemonError>>()?;
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issue_id": issue_id,
                 "direction": if *reverse { "dependents" } else { "dependencies" },
                 "deps": dep_list,
-            }))?;
+            });
             Ok(Some(json))
         }
 
@@ -932,6 +1889,7 @@ fn execute_command_inner(
                     _ => IssueState::Open,
                 }),
                 label: label.clone(),
+                ..Default::default()
             };
             let sorted = store.topological_order(&filter)?;
             let issues: Vec<serde_json::Value> = sorted
@@ -945,10 +1903,59 @@ fn execute_command_inner(
                     })
                 })
                 .collect();
-            let json = serde_json::to_string(&serde_json::json!({
+            let json = serde_json::json!({
                 "issues": issues,
                 "order": "topological",
-            }))?;
+            });
+            Ok(Some(json))
+        }
+
+        IpcCommand::IssueWhyBlocked { issue_id } => {
+            use libgrite_core::types::ids::id_to_hex;
+
+            let id = store
+                .resolve_issue_id(issue_id)
+                .map_err(DaemonError::Core)?;
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
+                    "Issue {} not found",
+                    issue_id
+                ))));
+            }
+            let paths = store
+                .blocking_chain(&id)?
+                .iter()
+                .map(|path| {
+                    path.iter()
+                        .map(|blocker| {
+                            Ok(serde_json::json!({
+                                "issue_id": id_to_hex(blocker),
+                                "title": store.get_issue(blocker)?.map(|p| p.title),
+                            }))
+                        })
+                        .collect::<Result<Vec<_>, DaemonError>>()
+                })
+                .collect::<Result<Vec<_>, DaemonError>>()?;
+            let json = serde_json::json!({
+                "issue_id": id_to_hex(&id),
+                "ready": paths.is_empty(),
+                "paths": paths,
+            });
+            Ok(Some(json))
+        }
+
+        IpcCommand::IssueTree { issue_id, depth } => {
+            let id = store
+                .resolve_issue_id(issue_id)
+                .map_err(DaemonError::Core)?;
+            if !store.issue_exists(&id)? {
+                return Err(DaemonError::Core(GriteError::NotFound(format!(
+                    "Issue {} not found",
+                    issue_id
+                ))));
+            }
+            let tree = store.dependency_tree(&id, *depth as usize)?;
+            let json = serde_json::to_value(tree.to_json())?;
             Ok(Some(json))
         }
 
@@ -966,6 +1973,7 @@ fn execute_command_inner(
             let do_push = *push || !*pull;
 
             // Auto-backfill WAL from sled if WAL is empty
+            let mut backfilled = 0;
             if do_push {
                 if let Some(w) = wal.as_ref() {
                     if w.head().unwrap_or(None).is_none() {
@@ -974,7 +1982,10 @@ fn execute_command_inner(
                             let mut sorted = events;
                             sorted.sort_by_key(|e| e.ts_unix_ms);
                             match w.append(&actor_id_bytes, &sorted) {
-                                Ok(_) => info!("Auto-backfilled WAL with {} events", sorted.len()),
+                                Ok(_) => {
+                                    info!("Auto-backfilled WAL with {} events", sorted.len());
+                                    backfilled = sorted.len();
+                                }
                                 Err(e) => warn!("WAL backfill failed: {}", e),
                             }
                         }
@@ -982,46 +1993,22 @@ fn execute_command_inner(
                 }
             }
 
-            let result = if do_pull && !do_push {
+            let report = if do_pull && !do_push {
                 // Pull only
                 let pull_result = sync_mgr.pull(remote)?;
-                let wal_head: Option<String> = pull_result.new_wal_head.map(|oid| oid.to_string());
-                serde_json::json!({
-                    "pulled": true,
-                    "pushed": false,
-                    "pull_events": pull_result.events_pulled,
-                    "pull_wal_head": wal_head,
-                    "message": pull_result.message,
-                })
+                SyncReport::new(remote, backfilled, Some(&pull_result), None)
             } else if do_push && !do_pull {
                 // Push only with auto-rebase
                 let push_result = sync_mgr.push_with_rebase(remote, &actor_id_bytes)?;
-                serde_json::json!({
-                    "pulled": false,
-                    "pushed": true,
-                    "push_success": push_result.success,
-                    "push_rebased": push_result.rebased,
-                    "push_events_rebased": push_result.events_rebased,
-                    "message": push_result.message,
-                })
+                SyncReport::new(remote, backfilled, None, Some(&push_result))
             } else {
                 // Full sync: pull then push with auto-rebase
                 let (pull_result, push_result) =
                     sync_mgr.sync_with_rebase(remote, &actor_id_bytes)?;
-                let wal_head: Option<String> = pull_result.new_wal_head.map(|oid| oid.to_string());
-                serde_json::json!({
-                    "pulled": true,
-                    "pushed": true,
-                    "pull_events": pull_result.events_pulled,
-                    "pull_wal_head": wal_head,
-                    "push_success": push_result.success,
-                    "push_rebased": push_result.rebased,
-                    "push_events_rebased": push_result.events_rebased,
-                    "message": format!("{} / {}", pull_result.message, push_result.message),
-                })
+                SyncReport::new(remote, backfilled, Some(&pull_result), Some(&push_result))
             };
 
-            Ok(Some(result.to_string()))
+            Ok(Some(serde_json::to_value(&report)?))
         }
 
         IpcCommand::SnapshotCreate | IpcCommand::SnapshotList | IpcCommand::SnapshotGc { .. } => {
@@ -1045,6 +2032,7 @@ fn projection_to_json(p: &libgrite_core::types::issue::IssueProjection) -> serde
                 "actor": id_to_hex(&c.actor),
                 "ts_unix_ms": c.ts_unix_ms,
                 "body": c.body,
+                "parent_event": c.parent_event.as_ref().map(id_to_hex),
             })
         })
         .collect();
@@ -1071,6 +2059,16 @@ fn projection_to_json(p: &libgrite_core::types::issue::IssueProjection) -> serde
             })
         })
         .collect();
+    let issue_links: Vec<serde_json::Value> = p
+        .issue_links
+        .iter()
+        .map(|l| {
+            serde_json::json!({
+                "target": id_to_hex(&l.target),
+                "relation": l.relation,
+            })
+        })
+        .collect();
     let deps: Vec<serde_json::Value> = p
         .dependencies
         .iter()
@@ -1085,7 +2083,9 @@ fn projection_to_json(p: &libgrite_core::types::issue::IssueProjection) -> serde
     serde_json::json!({
         "issue_id": id_to_hex(&p.issue_id),
         "title": p.title,
+        "title_conflict": p.title_conflict,
         "body": p.body,
+        "body_revisions": p.body_revisions,
         "state": format!("{:?}", p.state).to_lowercase(),
         "labels": p.labels,
         "assignees": p.assignees,
@@ -1093,17 +2093,39 @@ fn projection_to_json(p: &libgrite_core::types::issue::IssueProjection) -> serde
         "links": links,
         "attachments": attachments,
         "dependencies": deps,
+        "issue_links": issue_links,
+        "subscribers": p.subscribers,
+        "estimate_minutes": p.estimate_minutes,
+        "logged_minutes": p.logged_minutes,
+        "milestone": p.milestone,
+        "severity": p.severity.map(|s| s.as_str()),
+        "deleted": p.deleted,
+        "fields": p.custom_fields,
         "created_ts": p.created_ts,
+        "created_by": p.created_by.map(|actor| id_to_hex(&actor)),
         "updated_ts": p.updated_ts,
+        "first_response_ms": p.first_response_ms,
     })
 }
 
+/// Convert dependency edges to JSON; `title` is null for missing issues
+fn dependency_refs_to_json(refs: &[libgrite_core::DependencyRef]) -> serde_json::Value {
+    use libgrite_core::types::ids::id_to_hex;
+
+    refs.iter()
+        .map(|r| {
+            serde_json::json!({
+                "target": id_to_hex(&r.issue_id),
+                "dep_type": r.dep_type.as_str(),
+                "title": r.title,
+            })
+        })
+        .collect()
+}
+
 /// Get current time in milliseconds since Unix epoch
 fn current_time_ms() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+    libgrite_core::clock::now_ms()
 }
 
 /// Convert error to (code, message) for IPC response
@@ -1115,7 +2137,13 @@ fn error_to_code_message(e: &DaemonError) -> (String, String) {
         DaemonError::Core(GriteError::InvalidArgs(_)) => {
             (codes::INVALID_INPUT.to_string(), e.to_string())
         }
+        DaemonError::Core(GriteError::Ambiguous(_)) => {
+            (codes::AMBIGUOUS.to_string(), e.to_string())
+        }
         DaemonError::Core(GriteError::Io(_)) => (codes::IO_ERROR.to_string(), e.to_string()),
+        DaemonError::Git(libgrite_git::GitError::PushRejected { .. }) => {
+            (codes::CONFLICT.to_string(), e.to_string())
+        }
         DaemonError::Git(_) => (codes::GIT_ERROR.to_string(), e.to_string()),
         DaemonError::Ipc(_) => (codes::IPC_ERROR.to_string(), e.to_string()),
         _ => (codes::INTERNAL.to_string(), e.to_string()),
@@ -0,0 +1,54 @@
+//! Integration test for the audit log
+//!
+//! Verifies that with `audit = true` in the repo config, a write command run
+//! by the worker appends a record to `.git/grite/audit.log`.
+
+mod common;
+
+use std::path::Path;
+use std::time::Duration;
+
+use libgrite_core::audit::read_audit_log;
+use libgrite_ipc::IpcCommand;
+use tempfile::tempdir;
+
+/// Create a git repo whose config enables the audit log
+fn setup_repo(dir: &Path) {
+    common::setup_repo(dir);
+    common::write_repo_config(dir, "audit = true\n");
+}
+
+#[tokio::test]
+async fn test_issue_create_is_audited() {
+    let temp = tempdir().unwrap();
+    setup_repo(temp.path());
+    let git_dir = temp.path().join(".git");
+
+    let (tx, mut notify_rx, handle) = common::spawn_worker(temp.path());
+    tokio::spawn(async move { while notify_rx.recv().await.is_some() {} });
+
+    assert!(read_audit_log(&git_dir).unwrap().is_empty());
+
+    let data = tokio::time::timeout(
+        Duration::from_secs(10),
+        common::send(
+            &tx,
+            IpcCommand::IssueCreate {
+                title: "Audited issue".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        ),
+    )
+    .await
+    .expect("command timed out");
+    let records = read_audit_log(&git_dir).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].command, "issue create");
+    assert_eq!(records[0].actor, common::ACTOR_ID);
+    assert_eq!(records[0].issue_id, data["issue_id"].as_str().unwrap());
+    assert_eq!(records[0].event_id, data["event_id"].as_str().unwrap());
+    assert!(records[0].ts > 0);
+
+    common::shutdown(tx, handle).await;
+}
@@ -0,0 +1,111 @@
+//! Integration test for the worker's automatic rebuild
+//!
+//! Verifies that with `auto_rebuild = true` in the repo config, a worker that
+//! sees the rebuild threshold crossed rebuilds the store once idle and emits
+//! a `Rebuilt` notification, turning commands away with `db_busy` meanwhile.
+
+mod common;
+
+use std::path::Path;
+use std::time::Duration;
+
+use libgrite_core::config::repo_sled_path;
+use libgrite_core::hash::compute_event_id;
+use libgrite_core::store::DEFAULT_REBUILD_EVENTS_THRESHOLD;
+use libgrite_core::types::event::{Event, EventKind};
+use libgrite_core::types::ids::generate_issue_id;
+use libgrite_core::GriteStore;
+use libgrite_ipc::error::codes;
+use libgrite_ipc::{IpcCommand, Notification};
+use tempfile::tempdir;
+
+/// Create a git repo with auto-rebuild enabled and a store past the threshold
+fn setup_repo(dir: &Path) {
+    common::setup_repo(dir);
+    common::write_repo_config(dir, "auto_rebuild = true\n");
+
+    let store = GriteStore::open(&repo_sled_path(&dir.join(".git"))).unwrap();
+    let actor = [1u8; 16];
+    for i in 0..=DEFAULT_REBUILD_EVENTS_THRESHOLD as u64 {
+        let issue_id = generate_issue_id();
+        let kind = EventKind::IssueCreated {
+            title: format!("Issue {}", i),
+            body: String::new(),
+            labels: vec![],
+        };
+        let event_id = compute_event_id(&issue_id, &actor, 1000 + i, None, &kind);
+        store
+            .insert_event(&Event::new(event_id, issue_id, actor, 1000 + i, None, kind))
+            .unwrap();
+    }
+    store.flush().unwrap();
+}
+
+fn list() -> IpcCommand {
+    IpcCommand::IssueList {
+        state: None,
+        label: None,
+        assignee: None,
+        unassigned: false,
+        field: None,
+        milestone: None,
+        severity: None,
+        include_deleted: false,
+        sort: "created".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_auto_rebuild_after_threshold() {
+    let temp = tempdir().unwrap();
+    setup_repo(temp.path());
+
+    let (tx, mut notify_rx, handle) = common::spawn_worker(temp.path());
+
+    // A write pushes the counter further past the threshold
+    common::send(
+        &tx,
+        IpcCommand::IssueCreate {
+            title: "Trigger".to_string(),
+            body: String::new(),
+            labels: vec![],
+        },
+    )
+    .await;
+
+    // The rebuild eventually runs and is announced; commands sent meanwhile
+    // are answered at once instead of queueing behind it
+    let mut saw_busy = false;
+    let rebuilt = tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            while let Ok(notification) = notify_rx.try_recv() {
+                if let Notification::Rebuilt { event_count, .. } = notification {
+                    return Some(event_count);
+                }
+            }
+            let response = common::request(&tx, list()).await;
+            if !response.ok {
+                assert_eq!(response.error.unwrap().code, codes::DB_BUSY);
+                saw_busy = true;
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for Rebuilt notification");
+    assert!(saw_busy, "no command arrived during the rebuild");
+    assert_eq!(
+        rebuilt,
+        Some(DEFAULT_REBUILD_EVENTS_THRESHOLD as u64 + 2),
+        "all events should be replayed"
+    );
+
+    common::shutdown(tx, handle).await;
+
+    // The rebuild reset the counter
+    let sled_path = repo_sled_path(&temp.path().join(".git"));
+    let store = GriteStore::open(&sled_path).unwrap();
+    let stats = store.stats(&sled_path).unwrap();
+    assert_eq!(stats.events_since_rebuild, 0);
+    assert!(!stats.rebuild_recommended);
+}
@@ -0,0 +1,77 @@
+//! Integration test for labeling several issues in one request
+//!
+//! Verifies that `IssueLabel` with several IDs labels every issue, and that
+//! an unknown ID fails the whole batch without labeling any of them.
+
+mod common;
+
+use grite_daemon::WorkerMessage;
+use libgrite_ipc::IpcCommand;
+use tempfile::tempdir;
+use tokio::sync::mpsc;
+
+async fn labels(tx: &mpsc::Sender<WorkerMessage>, issue_id: &str) -> Vec<String> {
+    let shown = common::send(
+        tx,
+        IpcCommand::IssueShow {
+            issue_id: issue_id.to_string(),
+        },
+    )
+    .await;
+    serde_json::from_value(shown["labels"].clone()).unwrap()
+}
+
+#[tokio::test]
+async fn test_label_applied_to_several_issues() {
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+
+    let (tx, _notify_rx, handle) = common::spawn_worker(temp.path());
+
+    let mut issue_ids = Vec::new();
+    for title in ["One", "Two", "Three"] {
+        let created = common::send(
+            &tx,
+            IpcCommand::IssueCreate {
+                title: title.to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        )
+        .await;
+        issue_ids.push(created["issue_id"].as_str().unwrap().to_string());
+    }
+
+    let labeled = common::send(
+        &tx,
+        IpcCommand::IssueLabel {
+            issue_ids: issue_ids.clone(),
+            add: vec!["triage".to_string()],
+            remove: vec![],
+        },
+    )
+    .await;
+    assert_eq!(labeled["event_ids"].as_array().unwrap().len(), 3);
+    for issue_id in &issue_ids {
+        assert_eq!(labels(&tx, issue_id).await, vec!["triage"]);
+    }
+
+    // One unknown ID fails the batch and names the offender
+    let missing = "ffffffffffffffffffffffffffffffff".to_string();
+    let response = common::request(
+        &tx,
+        IpcCommand::IssueLabel {
+            issue_ids: vec![issue_ids[0].clone(), missing.clone()],
+            add: vec!["urgent".to_string()],
+            remove: vec![],
+        },
+    )
+    .await;
+    assert!(!response.ok);
+    let error = response.error.unwrap();
+    assert_eq!(error.code, "not_found");
+    assert!(error.message.contains(&missing), "{}", error.message);
+    assert_eq!(labels(&tx, &issue_ids[0]).await, vec!["triage"]);
+
+    common::shutdown(tx, handle).await;
+}
@@ -0,0 +1,147 @@
+//! Fixtures shared by the daemon integration tests
+
+// Each test binary uses only some of these helpers
+#![allow(dead_code)]
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use grite_daemon::supervisor::Supervisor;
+use grite_daemon::{Worker, WorkerMessage};
+use libgrite_ipc::{DataEncoding, IpcCommand, IpcResponse, Notification};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+/// Actor the test repos register and the worker runs as
+pub const ACTOR_ID: &str = "00112233445566778899aabbccddeeff";
+
+/// Create a git repo at `dir` with `ACTOR_ID` registered
+pub fn setup_repo(dir: &Path) {
+    assert!(Command::new("git")
+        .args(["init"])
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status
+        .success());
+    register_actor(dir, ACTOR_ID);
+}
+
+/// Register `actor_id` in the git repo at `dir`
+///
+/// Writes are only accepted from registered actors.
+pub fn register_actor(dir: &Path, actor_id: &str) {
+    let actor_dir = dir.join(".git/grite/actors").join(actor_id);
+    std::fs::create_dir_all(&actor_dir).unwrap();
+    std::fs::write(
+        actor_dir.join("config.toml"),
+        format!("actor_id = \"{}\"\n", actor_id),
+    )
+    .unwrap();
+}
+
+/// Write `contents` as the repo config of the git repo at `dir`
+pub fn write_repo_config(dir: &Path, contents: &str) {
+    let grite_dir = dir.join(".git").join("grite");
+    std::fs::create_dir_all(&grite_dir).unwrap();
+    std::fs::write(grite_dir.join("config.toml"), contents).unwrap();
+}
+
+/// IPC endpoint the test workers for the repo at `dir` advertise
+pub fn ipc_endpoint(dir: &Path) -> String {
+    dir.join("grite-test.sock").display().to_string()
+}
+
+/// Create a worker for the repo at `dir`, running as `ACTOR_ID`
+///
+/// Returns the command sender and notification receiver along with the
+/// worker, which is not started.
+pub fn new_worker(
+    dir: &Path,
+) -> (
+    mpsc::Sender<WorkerMessage>,
+    mpsc::Receiver<Notification>,
+    Worker,
+) {
+    let (tx, rx) = mpsc::channel(16);
+    let (notify_tx, notify_rx) = mpsc::channel(64);
+    let worker = Worker::new(
+        dir.to_path_buf(),
+        ACTOR_ID.to_string(),
+        rx,
+        notify_tx,
+        "test-host".to_string(),
+        ipc_endpoint(dir),
+    )
+    .unwrap();
+    (tx, notify_rx, worker)
+}
+
+/// Spawn a worker for the repo at `dir`, running as `ACTOR_ID`
+///
+/// Returns the command sender, the notification receiver and the task handle.
+pub fn spawn_worker(
+    dir: &Path,
+) -> (
+    mpsc::Sender<WorkerMessage>,
+    mpsc::Receiver<Notification>,
+    JoinHandle<()>,
+) {
+    let (tx, notify_rx, worker) = new_worker(dir);
+    (tx, notify_rx, tokio::spawn(worker.run()))
+}
+
+/// Send `command` as `actor_id`, with `data_dir` as its data directory, and
+/// return the raw response
+pub async fn request_as(
+    tx: &mpsc::Sender<WorkerMessage>,
+    actor_id: &str,
+    data_dir: &str,
+    command: IpcCommand,
+) -> IpcResponse {
+    let (response_tx, response_rx) = oneshot::channel();
+    tx.send(WorkerMessage::Command {
+        request_id: "req".to_string(),
+        actor_id: actor_id.to_string(),
+        data_dir: data_dir.to_string(),
+        command,
+        encoding: DataEncoding::Json,
+        response_tx,
+    })
+    .await
+    .unwrap();
+    response_rx.await.unwrap()
+}
+
+/// Send `command` as `ACTOR_ID` and return the raw response
+pub async fn request(tx: &mpsc::Sender<WorkerMessage>, command: IpcCommand) -> IpcResponse {
+    request_as(tx, ACTOR_ID, "", command).await
+}
+
+/// Send `command`, assert it succeeded and return its JSON data
+pub async fn send(tx: &mpsc::Sender<WorkerMessage>, command: IpcCommand) -> serde_json::Value {
+    let response = request(tx, command).await;
+    assert!(response.ok, "{:?}", response.error);
+    serde_json::from_str(&response.data.unwrap()).unwrap()
+}
+
+/// Stop the worker and wait for it to exit
+pub async fn shutdown(tx: mpsc::Sender<WorkerMessage>, handle: JoinHandle<()>) {
+    tx.send(WorkerMessage::Shutdown).await.unwrap();
+    handle.await.unwrap();
+}
+
+/// Run `supervisor` in the background and wait for `socket_path` to appear
+pub async fn spawn_supervisor(supervisor: Supervisor, socket_path: &Path) -> JoinHandle<()> {
+    let handle = tokio::spawn(async move {
+        if let Err(e) = supervisor.run(std::future::pending::<()>()).await {
+            eprintln!("Supervisor error: {}", e);
+        }
+    });
+    let start = Instant::now();
+    while !socket_path.exists() && start.elapsed() < Duration::from_secs(5) {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    handle
+}
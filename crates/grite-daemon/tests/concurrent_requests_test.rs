@@ -146,6 +146,13 @@ async fn test_single_request_succeeds() {
         IpcCommand::IssueList {
             state: Some("open".to_string()),
             label: None,
+            assignee: None,
+            unassigned: false,
+            field: None,
+            milestone: None,
+            severity: None,
+            include_deleted: false,
+            sort: "created".to_string(),
         },
     );
 
@@ -199,6 +206,13 @@ async fn test_concurrent_requests_all_succeed() {
                 IpcCommand::IssueList {
                     state: Some("open".to_string()),
                     label: None,
+                    assignee: None,
+                    unassigned: false,
+                    field: None,
+                    milestone: None,
+                    severity: None,
+                    include_deleted: false,
+                    sort: "created".to_string(),
                 },
             );
             let elapsed = start.elapsed();
@@ -0,0 +1,111 @@
+//! Integration test for MessagePack response data
+//!
+//! Over the daemon socket, a request asking for MessagePack gets the same
+//! data as a JSON request, and plain-text data falls back to `data`.
+
+mod common;
+
+use std::time::Duration;
+
+use grite_daemon::supervisor::Supervisor;
+use libgrite_ipc::{DataEncoding, IpcClient, IpcCommand, IpcRequest, IpcResponse};
+
+fn send(
+    socket_path: &str,
+    repo_root: &str,
+    command: IpcCommand,
+    encoding: DataEncoding,
+) -> IpcResponse {
+    let mut client = IpcClient::connect_with_timeout(socket_path, 30_000).unwrap();
+    let request = IpcRequest::new(
+        uuid::Uuid::new_v4().to_string(),
+        repo_root.to_string(),
+        common::ACTOR_ID.to_string(),
+        String::new(),
+        command,
+    )
+    .with_encoding(encoding);
+    let response = client.send(&request).unwrap();
+    assert!(response.ok, "{:?}", response.error);
+    response
+}
+
+fn list() -> IpcCommand {
+    IpcCommand::IssueList {
+        state: None,
+        label: None,
+        assignee: None,
+        unassigned: false,
+        field: None,
+        milestone: None,
+        severity: None,
+        include_deleted: false,
+        sort: "created".to_string(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_msgpack_data_over_the_socket() {
+    let temp = tempfile::tempdir().unwrap();
+    common::setup_repo(temp.path());
+    let repo_root = temp.path().to_string_lossy().to_string();
+    let socket_path = temp.path().join("daemon.sock");
+    let socket_str = socket_path.to_string_lossy().to_string();
+
+    let supervisor =
+        common::spawn_supervisor(Supervisor::new(socket_str.clone(), None), &socket_path).await;
+
+    let s = socket_str.clone();
+    let r = repo_root.clone();
+    tokio::task::spawn_blocking(move || {
+        send(
+            &s,
+            &r,
+            IpcCommand::IssueCreate {
+                title: "Encoded".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+            DataEncoding::Json,
+        );
+
+        // Structured data comes back as MessagePack only
+        let json = send(&s, &r, list(), DataEncoding::Json);
+        let msgpack = send(&s, &r, list(), DataEncoding::MsgPack);
+        assert!(json.data_msgpack.is_none());
+        assert!(msgpack.data.is_none());
+        assert!(msgpack.data_msgpack.is_some());
+        assert_eq!(msgpack.decode_data().unwrap(), json.decode_data().unwrap());
+
+        // So do daemon-level responses
+        let status = send(&s, &r, IpcCommand::DaemonStatus, DataEncoding::MsgPack);
+        assert!(status.data_msgpack.is_some());
+        assert_eq!(status.decode_data().unwrap().unwrap()["running"], true);
+
+        // A markdown export is text, so it stays in `data`
+        let export = send(
+            &s,
+            &r,
+            IpcCommand::Export {
+                format: "md".to_string(),
+                since: None,
+                order: "by-issue".to_string(),
+                issue: None,
+                kinds: None,
+                count_only: false,
+                include_attachments: false,
+                anonymize: false,
+                out: None,
+            },
+            DataEncoding::MsgPack,
+        );
+        assert!(export.data_msgpack.is_none());
+        assert!(export.data.unwrap().contains("Encoded"));
+
+        send(&s, &r, IpcCommand::DaemonStop, DataEncoding::MsgPack);
+    })
+    .await
+    .unwrap();
+
+    let _ = tokio::time::timeout(Duration::from_secs(5), supervisor).await;
+}
@@ -0,0 +1,50 @@
+//! Integration test for repo-configured default labels
+//!
+//! Verifies that `default_labels` from the repo config are merged into the
+//! labels of a new issue, without repeating labels given explicitly.
+
+mod common;
+
+use std::path::Path;
+
+use libgrite_ipc::IpcCommand;
+use tempfile::tempdir;
+
+fn setup_repo(dir: &Path) {
+    common::setup_repo(dir);
+    common::write_repo_config(dir, "default_labels = [\"needs-triage\", \"bug\"]\n");
+}
+
+#[tokio::test]
+async fn test_default_labels_merged_into_new_issue() {
+    let temp = tempdir().unwrap();
+    setup_repo(temp.path());
+
+    let (tx, _notify_rx, handle) = common::spawn_worker(temp.path());
+
+    let created = common::send(
+        &tx,
+        IpcCommand::IssueCreate {
+            title: "Triage me".to_string(),
+            body: String::new(),
+            labels: vec!["bug".to_string()],
+        },
+    )
+    .await;
+    let issue_id = created["issue_id"].as_str().unwrap().to_string();
+
+    // The IssueCreated event itself carries each label once
+    let events = common::send(
+        &tx,
+        IpcCommand::IssueEvents {
+            issue_id,
+            offset: 0,
+            limit: 10,
+        },
+    )
+    .await;
+    let labels = &events["events"][0]["kind"]["IssueCreated"]["labels"];
+    assert_eq!(labels, &serde_json::json!(["bug", "needs-triage"]));
+
+    common::shutdown(tx, handle).await;
+}
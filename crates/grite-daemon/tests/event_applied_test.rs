@@ -0,0 +1,81 @@
+//! Integration test for `EventApplied` notifications
+//!
+//! Verifies that every event the worker persists is announced with its
+//! issue and the issue's current subscribers.
+
+mod common;
+
+use std::time::Duration;
+
+use libgrite_ipc::{IpcCommand, Notification};
+use tempfile::tempdir;
+use tokio::sync::mpsc;
+
+/// Wait for the next `EventApplied`, skipping other notifications
+async fn next_event_applied(rx: &mut mpsc::Receiver<Notification>) -> (String, Vec<String>) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(notification) = rx.recv().await {
+            if let Notification::EventApplied {
+                issue_id,
+                subscribers,
+                ..
+            } = notification
+            {
+                return (issue_id, subscribers);
+            }
+        }
+        panic!("notification channel closed");
+    })
+    .await
+    .expect("timed out waiting for EventApplied notification")
+}
+
+#[tokio::test]
+async fn test_event_applied_notifications() {
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+
+    let (tx, mut notify_rx, handle) = common::spawn_worker(temp.path());
+
+    let created = common::send(
+        &tx,
+        IpcCommand::IssueCreate {
+            title: "Watched issue".to_string(),
+            body: String::new(),
+            labels: vec![],
+        },
+    )
+    .await;
+    let issue_id = created["issue_id"].as_str().unwrap().to_string();
+
+    let (applied_issue, subscribers) = next_event_applied(&mut notify_rx).await;
+    assert_eq!(applied_issue, issue_id);
+    assert!(subscribers.is_empty());
+
+    common::send(
+        &tx,
+        IpcCommand::IssueSubscribe {
+            issue_id: issue_id.clone(),
+            user: Some("alice".to_string()),
+            remove: false,
+        },
+    )
+    .await;
+    let (_, subscribers) = next_event_applied(&mut notify_rx).await;
+    assert_eq!(subscribers, vec!["alice".to_string()]);
+
+    common::send(
+        &tx,
+        IpcCommand::IssueComment {
+            issue_id: issue_id.clone(),
+            body: "Ping".to_string(),
+            reply_to: None,
+        },
+    )
+    .await;
+    let (applied_issue, subscribers) = next_event_applied(&mut notify_rx).await;
+    assert_eq!(applied_issue, issue_id);
+    assert_eq!(subscribers, vec!["alice".to_string()]);
+
+    common::shutdown(tx, handle).await;
+}
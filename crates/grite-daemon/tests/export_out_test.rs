@@ -0,0 +1,54 @@
+//! Integration test for `export --out` through the daemon
+//!
+//! The worker streams the export into the requested file and replies with a
+//! summary instead of the export itself.
+
+mod common;
+
+use libgrite_ipc::IpcCommand;
+use tempfile::tempdir;
+
+#[tokio::test]
+async fn test_export_out_streams_to_file() {
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+
+    let (tx, _notify_rx, handle) = common::spawn_worker(temp.path());
+
+    common::send(
+        &tx,
+        IpcCommand::IssueCreate {
+            title: "Exported".to_string(),
+            body: String::new(),
+            labels: vec![],
+        },
+    )
+    .await;
+
+    let out = temp.path().join("export.json");
+    let summary = common::send(
+        &tx,
+        IpcCommand::Export {
+            format: "json".to_string(),
+            since: None,
+            order: "by-issue".to_string(),
+            issue: None,
+            kinds: None,
+            count_only: false,
+            include_attachments: false,
+            anonymize: false,
+            out: Some(out.to_string_lossy().to_string()),
+        },
+    )
+    .await;
+    assert_eq!(summary["format"], "json");
+    assert_eq!(summary["event_count"], 1);
+    assert_eq!(summary["output_path"], out.to_string_lossy().as_ref());
+
+    let export: serde_json::Value = serde_json::from_slice(&std::fs::read(&out).unwrap()).unwrap();
+    assert_eq!(export["meta"]["event_count"], 1);
+    assert_eq!(export["issues"][0]["title"], "Exported");
+    assert!(!temp.path().join("export.json.partial").exists());
+
+    common::shutdown(tx, handle).await;
+}
@@ -154,6 +154,13 @@ fn test_ipc_message_roundtrip() {
         IpcCommand::IssueList {
             state: Some("open".to_string()),
             label: None,
+            assignee: None,
+            unassigned: false,
+            field: None,
+            milestone: None,
+            severity: None,
+            include_deleted: false,
+            sort: "created".to_string(),
         },
     );
 
@@ -191,6 +198,7 @@ fn test_notification_roundtrip() {
         issue_id: "issue123".to_string(),
         event_id: "event456".to_string(),
         ts_unix_ms: 1700000000000,
+        subscribers: vec!["alice".to_string()],
     };
 
     let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&notification).unwrap();
@@ -204,10 +212,12 @@ fn test_notification_roundtrip() {
             issue_id,
             event_id,
             ts_unix_ms,
+            subscribers,
         } => {
             assert_eq!(issue_id, "issue123");
             assert_eq!(event_id, "event456");
             assert_eq!(ts_unix_ms, 1700000000000);
+            assert_eq!(subscribers, vec!["alice".to_string()]);
         }
         _ => panic!("Wrong notification type"),
     }
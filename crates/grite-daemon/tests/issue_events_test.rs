@@ -0,0 +1,73 @@
+//! Integration test for paginated issue events
+//!
+//! Verifies that `IssueEvents` returns an issue's events in pages along with
+//! the total, and that an offset past the end yields an empty page.
+
+mod common;
+
+use libgrite_ipc::IpcCommand;
+use tempfile::tempdir;
+
+fn event_ids(page: &serde_json::Value) -> Vec<String> {
+    page["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["event_id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_issue_events_paginated() {
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+
+    let (tx, _notify_rx, handle) = common::spawn_worker(temp.path());
+
+    // One create plus nine comments makes ten events
+    let created = common::send(
+        &tx,
+        IpcCommand::IssueCreate {
+            title: "Busy issue".to_string(),
+            body: String::new(),
+            labels: vec![],
+        },
+    )
+    .await;
+    let issue_id = created["issue_id"].as_str().unwrap().to_string();
+    for i in 0..9 {
+        common::send(
+            &tx,
+            IpcCommand::IssueComment {
+                issue_id: issue_id.clone(),
+                body: format!("Comment {}", i),
+                reply_to: None,
+            },
+        )
+        .await;
+    }
+
+    let page = |offset| IpcCommand::IssueEvents {
+        issue_id: issue_id.clone(),
+        offset,
+        limit: 5,
+    };
+
+    let first = common::send(&tx, page(0)).await;
+    let second = common::send(&tx, page(5)).await;
+    assert_eq!(first["total"], 10);
+    assert_eq!(second["total"], 10);
+
+    let first_ids = event_ids(&first);
+    let second_ids = event_ids(&second);
+    assert_eq!(first_ids.len(), 5);
+    assert_eq!(second_ids.len(), 5);
+    assert!(first_ids.iter().all(|id| !second_ids.contains(id)));
+    assert!(first["events"][0]["kind"]["IssueCreated"].is_object());
+
+    let past_end = common::send(&tx, page(20)).await;
+    assert_eq!(past_end["total"], 10);
+    assert!(event_ids(&past_end).is_empty());
+
+    common::shutdown(tx, handle).await;
+}
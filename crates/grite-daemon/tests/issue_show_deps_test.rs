@@ -0,0 +1,67 @@
+//! Integration test for dependencies in issue show
+//!
+//! Verifies that after adding a `blocks` dependency, `IssueShow` on the
+//! blocked issue lists its blocker under `dependents` with the blocker's title.
+
+mod common;
+
+use grite_daemon::WorkerMessage;
+use libgrite_ipc::IpcCommand;
+use tempfile::tempdir;
+use tokio::sync::mpsc;
+
+async fn create(tx: &mpsc::Sender<WorkerMessage>, title: &str) -> String {
+    let created = common::send(
+        tx,
+        IpcCommand::IssueCreate {
+            title: title.to_string(),
+            body: String::new(),
+            labels: vec![],
+        },
+    )
+    .await;
+    created["issue_id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_issue_show_lists_blocker() {
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+
+    let (tx, _notify_rx, handle) = common::spawn_worker(temp.path());
+
+    let blocker = create(&tx, "Fix the build").await;
+    let blocked = create(&tx, "Ship the release").await;
+    common::send(
+        &tx,
+        IpcCommand::IssueDepAdd {
+            issue_id: blocker.clone(),
+            target_id: blocked.clone(),
+            dep_type: "blocks".to_string(),
+        },
+    )
+    .await;
+
+    let shown = common::send(
+        &tx,
+        IpcCommand::IssueShow {
+            issue_id: blocked.clone(),
+        },
+    )
+    .await;
+    assert!(shown["dependencies"].as_array().unwrap().is_empty());
+    let dependents = shown["dependents"].as_array().unwrap();
+    assert_eq!(dependents.len(), 1);
+    assert_eq!(dependents[0]["target"], blocker.as_str());
+    assert_eq!(dependents[0]["dep_type"], "blocks");
+    assert_eq!(dependents[0]["title"], "Fix the build");
+
+    // The blocker sees the same edge from its side
+    let shown = common::send(&tx, IpcCommand::IssueShow { issue_id: blocker }).await;
+    let dependencies = shown["dependencies"].as_array().unwrap();
+    assert_eq!(dependencies.len(), 1);
+    assert_eq!(dependencies[0]["target"], blocked.as_str());
+    assert_eq!(dependencies[0]["title"], "Ship the release");
+
+    common::shutdown(tx, handle).await;
+}
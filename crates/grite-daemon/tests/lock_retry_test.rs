@@ -0,0 +1,70 @@
+//! Integration tests for daemon lock acquisition
+//!
+//! A worker restarting while a previous daemon's lock is still leased waits
+//! for the lease to expire; a lock held by another running process fails at
+//! once.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use libgrite_ipc::DaemonLock;
+use tempfile::tempdir;
+
+/// Write a lock as if taken by `pid`, expiring after `lease_ms`
+fn write_lock(dir: &Path, pid: u32, lease_ms: u64) {
+    DaemonLock::new(
+        pid,
+        dir.to_string_lossy().to_string(),
+        common::ACTOR_ID.to_string(),
+        "test-host".to_string(),
+        "/tmp/grite-lock-retry-old.sock".to_string(),
+    )
+    .with_lease(lease_ms)
+    .write(&dir.join(".git").join("grite"))
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_acquire_lock_waits_for_expiring_lock() {
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+    let (_tx, _notify_rx, worker) = common::new_worker(temp.path());
+
+    // A lock left by a worker of this process that is shutting down
+    write_lock(temp.path(), std::process::id(), 500);
+
+    let start = Instant::now();
+    let lock = worker.acquire_lock().await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(400));
+    assert_eq!(lock.ipc_endpoint, common::ipc_endpoint(temp.path()));
+
+    let on_disk = DaemonLock::read(&temp.path().join(".git").join("grite"))
+        .unwrap()
+        .unwrap();
+    assert_eq!(on_disk.ipc_endpoint, lock.ipc_endpoint);
+}
+
+#[tokio::test]
+async fn test_acquire_lock_fails_fast_for_live_holder() {
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+    let (_tx, _notify_rx, worker) = common::new_worker(temp.path());
+
+    let mut holder = Command::new("sleep").arg("30").spawn().unwrap();
+    write_lock(temp.path(), holder.id(), 30_000);
+
+    let start = Instant::now();
+    let err = worker.acquire_lock().await.unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(
+        err.to_string().contains(&format!("pid {}", holder.id())),
+        "{}",
+        err
+    );
+
+    holder.kill().unwrap();
+    holder.wait().unwrap();
+}
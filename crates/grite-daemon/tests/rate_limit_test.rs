@@ -0,0 +1,113 @@
+//! Integration test for per-actor rate limiting
+//!
+//! With a rate limit set, an actor that exceeds it gets `rate_limited` errors
+//! while requests from other actors keep succeeding.
+
+mod common;
+
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+use grite_daemon::supervisor::Supervisor;
+use libgrite_ipc::error::codes;
+use libgrite_ipc::framing::{read_framed, write_framed};
+use libgrite_ipc::messages::IpcResponse;
+use libgrite_ipc::{IpcCommand, IpcRequest};
+
+const BUSY_ACTOR: &str = common::ACTOR_ID;
+const OTHER_ACTOR: &str = "ffeeddccbbaa99887766554433221100";
+
+/// Send an `issue list` request as `actor_id`
+fn list_issues(socket_path: &str, repo_root: &str, actor_id: &str) -> IpcResponse {
+    send_request(
+        socket_path,
+        repo_root,
+        actor_id,
+        IpcCommand::IssueList {
+            state: None,
+            label: None,
+            assignee: None,
+            unassigned: false,
+            field: None,
+            milestone: None,
+            severity: None,
+            include_deleted: false,
+            sort: "created".to_string(),
+        },
+    )
+}
+
+fn send_request(
+    socket_path: &str,
+    repo_root: &str,
+    actor_id: &str,
+    command: IpcCommand,
+) -> IpcResponse {
+    let mut stream = UnixStream::connect(socket_path).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(30)))
+        .unwrap();
+
+    let request = IpcRequest::new(
+        uuid::Uuid::new_v4().to_string(),
+        repo_root.to_string(),
+        actor_id.to_string(),
+        String::new(),
+        command,
+    );
+    let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&request).unwrap();
+    write_framed(&mut stream, &bytes).unwrap();
+
+    let response_bytes = read_framed(&mut stream).unwrap();
+    let archived =
+        rkyv::access::<rkyv::Archived<IpcResponse>, rkyv::rancor::Error>(&response_bytes).unwrap();
+    rkyv::deserialize::<IpcResponse, rkyv::rancor::Error>(archived).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_rate_limit_rejects_only_the_busy_actor() {
+    let temp = tempfile::tempdir().unwrap();
+    common::setup_repo(temp.path());
+    let repo_root = temp.path().to_string_lossy().to_string();
+    let socket_path = temp.path().join("daemon.sock");
+    let socket_str = socket_path.to_string_lossy().to_string();
+
+    let supervisor = common::spawn_supervisor(
+        Supervisor::new(socket_str.clone(), None).with_rate_limit(2.0),
+        &socket_path,
+    )
+    .await;
+
+    // Start the worker first so the burst below is not slowed by its startup
+    let response = list_issues(&socket_str, &repo_root, OTHER_ACTOR);
+    assert!(response.ok, "{:?}", response.error);
+
+    // A burst of two is allowed, the rest is rejected
+    for _ in 0..2 {
+        let response = list_issues(&socket_str, &repo_root, BUSY_ACTOR);
+        assert!(response.ok, "{:?}", response.error);
+    }
+    for _ in 0..3 {
+        let response = list_issues(&socket_str, &repo_root, BUSY_ACTOR);
+        assert!(!response.ok);
+        let error = response.error.unwrap();
+        assert_eq!(error.code, codes::RATE_LIMITED);
+        assert!(error.message.contains(BUSY_ACTOR), "{}", error.message);
+    }
+
+    // Another actor is unaffected
+    let response = list_issues(&socket_str, &repo_root, OTHER_ACTOR);
+    assert!(response.ok, "{:?}", response.error);
+
+    // Daemon-level commands are never limited
+    let response = send_request(
+        &socket_str,
+        &repo_root,
+        BUSY_ACTOR,
+        IpcCommand::DaemonStatus,
+    );
+    assert!(response.ok, "{:?}", response.error);
+
+    send_request(&socket_str, &repo_root, BUSY_ACTOR, IpcCommand::DaemonStop);
+    let _ = tokio::time::timeout(Duration::from_secs(5), supervisor).await;
+}
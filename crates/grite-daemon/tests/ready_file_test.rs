@@ -0,0 +1,46 @@
+//! Integration test for daemon readiness signaling
+//!
+//! Verifies that the supervisor writes its ready file only once the socket
+//! accepts connections, and removes it again on shutdown.
+
+use std::os::unix::net::UnixStream;
+use std::time::{Duration, Instant};
+
+use grite_daemon::supervisor::Supervisor;
+use tempfile::tempdir;
+use tokio::sync::oneshot;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_ready_file_written_after_bind() {
+    let temp = tempdir().unwrap();
+    let socket_path = temp.path().join("daemon.sock");
+    let ready_file = temp.path().join("daemon.ready");
+    let socket_str = socket_path.to_string_lossy().to_string();
+
+    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let supervisor = Supervisor::new(socket_str.clone(), None).with_ready_file(&ready_file);
+    let handle = tokio::spawn(async move {
+        supervisor
+            .run(async {
+                let _ = shutdown_rx.await;
+            })
+            .await
+    });
+
+    // Whenever the ready file exists, the socket must already be connectable
+    let start = Instant::now();
+    while !ready_file.exists() {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "timed out waiting for ready file"
+        );
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    UnixStream::connect(&socket_path).expect("socket should accept connections once ready");
+    assert_eq!(std::fs::read_to_string(&ready_file).unwrap(), socket_str);
+
+    shutdown_tx.send(()).unwrap();
+    handle.await.unwrap().unwrap();
+    assert!(!ready_file.exists(), "ready file is removed on shutdown");
+    assert!(!socket_path.exists());
+}
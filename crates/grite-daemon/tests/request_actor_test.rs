@@ -0,0 +1,81 @@
+//! Integration test for per-request actors
+//!
+//! One worker attributes each write to the actor named in the request, not
+//! to the actor it was started for, and rejects writes from unknown actors.
+
+mod common;
+
+use std::path::Path;
+
+use grite_daemon::WorkerMessage;
+use libgrite_ipc::error::codes;
+use libgrite_ipc::{IpcCommand, IpcResponse};
+use tempfile::tempdir;
+use tokio::sync::mpsc;
+
+const HOME_ACTOR: &str = "ffeeddccbbaa99887766554433221100";
+const UNKNOWN_ACTOR: &str = "0123456789abcdef0123456789abcdef";
+
+fn write_actor_config(dir: &Path, actor_id: &str) {
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(
+        dir.join("config.toml"),
+        format!("actor_id = \"{}\"\n", actor_id),
+    )
+    .unwrap();
+}
+
+fn create(title: &str) -> IpcCommand {
+    IpcCommand::IssueCreate {
+        title: title.to_string(),
+        body: String::new(),
+        labels: vec![],
+    }
+}
+
+/// Actor of the first event of the issue created by `response`
+async fn creator(tx: &mpsc::Sender<WorkerMessage>, response: IpcResponse) -> String {
+    assert!(response.ok, "{:?}", response.error);
+    let created: serde_json::Value = serde_json::from_str(&response.data.unwrap()).unwrap();
+    let events = common::send(
+        tx,
+        IpcCommand::IssueEvents {
+            issue_id: created["issue_id"].as_str().unwrap().to_string(),
+            offset: 0,
+            limit: 10,
+        },
+    )
+    .await;
+    events["events"][0]["actor"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_writes_are_attributed_to_the_request_actor() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path().join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+
+    // One actor registered in the repo, one kept in a separate data dir
+    common::setup_repo(&repo);
+    let home = temp.path().join("home");
+    write_actor_config(&home, HOME_ACTOR);
+    let home = home.to_string_lossy().to_string();
+
+    let (tx, _notify_rx, handle) = common::spawn_worker(&repo);
+
+    let response =
+        common::request_as(&tx, common::ACTOR_ID, "", create("From the repo actor")).await;
+    assert_eq!(creator(&tx, response).await, common::ACTOR_ID);
+
+    let response = common::request_as(&tx, HOME_ACTOR, &home, create("From the home actor")).await;
+    assert_eq!(creator(&tx, response).await, HOME_ACTOR);
+
+    // Neither registered nor in the request's data dir
+    let response = common::request_as(&tx, UNKNOWN_ACTOR, &home, create("From nobody")).await;
+    assert!(!response.ok);
+    let error = response.error.unwrap();
+    assert_eq!(error.code, codes::INVALID_INPUT);
+    assert!(error.message.contains(UNKNOWN_ACTOR), "{}", error.message);
+
+    common::shutdown(tx, handle).await;
+}
@@ -0,0 +1,76 @@
+//! Integration test for the shared sync report
+//!
+//! Verifies that a sync routed through the daemon returns the same JSON shape
+//! as a local sync built from `SyncReport`.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+
+use libgrite_git::{SyncManager, SyncReport};
+use libgrite_ipc::IpcCommand;
+use tempfile::tempdir;
+
+fn git(dir: &Path, args: &[&str]) {
+    assert!(Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+        .status
+        .success());
+}
+
+fn keys(value: &serde_json::Value) -> Vec<&String> {
+    value.as_object().unwrap().keys().collect()
+}
+
+#[tokio::test]
+async fn test_daemon_sync_matches_local_report() {
+    let remote = tempdir().unwrap();
+    git(remote.path(), &["init", "--bare"]);
+    let temp = tempdir().unwrap();
+    common::setup_repo(temp.path());
+    git(
+        temp.path(),
+        &["remote", "add", "origin", remote.path().to_str().unwrap()],
+    );
+
+    let (tx, _notify_rx, handle) = common::spawn_worker(temp.path());
+
+    common::send(
+        &tx,
+        IpcCommand::IssueCreate {
+            title: "Synced".to_string(),
+            body: String::new(),
+            labels: vec![],
+        },
+    )
+    .await;
+    let daemon = common::send(
+        &tx,
+        IpcCommand::Sync {
+            remote: "origin".to_string(),
+            pull: false,
+            push: false,
+        },
+    )
+    .await;
+
+    common::shutdown(tx, handle).await;
+
+    let report: SyncReport = serde_json::from_value(daemon.clone()).unwrap();
+    assert_eq!(report.remote, "origin");
+    assert!(report.pulled && report.pushed && report.push_success);
+
+    // The same full sync run locally serializes to the same fields
+    let mgr = SyncManager::open(&temp.path().join(".git")).unwrap();
+    let (pull, push) = mgr.sync_with_rebase("origin", &[0u8; 16]).unwrap();
+    let local =
+        serde_json::to_value(SyncReport::new("origin", 0, Some(&pull), Some(&push))).unwrap();
+    assert_eq!(keys(&local), keys(&daemon));
+    for field in ["remote", "pulled", "pushed", "push_success", "backfilled"] {
+        assert_eq!(local[field], daemon[field], "{}", field);
+    }
+}
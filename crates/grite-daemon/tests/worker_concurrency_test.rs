@@ -0,0 +1,116 @@
+//! Integration test for the worker's command concurrency limit
+//!
+//! Verifies that with `max_concurrent_commands` set in the repo config, a
+//! burst of commands larger than the limit all complete while no more than
+//! the limit run at once, and that a shutdown is not held up by commands
+//! still waiting for a slot.
+
+mod common;
+
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use grite_daemon::WorkerMessage;
+use libgrite_ipc::{DataEncoding, IpcCommand, IpcResponse};
+use tempfile::tempdir;
+use tokio::sync::{mpsc, oneshot};
+
+const LIMIT: usize = 2;
+const COMMANDS: usize = 20;
+
+/// Create a git repo whose config limits workers to `LIMIT` commands
+fn setup_repo(dir: &Path) {
+    common::setup_repo(dir);
+    common::write_repo_config(dir, &format!("max_concurrent_commands = {}\n", LIMIT));
+}
+
+/// Send `COMMANDS` issue creates without waiting for their responses
+async fn send_burst(tx: &mpsc::Sender<WorkerMessage>) -> Vec<oneshot::Receiver<IpcResponse>> {
+    let mut responses = Vec::with_capacity(COMMANDS);
+    for i in 0..COMMANDS {
+        let (response_tx, response_rx) = oneshot::channel();
+        tx.send(WorkerMessage::Command {
+            request_id: format!("req-{}", i),
+            actor_id: common::ACTOR_ID.to_string(),
+            data_dir: String::new(),
+            command: IpcCommand::IssueCreate {
+                title: format!("Issue {}", i),
+                body: String::new(),
+                labels: vec![],
+            },
+            encoding: DataEncoding::Json,
+            response_tx,
+        })
+        .await
+        .unwrap();
+        responses.push(response_rx);
+    }
+    responses
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_burst_is_bounded_by_limit() {
+    let temp = tempdir().unwrap();
+    setup_repo(temp.path());
+
+    let (tx, mut notify_rx, worker) = common::new_worker(temp.path());
+    let in_flight = Arc::clone(&worker.in_flight);
+    let handle = tokio::spawn(worker.run());
+    tokio::spawn(async move { while notify_rx.recv().await.is_some() {} });
+
+    // Record the most commands seen running at once
+    let peak = Arc::new(AtomicUsize::new(0));
+    let done = Arc::new(AtomicBool::new(false));
+    let sampler = {
+        let peak = Arc::clone(&peak);
+        let done = Arc::clone(&done);
+        tokio::spawn(async move {
+            while !done.load(Ordering::SeqCst) {
+                peak.fetch_max(in_flight.load(Ordering::SeqCst), Ordering::SeqCst);
+                tokio::task::yield_now().await;
+            }
+        })
+    };
+
+    for response_rx in send_burst(&tx).await {
+        let response = tokio::time::timeout(Duration::from_secs(30), response_rx)
+            .await
+            .expect("command timed out")
+            .unwrap();
+        assert!(response.ok, "{:?}", response.error);
+    }
+
+    done.store(true, Ordering::SeqCst);
+    sampler.await.unwrap();
+    let peak = peak.load(Ordering::SeqCst);
+    assert!(peak <= LIMIT, "{} commands ran at once", peak);
+
+    common::shutdown(tx, handle).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_shutdown_while_commands_queue() {
+    let temp = tempdir().unwrap();
+    setup_repo(temp.path());
+
+    let (tx, mut notify_rx, handle) = common::spawn_worker(temp.path());
+    tokio::spawn(async move { while notify_rx.recv().await.is_some() {} });
+
+    let responses = send_burst(&tx).await;
+    tx.send(WorkerMessage::Heartbeat).await.unwrap();
+    tx.send(WorkerMessage::Shutdown).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(30), handle)
+        .await
+        .expect("shutdown timed out")
+        .unwrap();
+
+    // Every command either ran or was turned away, none was dropped
+    for response_rx in responses {
+        let response = response_rx.await.unwrap();
+        if !response.ok {
+            assert_eq!(response.error.unwrap().code, "worker_unavailable");
+        }
+    }
+}
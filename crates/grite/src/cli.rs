@@ -12,6 +12,10 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub quiet: bool,
 
+    /// Write output line by line as it is produced instead of in large blocks
+    #[arg(long, global = true)]
+    pub no_buffer: bool,
+
     /// Override the data directory
     #[arg(long, global = true)]
     pub data_dir: Option<PathBuf>,
@@ -24,6 +28,22 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub no_daemon: bool,
 
+    /// Explain on stderr how the command would be routed, then run it locally
+    #[arg(long, global = true)]
+    pub explain_routing: bool,
+
+    /// Fail instead of creating an actor when none exists (also GRITE_NO_AUTO_INIT)
+    #[arg(long, global = true)]
+    pub no_auto_init: bool,
+
+    /// Seconds to keep retrying when another process holds the local database (0 = fail at once; default: 2, or 0 while a daemon runs)
+    #[arg(long, global = true, value_name = "SECONDS")]
+    pub wait: Option<u64>,
+
+    /// Characters of each ID shown in human output, 4-32 (default: id_len in config, else 8)
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u8).range(4..=32))]
+    pub id_len: Option<u8>,
+
     #[command(subcommand)]
     pub command: Command,
 }
@@ -58,12 +78,40 @@ pub enum Command {
     /// Export issues to file
     Export {
         /// Export format
-        #[arg(long)]
-        format: ExportFormat,
+        #[arg(long, required_unless_present = "count_only")]
+        format: Option<ExportFormat>,
 
         /// Export changes since timestamp or event ID
         #[arg(long)]
         since: Option<String>,
+
+        /// Event ordering in JSON exports
+        #[arg(long, value_enum, default_value = "by-issue")]
+        order: ExportOrder,
+
+        /// Export only this issue (ID or prefix)
+        #[arg(long)]
+        issue: Option<String>,
+
+        /// Export only these event kinds in JSON exports (comma-separated, e.g. issue_created,comment_added)
+        #[arg(long)]
+        kinds: Option<String>,
+
+        /// Only report how many events and issues a JSON export would contain
+        #[arg(long)]
+        count_only: bool,
+
+        /// Embed the bytes of referenced attachments in JSON exports, base64-encoded
+        #[arg(long)]
+        include_attachments: bool,
+
+        /// Replace actor IDs and assignee/subscriber names with stable pseudonyms
+        #[arg(long)]
+        anonymize: bool,
+
+        /// Write the export here instead of .grite/export.<format>
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
     },
 
     /// Rebuild local database from events
@@ -71,6 +119,14 @@ pub enum Command {
         /// Use latest snapshot as base (faster for large repos)
         #[arg(long)]
         from_snapshot: bool,
+
+        /// Report event and issue counts without rebuilding
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Skip events that fail to apply and list them, instead of aborting
+        #[arg(long, conflicts_with = "dry_run")]
+        skip_errors: bool,
     },
 
     /// Sync with remote repository
@@ -86,6 +142,14 @@ pub enum Command {
         /// Push only (don't pull)
         #[arg(long)]
         push: bool,
+
+        /// Check that local chunks use formats REMOTE can read, without syncing
+        #[arg(long, value_name = "REMOTE", conflicts_with_all = ["pull", "push"])]
+        check_compat: Option<String>,
+
+        /// Show the events a pull would bring in, without applying them
+        #[arg(long, conflicts_with_all = ["pull", "push", "check_compat"])]
+        preview: bool,
     },
 
     /// Snapshot management
@@ -113,12 +177,30 @@ pub enum Command {
         fix: bool,
     },
 
+    /// Milestone operations
+    Milestone {
+        #[command(subcommand)]
+        cmd: MilestoneCommand,
+    },
+
+    /// Summaries across all issues
+    Report {
+        #[command(subcommand)]
+        cmd: ReportCommand,
+    },
+
+    /// Browse, filter, create and close issues interactively
+    Tui,
+
     /// Context store management (file/symbol indexing)
     Context {
         #[command(subcommand)]
         cmd: ContextCommand,
     },
 
+    /// List every error with its exit code and JSON error code
+    Errors,
+
     /// Install the Claude Code skill for grite
     InstallSkill {
         /// Install globally instead of per-repo
@@ -145,7 +227,11 @@ pub enum ActorCommand {
     },
 
     /// List all actors
-    List,
+    List {
+        /// Include each actor's most recent event time
+        #[arg(long)]
+        activity: bool,
+    },
 
     /// Show actor details
     Show {
@@ -161,6 +247,9 @@ pub enum ActorCommand {
         /// Actor ID to use as default
         id: String,
     },
+
+    /// Replace the current actor's signing key, keeping old events verifiable
+    RotateKey,
 }
 
 #[derive(Clone, Subcommand)]
@@ -178,6 +267,14 @@ pub enum IssueCommand {
         /// Labels to add
         #[arg(long)]
         label: Vec<String>,
+
+        /// Prefill title prefix, body and labels from a template
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Print the event that would be written instead of writing it
+        #[arg(long)]
+        simulate: bool,
     },
 
     /// List issues
@@ -189,6 +286,34 @@ pub enum IssueCommand {
         /// Filter by label
         #[arg(long)]
         label: Option<String>,
+
+        /// Filter by assignee
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Only show issues with no assignees
+        #[arg(long)]
+        unassigned: bool,
+
+        /// Filter by custom field (key=value)
+        #[arg(long)]
+        field: Option<String>,
+
+        /// Filter by milestone
+        #[arg(long)]
+        milestone: Option<String>,
+
+        /// Filter by severity (`none` lists issues without one)
+        #[arg(long, value_enum)]
+        severity: Option<SeverityLevel>,
+
+        /// Also list soft-deleted issues
+        #[arg(long)]
+        include_deleted: bool,
+
+        /// Sort order
+        #[arg(long, value_enum, default_value = "created")]
+        sort: IssueSort,
     },
 
     /// Show issue details
@@ -213,6 +338,10 @@ pub enum IssueCommand {
         /// Acquire lock before operation, release after
         #[arg(long)]
         lock: bool,
+
+        /// Print the event that would be written instead of writing it
+        #[arg(long, conflicts_with = "lock")]
+        simulate: bool,
     },
 
     /// Add a comment to an issue
@@ -224,9 +353,17 @@ pub enum IssueCommand {
         #[arg(long, allow_hyphen_values = true)]
         body: String,
 
+        /// Reply to the comment with this event ID (or unique prefix)
+        #[arg(long)]
+        reply_to: Option<String>,
+
         /// Acquire lock before operation, release after
         #[arg(long)]
         lock: bool,
+
+        /// Print the event that would be written instead of writing it
+        #[arg(long, conflicts_with = "lock")]
+        simulate: bool,
     },
 
     /// Close an issue
@@ -267,6 +404,130 @@ pub enum IssueCommand {
         cmd: LinkCommand,
     },
 
+    /// Link this issue to another issue with a typed relation
+    LinkIssue {
+        /// Issue ID
+        id: String,
+
+        /// Target issue ID
+        target: String,
+
+        /// Relation, e.g. duplicate-of, relates, parent
+        #[arg(long)]
+        rel: String,
+
+        /// Remove the link instead of adding it
+        #[arg(long)]
+        remove: bool,
+
+        /// Acquire lock before operation, release after
+        #[arg(long)]
+        lock: bool,
+    },
+
+    /// Close an issue as a duplicate of another, linking it with `duplicate-of`
+    Dup {
+        /// Issue ID
+        id: String,
+
+        /// Issue it duplicates
+        #[arg(long)]
+        of: String,
+
+        /// Acquire lock before operation, release after
+        #[arg(long)]
+        lock: bool,
+    },
+
+    /// Subscribe to an issue's notifications
+    Subscribe {
+        /// Issue ID
+        id: String,
+
+        /// User to subscribe (defaults to the current actor ID)
+        #[arg(long)]
+        user: Option<String>,
+    },
+
+    /// Unsubscribe from an issue's notifications
+    Unsubscribe {
+        /// Issue ID
+        id: String,
+
+        /// User to unsubscribe (defaults to the current actor ID)
+        #[arg(long)]
+        user: Option<String>,
+    },
+
+    /// Set the estimated effort for an issue (the latest estimate wins)
+    Estimate {
+        /// Issue ID
+        id: String,
+
+        /// Estimated effort in minutes
+        minutes: u32,
+    },
+
+    /// Log time spent on an issue (logged time adds up)
+    LogTime {
+        /// Issue ID
+        id: String,
+
+        /// Minutes spent
+        minutes: u32,
+
+        /// What the time was spent on
+        #[arg(long)]
+        note: Option<String>,
+    },
+
+    /// Move an issue to a milestone (the latest wins)
+    Milestone {
+        /// Issue ID
+        id: String,
+
+        /// Milestone name, or `clear` to remove the issue from its milestone
+        milestone: String,
+    },
+
+    /// Set an issue's customer-facing severity (the latest wins)
+    Severity {
+        /// Issue ID
+        id: String,
+
+        /// Severity, from sev1 (most severe) to sev4, or `none` to clear it
+        #[arg(value_enum)]
+        severity: SeverityLevel,
+    },
+
+    /// Soft-delete an issue created by mistake (hides it from lists)
+    Delete {
+        /// Issue ID
+        id: String,
+    },
+
+    /// Restore a soft-deleted issue
+    Undelete {
+        /// Issue ID
+        id: String,
+    },
+
+    /// Show the chains of open blockers that keep an issue from being ready
+    WhyBlocked {
+        /// Issue ID
+        id: String,
+    },
+
+    /// Show an issue's dependency graph as a tree
+    Tree {
+        /// Issue ID
+        id: String,
+
+        /// Maximum depth to follow dependencies
+        #[arg(long, default_value = "10")]
+        depth: u32,
+    },
+
     /// Attachment operations
     Attachment {
         #[command(subcommand)]
@@ -278,14 +539,21 @@ pub enum IssueCommand {
         #[command(subcommand)]
         cmd: DepCommand,
     },
+
+    /// Custom field operations
+    Field {
+        #[command(subcommand)]
+        cmd: FieldCommand,
+    },
 }
 
 #[derive(Clone, Subcommand)]
 pub enum LabelCommand {
-    /// Add a label to an issue
+    /// Add a label to one or more issues
     Add {
-        /// Issue ID
-        id: String,
+        /// Issue IDs, space or comma separated; all must exist
+        #[arg(required = true, value_delimiter = ',')]
+        ids: Vec<String>,
 
         /// Label to add
         #[arg(long)]
@@ -296,10 +564,11 @@ pub enum LabelCommand {
         lock: bool,
     },
 
-    /// Remove a label from an issue
+    /// Remove a label from one or more issues
     Remove {
-        /// Issue ID
-        id: String,
+        /// Issue IDs, space or comma separated; all must exist
+        #[arg(required = true, value_delimiter = ',')]
+        ids: Vec<String>,
 
         /// Label to remove
         #[arg(long)]
@@ -313,10 +582,11 @@ pub enum LabelCommand {
 
 #[derive(Clone, Subcommand)]
 pub enum AssigneeCommand {
-    /// Add an assignee to an issue
+    /// Add an assignee to one or more issues
     Add {
-        /// Issue ID
-        id: String,
+        /// Issue IDs, space or comma separated; all must exist
+        #[arg(required = true, value_delimiter = ',')]
+        ids: Vec<String>,
 
         /// User to assign
         #[arg(long)]
@@ -327,10 +597,11 @@ pub enum AssigneeCommand {
         lock: bool,
     },
 
-    /// Remove an assignee from an issue
+    /// Remove an assignee from one or more issues
     Remove {
-        /// Issue ID
-        id: String,
+        /// Issue IDs, space or comma separated; all must exist
+        #[arg(required = true, value_delimiter = ',')]
+        ids: Vec<String>,
 
         /// User to unassign
         #[arg(long)]
@@ -375,8 +646,12 @@ pub enum AttachmentCommand {
         name: String,
 
         /// SHA256 hash of the attachment
-        #[arg(long)]
-        sha256: String,
+        #[arg(long, required_unless_present = "file")]
+        sha256: Option<String>,
+
+        /// Store this file's bytes and reference them by their SHA256
+        #[arg(long, conflicts_with = "sha256")]
+        file: Option<PathBuf>,
 
         /// MIME type
         #[arg(long)]
@@ -388,6 +663,39 @@ pub enum AttachmentCommand {
     },
 }
 
+#[derive(Clone, Subcommand)]
+pub enum FieldCommand {
+    /// Set a custom field on an issue
+    Set {
+        /// Issue ID
+        id: String,
+
+        /// Field name
+        key: String,
+
+        /// Field value
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+
+        /// Acquire lock before operation, release after
+        #[arg(long)]
+        lock: bool,
+    },
+
+    /// Clear a custom field on an issue
+    Clear {
+        /// Issue ID
+        id: String,
+
+        /// Field name
+        key: String,
+
+        /// Acquire lock before operation, release after
+        #[arg(long)]
+        lock: bool,
+    },
+}
+
 #[derive(Clone, Subcommand)]
 pub enum DbCommand {
     /// Show database statistics
@@ -406,12 +714,62 @@ pub enum DbCommand {
         #[arg(long)]
         verbose: bool,
     },
+
+    /// List all events of one kind
+    Events {
+        /// Event kind, e.g. state_changed or StateChanged
+        #[arg(long)]
+        kind: String,
+    },
+
+    /// Sign the current actor's unsigned events with its signing key
+    SignHistory,
+
+    /// Write all events and the repo config to a single archive file
+    Archive {
+        /// Archive file to write
+        file: PathBuf,
+    },
+
+    /// Replace the store with the contents of an archive file
+    Restore {
+        /// Archive file to read
+        file: PathBuf,
+
+        /// Replace a store that already contains events
+        #[arg(long)]
+        force: bool,
+
+        /// Restore only these event kinds (comma-separated, must include issue_created for issue events)
+        #[arg(long)]
+        kinds: Option<String>,
+    },
+
+    /// Print the canonical CBOR an event's ID is hashed from, and the recomputed ID
+    EventPreimage {
+        /// Event ID (full hex)
+        event_id: String,
+    },
+
+    /// Store the attachment bytes embedded in a JSON export
+    ImportAttachments {
+        /// Export file written with `export --include-attachments`
+        file: PathBuf,
+    },
 }
 
 #[derive(Clone, Subcommand)]
 pub enum SnapshotCommand {
     /// Create a new snapshot
-    Create,
+    Create {
+        /// Sign the snapshot with this actor's signing key
+        #[arg(long)]
+        sign: bool,
+
+        /// Store only the events added since the latest snapshot, chained to it
+        #[arg(long, conflicts_with = "sign")]
+        delta: bool,
+    },
 
     /// List all snapshots
     List,
@@ -422,14 +780,74 @@ pub enum SnapshotCommand {
         #[arg(long, default_value = "5")]
         keep: usize,
     },
+
+    /// Protect a snapshot from garbage collection
+    Pin {
+        /// Snapshot timestamp (from `snapshot list`)
+        ts: u64,
+    },
+
+    /// Let garbage collection delete a pinned snapshot again
+    Unpin {
+        /// Snapshot timestamp (from `snapshot list`)
+        ts: u64,
+    },
+
+    /// Check a snapshot's signature and compare the store's issues against it
+    Verify {
+        /// Snapshot timestamp (from `snapshot list`)
+        ts: u64,
+
+        /// Hex Ed25519 public key to check against (default: this actor's key, if any)
+        #[arg(long)]
+        public_key: Option<String>,
+    },
 }
 
-#[derive(Clone, ValueEnum)]
+#[derive(Clone, Copy, ValueEnum)]
 pub enum ExportFormat {
     Json,
     Md,
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+pub enum IssueSort {
+    /// Oldest first
+    Created,
+    /// Issues blocking the most others first
+    MostBlocking,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SeverityLevel {
+    Sev1,
+    Sev2,
+    Sev3,
+    Sev4,
+    /// No severity
+    None,
+}
+
+impl SeverityLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeverityLevel::Sev1 => "sev1",
+            SeverityLevel::Sev2 => "sev2",
+            SeverityLevel::Sev3 => "sev3",
+            SeverityLevel::Sev4 => "sev4",
+            SeverityLevel::None => "none",
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportOrder {
+    /// Group events by issue
+    ByIssue,
+    /// Order events strictly by timestamp
+    Chronological,
+}
+
 #[derive(Clone, Subcommand)]
 pub enum DaemonCommand {
     /// Start the daemon in background
@@ -446,6 +864,20 @@ pub enum DaemonCommand {
     Stop,
 }
 
+#[derive(Clone, Subcommand)]
+pub enum MilestoneCommand {
+    /// List milestones with their open and closed issue counts
+    List,
+}
+
+#[derive(Clone, Subcommand)]
+pub enum ReportCommand {
+    /// Total logged time per actor
+    Time,
+    /// Time to first response by someone other than the issue's creator
+    ResponseTime,
+}
+
 #[derive(Clone, Subcommand)]
 pub enum LockCommand {
     /// Acquire a lock on a resource
@@ -543,9 +975,12 @@ pub enum DepCommand {
 
 #[derive(Clone, Subcommand)]
 pub enum ContextCommand {
-    /// Index files in the repository
+    /// Index files in the working tree
     Index {
-        /// Specific paths to index (default: all tracked files)
+        /// Files or directories to index (default: the whole working tree)
+        paths: Vec<String>,
+
+        /// Same as a positional path; may be repeated
         #[arg(long)]
         path: Vec<String>,
 
@@ -562,6 +997,10 @@ pub enum ContextCommand {
     Query {
         /// Search query (symbol name)
         query: String,
+
+        /// Return at most this many matches
+        #[arg(long)]
+        limit: Option<usize>,
     },
 
     /// Show context for a specific file
@@ -570,6 +1009,13 @@ pub enum ContextCommand {
         path: String,
     },
 
+    /// List indexed files, most recently updated first
+    Recent {
+        /// List at most this many files
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
     /// Show or set project-level context
     Project {
         /// Key to show (or all if omitted)
@@ -1,14 +1,14 @@
 use crate::cli::{ActorCommand, Cli};
-use crate::context::GriteContext;
-use crate::output::output_success;
+use crate::context::{open_store_at, store_wait, ActorSource, GriteContext};
+use crate::output::{format_last_seen, output_success};
 use libgrite_core::{
     config::{
-        actor_dir, list_actors, load_actor_config, load_repo_config, save_actor_config,
-        save_repo_config,
+        actor_dir, list_actors, load_actor_config, save_actor_config, save_signing_key,
+        update_repo_config,
     },
     signing::SigningKeyPair,
     types::actor::ActorConfig,
-    types::ids::{generate_actor_id, id_to_hex},
+    types::ids::{generate_actor_id, hex_to_id, id_to_hex},
     GriteError,
 };
 use serde::Serialize;
@@ -32,6 +32,9 @@ struct ActorInfo {
     actor_id: String,
     label: Option<String>,
     data_dir: String,
+    /// Newest event timestamp; only with `--activity`, null if none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_event_ts: Option<Option<u64>>,
 }
 
 #[derive(Serialize)]
@@ -57,6 +60,15 @@ struct ActorCurrentOutput {
     source: String,
 }
 
+#[derive(Serialize)]
+struct ActorRotateKeyOutput {
+    actor_id: String,
+    public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    previous_public_key: Option<String>,
+    effective_ts: u64,
+}
+
 #[derive(Serialize)]
 struct ActorUseOutput {
     default_actor: String,
@@ -69,10 +81,11 @@ pub fn run(cli: &Cli, cmd: ActorCommand) -> Result<(), GriteError> {
             label,
             generate_key,
         } => run_init(cli, label, generate_key),
-        ActorCommand::List => run_list(cli),
+        ActorCommand::List { activity } => run_list(cli, activity),
         ActorCommand::Show { id } => run_show(cli, id),
         ActorCommand::Current => run_current(cli),
         ActorCommand::Use { id } => run_use(cli, id),
+        ActorCommand::RotateKey => run_rotate_key(cli),
     }
 }
 
@@ -95,29 +108,7 @@ fn run_init(cli: &Cli, label: Option<String>, generate_key: bool) -> Result<(),
         config.key_scheme = Some("ed25519".to_string());
 
         // Store private key seed in separate file (hex-encoded)
-        // Use restricted permissions (0600) on Unix to protect the key
-        std::fs::create_dir_all(&data_dir)?;
-        let signing_key_path = data_dir.join("signing_key");
-
-        #[cfg(unix)]
-        {
-            use std::fs::OpenOptions;
-            use std::io::Write;
-            use std::os::unix::fs::OpenOptionsExt;
-
-            let mut file = OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .mode(0o600)
-                .open(&signing_key_path)?;
-            file.write_all(keypair.seed_hex().as_bytes())?;
-        }
-
-        #[cfg(not(unix))]
-        {
-            std::fs::write(&signing_key_path, keypair.seed_hex())?;
-        }
+        save_signing_key(&git_dir, &actor_id_hex, &keypair.seed_hex())?;
     }
 
     save_actor_config(&data_dir, &config)?;
@@ -135,22 +126,48 @@ fn run_init(cli: &Cli, label: Option<String>, generate_key: bool) -> Result<(),
     Ok(())
 }
 
-fn run_list(cli: &Cli) -> Result<(), GriteError> {
+fn run_list(cli: &Cli, activity: bool) -> Result<(), GriteError> {
     let git_dir = GriteContext::find_git_dir()?;
     let actors = list_actors(&git_dir)?;
+    let last_activity = if activity {
+        let store = open_store_at(&git_dir, store_wait(cli, &git_dir))?;
+        Some(store.actor_last_activity()?)
+    } else {
+        None
+    };
 
     let actor_infos: Vec<ActorInfo> = actors
         .into_iter()
         .map(|a| {
             let data_dir = actor_dir(&git_dir, &a.actor_id);
+            let last_event_ts = last_activity.as_ref().map(|seen| {
+                hex_to_id::<16>(&a.actor_id)
+                    .ok()
+                    .and_then(|id| seen.get(&id).copied())
+            });
             ActorInfo {
                 actor_id: a.actor_id,
                 label: a.label,
                 data_dir: data_dir.to_string_lossy().to_string(),
+                last_event_ts,
             }
         })
         .collect();
 
+    if activity && !cli.json {
+        if !cli.quiet {
+            for actor in &actor_infos {
+                println!(
+                    "{}  {}  last seen: {}",
+                    actor.actor_id,
+                    actor.label.as_deref().unwrap_or("-"),
+                    format_last_seen(actor.last_event_ts.flatten())
+                );
+            }
+        }
+        return Ok(());
+    }
+
     output_success(
         cli,
         ActorListOutput {
@@ -215,11 +232,8 @@ fn run_use(cli: &Cli, id: String) -> Result<(), GriteError> {
     let _config = load_actor_config(&data_dir)?;
 
     // Update repo config
-    let mut repo_config = load_repo_config(&git_dir)?.unwrap_or_default();
-    repo_config.default_actor = Some(id.clone());
-
+    update_repo_config(&git_dir, |cfg| cfg.default_actor = Some(id.clone()))?;
     let repo_config_path = git_dir.join("grite").join("config.toml");
-    save_repo_config(&git_dir, &repo_config)?;
 
     output_success(
         cli,
@@ -231,3 +245,32 @@ fn run_use(cli: &Cli, id: String) -> Result<(), GriteError> {
 
     Ok(())
 }
+
+fn run_rotate_key(cli: &Cli) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let lib_ctx = libgrite_cli::GriteContext {
+        git_dir: ctx.git_dir.clone(),
+        actor_id: ctx.actor_id.clone(),
+        actor_config: ctx.actor_config.clone(),
+        data_dir: ctx.data_dir.clone(),
+        source: match ctx.source {
+            ActorSource::DataDir => libgrite_cli::context::ActorSource::DataDir,
+            ActorSource::Flag => libgrite_cli::context::ActorSource::Flag,
+            ActorSource::RepoDefault => libgrite_cli::context::ActorSource::RepoDefault,
+            ActorSource::Auto => libgrite_cli::context::ActorSource::Auto,
+        },
+    };
+    let result = libgrite_cli::actor::actor_rotate_key(&lib_ctx)?;
+
+    output_success(
+        cli,
+        ActorRotateKeyOutput {
+            actor_id: result.actor_id,
+            public_key: result.public_key,
+            previous_public_key: result.previous_public_key,
+            effective_ts: result.effective_ts,
+        },
+    );
+
+    Ok(())
+}
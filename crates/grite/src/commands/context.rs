@@ -1,4 +1,5 @@
 use sha2::{Digest, Sha256};
+use std::path::Path;
 use std::process::Command as StdCommand;
 
 use crate::cli::{Cli, ContextCommand};
@@ -17,22 +18,24 @@ use libgrite_core::{
 pub fn run(cli: &Cli, cmd: ContextCommand) -> Result<(), GriteError> {
     match cmd {
         ContextCommand::Index {
+            mut paths,
             path,
             force,
             pattern,
-        } => run_index(cli, path, force, pattern),
-        ContextCommand::Query { query } => run_query(cli, query),
+        } => {
+            paths.extend(path);
+            run_index(cli, paths, force, pattern)
+        }
+        ContextCommand::Query { query, limit } => run_query(cli, query, limit),
         ContextCommand::Show { path } => run_show(cli, path),
+        ContextCommand::Recent { limit } => run_recent(cli, limit),
         ContextCommand::Project { key } => run_project(cli, key),
         ContextCommand::Set { key, value } => run_set(cli, key, value),
     }
 }
 
 fn current_ts() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+    libgrite_core::clock::now_ms()
 }
 
 fn run_index(
@@ -48,19 +51,33 @@ fn run_index(
     let actor_id_bytes = libgrite_core::types::ids::hex_to_id::<16>(&ctx.actor_id)
         .map_err(|e| GriteError::InvalidArgs(format!("Invalid actor ID: {}", e)))?;
 
-    // Get list of files to index
-    let files = get_files_to_index(&paths, &pattern)?;
+    // Get list of files to index, relative to the repository root
+    let repo_root = ctx.repo_root();
+    let files = get_files_to_index(&repo_root, &paths, &pattern)?;
 
     let mut indexed = 0u32;
     let mut skipped = 0u32;
 
     for file_path in &files {
-        // Read file content
-        let content = match std::fs::read_to_string(file_path) {
-            Ok(c) => c,
-            Err(_) => {
+        // Skip files in languages we cannot extract symbols from
+        let language = detect_language(file_path);
+        if language == "unknown" {
+            skipped += 1;
+            continue;
+        }
+
+        // Read file content, skipping binary or unreadable files
+        let content = match std::fs::read(repo_root.join(file_path)) {
+            Ok(bytes) if !bytes.contains(&0) => match String::from_utf8(bytes) {
+                Ok(c) => c,
+                Err(_) => {
+                    skipped += 1;
+                    continue;
+                }
+            },
+            _ => {
                 skipped += 1;
-                continue; // Skip binary or unreadable files
+                continue;
             }
         };
 
@@ -80,12 +97,6 @@ fn run_index(
         }
 
         // Extract symbols
-        let language = detect_language(file_path);
-        if language == "unknown" {
-            skipped += 1;
-            continue;
-        }
-
         let symbols = extract_symbols(&content, language);
         let summary = generate_summary(file_path, &symbols, language);
 
@@ -116,11 +127,11 @@ fn run_index(
     Ok(())
 }
 
-fn run_query(cli: &Cli, query: String) -> Result<(), GriteError> {
+fn run_query(cli: &Cli, query: String, limit: Option<usize>) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
     let store = ctx.open_store()?;
 
-    let results = store.query_symbols(&query)?;
+    let results = store.query_symbols(&query, limit)?;
 
     let matches: Vec<serde_json::Value> = results
         .iter()
@@ -176,6 +187,32 @@ fn run_show(cli: &Cli, path: String) -> Result<(), GriteError> {
     Ok(())
 }
 
+fn run_recent(cli: &Cli, limit: Option<usize>) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+
+    let files: Vec<serde_json::Value> = store
+        .recent_context_files(limit)?
+        .iter()
+        .map(|f| {
+            serde_json::json!({
+                "path": f.path,
+                "language": f.language,
+                "updated_ts": f.version.ts_unix_ms,
+                "updated_by": id_to_hex(&f.version.actor),
+            })
+        })
+        .collect();
+
+    let output = serde_json::json!({
+        "files": files,
+        "count": files.len(),
+    });
+
+    output_success(cli, &output);
+    Ok(())
+}
+
 fn run_project(cli: &Cli, key: Option<String>) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
     let store = ctx.open_store()?;
@@ -247,13 +284,27 @@ fn run_set(cli: &Cli, key: String, value: String) -> Result<(), GriteError> {
     Ok(())
 }
 
-/// Get the list of files to index, using git ls-files
+/// File listing ignore patterns for `grite context index`, at the repo root
+const GRITIGNORE_FILE: &str = ".gritignore";
+
+/// Get the list of files to index, relative to the repository root
+///
+/// Lists tracked files plus untracked files not excluded by `.gitignore`,
+/// then drops those matching `.gritignore`.
 fn get_files_to_index(
+    repo_root: &Path,
     paths: &[String],
     pattern: &Option<String>,
 ) -> Result<Vec<String>, GriteError> {
     let mut cmd = StdCommand::new("git");
-    cmd.arg("ls-files");
+    cmd.args([
+        "ls-files",
+        "--cached",
+        "--others",
+        "--exclude-standard",
+        "--full-name",
+        "--",
+    ]);
 
     if !paths.is_empty() {
         for p in paths {
@@ -275,6 +326,11 @@ fn get_files_to_index(
         .filter(|l| !l.is_empty())
         .map(|l| l.to_string())
         .collect();
+    files.sort();
+    files.dedup();
+
+    let ignored = read_gritignore(repo_root)?;
+    files.retain(|f| !is_ignored(f, &ignored));
 
     // Apply glob pattern filter if specified
     if let Some(pat) = pattern {
@@ -285,3 +341,39 @@ fn get_files_to_index(
 
     Ok(files)
 }
+
+/// Read the patterns in `.gritignore`, skipping blank lines and `#` comments
+fn read_gritignore(repo_root: &Path) -> Result<Vec<glob::Pattern>, GriteError> {
+    let content = match std::fs::read_to_string(repo_root.join(GRITIGNORE_FILE)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| {
+            let l = l.trim_start_matches('/').trim_end_matches('/');
+            glob::Pattern::new(l).map_err(|e| {
+                GriteError::InvalidArgs(format!("Invalid pattern '{}' in .gritignore: {}", l, e))
+            })
+        })
+        .collect()
+}
+
+/// Whether `.gritignore` excludes `path`
+///
+/// As in `.gitignore`, a pattern without a slash matches any file or
+/// directory name, and one with a slash matches from the repo root. Matching
+/// a directory excludes everything under it.
+fn is_ignored(path: &str, patterns: &[glob::Pattern]) -> bool {
+    let components: Vec<&str> = path.split('/').collect();
+    patterns.iter().any(|pattern| {
+        if pattern.as_str().contains('/') {
+            (1..=components.len()).any(|n| pattern.matches(&components[..n].join("/")))
+        } else {
+            components.iter().any(|c| pattern.matches(c))
+        }
+    })
+}
@@ -1,14 +1,25 @@
 use crate::cli::{Cli, DbCommand};
 use crate::context::GriteContext;
+use crate::event_helper::insert_and_append;
 use crate::output::output_success;
 use libgrite_core::{
-    config::list_actors,
-    integrity::{check_store_integrity, verify_store_signatures, CorruptionKind},
-    types::ids::id_to_hex,
+    config::{list_actors, parse_repo_config, update_repo_config, RepoConfig},
+    context::PROJECT_CONTEXT_ISSUE_ID,
+    export::{import_attachments, parse_kinds, retain_kinds, AttachmentJson, EventJson},
+    hash::compute_event_id,
+    integrity::{
+        check_store_integrity, load_key_histories, sign_unsigned_events, verify_store_signatures,
+        CorruptionKind,
+    },
+    signing::{actor_key_context_key, ACTOR_KEY_CONTEXT_PREFIX},
+    types::event::{Event, EventKind},
+    types::ids::{id_to_hex, parse_event_id},
     GriteError,
 };
-use serde::Serialize;
-use std::collections::HashMap;
+use libgrite_git::StoreArchive;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
 
 #[derive(Serialize)]
 struct DbStatsOutput {
@@ -20,6 +31,9 @@ struct DbStatsOutput {
     events_since_rebuild: usize,
     days_since_rebuild: Option<u32>,
     rebuild_recommended: bool,
+    context_file_count: usize,
+    context_symbol_count: usize,
+    last_context_update_ts: Option<u64>,
 }
 
 pub fn run(cli: &Cli, cmd: DbCommand) -> Result<(), GriteError> {
@@ -27,6 +41,12 @@ pub fn run(cli: &Cli, cmd: DbCommand) -> Result<(), GriteError> {
         DbCommand::Stats => run_stats(cli),
         DbCommand::Check { verify_parents } => run_check(cli, verify_parents),
         DbCommand::Verify { verbose } => run_verify(cli, verbose),
+        DbCommand::Events { kind } => run_events(cli, &kind),
+        DbCommand::SignHistory => run_sign_history(cli),
+        DbCommand::Archive { file } => run_archive(cli, &file),
+        DbCommand::Restore { file, force, kinds } => run_restore(cli, &file, force, kinds),
+        DbCommand::EventPreimage { event_id } => run_event_preimage(cli, &event_id),
+        DbCommand::ImportAttachments { file } => run_import_attachments(cli, &file),
     }
 }
 
@@ -48,6 +68,9 @@ fn run_stats(cli: &Cli) -> Result<(), GriteError> {
             events_since_rebuild: stats.events_since_rebuild,
             days_since_rebuild: stats.days_since_rebuild,
             rebuild_recommended: stats.rebuild_recommended,
+            context_file_count: stats.context_file_count,
+            context_symbol_count: stats.context_symbol_count,
+            last_context_update_ts: stats.last_context_update_ts,
         },
     );
 
@@ -139,17 +162,16 @@ fn run_verify(cli: &Cli, verbose: bool) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
     let store = ctx.open_store()?;
 
-    // Build a map of actor_id -> public_key from all actors
+    // Build each actor's key history (rotations plus configured keys)
     let actors = list_actors(&ctx.git_dir)?;
-    let mut public_keys: HashMap<String, String> = HashMap::new();
+    let key_histories = load_key_histories(&store, &actors)?;
 
-    for actor in &actors {
-        if let Some(pk) = &actor.public_key {
-            public_keys.insert(actor.actor_id.clone(), pk.clone());
-        }
-    }
-
-    let get_public_key = |actor_id: &str| -> Option<String> { public_keys.get(actor_id).cloned() };
+    let get_public_key = |actor_id: &str, ts: u64| -> Option<String> {
+        key_histories
+            .get(actor_id)
+            .and_then(|h| h.key_at(ts))
+            .map(str::to_string)
+    };
 
     let report = verify_store_signatures(&store, get_public_key)?;
 
@@ -197,3 +219,299 @@ fn run_verify(cli: &Cli, verbose: bool) -> Result<(), GriteError> {
 
     Ok(())
 }
+
+#[derive(Serialize)]
+struct DbEventsOutput {
+    kind: &'static str,
+    count: usize,
+    events: Vec<EventJson>,
+}
+
+fn run_events(cli: &Cli, kind: &str) -> Result<(), GriteError> {
+    let tag = EventKind::tag_for_name(kind).ok_or_else(|| {
+        GriteError::InvalidArgs(format!(
+            "Unknown event kind '{}'. Valid kinds: {}",
+            kind,
+            EventKind::names().join(", ")
+        ))
+    })?;
+
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+    let events = store.events_of_kind(tag)?;
+
+    output_success(
+        cli,
+        DbEventsOutput {
+            kind: EventKind::names()[tag as usize - 1],
+            count: events.len(),
+            events: events.iter().map(EventJson::from).collect(),
+        },
+    );
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DbSignHistoryOutput {
+    actor_id: String,
+    events_signed: usize,
+    already_signed: usize,
+    other_actors: usize,
+    key_mismatch: usize,
+    /// Whether a record of the actor's key was added to project context
+    key_record_added: bool,
+}
+
+fn run_sign_history(cli: &Cli) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let keypair = ctx.load_signing_key().ok_or_else(|| {
+        GriteError::InvalidArgs(
+            "No signing key for this actor. Run 'grite actor rotate-key' to create one".to_string(),
+        )
+    })?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+    let store = ctx.open_store()?;
+
+    let histories = load_key_histories(&store, &list_actors(&ctx.git_dir)?)?;
+    let mut history = histories.get(&ctx.actor_id).cloned().unwrap_or_default();
+    let wal = ctx.open_wal()?;
+
+    // Other clones only know keys recorded in project context. Record this
+    // one now if the actor has no record yet; an actor's first key also
+    // covers its earlier events, so the backfilled signatures verify there.
+    let record_prefix = format!("{}{}/", ACTOR_KEY_CONTEXT_PREFIX, ctx.actor_id);
+    let needs_key_record = !store
+        .list_project_context()?
+        .iter()
+        .any(|(key, _)| key.starts_with(&record_prefix));
+    if needs_key_record {
+        let ts = libgrite_core::clock::now_ms();
+        let kind = EventKind::ProjectContextUpdated {
+            key: actor_key_context_key(&ctx.actor_id, ts),
+            value: keypair.public_key_hex(),
+        };
+        let event_id = compute_event_id(&PROJECT_CONTEXT_ISSUE_ID, &actor, ts, None, &kind);
+        let event = ctx.sign_event(Event::new(
+            event_id,
+            PROJECT_CONTEXT_ISSUE_ID,
+            actor,
+            ts,
+            None,
+            kind,
+        ));
+        insert_and_append(&store, &wal, &actor, &event)?;
+        history.add(ts, keypair.public_key_hex());
+    }
+
+    // The signed copies go to the WAL first so the signatures reach other
+    // clones; event IDs do not change, and readers keep the signed copy.
+    let report = sign_unsigned_events(
+        &store,
+        &actor,
+        &keypair,
+        |ts| history.key_at(ts).map(str::to_string),
+        |events| {
+            wal.append(&actor, events)?;
+            Ok(())
+        },
+    )?;
+    store.flush()?;
+
+    output_success(
+        cli,
+        DbSignHistoryOutput {
+            actor_id: ctx.actor_id.clone(),
+            events_signed: report.events_signed,
+            already_signed: report.already_signed,
+            other_actors: report.other_actors,
+            key_mismatch: report.key_mismatch,
+            key_record_added: needs_key_record,
+        },
+    );
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DbArchiveOutput {
+    path: String,
+    event_count: usize,
+    attachment_count: usize,
+    config_included: bool,
+}
+
+fn run_archive(cli: &Cli, file: &Path) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+
+    let config_path = ctx.git_dir.join("grite").join("config.toml");
+    let repo_config = match std::fs::read_to_string(&config_path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let archive = StoreArchive::new(
+        store.get_all_events()?,
+        store.get_all_attachments()?,
+        repo_config,
+    );
+    archive.write_to(file)?;
+
+    output_success(
+        cli,
+        DbArchiveOutput {
+            path: file.to_string_lossy().to_string(),
+            event_count: archive.manifest.event_count,
+            attachment_count: archive.manifest.attachment_count,
+            config_included: archive.repo_config.is_some(),
+        },
+    );
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DbRestoreOutput {
+    path: String,
+    event_count: usize,
+    issue_count: usize,
+    attachment_count: usize,
+    config_restored: bool,
+}
+
+fn run_restore(
+    cli: &Cli,
+    file: &Path,
+    force: bool,
+    kinds: Option<String>,
+) -> Result<(), GriteError> {
+    let mut archive = StoreArchive::read_from(file)?;
+    if let Some(kinds) = kinds.as_deref().map(parse_kinds).transpose()? {
+        retain_kinds(&mut archive.events, Some(&kinds));
+        // Issue events replay onto the projection that IssueCreated starts
+        let created: HashSet<_> = archive
+            .events
+            .iter()
+            .filter(|e| matches!(e.kind, EventKind::IssueCreated { .. }))
+            .map(|e| e.issue_id)
+            .collect();
+        let orphaned = archive.events.iter().any(|e| {
+            !matches!(
+                e.kind,
+                EventKind::ContextUpdated { .. } | EventKind::ProjectContextUpdated { .. }
+            ) && !created.contains(&e.issue_id)
+        });
+        if orphaned {
+            return Err(GriteError::InvalidArgs(
+                "--kinds must include issue_created to restore other issue events".to_string(),
+            ));
+        }
+    }
+    let archived_config: Option<RepoConfig> = archive
+        .repo_config
+        .as_deref()
+        .map(parse_repo_config)
+        .transpose()?;
+
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+
+    let existing = store.stats(&ctx.sled_path())?.event_count;
+    if existing > 0 && !force {
+        return Err(GriteError::Conflict(format!(
+            "Store already contains {} events; use --force to replace them",
+            existing
+        )));
+    }
+
+    let stats = store.rebuild_from_events(&archive.events)?;
+    for data in &archive.attachments {
+        store.put_attachment(data)?;
+    }
+    store.flush()?;
+
+    // Actor directories are not archived, so keep this clone's default actor
+    let config_restored = archived_config.is_some();
+    if let Some(archived) = archived_config {
+        update_repo_config(&ctx.git_dir, |cfg| {
+            let default_actor = cfg.default_actor.take();
+            *cfg = archived;
+            if default_actor.is_some() {
+                cfg.default_actor = default_actor;
+            }
+        })?;
+    }
+
+    output_success(
+        cli,
+        DbRestoreOutput {
+            path: file.to_string_lossy().to_string(),
+            event_count: stats.event_count,
+            issue_count: stats.issue_count,
+            attachment_count: archive.attachments.len(),
+            config_restored,
+        },
+    );
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct DbEventPreimageOutput {
+    event_id: String,
+    computed_id: String,
+    matches: bool,
+    preimage_hex: String,
+}
+
+fn run_event_preimage(cli: &Cli, event_id: &str) -> Result<(), GriteError> {
+    let event_id = parse_event_id(event_id).map_err(|e| GriteError::InvalidArgs(e.to_string()))?;
+
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+    let preimage = store.event_preimage(&event_id)?;
+
+    output_success(
+        cli,
+        DbEventPreimageOutput {
+            event_id: id_to_hex(&preimage.event_id),
+            computed_id: id_to_hex(&preimage.computed_id),
+            matches: preimage.computed_id == preimage.event_id,
+            preimage_hex: hex::encode(&preimage.preimage),
+        },
+    );
+
+    Ok(())
+}
+
+/// The part of a JSON export that `import-attachments` reads
+#[derive(Deserialize)]
+struct ExportAttachments {
+    #[serde(default)]
+    attachments: Vec<AttachmentJson>,
+}
+
+#[derive(Serialize)]
+struct DbImportAttachmentsOutput {
+    attachments_imported: usize,
+}
+
+fn run_import_attachments(cli: &Cli, file: &Path) -> Result<(), GriteError> {
+    let export: ExportAttachments = serde_json::from_slice(&std::fs::read(file)?)?;
+
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+    let attachments_imported = import_attachments(&store, &export.attachments)?;
+    store.flush()?;
+
+    output_success(
+        cli,
+        DbImportAttachmentsOutput {
+            attachments_imported,
+        },
+    );
+
+    Ok(())
+}
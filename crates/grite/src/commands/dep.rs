@@ -6,7 +6,7 @@ use libgrite_core::{
     hash::compute_event_id,
     store::IssueFilter,
     types::event::{DependencyType, Event, EventKind},
-    types::ids::{id_to_hex, parse_issue_id},
+    types::ids::id_to_hex,
     GriteError,
 };
 
@@ -39,10 +39,7 @@ fn parse_dep_type(s: &str) -> Result<DependencyType, GriteError> {
 }
 
 fn current_ts() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+    libgrite_core::clock::now_ms()
 }
 
 fn run_add(cli: &Cli, id: String, target: String, dep_type_str: String) -> Result<(), GriteError> {
@@ -50,17 +47,15 @@ fn run_add(cli: &Cli, id: String, target: String, dep_type_str: String) -> Resul
     let store = ctx.open_store()?;
     let wal = ctx.open_wal()?;
 
-    let issue_id = parse_issue_id(&id)
-        .map_err(|e| GriteError::InvalidArgs(format!("Invalid issue ID: {}", e)))?;
-    let target_id = parse_issue_id(&target)
-        .map_err(|e| GriteError::InvalidArgs(format!("Invalid target ID: {}", e)))?;
+    let issue_id = store.resolve_issue_id(&id)?;
+    let target_id = store.resolve_issue_id(&target)?;
     let dep_type = parse_dep_type(&dep_type_str)?;
 
     // Verify both issues exist
-    if store.get_issue(&issue_id)?.is_none() {
+    if !store.issue_exists(&issue_id)? {
         return Err(GriteError::NotFound(format!("Issue {} not found", id)));
     }
-    if store.get_issue(&target_id)?.is_none() {
+    if !store.issue_exists(&target_id)? {
         return Err(GriteError::NotFound(format!(
             "Target issue {} not found",
             target
@@ -90,8 +85,8 @@ fn run_add(cli: &Cli, id: String, target: String, dep_type_str: String) -> Resul
 
     let output = serde_json::json!({
         "event_id": id_to_hex(&event_id),
-        "issue_id": id,
-        "target": target,
+        "issue_id": id_to_hex(&issue_id),
+        "target": id_to_hex(&target_id),
         "dep_type": dep_type.as_str(),
         "action": "added"
     });
@@ -110,10 +105,8 @@ fn run_remove(
     let store = ctx.open_store()?;
     let wal = ctx.open_wal()?;
 
-    let issue_id = parse_issue_id(&id)
-        .map_err(|e| GriteError::InvalidArgs(format!("Invalid issue ID: {}", e)))?;
-    let target_id = parse_issue_id(&target)
-        .map_err(|e| GriteError::InvalidArgs(format!("Invalid target ID: {}", e)))?;
+    let issue_id = store.resolve_issue_id(&id)?;
+    let target_id = store.resolve_issue_id(&target)?;
     let dep_type = parse_dep_type(&dep_type_str)?;
 
     let actor_id_bytes = libgrite_core::types::ids::hex_to_id::<16>(&ctx.actor_id)
@@ -131,8 +124,8 @@ fn run_remove(
 
     let output = serde_json::json!({
         "event_id": id_to_hex(&event_id),
-        "issue_id": id,
-        "target": target,
+        "issue_id": id_to_hex(&issue_id),
+        "target": id_to_hex(&target_id),
         "dep_type": dep_type.as_str(),
         "action": "removed"
     });
@@ -145,8 +138,7 @@ fn run_list(cli: &Cli, id: String, reverse: bool) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
     let store = ctx.open_store()?;
 
-    let issue_id = parse_issue_id(&id)
-        .map_err(|e| GriteError::InvalidArgs(format!("Invalid issue ID: {}", e)))?;
+    let issue_id = store.resolve_issue_id(&id)?;
 
     let deps = if reverse {
         store.get_dependents(&issue_id)?
@@ -172,7 +164,7 @@ fn run_list(cli: &Cli, id: String, reverse: bool) -> Result<(), GriteError> {
         .collect();
 
     let output = serde_json::json!({
-        "issue_id": id,
+        "issue_id": id_to_hex(&issue_id),
         "direction": if reverse { "dependents" } else { "dependencies" },
         "deps": dep_list,
     });
@@ -192,6 +184,7 @@ fn run_topo(cli: &Cli, state: Option<String>, label: Option<String>) -> Result<(
             _ => libgrite_core::types::event::IssueState::Open,
         }),
         label,
+        ..Default::default()
     };
 
     let sorted = store.topological_order(&filter)?;
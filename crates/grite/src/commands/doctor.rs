@@ -3,16 +3,18 @@
 use std::collections::HashSet;
 use std::fs;
 
-use libgrite_core::config::{actor_sled_path, list_actors};
+use libgrite_core::config::{actor_sled_path, list_actors, load_repo_config, update_repo_config};
 use libgrite_core::integrity::check_store_integrity;
-use libgrite_core::{EventId, GriteError, GriteStore};
+use libgrite_core::store::DEFAULT_LARGE_EVENT_WARN_BYTES;
+use libgrite_core::types::ids::id_to_hex;
+use libgrite_core::{EventId, GriteError, GriteStore, IssueEventsReindex};
 use libgrite_git::WalManager;
 use serde::Serialize;
 
 use crate::cli::Cli;
 use crate::commands::daemon::{is_daemon_running, start_daemon, stop_daemon};
 use crate::context::{ExecutionMode, GriteContext};
-use crate::output::output_success;
+use crate::output::{id_len, output_success, short_id};
 
 #[derive(Serialize)]
 struct DoctorOutput {
@@ -102,10 +104,36 @@ pub fn run(cli: &Cli, fix: bool) -> Result<(), GriteError> {
     // Check 5: Rebuild threshold
     checks.push(check_rebuild_threshold(cli));
 
+    // Counter repair runs before the other fixes so a rebuild resets it last
+    if fix {
+        if let Ok((before, after)) = fix_rebuild_counter(cli) {
+            if before != after {
+                applied.push(format!(
+                    "recounted events since rebuild ({} -> {})",
+                    before, after
+                ));
+                if let Some(c) = checks.iter_mut().find(|c| c.id == "rebuild_threshold") {
+                    *c = check_rebuild_threshold(cli);
+                }
+            }
+        }
+    }
+
     // Check 6: Legacy per-actor sleds
     let (orphan_check, needs_merge) = check_legacy_actor_sleds(cli);
     checks.push(orphan_check);
 
+    // Check 7: Oversized events
+    checks.push(check_large_events(cli));
+
+    // Check 8: Short ID collisions
+    let (id_check, needed_id_len) = check_id_collisions(cli);
+    checks.push(id_check);
+
+    // Check 9: issue_events index matches the events tree
+    let (index_check, needs_reindex) = check_issue_events_index(cli);
+    checks.push(index_check);
+
     // Auto-repair if requested
     if fix && needs_rebuild {
         if let Ok(ctx) = GriteContext::resolve(cli) {
@@ -168,6 +196,43 @@ pub fn run(cli: &Cli, fix: bool) -> Result<(), GriteError> {
         }
     }
 
+    if fix && needs_reindex {
+        if let Ok(report) = fix_issue_events_index(cli) {
+            applied.push(format!(
+                "reindexed issue events ({} added, {} removed)",
+                report.missing, report.stale
+            ));
+            if let Some(c) = checks.iter_mut().find(|c| c.id == "issue_events_index") {
+                *c = CheckResult::ok("issue_events_index", "Issue event index rebuilt");
+            }
+        }
+    }
+
+    if fix {
+        if let Some(len) = needed_id_len {
+            match fix_id_len(cli, len) {
+                Ok(()) => {
+                    applied.push(format!("set id_len = {}", len));
+                    if let Some(c) = checks.iter_mut().find(|c| c.id == "id_collisions") {
+                        *c = CheckResult::ok(
+                            "id_collisions",
+                            &format!("id_len set to {} in repo config", len),
+                        );
+                    }
+                }
+                Err(e) => {
+                    if let Some(c) = checks.iter_mut().find(|c| c.id == "id_collisions") {
+                        *c = CheckResult::error(
+                            "id_collisions",
+                            &format!("Setting id_len = {} failed: {}", len, e),
+                            vec![],
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     // Restart daemon if we stopped it
     if daemon_was_running {
         if !cli.quiet && !cli.json {
@@ -304,10 +369,7 @@ fn check_actor_config(cli: &Cli) -> CheckResult {
             } else {
                 CheckResult::ok(
                     "actor_config",
-                    &format!(
-                        "Actor configured: {}",
-                        &ctx.actor_id[..8.min(ctx.actor_id.len())]
-                    ),
+                    &format!("Actor configured: {}", short_id(&ctx.actor_id, id_len(cli))),
                 )
             }
         }
@@ -456,6 +518,250 @@ fn check_rebuild_threshold(cli: &Cli) -> CheckResult {
     }
 }
 
+/// Recompute the events_since_rebuild counter, returning (old, new) counts
+fn fix_rebuild_counter(cli: &Cli) -> Result<(usize, usize), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+    let before = store.stats(&ctx.sled_path())?.events_since_rebuild;
+    let after = store.recount_since_rebuild()?;
+    Ok((before, after))
+}
+
+/// Number of largest events listed by the large event check
+const LARGE_EVENTS_REPORTED: usize = 5;
+
+/// Report the largest stored events and flag any above the configured size
+fn check_large_events(cli: &Cli) -> CheckResult {
+    let ctx = match GriteContext::resolve(cli) {
+        Ok(ctx) => ctx,
+        Err(_) => {
+            return CheckResult::warn(
+                "large_events",
+                "Cannot check event sizes - no context",
+                vec!["Fix git_repo first"],
+            )
+        }
+    };
+
+    // Daemon holds the store; skip this check to avoid lock contention.
+    if store_held_by_daemon(cli) {
+        return CheckResult::ok("large_events", "Store held by running daemon");
+    }
+
+    let store = match ctx.open_store() {
+        Ok(store) => store,
+        Err(_) => {
+            return CheckResult::warn(
+                "large_events",
+                "Cannot check event sizes - cannot open store",
+                vec!["Fix store_integrity first"],
+            )
+        }
+    };
+
+    let threshold = load_repo_config(&ctx.git_dir)
+        .ok()
+        .flatten()
+        .map(|c| c.large_event_warn_bytes())
+        .unwrap_or(DEFAULT_LARGE_EVENT_WARN_BYTES);
+
+    let largest = match store.largest_events(LARGE_EVENTS_REPORTED) {
+        Ok(largest) => largest,
+        Err(e) => {
+            return CheckResult::warn(
+                "large_events",
+                &format!("Cannot scan event sizes: {}", e),
+                vec![],
+            )
+        }
+    };
+
+    let Some(top) = largest.first() else {
+        return CheckResult::ok("large_events", "No events stored");
+    };
+
+    let over = largest.iter().filter(|e| e.size_bytes > threshold).count();
+    if over == 0 {
+        return CheckResult::ok(
+            "large_events",
+            &format!(
+                "Largest event is {} bytes (limit {})",
+                top.size_bytes, threshold
+            ),
+        );
+    }
+
+    let offenders: Vec<String> = largest
+        .iter()
+        .map(|e| {
+            format!(
+                "{} bytes: {} on issue {}",
+                e.size_bytes,
+                e.kind,
+                short_id(&id_to_hex(&e.issue_id), id_len(cli))
+            )
+        })
+        .collect();
+    CheckResult {
+        id: "large_events".to_string(),
+        status: "warn".to_string(),
+        message: format!(
+            "{} of the {} largest events exceed {} bytes",
+            over,
+            largest.len(),
+            threshold
+        ),
+        plan: offenders,
+    }
+}
+
+/// Check whether issue IDs shortened to the current display length collide.
+///
+/// Returns the ID length that would tell every issue apart when the current
+/// one does not.
+fn check_id_collisions(cli: &Cli) -> (CheckResult, Option<usize>) {
+    let ctx = match GriteContext::resolve(cli) {
+        Ok(ctx) => ctx,
+        Err(_) => {
+            return (
+                CheckResult::warn(
+                    "id_collisions",
+                    "Cannot check ID collisions - no context",
+                    vec!["Fix git_repo first"],
+                ),
+                None,
+            )
+        }
+    };
+
+    // Daemon holds the store; skip this check to avoid lock contention.
+    if store_held_by_daemon(cli) {
+        return (
+            CheckResult::ok("id_collisions", "Store held by running daemon"),
+            None,
+        );
+    }
+
+    let needed = match ctx
+        .open_store()
+        .and_then(|store| store.min_unique_id_prefix())
+    {
+        Ok(needed) => needed,
+        Err(e) => {
+            return (
+                CheckResult::warn(
+                    "id_collisions",
+                    &format!("Cannot check ID collisions: {}", e),
+                    vec![],
+                ),
+                None,
+            )
+        }
+    };
+
+    let current = id_len(cli);
+    if needed <= current {
+        return (
+            CheckResult::ok(
+                "id_collisions",
+                &format!("Issue IDs are unique at {} characters", current),
+            ),
+            None,
+        );
+    }
+
+    let plan = format!("Set id_len = {} in .git/grite/config.toml", needed);
+    (
+        CheckResult::warn(
+            "id_collisions",
+            &format!(
+                "Issue IDs shortened to {} characters collide; {} are needed",
+                current, needed
+            ),
+            vec![&plan, "Run 'grite doctor --fix' to set it"],
+        ),
+        Some(needed),
+    )
+}
+
+/// Check that every stored event is indexed under its issue exactly once.
+///
+/// Returns whether the index needs rebuilding.
+fn check_issue_events_index(cli: &Cli) -> (CheckResult, bool) {
+    let ctx = match GriteContext::resolve(cli) {
+        Ok(ctx) => ctx,
+        Err(_) => {
+            return (
+                CheckResult::warn(
+                    "issue_events_index",
+                    "Cannot check issue event index - no context",
+                    vec!["Fix git_repo first"],
+                ),
+                false,
+            )
+        }
+    };
+
+    // Daemon holds the store; skip this check to avoid lock contention.
+    if store_held_by_daemon(cli) {
+        return (
+            CheckResult::ok("issue_events_index", "Store held by running daemon"),
+            false,
+        );
+    }
+
+    let report = match ctx
+        .open_store()
+        .and_then(|store| store.check_issue_events_index())
+    {
+        Ok(report) => report,
+        Err(e) => {
+            return (
+                CheckResult::warn(
+                    "issue_events_index",
+                    &format!("Cannot check issue event index: {}", e),
+                    vec![],
+                ),
+                false,
+            )
+        }
+    };
+
+    if report.is_clean() {
+        return (
+            CheckResult::ok("issue_events_index", "Issue event index is complete"),
+            false,
+        );
+    }
+
+    (
+        CheckResult::error(
+            "issue_events_index",
+            &format!(
+                "Issue event index is out of sync ({} event(s) missing, {} stale \
+                 entries); issue history may be incomplete",
+                report.missing, report.stale
+            ),
+            vec!["Run 'grite doctor --fix' to reindex issue events"],
+        ),
+        true,
+    )
+}
+
+/// Rebuild the issue_events index from the events tree
+fn fix_issue_events_index(cli: &Cli) -> Result<IssueEventsReindex, GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+    store.reindex_issue_events()
+}
+
+/// Write `len` as the repo's `id_len`
+fn fix_id_len(cli: &Cli, len: usize) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    update_repo_config(&ctx.git_dir, |config| config.id_len = Some(len))?;
+    Ok(())
+}
+
 /// Check for legacy per-actor sleds — actor directories under .git/grite/actors/
 /// that still contain a sled/ subdirectory with events not yet in the shared store.
 ///
@@ -0,0 +1,31 @@
+//! Error code listing for scripts
+
+use libgrite_core::{ErrorCodeInfo, GriteError};
+use serde::Serialize;
+
+use crate::cli::Cli;
+use crate::output::{output_success, print_human};
+
+#[derive(Serialize)]
+struct ErrorsOutput {
+    errors: Vec<ErrorCodeInfo>,
+}
+
+pub fn run(cli: &Cli) -> Result<(), GriteError> {
+    let errors = GriteError::code_table();
+
+    if cli.json {
+        output_success(cli, ErrorsOutput { errors });
+    } else {
+        let width = errors.iter().map(|e| e.variant.len()).max().unwrap_or(0);
+        print_human(cli, &format!("{:width$}  EXIT  CODE", "VARIANT"));
+        for e in &errors {
+            print_human(
+                cli,
+                &format!("{:width$}  {:<4}  {}", e.variant, e.exit_code, e.error_code),
+            );
+        }
+    }
+
+    Ok(())
+}
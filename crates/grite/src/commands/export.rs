@@ -1,8 +1,13 @@
-use crate::cli::{Cli, ExportFormat};
+use std::path::PathBuf;
+
+use crate::cli::{Cli, ExportFormat, ExportOrder};
 use crate::context::GriteContext;
-use crate::output::output_success;
+use crate::output::{output_success, print_human};
 use libgrite_core::{
-    export::{export_json, export_markdown, ExportSince},
+    export::{
+        export_counts, export_json_to, export_markdown_to, parse_kinds, write_streamed,
+        ExportOptions, ExportSince,
+    },
     types::ids::hex_to_id,
     GriteError,
 };
@@ -16,9 +21,26 @@ struct ExportOutput {
     event_count: usize,
 }
 
-pub fn run(cli: &Cli, format: ExportFormat, since: Option<String>) -> Result<(), GriteError> {
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    cli: &Cli,
+    format: Option<ExportFormat>,
+    since: Option<String>,
+    order: ExportOrder,
+    issue: Option<String>,
+    kinds: Option<String>,
+    count_only: bool,
+    include_attachments: bool,
+    anonymize: bool,
+    out: Option<PathBuf>,
+) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
     let store = ctx.open_store()?;
+    let issue = issue
+        .as_deref()
+        .map(|id| store.resolve_issue_id(id))
+        .transpose()?;
+    let kinds = kinds.as_deref().map(parse_kinds).transpose()?;
 
     // Parse since filter
     let since_filter = match since {
@@ -37,35 +59,60 @@ pub fn run(cli: &Cli, format: ExportFormat, since: Option<String>) -> Result<(),
         None => None,
     };
 
-    // Create .grite directory if needed
-    let repo_root = ctx.git_dir.parent().ok_or_else(|| {
-        GriteError::Internal("Cannot determine repository root from git directory".to_string())
-    })?;
-    let grite_export_dir = repo_root.join(".grite");
-    std::fs::create_dir_all(&grite_export_dir)?;
+    let options = ExportOptions {
+        order: match order {
+            ExportOrder::ByIssue => libgrite_core::ExportOrder::ByIssue,
+            ExportOrder::Chronological => libgrite_core::ExportOrder::Chronological,
+        },
+        issue,
+        kinds,
+        include_attachments,
+        anonymize,
+    };
 
-    let (format_str, output_path, event_count) = match format {
-        ExportFormat::Json => {
-            let export = export_json(&store, since_filter)?;
-            let output_path = grite_export_dir.join("export.json");
-            let content = serde_json::to_string_pretty(&export)?;
-            std::fs::write(&output_path, &content)?;
-            ("json".to_string(), output_path, export.meta.event_count)
+    if count_only {
+        let counts = export_counts(&store, since_filter, &options)?;
+        if cli.json {
+            output_success(cli, counts);
+        } else {
+            print_human(
+                cli,
+                &format!("{} events, {} issues", counts.events, counts.issues),
+            );
         }
-        ExportFormat::Md => {
-            let md = export_markdown(&store, since_filter)?;
-            let output_path = grite_export_dir.join("export.md");
-            std::fs::write(&output_path, &md)?;
-            // Count events by parsing (approximate)
-            let event_count = md.lines().filter(|l| l.starts_with("**ID:**")).count();
-            ("md".to_string(), output_path, event_count)
+        return Ok(());
+    }
+
+    let format = format.unwrap_or(ExportFormat::Json);
+    let format_str = match format {
+        ExportFormat::Json => "json",
+        ExportFormat::Md => "md",
+    };
+    let output_path = match out {
+        Some(path) => path,
+        None => {
+            // Create .grite directory if needed
+            let repo_root = ctx.git_dir.parent().ok_or_else(|| {
+                GriteError::Internal(
+                    "Cannot determine repository root from git directory".to_string(),
+                )
+            })?;
+            let grite_export_dir = repo_root.join(".grite");
+            std::fs::create_dir_all(&grite_export_dir)?;
+            grite_export_dir.join(format!("export.{}", format_str))
         }
     };
 
+    // Markdown exports report the number of issues written
+    let event_count = write_streamed(&output_path, |w| match format {
+        ExportFormat::Json => export_json_to(&store, since_filter, &options, w),
+        ExportFormat::Md => export_markdown_to(&store, since_filter, &options, w),
+    })?;
+
     output_success(
         cli,
         ExportOutput {
-            format: format_str,
+            format: format_str.to_string(),
             output_path: output_path.to_string_lossy().to_string(),
             wal_head: None,
             event_count,
@@ -1,11 +1,11 @@
 use crate::agents_md::GRITE_AGENTS_SECTION;
 use crate::cli::Cli;
 use crate::context::GriteContext;
-use crate::output::{output_success, print_human};
+use crate::output::{id_len, output_success, print_human, short_id};
 use libgrite_core::{
     config::{
         actor_dir, load_actor_config, load_repo_config, repo_sled_path, save_actor_config,
-        save_repo_config, RepoConfig,
+        update_repo_config,
     },
     types::actor::ActorConfig,
     types::ids::{generate_actor_id, id_to_hex},
@@ -68,11 +68,9 @@ pub fn run(cli: &Cli, no_agents_md: bool) -> Result<(), GriteError> {
             let sled_path = repo_sled_path(&git_dir);
             let _store = GriteStore::open_locked(&sled_path)?;
 
-            let repo_config = RepoConfig {
-                default_actor: Some(actor_id_hex.clone()),
-                ..Default::default()
-            };
-            save_repo_config(&git_dir, &repo_config)?;
+            update_repo_config(&git_dir, |cfg| {
+                cfg.default_actor = Some(actor_id_hex.clone())
+            })?;
 
             (actor_id_hex, data_dir, true)
         }
@@ -103,12 +101,18 @@ pub fn run(cli: &Cli, no_agents_md: bool) -> Result<(), GriteError> {
     if is_new {
         print_human(
             cli,
-            &format!("Initialized grite with actor {}", &actor_id_hex[..8]),
+            &format!(
+                "Initialized grite with actor {}",
+                short_id(&actor_id_hex, id_len(cli))
+            ),
         );
     } else {
         print_human(
             cli,
-            &format!("Already initialized with actor {}", &actor_id_hex[..8]),
+            &format!(
+                "Already initialized with actor {}",
+                short_id(&actor_id_hex, id_len(cli))
+            ),
         );
     }
 
@@ -1,20 +1,61 @@
 use crate::cli::{
-    AssigneeCommand, AttachmentCommand, Cli, IssueCommand, LabelCommand, LinkCommand,
+    AssigneeCommand, AttachmentCommand, Cli, Command, FieldCommand, IssueCommand, IssueSort,
+    LabelCommand, LinkCommand, SeverityLevel,
 };
-use crate::context::GriteContext;
-use crate::event_helper::insert_and_append;
-use crate::output::{format_issue_table, output_success, IssueRow};
+use crate::context::{ExecutionMode, GriteContext};
+use crate::event_helper::{insert_all_and_append, insert_and_append};
+use crate::output::{
+    actor_display, actor_names, color_enabled, format_blocking_chain, format_issue_tree, id_len,
+    output_success, print_human, stdout_writer, write_issue_table, BlockerRow, IssueRow,
+    IssueTreeNode,
+};
+use crate::router;
 use libgrite_core::{
-    hash::compute_event_id,
+    config::{load_issue_template, load_repo_config, IssueTemplate},
+    hash::{build_canonical_cbor, compute_event_id},
     lock::LockCheckResult,
+    projection::resolve_comment_prefix,
     store::IssueFilter,
-    types::event::{Event, EventKind, IssueState},
-    types::ids::{generate_issue_id, hex_to_id, id_to_hex},
-    types::issue::IssueSummary,
-    GriteError,
+    types::event::{Event, EventKind, IssueState, Severity},
+    types::ids::{generate_issue_id, hex_to_id, id_to_hex, ActorId, EventId, IssueId},
+    types::issue::{IssueLink, IssueSummary},
+    DependencyRef, GriteError,
 };
 use libgrite_git;
+use libgrite_ipc::IpcCommand;
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Load a named issue template from `.git/grite/templates` or the repo config
+fn load_template(name: &str) -> Result<IssueTemplate, GriteError> {
+    let git_dir = GriteContext::find_git_dir()?;
+    load_issue_template(&git_dir, name)
+}
+
+/// Expand `issue create --template` in place, before the command is routed.
+///
+/// Explicit `--body` and `--label` values win over the template.
+pub fn apply_template(cli: &mut Cli) -> Result<(), GriteError> {
+    if let Command::Issue {
+        cmd:
+            IssueCommand::Create {
+                title,
+                body,
+                label,
+                template,
+                ..
+            },
+    } = &mut cli.command
+    {
+        if let Some(name) = template.take() {
+            let (new_title, new_body, labels) = load_template(&name)?.apply(title, body, label);
+            *title = new_title;
+            *body = new_body;
+            *label = labels;
+        }
+    }
+    Ok(())
+}
 
 /// Check lock for an issue operation
 ///
@@ -144,12 +185,18 @@ struct IssueSummaryJson {
     labels: Vec<String>,
     assignees: Vec<String>,
     created_ts: u64,
+    /// Creating actor; `None` for issues stored before it was recorded
+    created_by: Option<String>,
+    /// Label of the creating actor, if it is one of this repo's actors
+    created_by_name: Option<String>,
     updated_ts: u64,
     comment_count: usize,
+    blocks_count: usize,
+    deleted: bool,
 }
 
-impl From<&IssueSummary> for IssueSummaryJson {
-    fn from(s: &IssueSummary) -> Self {
+impl IssueSummaryJson {
+    fn new(s: &IssueSummary, names: &HashMap<ActorId, String>) -> Self {
         Self {
             issue_id: id_to_hex(&s.issue_id),
             title: s.title.clone(),
@@ -157,8 +204,12 @@ impl From<&IssueSummary> for IssueSummaryJson {
             labels: s.labels.clone(),
             assignees: s.assignees.clone(),
             created_ts: s.created_ts,
+            created_by: s.created_by.map(|actor| id_to_hex(&actor)),
+            created_by_name: s.created_by.and_then(|actor| names.get(&actor).cloned()),
             updated_ts: s.updated_ts,
             comment_count: s.comment_count,
+            blocks_count: s.blocks_count,
+            deleted: s.deleted,
         }
     }
 }
@@ -166,9 +217,50 @@ impl From<&IssueSummary> for IssueSummaryJson {
 #[derive(Serialize)]
 struct IssueShowOutput {
     issue: IssueSummaryJson,
+    /// Competing same-timestamp titles, if any
+    title_conflict: Option<Vec<String>>,
+    /// Body edits after creation; earlier bodies are in `events`
+    body_revisions: u32,
+    fields: BTreeMap<String, String>,
+    issue_links: Vec<IssueLinkJson>,
+    dependencies: Vec<DependencyRefJson>,
+    dependents: Vec<DependencyRefJson>,
+    subscribers: Vec<String>,
+    estimate_minutes: Option<u32>,
+    logged_minutes: u64,
+    milestone: Option<String>,
+    severity: Option<&'static str>,
+    /// Time from creation to the first response by someone else
+    first_response_ms: Option<u64>,
+    /// Digest of the issue's event IDs; changes whenever an event is added
+    digest: String,
     events: Vec<EventJson>,
 }
 
+#[derive(Serialize)]
+struct DependencyRefJson {
+    target: String,
+    dep_type: &'static str,
+    /// None if the other issue no longer exists
+    title: Option<String>,
+}
+
+impl From<&DependencyRef> for DependencyRefJson {
+    fn from(r: &DependencyRef) -> Self {
+        Self {
+            target: id_to_hex(&r.issue_id),
+            dep_type: r.dep_type.as_str(),
+            title: r.title.clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct IssueLinkJson {
+    target: String,
+    relation: String,
+}
+
 #[derive(Serialize)]
 struct EventJson {
     event_id: String,
@@ -179,6 +271,22 @@ struct EventJson {
     kind: serde_json::Value,
 }
 
+#[derive(Serialize)]
+struct IssueDupOutput {
+    issue_id: String,
+    target: String,
+    event_ids: Vec<String>,
+    state: String,
+    wal_head: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SimulatedEventOutput {
+    simulated: bool,
+    event: EventJson,
+    preimage_hex: String,
+}
+
 #[derive(Serialize)]
 struct IssueUpdateOutput {
     issue_id: String,
@@ -186,6 +294,13 @@ struct IssueUpdateOutput {
     wal_head: Option<String>,
 }
 
+#[derive(Serialize)]
+struct IssueBatchOutput {
+    issue_ids: Vec<String>,
+    event_ids: Vec<String>,
+    wal_head: Option<String>,
+}
+
 #[derive(Serialize)]
 struct IssueStateOutput {
     issue_id: String,
@@ -194,33 +309,186 @@ struct IssueStateOutput {
     wal_head: Option<String>,
 }
 
+#[derive(Serialize)]
+struct WhyBlockedOutput {
+    issue_id: String,
+    ready: bool,
+    paths: Vec<Vec<BlockerRow>>,
+}
+
 pub fn run(cli: &Cli, cmd: IssueCommand) -> Result<(), GriteError> {
     match cmd {
-        IssueCommand::Create { title, body, label } => run_create(cli, title, body, label),
-        IssueCommand::List { state, label } => run_list(cli, state, label),
+        IssueCommand::Create {
+            title,
+            body,
+            label,
+            simulate,
+            ..
+        } => run_create(cli, title, body, label, simulate),
+        IssueCommand::List {
+            state,
+            label,
+            assignee,
+            unassigned,
+            field,
+            milestone,
+            severity,
+            include_deleted,
+            sort,
+        } => run_list(
+            cli,
+            state,
+            label,
+            assignee,
+            unassigned,
+            field,
+            milestone,
+            severity,
+            include_deleted,
+            sort,
+        ),
         IssueCommand::Show { id } => run_show(cli, id),
         IssueCommand::Update {
             id,
             title,
             body,
             lock,
-        } => run_update(cli, id, title, body, lock),
-        IssueCommand::Comment { id, body, lock } => run_comment(cli, id, body, lock),
+            simulate,
+        } => run_update(cli, id, title, body, lock, simulate),
+        IssueCommand::Comment {
+            id,
+            body,
+            reply_to,
+            lock,
+            simulate,
+        } => run_comment(cli, id, body, reply_to, lock, simulate),
         IssueCommand::Close { id, lock } => run_close(cli, id, lock),
         IssueCommand::Reopen { id, lock } => run_reopen(cli, id, lock),
         IssueCommand::Label { cmd } => run_label(cli, cmd),
         IssueCommand::Assignee { cmd } => run_assignee(cli, cmd),
         IssueCommand::Link { cmd } => run_link(cli, cmd),
+        IssueCommand::LinkIssue {
+            id,
+            target,
+            rel,
+            remove,
+            lock,
+        } => run_link_issue(cli, id, target, rel, remove, lock),
+        IssueCommand::Dup { id, of, lock } => run_dup(cli, id, of, lock),
+        IssueCommand::Subscribe { id, user } => run_subscribe(cli, id, user, false),
+        IssueCommand::Unsubscribe { id, user } => run_subscribe(cli, id, user, true),
+        IssueCommand::Estimate { id, minutes } => {
+            run_simple_event(cli, id, EventKind::TimeEstimated { minutes })
+        }
+        IssueCommand::LogTime { id, minutes, note } => {
+            run_simple_event(cli, id, EventKind::TimeLogged { minutes, note })
+        }
+        IssueCommand::Milestone { id, milestone } => {
+            let milestone = (milestone != "clear").then_some(milestone);
+            run_simple_event(cli, id, EventKind::MilestoneSet { milestone })
+        }
+        IssueCommand::Severity { id, severity } => {
+            let severity = to_core_severity(severity);
+            run_simple_event(cli, id, EventKind::SeveritySet { severity })
+        }
+        IssueCommand::Delete { id } => run_simple_event(cli, id, EventKind::IssueDeleted {}),
+        IssueCommand::Undelete { id } => run_simple_event(cli, id, EventKind::IssueUndeleted {}),
+        IssueCommand::WhyBlocked { id } => run_why_blocked(cli, id),
+        IssueCommand::Tree { id, depth } => run_tree(cli, id, depth),
         IssueCommand::Attachment { cmd } => run_attachment(cli, cmd),
         IssueCommand::Dep { cmd } => super::dep::run(cli, cmd),
+        IssueCommand::Field { cmd } => run_field(cli, cmd),
+    }
+}
+
+/// Print `event` as it would be written, with its canonical hash preimage,
+/// without touching the store or the WAL
+fn output_simulated(cli: &Cli, event: &Event) {
+    let preimage = build_canonical_cbor(
+        &event.issue_id,
+        &event.actor,
+        event.ts_unix_ms,
+        event.parent.as_ref(),
+        &event.kind,
+    );
+    output_success(
+        cli,
+        SimulatedEventOutput {
+            simulated: true,
+            event: EventJson {
+                event_id: id_to_hex(&event.event_id),
+                issue_id: id_to_hex(&event.issue_id),
+                actor: id_to_hex(&event.actor),
+                ts_unix_ms: event.ts_unix_ms,
+                parent: event.parent.as_ref().map(id_to_hex),
+                kind: serde_json::to_value(&event.kind).unwrap_or(serde_json::Value::Null),
+            },
+            preimage_hex: hex::encode(preimage),
+        },
+    );
+}
+
+/// Look up the issue a simulated write targets: its full ID and its comments
+///
+/// A running daemon holds the store, so the lookup goes through it; the
+/// store is only opened here when no daemon serves the repository.
+fn simulated_target(
+    cli: &Cli,
+    ctx: &GriteContext,
+    id: &str,
+) -> Result<(IssueId, Vec<EventId>), GriteError> {
+    match ctx.execution_mode(cli.no_daemon) {
+        ExecutionMode::Local => {
+            let store = ctx.open_store()?;
+            let issue_id = store.resolve_issue_id(id)?;
+            let issue = store
+                .get_issue(&issue_id)?
+                .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+            let comment_ids = issue.comments.iter().map(|c| c.event_id).collect();
+            Ok((issue_id, comment_ids))
+        }
+        ExecutionMode::Daemon { mut client, .. } => {
+            fn parse<const N: usize>(value: &serde_json::Value) -> Result<[u8; N], GriteError> {
+                value
+                    .as_str()
+                    .and_then(|hex| hex_to_id(hex).ok())
+                    .ok_or_else(|| GriteError::Internal("Malformed issue from daemon".to_string()))
+            }
+
+            let command = IpcCommand::IssueShow {
+                issue_id: id.to_string(),
+            };
+            let response = router::send_to_daemon(ctx, &mut client, command)?;
+            if !response.ok {
+                return Err(match response.error {
+                    Some(err) => router::daemon_error(err.code, err.message),
+                    None => GriteError::Internal("Unknown daemon error".to_string()),
+                });
+            }
+            let issue = response
+                .decode_data()
+                .map_err(|e| GriteError::Internal(format!("IPC error: {}", e)))?
+                .unwrap_or_default();
+            let issue_id = parse(&issue["issue_id"])?;
+            let comment_ids = match issue["comments"].as_array() {
+                Some(comments) => comments
+                    .iter()
+                    .map(|c| parse(&c["event_id"]))
+                    .collect::<Result<_, _>>()?,
+                None => Vec::new(),
+            };
+            Ok((issue_id, comment_ids))
+        }
+        ExecutionMode::Blocked { lock } => Err(GriteError::DbBusy(format!(
+            "Data directory locked by daemon (PID {}, expires in {}s). Use --no-daemon to wait or try later.",
+            lock.pid,
+            lock.time_remaining_ms() / 1000
+        ))),
     }
 }
 
 fn current_ts() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+    libgrite_core::clock::now_ms()
 }
 
 fn run_create(
@@ -228,15 +496,17 @@ fn run_create(
     title: String,
     body: String,
     labels: Vec<String>,
+    simulate: bool,
 ) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
 
     // Check for repo-level locks before creating
     check_repo_lock(cli, &ctx)?;
 
-    let store = ctx.open_store()?;
-    let wal = ctx.open_wal()?;
     let actor = ctx.actor_config.actor_id_bytes()?;
+    let labels = load_repo_config(&ctx.git_dir)?
+        .unwrap_or_default()
+        .issue_labels(&labels)?;
 
     let issue_id = generate_issue_id();
     let ts = current_ts();
@@ -249,6 +519,13 @@ fn run_create(
     let event = Event::new(event_id, issue_id, actor, ts, None, kind);
     let event = ctx.sign_event(event);
 
+    if simulate {
+        output_simulated(cli, &event);
+        return Ok(());
+    }
+
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
     let result = insert_and_append(&store, &wal, &actor, &event)?;
 
     output_success(
@@ -263,7 +540,20 @@ fn run_create(
     Ok(())
 }
 
-fn run_list(cli: &Cli, state: Option<String>, label: Option<String>) -> Result<(), GriteError> {
+#[allow(clippy::too_many_arguments)]
+fn run_list(
+    cli: &Cli,
+    state: Option<String>,
+    label: Option<String>,
+    assignee: Option<String>,
+    unassigned: bool,
+    field: Option<String>,
+    milestone: Option<String>,
+    severity: Option<SeverityLevel>,
+    include_deleted: bool,
+    sort: IssueSort,
+) -> Result<(), GriteError> {
+    let field = field.as_deref().map(IssueFilter::parse_field).transpose()?;
     let ctx = GriteContext::resolve(cli)?;
     let store = ctx.open_store()?;
 
@@ -276,11 +566,25 @@ fn run_list(cli: &Cli, state: Option<String>, label: Option<String>) -> Result<(
     let filter = IssueFilter {
         state: state_filter,
         label,
+        assignee,
+        unassigned,
+        field,
+        milestone,
+        severity: severity.map(to_core_severity),
+        include_deleted,
     };
 
-    let issues = store.list_issues(&filter)?;
+    let sort = match sort {
+        IssueSort::Created => libgrite_core::IssueSort::Created,
+        IssueSort::MostBlocking => libgrite_core::IssueSort::MostBlocking,
+    };
+    let issues = store.list_issues_sorted(&filter, sort)?;
     let total = issues.len();
-    let issue_jsons: Vec<IssueSummaryJson> = issues.iter().map(IssueSummaryJson::from).collect();
+    let names = actor_names(&ctx.git_dir)?;
+    let issue_jsons: Vec<IssueSummaryJson> = issues
+        .iter()
+        .map(|s| IssueSummaryJson::new(s, &names))
+        .collect();
 
     if cli.json {
         output_success(
@@ -296,11 +600,19 @@ fn run_list(cli: &Cli, state: Option<String>, label: Option<String>) -> Result<(
             .map(|i| IssueRow {
                 id: id_to_hex(&i.issue_id),
                 state: format!("{:?}", i.state).to_lowercase(),
-                title: i.title.clone(),
+                title: if i.deleted {
+                    format!("[deleted] {}", i.title)
+                } else {
+                    i.title.clone()
+                },
                 created_ts: i.created_ts,
+                author: i.created_by.map_or_else(
+                    || "?".to_string(),
+                    |actor| actor_display(&names, &actor, id_len(cli)),
+                ),
             })
             .collect();
-        println!("{}", format_issue_table(&rows));
+        write_issue_table(&mut stdout_writer(cli), &rows, id_len(cli))?;
     }
 
     Ok(())
@@ -328,12 +640,40 @@ fn run_show(cli: &Cli, id: String) -> Result<(), GriteError> {
         })
         .collect();
 
-    let summary = IssueSummary::from(&proj);
+    let summary = store.issue_summary(&proj)?;
 
     output_success(
         cli,
         IssueShowOutput {
-            issue: IssueSummaryJson::from(&summary),
+            issue: IssueSummaryJson::new(&summary, &actor_names(&ctx.git_dir)?),
+            title_conflict: proj.title_conflict.clone(),
+            body_revisions: proj.body_revisions,
+            fields: proj.custom_fields.clone(),
+            issue_links: proj
+                .issue_links
+                .iter()
+                .map(|l| IssueLinkJson {
+                    target: id_to_hex(&l.target),
+                    relation: l.relation.clone(),
+                })
+                .collect(),
+            dependencies: store
+                .get_dependency_refs(&issue_id, false)?
+                .iter()
+                .map(DependencyRefJson::from)
+                .collect(),
+            dependents: store
+                .get_dependency_refs(&issue_id, true)?
+                .iter()
+                .map(DependencyRefJson::from)
+                .collect(),
+            subscribers: proj.subscribers.iter().cloned().collect(),
+            estimate_minutes: proj.estimate_minutes,
+            logged_minutes: proj.logged_minutes,
+            milestone: proj.milestone.clone(),
+            severity: proj.severity.map(|s| s.as_str()),
+            first_response_ms: proj.first_response_ms,
+            digest: id_to_hex(&store.issue_digest(&issue_id)?),
             events: event_jsons,
         },
     );
@@ -341,12 +681,23 @@ fn run_show(cli: &Cli, id: String) -> Result<(), GriteError> {
     Ok(())
 }
 
+fn to_core_severity(level: SeverityLevel) -> Severity {
+    match level {
+        SeverityLevel::Sev1 => Severity::Sev1,
+        SeverityLevel::Sev2 => Severity::Sev2,
+        SeverityLevel::Sev3 => Severity::Sev3,
+        SeverityLevel::Sev4 => Severity::Sev4,
+        SeverityLevel::None => Severity::None,
+    }
+}
+
 fn run_update(
     cli: &Cli,
     id: String,
     title: Option<String>,
     body: Option<String>,
     lock: bool,
+    simulate: bool,
 ) -> Result<(), GriteError> {
     if title.is_none() && body.is_none() {
         return Err(GriteError::InvalidArgs(
@@ -362,19 +713,29 @@ fn run_update(
         check_issue_lock(cli, &ctx, &id)?;
     }
 
+    let actor = ctx.actor_config.actor_id_bytes()?;
+    let kind = EventKind::IssueUpdated { title, body };
+
+    if simulate {
+        let (issue_id, _) = simulated_target(cli, &ctx, &id)?;
+        let ts = current_ts();
+        let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+        let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+        output_simulated(cli, &ctx.sign_event(event));
+        return Ok(());
+    }
+
     let store = ctx.open_store()?;
     let wal = ctx.open_wal()?;
-    let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&id)?;
 
     // Verify issue exists
-    store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
 
     let ts = current_ts();
-    let kind = EventKind::IssueUpdated { title, body };
     let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
     let event = Event::new(event_id, issue_id, actor, ts, None, kind);
     let event = ctx.sign_event(event);
@@ -393,7 +754,14 @@ fn run_update(
     Ok(())
 }
 
-fn run_comment(cli: &Cli, id: String, body: String, lock: bool) -> Result<(), GriteError> {
+fn run_comment(
+    cli: &Cli,
+    id: String,
+    body: String,
+    reply_to: Option<String>,
+    lock: bool,
+    simulate: bool,
+) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
 
     // Acquire lock if requested (or just check for conflicts)
@@ -402,19 +770,38 @@ fn run_comment(cli: &Cli, id: String, body: String, lock: bool) -> Result<(), Gr
         check_issue_lock(cli, &ctx, &id)?;
     }
 
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    if simulate {
+        let (issue_id, comment_ids) = simulated_target(cli, &ctx, &id)?;
+        let parent_event = reply_to
+            .as_deref()
+            .map(|prefix| resolve_comment_prefix(&comment_ids, prefix))
+            .transpose()?;
+        let ts = current_ts();
+        let kind = EventKind::CommentAdded { body, parent_event };
+        let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+        let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+        output_simulated(cli, &ctx.sign_event(event));
+        return Ok(());
+    }
+
     let store = ctx.open_store()?;
     let wal = ctx.open_wal()?;
-    let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&id)?;
 
     // Verify issue exists
-    store
+    let issue = store
         .get_issue(&issue_id)?
         .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+    let parent_event = reply_to
+        .as_deref()
+        .map(|prefix| issue.resolve_comment(prefix))
+        .transpose()?;
 
     let ts = current_ts();
-    let kind = EventKind::CommentAdded { body };
+    let kind = EventKind::CommentAdded { body, parent_event };
     let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
     let event = Event::new(event_id, issue_id, actor, ts, None, kind);
     let event = ctx.sign_event(event);
@@ -449,9 +836,9 @@ fn run_close(cli: &Cli, id: String, lock: bool) -> Result<(), GriteError> {
     let issue_id = store.resolve_issue_id(&id)?;
 
     // Verify issue exists
-    store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
 
     let ts = current_ts();
     let kind = EventKind::StateChanged {
@@ -492,9 +879,9 @@ fn run_reopen(cli: &Cli, id: String, lock: bool) -> Result<(), GriteError> {
     let issue_id = store.resolve_issue_id(&id)?;
 
     // Verify issue exists
-    store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
 
     let ts = current_ts();
     let kind = EventKind::StateChanged {
@@ -521,141 +908,94 @@ fn run_reopen(cli: &Cli, id: String, lock: bool) -> Result<(), GriteError> {
 
 fn run_label(cli: &Cli, cmd: LabelCommand) -> Result<(), GriteError> {
     match cmd {
-        LabelCommand::Add { id, label, lock } => {
-            let ctx = GriteContext::resolve(cli)?;
-            let _lock_guard = LockGuard::acquire(&ctx, &id, lock)?;
-            if !lock {
-                check_issue_lock(cli, &ctx, &id)?;
-            }
-            let store = ctx.open_store()?;
-            let wal = ctx.open_wal()?;
-            let actor = ctx.actor_config.actor_id_bytes()?;
-
-            let issue_id = store.resolve_issue_id(&id)?;
-            store
-                .get_issue(&issue_id)?
-                .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
-
-            let ts = current_ts();
-            let kind = EventKind::LabelAdded { label };
-            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
-            let event = Event::new(event_id, issue_id, actor, ts, None, kind);
-            let event = ctx.sign_event(event);
-
-            let result = insert_and_append(&store, &wal, &actor, &event)?;
-
-            output_success(
-                cli,
-                IssueUpdateOutput {
-                    issue_id: id_to_hex(&issue_id),
-                    event_id: id_to_hex(&event_id),
-                    wal_head: result.wal_head,
-                },
-            );
+        LabelCommand::Add { ids, label, lock } => {
+            run_batch(cli, ids, lock, EventKind::LabelAdded { label })
         }
-        LabelCommand::Remove { id, label, lock } => {
-            let ctx = GriteContext::resolve(cli)?;
-            let _lock_guard = LockGuard::acquire(&ctx, &id, lock)?;
-            if !lock {
-                check_issue_lock(cli, &ctx, &id)?;
-            }
-            let store = ctx.open_store()?;
-            let wal = ctx.open_wal()?;
-            let actor = ctx.actor_config.actor_id_bytes()?;
-
-            let issue_id = store.resolve_issue_id(&id)?;
-            store
-                .get_issue(&issue_id)?
-                .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
-
-            let ts = current_ts();
-            let kind = EventKind::LabelRemoved { label };
-            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
-            let event = Event::new(event_id, issue_id, actor, ts, None, kind);
-            let event = ctx.sign_event(event);
-
-            let result = insert_and_append(&store, &wal, &actor, &event)?;
-
-            output_success(
-                cli,
-                IssueUpdateOutput {
-                    issue_id: id_to_hex(&issue_id),
-                    event_id: id_to_hex(&event_id),
-                    wal_head: result.wal_head,
-                },
-            );
+        LabelCommand::Remove { ids, label, lock } => {
+            run_batch(cli, ids, lock, EventKind::LabelRemoved { label })
         }
     }
-    Ok(())
 }
 
 fn run_assignee(cli: &Cli, cmd: AssigneeCommand) -> Result<(), GriteError> {
     match cmd {
-        AssigneeCommand::Add { id, user, lock } => {
-            let ctx = GriteContext::resolve(cli)?;
-            let _lock_guard = LockGuard::acquire(&ctx, &id, lock)?;
-            if !lock {
-                check_issue_lock(cli, &ctx, &id)?;
-            }
-            let store = ctx.open_store()?;
-            let wal = ctx.open_wal()?;
-            let actor = ctx.actor_config.actor_id_bytes()?;
+        AssigneeCommand::Add { ids, user, lock } => {
+            run_batch(cli, ids, lock, EventKind::AssigneeAdded { user })
+        }
+        AssigneeCommand::Remove { ids, user, lock } => {
+            run_batch(cli, ids, lock, EventKind::AssigneeRemoved { user })
+        }
+    }
+}
 
-            let issue_id = store.resolve_issue_id(&id)?;
-            store
-                .get_issue(&issue_id)?
-                .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+/// Record the same event on every issue in `ids`
+///
+/// Every ID is resolved and lock-checked before anything is written, and the
+/// events go to the WAL as one commit.
+fn run_batch(cli: &Cli, ids: Vec<String>, lock: bool, kind: EventKind) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
 
-            let ts = current_ts();
-            let kind = EventKind::AssigneeAdded { user };
-            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
-            let event = Event::new(event_id, issue_id, actor, ts, None, kind);
-            let event = ctx.sign_event(event);
+    let mut _lock_guards = Vec::with_capacity(ids.len());
+    for id in &ids {
+        _lock_guards.push(LockGuard::acquire(&ctx, id, lock)?);
+        if !lock {
+            check_issue_lock(cli, &ctx, id)?;
+        }
+    }
 
-            let result = insert_and_append(&store, &wal, &actor, &event)?;
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
 
-            output_success(
-                cli,
-                IssueUpdateOutput {
-                    issue_id: id_to_hex(&issue_id),
-                    event_id: id_to_hex(&event_id),
-                    wal_head: result.wal_head,
-                },
-            );
-        }
-        AssigneeCommand::Remove { id, user, lock } => {
-            let ctx = GriteContext::resolve(cli)?;
-            let _lock_guard = LockGuard::acquire(&ctx, &id, lock)?;
-            if !lock {
-                check_issue_lock(cli, &ctx, &id)?;
-            }
-            let store = ctx.open_store()?;
-            let wal = ctx.open_wal()?;
-            let actor = ctx.actor_config.actor_id_bytes()?;
+    let issue_ids = store.resolve_existing_issues(&ids)?;
 
-            let issue_id = store.resolve_issue_id(&id)?;
-            store
-                .get_issue(&issue_id)?
-                .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+    if let EventKind::LabelAdded { label } = &kind {
+        let config = load_repo_config(&ctx.git_dir)?.unwrap_or_default();
+        for issue_id in &issue_ids {
+            if let Some(proj) = store.get_issue(issue_id)? {
+                config.check_label_limit(&proj, std::slice::from_ref(label), &[])?;
+            }
+        }
+    }
 
-            let ts = current_ts();
-            let kind = EventKind::AssigneeRemoved { user };
-            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
-            let event = Event::new(event_id, issue_id, actor, ts, None, kind);
-            let event = ctx.sign_event(event);
+    let ts = current_ts();
+    let events: Vec<Event> = issue_ids
+        .iter()
+        .map(|issue_id| {
+            let event_id = compute_event_id(issue_id, &actor, ts, None, &kind);
+            ctx.sign_event(Event::new(
+                event_id,
+                *issue_id,
+                actor,
+                ts,
+                None,
+                kind.clone(),
+            ))
+        })
+        .collect();
 
-            let result = insert_and_append(&store, &wal, &actor, &event)?;
+    let result = insert_all_and_append(&store, &wal, &actor, &events)?;
 
-            output_success(
-                cli,
-                IssueUpdateOutput {
-                    issue_id: id_to_hex(&issue_id),
-                    event_id: id_to_hex(&event_id),
-                    wal_head: result.wal_head,
-                },
-            );
-        }
+    if let [event] = events.as_slice() {
+        output_success(
+            cli,
+            IssueUpdateOutput {
+                issue_id: id_to_hex(&event.issue_id),
+                event_id: id_to_hex(&event.event_id),
+                wal_head: result.wal_head,
+            },
+        );
+    } else {
+        output_success(
+            cli,
+            IssueBatchOutput {
+                issue_ids: events.iter().map(|e| id_to_hex(&e.issue_id)).collect(),
+                event_ids: events.iter().map(|e| id_to_hex(&e.event_id)).collect(),
+                wal_head: result.wal_head,
+            },
+        );
     }
+
     Ok(())
 }
 
@@ -677,9 +1017,9 @@ fn run_link(cli: &Cli, cmd: LinkCommand) -> Result<(), GriteError> {
             let actor = ctx.actor_config.actor_id_bytes()?;
 
             let issue_id = store.resolve_issue_id(&id)?;
-            store
-                .get_issue(&issue_id)?
-                .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+            if !store.issue_exists(&issue_id)? {
+                return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+            }
 
             let ts = current_ts();
             let kind = EventKind::LinkAdded { url, note };
@@ -702,12 +1042,263 @@ fn run_link(cli: &Cli, cmd: LinkCommand) -> Result<(), GriteError> {
     Ok(())
 }
 
+fn run_link_issue(
+    cli: &Cli,
+    id: String,
+    target: String,
+    rel: String,
+    remove: bool,
+    lock: bool,
+) -> Result<(), GriteError> {
+    let relation = IssueLink::normalize_relation(&rel)
+        .ok_or_else(|| GriteError::InvalidArgs(format!("Invalid relation: '{}'", rel)))?;
+
+    let ctx = GriteContext::resolve(cli)?;
+    let _lock_guard = LockGuard::acquire(&ctx, &id, lock)?;
+    if !lock {
+        check_issue_lock(cli, &ctx, &id)?;
+    }
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+    let target_id = store.resolve_issue_id(&target)?;
+    if !store.issue_exists(&target_id)? {
+        return Err(GriteError::NotFound(format!("Target {} not found", target)));
+    }
+    if issue_id == target_id {
+        return Err(GriteError::InvalidArgs(
+            "Cannot link an issue to itself".to_string(),
+        ));
+    }
+
+    let ts = current_ts();
+    let kind = if remove {
+        EventKind::IssueUnlinked {
+            target: target_id,
+            relation,
+        }
+    } else {
+        EventKind::IssueLinked {
+            target: target_id,
+            relation,
+        }
+    };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    let result = insert_and_append(&store, &wal, &actor, &event)?;
+
+    output_success(
+        cli,
+        IssueUpdateOutput {
+            issue_id: id_to_hex(&issue_id),
+            event_id: id_to_hex(&event_id),
+            wal_head: result.wal_head,
+        },
+    );
+    Ok(())
+}
+
+fn run_dup(cli: &Cli, id: String, of: String, lock: bool) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let _lock_guard = LockGuard::acquire(&ctx, &id, lock)?;
+    if !lock {
+        check_issue_lock(cli, &ctx, &id)?;
+    }
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+    let target_id = store.resolve_issue_id(&of)?;
+    if !store.issue_exists(&target_id)? {
+        return Err(GriteError::NotFound(format!("Target {} not found", of)));
+    }
+    if issue_id == target_id {
+        return Err(GriteError::InvalidArgs(
+            "Cannot mark an issue a duplicate of itself".to_string(),
+        ));
+    }
+
+    // Link and close in one WAL commit
+    let ts = current_ts();
+    let events: Vec<Event> = [
+        EventKind::IssueLinked {
+            target: target_id,
+            relation: "duplicate-of".to_string(),
+        },
+        EventKind::StateChanged {
+            state: IssueState::Closed,
+        },
+    ]
+    .into_iter()
+    .map(|kind| {
+        let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+        ctx.sign_event(Event::new(event_id, issue_id, actor, ts, None, kind))
+    })
+    .collect();
+
+    let result = insert_all_and_append(&store, &wal, &actor, &events)?;
+
+    output_success(
+        cli,
+        IssueDupOutput {
+            issue_id: id_to_hex(&issue_id),
+            target: id_to_hex(&target_id),
+            event_ids: events.iter().map(|e| id_to_hex(&e.event_id)).collect(),
+            state: "closed".to_string(),
+            wal_head: result.wal_head,
+        },
+    );
+    Ok(())
+}
+
+fn run_subscribe(
+    cli: &Cli,
+    id: String,
+    user: Option<String>,
+    remove: bool,
+) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+    let user = user.unwrap_or_else(|| ctx.actor_id.clone());
+
+    let issue_id = store.resolve_issue_id(&id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+
+    let ts = current_ts();
+    let kind = if remove {
+        EventKind::Unsubscribed { user }
+    } else {
+        EventKind::Subscribed { user }
+    };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    let result = insert_and_append(&store, &wal, &actor, &event)?;
+
+    output_success(
+        cli,
+        IssueUpdateOutput {
+            issue_id: id_to_hex(&issue_id),
+            event_id: id_to_hex(&event_id),
+            wal_head: result.wal_head,
+        },
+    );
+    Ok(())
+}
+
+/// Record a single event that needs no lock, such as `TimeLogged`
+fn run_simple_event(cli: &Cli, id: String, kind: EventKind) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+
+    let ts = current_ts();
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    let result = insert_and_append(&store, &wal, &actor, &event)?;
+
+    output_success(
+        cli,
+        IssueUpdateOutput {
+            issue_id: id_to_hex(&issue_id),
+            event_id: id_to_hex(&event_id),
+            wal_head: result.wal_head,
+        },
+    );
+    Ok(())
+}
+
+fn run_why_blocked(cli: &Cli, id: String) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+
+    let issue_id = store.resolve_issue_id(&id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+
+    let paths = store
+        .blocking_chain(&issue_id)?
+        .iter()
+        .map(|path| {
+            path.iter()
+                .map(|blocker| {
+                    Ok(BlockerRow {
+                        issue_id: id_to_hex(blocker),
+                        title: store.get_issue(blocker)?.map(|p| p.title),
+                    })
+                })
+                .collect::<Result<Vec<_>, GriteError>>()
+        })
+        .collect::<Result<Vec<_>, GriteError>>()?;
+    let output = WhyBlockedOutput {
+        issue_id: id_to_hex(&issue_id),
+        ready: paths.is_empty(),
+        paths,
+    };
+
+    if cli.json {
+        output_success(cli, &output);
+    } else {
+        print_human(
+            cli,
+            &format_blocking_chain(&output.issue_id, &output.paths, id_len(cli)),
+        );
+    }
+    Ok(())
+}
+
+fn run_tree(cli: &Cli, id: String, depth: u32) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let store = ctx.open_store()?;
+
+    let issue_id = store.resolve_issue_id(&id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+
+    let tree = store.dependency_tree(&issue_id, depth as usize)?.to_json();
+    if cli.json {
+        output_success(cli, &tree);
+    } else {
+        let tree: IssueTreeNode = serde_json::from_value(tree)
+            .map_err(|e| GriteError::Internal(format!("Invalid tree: {}", e)))?;
+        print_human(cli, &format_issue_tree(&tree, id_len(cli), color_enabled()));
+    }
+    Ok(())
+}
+
 fn run_attachment(cli: &Cli, cmd: AttachmentCommand) -> Result<(), GriteError> {
     match cmd {
         AttachmentCommand::Add {
             id,
             name,
             sha256,
+            file,
             mime,
             lock,
         } => {
@@ -721,11 +1312,19 @@ fn run_attachment(cli: &Cli, cmd: AttachmentCommand) -> Result<(), GriteError> {
             let actor = ctx.actor_config.actor_id_bytes()?;
 
             let issue_id = store.resolve_issue_id(&id)?;
-            store
-                .get_issue(&issue_id)?
-                .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", id)))?;
+            if !store.issue_exists(&issue_id)? {
+                return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+            }
 
-            let sha256_bytes: [u8; 32] = hex_to_id(&sha256)?;
+            let sha256_bytes: [u8; 32] = match (file, sha256) {
+                (Some(path), _) => store.put_attachment(&std::fs::read(path)?)?,
+                (None, Some(sha256)) => hex_to_id(&sha256)?,
+                (None, None) => {
+                    return Err(GriteError::InvalidArgs(
+                        "either --sha256 or --file is required".to_string(),
+                    ))
+                }
+            };
 
             let ts = current_ts();
             let kind = EventKind::AttachmentAdded {
@@ -751,3 +1350,47 @@ fn run_attachment(cli: &Cli, cmd: AttachmentCommand) -> Result<(), GriteError> {
     }
     Ok(())
 }
+
+fn run_field(cli: &Cli, cmd: FieldCommand) -> Result<(), GriteError> {
+    let (id, key, value, lock) = match cmd {
+        FieldCommand::Set {
+            id,
+            key,
+            value,
+            lock,
+        } => (id, key, Some(value), lock),
+        FieldCommand::Clear { id, key, lock } => (id, key, None, lock),
+    };
+
+    let ctx = GriteContext::resolve(cli)?;
+    let _lock_guard = LockGuard::acquire(&ctx, &id, lock)?;
+    if !lock {
+        check_issue_lock(cli, &ctx, &id)?;
+    }
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+
+    let ts = current_ts();
+    let kind = EventKind::FieldSet { key, value };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    let result = insert_and_append(&store, &wal, &actor, &event)?;
+
+    output_success(
+        cli,
+        IssueUpdateOutput {
+            issue_id: id_to_hex(&issue_id),
+            event_id: id_to_hex(&event_id),
+            wal_head: result.wal_head,
+        },
+    );
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Milestones grouping issues
+
+use libgrite_core::GriteError;
+use serde::Serialize;
+
+use crate::cli::{Cli, MilestoneCommand};
+use crate::context::{open_store_at, store_wait, GriteContext};
+use crate::output::{output_success, print_human};
+
+#[derive(Serialize)]
+struct MilestoneListOutput {
+    milestones: Vec<MilestoneJson>,
+}
+
+#[derive(Serialize)]
+struct MilestoneJson {
+    name: String,
+    open: usize,
+    closed: usize,
+}
+
+pub fn run(cli: &Cli, cmd: MilestoneCommand) -> Result<(), GriteError> {
+    match cmd {
+        MilestoneCommand::List => run_list(cli),
+    }
+}
+
+fn run_list(cli: &Cli) -> Result<(), GriteError> {
+    let git_dir = GriteContext::find_git_dir()?;
+    let store = open_store_at(&git_dir, store_wait(cli, &git_dir))?;
+    let milestones: Vec<MilestoneJson> = store
+        .milestone_rollups()?
+        .into_iter()
+        .map(|r| MilestoneJson {
+            name: r.milestone,
+            open: r.open,
+            closed: r.closed,
+        })
+        .collect();
+
+    if cli.json {
+        output_success(cli, MilestoneListOutput { milestones });
+    } else if milestones.is_empty() {
+        print_human(cli, "No milestones");
+    } else {
+        for m in &milestones {
+            print_human(
+                cli,
+                &format!("{}  {} open, {} closed", m.name, m.open, m.closed),
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -4,11 +4,15 @@ pub mod daemon;
 pub mod db;
 pub mod dep;
 pub mod doctor;
+pub mod errors;
 pub mod export;
 pub mod init;
 pub mod install_skill;
 pub mod issue;
 pub mod lock;
+pub mod milestone;
 pub mod rebuild;
+pub mod report;
 pub mod snapshot;
 pub mod sync;
+pub mod tui;
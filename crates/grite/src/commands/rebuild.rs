@@ -1,10 +1,12 @@
 use crate::cli::Cli;
 use crate::context::{ExecutionMode, GriteContext};
 use crate::output::{output_success, print_human};
-use libgrite_core::GriteError;
+use crate::router::local_commands;
+use libgrite_core::{types::ids::id_to_hex, GriteError, RebuildPlan, RebuildStats};
 use libgrite_git::{SnapshotManager, WalManager};
 use libgrite_ipc::{IpcClient, IpcCommand, IpcRequest};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Rebuild can take much longer than normal IPC commands (minutes for large stores).
 const REBUILD_TIMEOUT_MS: u64 = 300_000; // 5 minutes
@@ -15,16 +17,73 @@ struct RebuildOutput {
     event_count: usize,
     from_snapshot: Option<String>,
     snapshot_events: Option<usize>,
+    /// Events that failed to apply, with `--skip-errors`
+    skipped: Vec<SkippedEventJson>,
 }
 
-pub fn run(cli: &Cli, use_snapshot: bool) -> Result<(), GriteError> {
+#[derive(Serialize, Deserialize)]
+struct SkippedEventJson {
+    event_id: String,
+    error: String,
+}
+
+fn skipped_json(stats: &RebuildStats) -> Vec<SkippedEventJson> {
+    stats
+        .skipped
+        .iter()
+        .map(|(event_id, error)| SkippedEventJson {
+            event_id: id_to_hex(event_id),
+            error: error.clone(),
+        })
+        .collect()
+}
+
+/// Print one line per skipped event for human output of a daemon rebuild
+fn print_skipped(cli: &Cli, skipped: &[SkippedEventJson]) {
+    if !skipped.is_empty() {
+        print_human(cli, &format!("Skipped {} events:", skipped.len()));
+    }
+    for event in skipped {
+        print_human(cli, &format!("  {}  {}", event.event_id, event.error));
+    }
+}
+
+#[derive(Serialize)]
+struct RebuildPlanOutput {
+    dry_run: bool,
+    event_count: usize,
+    issue_count: usize,
+    kinds: BTreeMap<&'static str, usize>,
+    from_snapshot: Option<String>,
+}
+
+impl RebuildPlanOutput {
+    fn new(plan: RebuildPlan, from_snapshot: Option<String>) -> Self {
+        Self {
+            dry_run: true,
+            event_count: plan.event_count,
+            issue_count: plan.issue_count,
+            kinds: plan.kinds,
+            from_snapshot,
+        }
+    }
+}
+
+pub fn run(
+    cli: &Cli,
+    use_snapshot: bool,
+    dry_run: bool,
+    skip_errors: bool,
+) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
 
-    match ctx.execution_mode(cli.no_daemon) {
+    // Listing rebuild in GRITE_LOCAL_CMDS has the same effect as --no-daemon
+    let force_local = cli.no_daemon || local_commands().iter().any(|c| c == "rebuild");
+    match ctx.execution_mode(force_local) {
         ExecutionMode::Daemon { endpoint, .. } => {
             // The daemon holds the store flock. Route rebuild through it
             // with a generous timeout since rebuilds can be slow.
-            rebuild_via_daemon(cli, &ctx, &endpoint)
+            rebuild_via_daemon(cli, &ctx, &endpoint, dry_run, skip_errors)
         }
         ExecutionMode::Blocked { lock } => Err(GriteError::DbBusy(format!(
             "Store is locked by pid {} (expires in {}s). \
@@ -35,13 +94,23 @@ pub fn run(cli: &Cli, use_snapshot: bool) -> Result<(), GriteError> {
         ExecutionMode::Local => {
             let store = ctx.open_store()?;
             let git_dir = ctx.repo_root().join(".git");
-            do_rebuild(cli, &store, &git_dir, use_snapshot)
+            if dry_run {
+                plan_rebuild(cli, &store, &git_dir, use_snapshot)
+            } else {
+                do_rebuild(cli, &store, &git_dir, use_snapshot, skip_errors)
+            }
         }
     }
 }
 
 /// Send rebuild command through the daemon's IPC with a long timeout.
-fn rebuild_via_daemon(cli: &Cli, ctx: &GriteContext, endpoint: &str) -> Result<(), GriteError> {
+fn rebuild_via_daemon(
+    cli: &Cli,
+    ctx: &GriteContext,
+    endpoint: &str,
+    dry_run: bool,
+    skip_errors: bool,
+) -> Result<(), GriteError> {
     let mut client = IpcClient::connect_with_timeout(endpoint, REBUILD_TIMEOUT_MS)
         .map_err(|e| GriteError::Internal(format!("Failed to connect to daemon: {}", e)))?;
 
@@ -50,7 +119,11 @@ fn rebuild_via_daemon(cli: &Cli, ctx: &GriteContext, endpoint: &str) -> Result<(
         ctx.repo_root().to_string_lossy().to_string(),
         ctx.actor_id.clone(),
         ctx.data_dir.to_string_lossy().to_string(),
-        IpcCommand::Rebuild,
+        if dry_run {
+            IpcCommand::RebuildPlan
+        } else {
+            IpcCommand::Rebuild { skip_errors }
+        },
     );
 
     let response = client
@@ -61,6 +134,10 @@ fn rebuild_via_daemon(cli: &Cli, ctx: &GriteContext, endpoint: &str) -> Result<(
         if let Some(data) = &response.data {
             if cli.json {
                 println!("{}", data);
+            } else if dry_run {
+                if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
+                    output_success(cli, json);
+                }
             } else if !cli.quiet {
                 if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
                     let count = json
@@ -68,6 +145,11 @@ fn rebuild_via_daemon(cli: &Cli, ctx: &GriteContext, endpoint: &str) -> Result<(
                         .and_then(|v| v.as_u64())
                         .unwrap_or(0);
                     print_human(cli, &format!("Rebuilt {} events (via daemon)", count));
+                    if let Ok(skipped) = serde_json::from_value::<Vec<SkippedEventJson>>(
+                        json.get("skipped").cloned().unwrap_or_default(),
+                    ) {
+                        print_skipped(cli, &skipped);
+                    }
                 }
             }
         }
@@ -84,12 +166,48 @@ fn rebuild_via_daemon(cli: &Cli, ctx: &GriteContext, endpoint: &str) -> Result<(
     }
 }
 
+/// Report what a rebuild would process without touching the store
+fn plan_rebuild(
+    cli: &Cli,
+    store: &libgrite_core::LockedStore,
+    git_dir: &std::path::Path,
+    use_snapshot: bool,
+) -> Result<(), GriteError> {
+    if use_snapshot {
+        let snap_mgr = SnapshotManager::open(git_dir)?;
+        if let Some(latest) = snap_mgr.list()?.first() {
+            let events = snap_mgr.read(latest.oid)?;
+            output_success(
+                cli,
+                RebuildPlanOutput::new(
+                    RebuildPlan::from_events(&events),
+                    Some(latest.ref_name.clone()),
+                ),
+            );
+            return Ok(());
+        }
+        print_human(cli, "No snapshots found, planning a full rebuild");
+    }
+
+    output_success(cli, RebuildPlanOutput::new(store.rebuild_plan()?, None));
+    Ok(())
+}
+
 fn do_rebuild(
     cli: &Cli,
     store: &libgrite_core::LockedStore,
     git_dir: &std::path::Path,
     use_snapshot: bool,
+    skip_errors: bool,
 ) -> Result<(), GriteError> {
+    let rebuild = || {
+        if skip_errors {
+            store.rebuild_skipping_errors()
+        } else {
+            store.rebuild()
+        }
+    };
+
     if use_snapshot {
         // Snapshot-based rebuild: load from latest snapshot
         let snap_mgr = SnapshotManager::open(git_dir)?;
@@ -100,7 +218,8 @@ fn do_rebuild(
 
         if snapshots.is_empty() {
             print_human(cli, "No snapshots found, falling back to full rebuild");
-            let stats = store.rebuild()?;
+            let stats = rebuild()?;
+            let skipped = skipped_json(&stats);
             output_success(
                 cli,
                 RebuildOutput {
@@ -108,6 +227,7 @@ fn do_rebuild(
                     event_count: stats.event_count,
                     from_snapshot: None,
                     snapshot_events: None,
+                    skipped,
                 },
             );
             return Ok(());
@@ -124,9 +244,14 @@ fn do_rebuild(
         // Rebuild from snapshot events
         // Note: This rebuilds from the snapshot state only. For events added after
         // the snapshot, they should already be in the local store's event log.
-        let stats = store.rebuild_from_events(&snapshot_events)?;
+        let stats = if skip_errors {
+            store.rebuild_from_events_skipping_errors(&snapshot_events)?
+        } else {
+            store.rebuild_from_events(&snapshot_events)?
+        };
 
         print_human(cli, &format!("Rebuilt from {} snapshot events", snap_count));
+        let skipped = skipped_json(&stats);
 
         output_success(
             cli,
@@ -135,6 +260,7 @@ fn do_rebuild(
                 event_count: stats.event_count,
                 from_snapshot: Some(latest.ref_name.clone()),
                 snapshot_events: Some(snap_count),
+                skipped,
             },
         );
     } else {
@@ -143,7 +269,8 @@ fn do_rebuild(
             .ok()
             .and_then(|wal| wal.head().ok().flatten());
 
-        let stats = store.rebuild()?;
+        let stats = rebuild()?;
+        let skipped = skipped_json(&stats);
 
         output_success(
             cli,
@@ -152,6 +279,7 @@ fn do_rebuild(
                 event_count: stats.event_count,
                 from_snapshot: None,
                 snapshot_events: None,
+                skipped,
             },
         );
     }
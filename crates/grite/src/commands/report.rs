@@ -0,0 +1,101 @@
+//! Reports summarizing activity across all issues
+
+use libgrite_core::GriteError;
+use serde::Serialize;
+
+use crate::cli::{Cli, ReportCommand};
+use crate::context::{open_store_at, store_wait, GriteContext};
+use crate::output::{actor_names, format_duration_ms, output_success, print_human};
+
+#[derive(Serialize)]
+struct TimeReportOutput {
+    actors: Vec<ActorTime>,
+    total_minutes: u64,
+}
+
+#[derive(Serialize)]
+struct ActorTime {
+    actor: String,
+    minutes: u64,
+}
+
+#[derive(Serialize)]
+struct ResponseTimeReportOutput {
+    responded: usize,
+    unanswered: usize,
+    mean_ms: Option<u64>,
+    median_ms: Option<u64>,
+}
+
+pub fn run(cli: &Cli, cmd: ReportCommand) -> Result<(), GriteError> {
+    match cmd {
+        ReportCommand::Time => run_time(cli),
+        ReportCommand::ResponseTime => run_response_time(cli),
+    }
+}
+
+fn run_time(cli: &Cli) -> Result<(), GriteError> {
+    let git_dir = GriteContext::find_git_dir()?;
+
+    // Actors are reported by label where they have one
+    let names = actor_names(&git_dir)?;
+
+    let store = open_store_at(&git_dir, store_wait(cli, &git_dir))?;
+    let actors: Vec<ActorTime> = store
+        .time_by_assignee(&names)?
+        .into_iter()
+        .map(|(actor, minutes)| ActorTime { actor, minutes })
+        .collect();
+    let total_minutes = actors.iter().map(|a| a.minutes).sum();
+
+    if cli.json {
+        output_success(
+            cli,
+            TimeReportOutput {
+                actors,
+                total_minutes,
+            },
+        );
+    } else if actors.is_empty() {
+        print_human(cli, "No time logged");
+    } else {
+        for actor in &actors {
+            print_human(cli, &format!("{}  {}m", actor.actor, actor.minutes));
+        }
+        print_human(cli, &format!("total  {}m", total_minutes));
+    }
+
+    Ok(())
+}
+
+fn run_response_time(cli: &Cli) -> Result<(), GriteError> {
+    let git_dir = GriteContext::find_git_dir()?;
+    let store = open_store_at(&git_dir, store_wait(cli, &git_dir))?;
+    let stats = store.response_time_stats()?;
+
+    if cli.json {
+        output_success(
+            cli,
+            ResponseTimeReportOutput {
+                responded: stats.responded,
+                unanswered: stats.unanswered,
+                mean_ms: stats.mean_ms,
+                median_ms: stats.median_ms,
+            },
+        );
+    } else {
+        print_human(
+            cli,
+            &format!(
+                "{} responded, {} unanswered",
+                stats.responded, stats.unanswered
+            ),
+        );
+        if let (Some(mean), Some(median)) = (stats.mean_ms, stats.median_ms) {
+            print_human(cli, &format!("mean    {}", format_duration_ms(mean)));
+            print_human(cli, &format!("median  {}", format_duration_ms(median)));
+        }
+    }
+
+    Ok(())
+}
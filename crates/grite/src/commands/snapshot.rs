@@ -2,15 +2,46 @@
 
 use crate::cli::{Cli, SnapshotCommand};
 use crate::context::GriteContext;
-use crate::output::output_success;
-use libgrite_core::GriteError;
+use crate::output::{id_len, output_success, print_human, short_id};
+use libgrite_core::{id_to_hex, GriteError, ProjectionDrift};
+use libgrite_git::SignatureStatus;
 use serde::Serialize;
 
 #[derive(Serialize)]
 struct SnapshotCreateOutput {
     oid: String,
+    /// Events stored in the snapshot itself
     event_count: usize,
+    /// Snapshot this delta builds on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base_ts: Option<u64>,
     wal_head: String,
+    signed: bool,
+}
+
+#[derive(Serialize)]
+struct SnapshotVerifyOutput {
+    timestamp: u64,
+    oid: String,
+    /// "valid", "invalid", "unsigned" or "unchecked" (no key to check with)
+    status: &'static str,
+    public_key: Option<String>,
+    /// Why verification failed, for an invalid snapshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    /// Issues with events added after the snapshot
+    newer_than_snapshot: Vec<String>,
+    /// Issues whose stored projection does not match the snapshot
+    mismatches: Vec<ProjectionMismatch>,
+}
+
+#[derive(Serialize)]
+struct ProjectionMismatch {
+    issue_id: String,
+    /// "fields", "missing" (not in the store) or "unexpected" (not in the snapshot)
+    kind: &'static str,
+    /// Differing projection fields, for `fields`
+    fields: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -24,24 +55,49 @@ struct SnapshotInfo {
     oid: String,
     timestamp: u64,
     ref_name: String,
+    /// None if the snapshot metadata cannot be read
+    event_count: Option<usize>,
+    /// None for snapshots written before sizes were recorded
+    size_bytes: Option<u64>,
+    pinned: bool,
+    /// Base snapshot, for a delta
+    base_ts: Option<u64>,
 }
 
 #[derive(Serialize)]
 struct SnapshotGcOutput {
     deleted: usize,
     kept: usize,
+    pinned: usize,
+    bases: usize,
+    reclaimable_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct SnapshotPinOutput {
+    timestamp: u64,
+    oid: String,
+    pinned: bool,
 }
 
 pub fn run(cli: &Cli, cmd: SnapshotCommand) -> Result<(), GriteError> {
     match cmd {
-        SnapshotCommand::Create => run_create(cli),
+        SnapshotCommand::Create { sign, delta } => run_create(cli, sign, delta),
         SnapshotCommand::List => run_list(cli),
         SnapshotCommand::Gc { keep } => run_gc(cli, keep),
+        SnapshotCommand::Pin { ts } => run_pin(cli, ts, true),
+        SnapshotCommand::Unpin { ts } => run_pin(cli, ts, false),
+        SnapshotCommand::Verify { ts, public_key } => run_verify(cli, ts, public_key),
     }
 }
 
-fn run_create(cli: &Cli) -> Result<(), GriteError> {
+fn run_create(cli: &Cli, sign: bool, delta: bool) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
+    let keypair = if sign {
+        Some(ctx.load_signing_key().ok_or_else(no_signing_key)?)
+    } else {
+        None
+    };
     let wal = ctx.open_wal()?;
     let snapshot_mgr = ctx.open_snapshot()?;
 
@@ -58,14 +114,28 @@ fn run_create(cli: &Cli) -> Result<(), GriteError> {
     }
 
     // Create snapshot
-    let oid = snapshot_mgr.create(wal_head, &events)?;
+    let base_ts = if delta {
+        let latest = snapshot_mgr
+            .latest()?
+            .ok_or_else(|| GriteError::NotFound("No snapshot to build a delta on".to_string()))?;
+        Some(latest.timestamp)
+    } else {
+        None
+    };
+    let oid = match (&keypair, base_ts) {
+        (_, Some(base_ts)) => snapshot_mgr.create_delta(base_ts, wal_head, &events)?,
+        (Some(keypair), None) => snapshot_mgr.create_signed(wal_head, &events, keypair)?,
+        (None, None) => snapshot_mgr.create(wal_head, &events)?,
+    };
 
     output_success(
         cli,
         SnapshotCreateOutput {
             oid: oid.to_string(),
-            event_count: events.len(),
+            event_count: snapshot_mgr.meta(oid)?.event_count,
+            base_ts,
             wal_head: wal_head.to_string(),
+            signed: keypair.is_some(),
         },
     );
 
@@ -81,20 +151,46 @@ fn run_list(cli: &Cli) -> Result<(), GriteError> {
 
     let snapshot_infos: Vec<SnapshotInfo> = snapshots
         .into_iter()
-        .map(|s| SnapshotInfo {
-            oid: s.oid.to_string(),
-            timestamp: s.timestamp,
-            ref_name: s.ref_name,
+        .map(|s| {
+            let meta = snapshot_mgr.meta(s.oid).ok();
+            SnapshotInfo {
+                oid: s.oid.to_string(),
+                timestamp: s.timestamp,
+                ref_name: s.ref_name,
+                event_count: meta.as_ref().map(|m| m.event_count),
+                size_bytes: meta.as_ref().and_then(|m| m.size_bytes),
+                pinned: meta.as_ref().is_some_and(|m| m.pinned),
+                base_ts: meta.and_then(|m| m.base_ts),
+            }
         })
         .collect();
 
-    output_success(
-        cli,
-        SnapshotListOutput {
-            snapshots: snapshot_infos,
-            total,
-        },
-    );
+    if cli.json {
+        output_success(
+            cli,
+            SnapshotListOutput {
+                snapshots: snapshot_infos,
+                total,
+            },
+        );
+    } else {
+        let unknown = || "unknown".to_string();
+        for info in &snapshot_infos {
+            print_human(
+                cli,
+                &format!(
+                    "{}  {} events  {} bytes{}{}",
+                    info.ref_name,
+                    info.event_count.map_or_else(unknown, |n| n.to_string()),
+                    info.size_bytes.map_or_else(unknown, |n| n.to_string()),
+                    if info.pinned { "  pinned" } else { "" },
+                    info.base_ts
+                        .map_or_else(String::new, |ts| format!("  delta of {}", ts)),
+                ),
+            );
+        }
+        print_human(cli, &format!("{} snapshot(s)", total));
+    }
 
     Ok(())
 }
@@ -110,8 +206,150 @@ fn run_gc(cli: &Cli, keep: usize) -> Result<(), GriteError> {
         SnapshotGcOutput {
             deleted: stats.deleted,
             kept: stats.kept,
+            pinned: stats.pinned,
+            bases: stats.bases,
+            reclaimable_bytes: stats.reclaimable_bytes,
         },
     );
 
     Ok(())
 }
+
+fn run_pin(cli: &Cli, ts: u64, pinned: bool) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let snapshot_mgr = ctx.open_snapshot()?;
+
+    let oid = snapshot_mgr.set_pinned(ts, pinned)?;
+
+    output_success(
+        cli,
+        SnapshotPinOutput {
+            timestamp: ts,
+            oid: oid.to_string(),
+            pinned,
+        },
+    );
+
+    Ok(())
+}
+
+fn no_signing_key() -> GriteError {
+    GriteError::InvalidArgs(
+        "No signing key for this actor. Run 'grite actor rotate-key' to create one".to_string(),
+    )
+}
+
+fn run_verify(cli: &Cli, ts: u64, public_key: Option<String>) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let snapshot_mgr = ctx.open_snapshot()?;
+    let store = ctx.open_store()?;
+
+    let public_key = public_key.or_else(|| ctx.load_signing_key().map(|key| key.public_key_hex()));
+    let snapshot = snapshot_mgr
+        .list()?
+        .into_iter()
+        .find(|s| s.timestamp == ts)
+        .ok_or_else(|| GriteError::NotFound(format!("No snapshot at {}", ts)))?;
+
+    // Without a key, an unsigned snapshot is still reported as unsigned
+    let (status, reason) = match &public_key {
+        Some(key) => match snapshot_mgr.verify(snapshot.oid, key)? {
+            SignatureStatus::Valid => ("valid", None),
+            SignatureStatus::Unsigned => ("unsigned", None),
+            SignatureStatus::Invalid(reason) => ("invalid", Some(reason)),
+        },
+        None if snapshot_mgr.meta(snapshot.oid)?.signature.is_none() => ("unsigned", None),
+        None => ("unchecked", None),
+    };
+
+    let events = snapshot_mgr.read(snapshot.oid)?;
+    let mut newer_than_snapshot = Vec::new();
+    let mut mismatches = Vec::new();
+    for drift in store.diff_against_snapshot(&events)? {
+        let issue_id = id_to_hex(&drift.issue_id);
+        let (kind, fields) = match drift.drift {
+            ProjectionDrift::NewerThanSnapshot => {
+                newer_than_snapshot.push(issue_id);
+                continue;
+            }
+            ProjectionDrift::Mismatch(fields) => ("fields", fields),
+            ProjectionDrift::Missing => ("missing", Vec::new()),
+            ProjectionDrift::Unexpected => ("unexpected", Vec::new()),
+        };
+        mismatches.push(ProjectionMismatch {
+            issue_id,
+            kind,
+            fields,
+        });
+    }
+
+    let output = SnapshotVerifyOutput {
+        timestamp: ts,
+        oid: snapshot.oid.to_string(),
+        status,
+        public_key,
+        reason,
+        newer_than_snapshot,
+        mismatches,
+    };
+
+    if cli.json {
+        output_success(cli, &output);
+    } else {
+        print_human(
+            cli,
+            &format_verify_report(&snapshot.ref_name, &output, id_len(cli)),
+        );
+    }
+
+    if let Some(reason) = output.reason {
+        return Err(GriteError::Internal(format!(
+            "Snapshot {} failed verification: {}",
+            ts, reason
+        )));
+    }
+    if !output.mismatches.is_empty() {
+        return Err(GriteError::Internal(format!(
+            "{} issue(s) in the store do not match snapshot {}",
+            output.mismatches.len(),
+            ts
+        )));
+    }
+
+    Ok(())
+}
+
+fn format_verify_report(ref_name: &str, output: &SnapshotVerifyOutput, id_len: usize) -> String {
+    let detail = output
+        .reason
+        .as_deref()
+        .map(|r| format!(" ({})", r))
+        .unwrap_or_default();
+    let mut lines = vec![format!(
+        "{}: signature {}{}",
+        ref_name, output.status, detail
+    )];
+    if !output.newer_than_snapshot.is_empty() {
+        lines.push(format!(
+            "{} issue(s) have events newer than the snapshot",
+            output.newer_than_snapshot.len()
+        ));
+    }
+    if output.mismatches.is_empty() {
+        lines.push("Store matches the snapshot".to_string());
+    } else {
+        lines.push(format!(
+            "{} issue(s) do not match the snapshot:",
+            output.mismatches.len()
+        ));
+        for m in &output.mismatches {
+            let what = match m.kind {
+                "missing" => "missing from the store".to_string(),
+                "unexpected" => "not in the snapshot".to_string(),
+                _ => format!("differs in {}", m.fields.join(", ")),
+            };
+            lines.push(format!("  {} {}", short_id(&m.issue_id, id_len), what));
+        }
+    }
+    lines.join("\n")
+}
@@ -2,11 +2,10 @@
 
 use crate::cli::Cli;
 use crate::context::GriteContext;
-use crate::output::{output_success, print_human};
+use crate::output::{id_len, output_success, print_human, short_id};
 use libgrite_core::types::ids::ActorId;
 use libgrite_core::{lock::LockCheckResult, GriteError};
-use libgrite_git::WalManager;
-use serde::Serialize;
+use libgrite_git::{CompatReport, PullPreview, SyncReport, WalManager};
 
 /// Check repo lock for push operations
 fn check_push_lock(cli: &Cli, ctx: &GriteContext) -> Result<(), GriteError> {
@@ -31,35 +30,6 @@ fn check_push_lock(cli: &Cli, ctx: &GriteContext) -> Result<(), GriteError> {
     }
 }
 
-#[derive(Serialize)]
-struct SyncOutput {
-    pulled: bool,
-    pushed: bool,
-    pull_events: usize,
-    pull_wal_head: Option<String>,
-    push_success: bool,
-    push_rebased: bool,
-    push_events_rebased: usize,
-    message: String,
-}
-
-#[derive(Serialize)]
-struct PullOutput {
-    success: bool,
-    events: usize,
-    wal_head: Option<String>,
-    message: String,
-}
-
-#[derive(Serialize)]
-struct PushOutput {
-    success: bool,
-    rebased: bool,
-    events_rebased: usize,
-    backfilled: usize,
-    message: String,
-}
-
 pub fn run(cli: &Cli, remote: String, pull_only: bool, push_only: bool) -> Result<(), GriteError> {
     let ctx = GriteContext::resolve(cli)?;
     let sync_mgr = ctx.open_sync()?;
@@ -75,120 +45,161 @@ pub fn run(cli: &Cli, remote: String, pull_only: bool, push_only: bool) -> Resul
     let do_push = !pull_only;
 
     // Check locks for push operations
+    let mut backfilled = 0;
     if do_push {
         check_push_lock(cli, &ctx)?;
 
         // Auto-backfill WAL from sled if WAL is empty
-        if let Some(count) = backfill_wal_if_needed(&ctx, &actor_id)? {
-            print_human(
-                cli,
-                &format!("Backfilled WAL with {} event(s) from local store", count),
-            );
-        }
+        backfilled = backfill_wal_if_needed(&ctx, &actor_id)?.unwrap_or(0);
     }
 
-    if do_pull && !do_push {
-        // Pull only
-        let result = sync_mgr.pull(&remote)?;
-
-        // Human-readable output
-        if result.events_pulled > 0 {
-            print_human(
-                cli,
-                &format!("Pulled {} events from {}", result.events_pulled, remote),
-            );
-        } else {
-            print_human(cli, &format!("Already up to date with {}", remote));
-        }
-
-        output_success(
-            cli,
-            PullOutput {
-                success: result.success,
-                events: result.events_pulled,
-                wal_head: result.new_wal_head.map(|oid| oid.to_string()),
-                message: result.message,
-            },
-        );
+    let report = if do_pull && !do_push {
+        let pull_result = sync_mgr.pull(&remote)?;
+        SyncReport::new(&remote, backfilled, Some(&pull_result), None)
     } else if do_push && !do_pull {
         // Push only with auto-rebase on conflict
-        let result = sync_mgr.push_with_rebase(&remote, &actor_id)?;
-
-        // Human-readable output with conflict reporting
-        if result.rebased {
-            print_human(
-                cli,
-                &format!(
-                    "Conflict resolved: rebased {} local events on top of remote",
-                    result.events_rebased
-                ),
-            );
-        }
-        if result.success {
-            print_human(cli, &format!("Pushed to {}", remote));
-        } else {
-            print_human(cli, &format!("Push failed: {}", result.message));
-        }
-
-        output_success(
-            cli,
-            PushOutput {
-                success: result.success,
-                rebased: result.rebased,
-                events_rebased: result.events_rebased,
-                backfilled: 0,
-                message: result.message,
-            },
-        );
+        let push_result = sync_mgr.push_with_rebase(&remote, &actor_id)?;
+        SyncReport::new(&remote, backfilled, None, Some(&push_result))
     } else {
         // Full sync: pull then push with auto-rebase
         let (pull_result, push_result) = sync_mgr.sync_with_rebase(&remote, &actor_id)?;
+        SyncReport::new(&remote, backfilled, Some(&pull_result), Some(&push_result))
+    };
 
-        // Human-readable output with conflict reporting
-        if pull_result.events_pulled > 0 {
-            print_human(
-                cli,
-                &format!(
-                    "Pulled {} events from {}",
-                    pull_result.events_pulled, remote
-                ),
-            );
-        }
+    if cli.json {
+        output_success(cli, &report);
+    } else {
+        print_human(cli, &format_sync_report(&report));
+    }
 
-        if push_result.rebased {
-            print_human(
-                cli,
-                &format!(
-                    "Conflict resolved: rebased {} local events on top of remote",
-                    push_result.events_rebased
-                ),
-            );
-        }
+    Ok(())
+}
+
+/// Report local chunks the remote has not declared it can read
+pub fn run_check_compat(cli: &Cli, remote: &str) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let report = ctx.open_sync()?.check_compat(remote)?;
+
+    if cli.json {
+        output_success(cli, &report);
+    } else {
+        print_human(cli, &format_compat_report(&report));
+    }
+
+    if !report.is_compatible() {
+        return Err(GriteError::Conflict(format!(
+            "{} chunk(s) incompatible with {}",
+            report.incompatible.len(),
+            report.remote
+        )));
+    }
 
-        if push_result.success {
-            print_human(cli, &format!("Pushed to {}", remote));
+    Ok(())
+}
+
+fn format_compat_report(report: &CompatReport) -> String {
+    let mut lines = vec![format!(
+        "Checked {} chunk(s) against {} ({})",
+        report.chunks_checked,
+        report.remote,
+        if report.declared {
+            "declared capabilities"
         } else {
-            print_human(cli, &format!("Push failed: {}", push_result.message));
+            "no declared capabilities, assuming cbor-v1 baseline"
         }
+    )];
+    for chunk in &report.incompatible {
+        lines.push(format!(
+            "  {} {}: {}",
+            &chunk.commit[..chunk.commit.len().min(8)],
+            chunk.path,
+            chunk.reasons.join(", ")
+        ));
+    }
+    if report.is_compatible() {
+        lines.push("All chunks compatible".to_string());
+    }
+    lines.join("\n")
+}
+
+/// Report what a pull from `remote` would apply
+pub fn run_preview(cli: &Cli, remote: &str) -> Result<(), GriteError> {
+    let ctx = GriteContext::resolve(cli)?;
+    let preview = ctx.open_sync()?.preview(remote)?;
 
-        output_success(
-            cli,
-            SyncOutput {
-                pulled: true,
-                pushed: true,
-                pull_events: pull_result.events_pulled,
-                pull_wal_head: pull_result.new_wal_head.map(|oid| oid.to_string()),
-                push_success: push_result.success,
-                push_rebased: push_result.rebased,
-                push_events_rebased: push_result.events_rebased,
-                message: format!("{} / {}", pull_result.message, push_result.message),
-            },
-        );
+    if cli.json {
+        output_success(cli, &preview);
+    } else {
+        print_human(cli, &format_pull_preview(&preview, id_len(cli)));
     }
 
     Ok(())
 }
 
+fn format_pull_preview(preview: &PullPreview, id_len: usize) -> String {
+    if preview.issues.is_empty() {
+        return format!("No new events from {}", preview.remote);
+    }
+    let mut lines = vec![format!(
+        "Pulling from {} would apply {} event(s) to {} issue(s):",
+        preview.remote,
+        preview.event_count,
+        preview.issues.len()
+    )];
+    for issue in &preview.issues {
+        let kinds: Vec<String> = issue
+            .kinds
+            .iter()
+            .map(|(kind, count)| format!("{} x{}", kind, count))
+            .collect();
+        lines.push(format!(
+            "  {} [{}] {}{}: {}",
+            short_id(&issue.issue_id, id_len),
+            issue.state.as_deref().unwrap_or("?"),
+            issue.title.as_deref().unwrap_or("(unknown)"),
+            if issue.new_issue { " (new)" } else { "" },
+            kinds.join(", ")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Human-readable summary of a sync, shared with daemon-routed syncs
+pub fn format_sync_report(report: &SyncReport) -> String {
+    let mut lines = Vec::new();
+    if report.backfilled > 0 {
+        lines.push(format!(
+            "Backfilled WAL with {} event(s) from local store",
+            report.backfilled
+        ));
+    }
+    if report.pulled {
+        if report.pull_events > 0 {
+            lines.push(format!(
+                "Pulled {} events from {}",
+                report.pull_events, report.remote
+            ));
+        } else if !report.pushed {
+            lines.push(format!("Already up to date with {}", report.remote));
+        }
+    }
+    if report.pushed {
+        // Conflict reporting
+        if report.push_rebased {
+            lines.push(format!(
+                "Conflict resolved: rebased {} local events on top of remote",
+                report.push_events_rebased
+            ));
+        }
+        if report.push_success {
+            lines.push(format!("Pushed to {}", report.remote));
+        } else {
+            lines.push(format!("Push failed: {}", report.message));
+        }
+    }
+    lines.join("\n")
+}
+
 /// Backfill WAL from sled events if WAL is empty.
 // Needed before push/sync.
 // Returns number of events backfilled, or None if WAL was already populated.
@@ -0,0 +1,690 @@
+//! Interactive terminal UI for browsing issues
+//!
+//! Reads through the daemon when one is running and through the local store
+//! otherwise. The daemon does not publish notifications to clients, so the
+//! open view reloads on a timer instead.
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{self, Event as TermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use crossterm::ExecutableCommand;
+use libgrite_core::{
+    config::load_repo_config,
+    export::EventJson,
+    hash::compute_event_id,
+    store::IssueFilter,
+    types::event::{EventKind, IssueState},
+    types::ids::{generate_issue_id, hex_to_id, id_to_hex},
+    Event, GriteError, IssueId, IssueSummary,
+};
+use libgrite_ipc::{DataEncoding, IpcCommand};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Terminal;
+
+use crate::cli::Cli;
+use crate::context::{ExecutionMode, GriteContext};
+use crate::event_helper::insert_and_append;
+use crate::output::{format_local_date, id_len, short_id};
+use crate::router::{daemon_error, send_to_daemon_encoded};
+
+/// How often the open view is reloaded
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Upper bound on timeline events fetched from the daemon
+const TIMELINE_LIMIT: u32 = 10_000;
+
+pub fn run(cli: &Cli) -> Result<(), GriteError> {
+    let mut source = Source::connect(cli)?;
+    let mut app = App::new(id_len(cli));
+    app.reload(&mut source);
+
+    let _guard = TerminalGuard::enter()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    let mut last_refresh = Instant::now();
+
+    while !app.should_quit {
+        terminal.draw(|frame| draw(frame, &app))?;
+
+        let timeout = REFRESH_INTERVAL.saturating_sub(last_refresh.elapsed());
+        if event::poll(timeout)? {
+            if let TermEvent::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    let action = app.handle_key(key);
+                    app.perform(&mut source, action);
+                }
+            }
+        }
+        if last_refresh.elapsed() >= REFRESH_INTERVAL {
+            app.reload(&mut source);
+            last_refresh = Instant::now();
+        }
+    }
+
+    Ok(())
+}
+
+/// Restores the terminal on exit, including on errors and panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> Result<Self, GriteError> {
+        enable_raw_mode()?;
+        io::stdout().execute(EnterAlternateScreen)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = io::stdout().execute(LeaveAlternateScreen);
+    }
+}
+
+/// Where issues are read from and written to
+enum Source {
+    Local(GriteContext),
+    Daemon {
+        ctx: GriteContext,
+        client: libgrite_ipc::IpcClient,
+    },
+}
+
+impl Source {
+    fn connect(cli: &Cli) -> Result<Self, GriteError> {
+        let ctx = GriteContext::resolve(cli)?;
+        match ctx.execution_mode(cli.no_daemon) {
+            ExecutionMode::Local => Ok(Source::Local(ctx)),
+            ExecutionMode::Daemon { client, .. } => Ok(Source::Daemon { ctx, client }),
+            ExecutionMode::Blocked { lock } => Err(GriteError::DbBusy(format!(
+                "Data directory is owned by daemon (pid {}) which is not responding",
+                lock.pid
+            ))),
+        }
+    }
+
+    /// Send a command to the daemon and decode its reply
+    ///
+    /// Issue lists can be large, so data is requested as MessagePack.
+    fn request(
+        ctx: &GriteContext,
+        client: &mut libgrite_ipc::IpcClient,
+        command: IpcCommand,
+    ) -> Result<serde_json::Value, GriteError> {
+        let response = send_to_daemon_encoded(ctx, client, command, DataEncoding::MsgPack)?;
+        if !response.ok {
+            let (code, message) = match response.error {
+                Some(err) => (err.code, err.message),
+                None => ("unknown".to_string(), "Unknown error".to_string()),
+            };
+            return Err(daemon_error(code, message));
+        }
+        let data = response
+            .decode_data()
+            .map_err(|e| GriteError::Ipc(e.to_string()))?;
+        Ok(data.unwrap_or(serde_json::Value::Null))
+    }
+
+    fn list(&mut self, filter: &IssueFilter) -> Result<Vec<IssueSummary>, GriteError> {
+        match self {
+            Source::Local(ctx) => ctx.open_store()?.list_issues(filter),
+            Source::Daemon { ctx, client } => {
+                let json = Self::request(
+                    ctx,
+                    client,
+                    IpcCommand::IssueList {
+                        state: filter.state.map(|s| s.as_str().to_string()),
+                        label: filter.label.clone(),
+                        assignee: None,
+                        unassigned: false,
+                        field: None,
+                        milestone: None,
+                        severity: None,
+                        include_deleted: filter.include_deleted,
+                        sort: "created".to_string(),
+                    },
+                )?;
+                let issues = json["issues"].as_array().cloned().unwrap_or_default();
+                Ok(issues.iter().filter_map(summary_from_json).collect())
+            }
+        }
+    }
+
+    fn timeline(&mut self, issue_id: &IssueId) -> Result<Vec<EventJson>, GriteError> {
+        match self {
+            Source::Local(ctx) => Ok(ctx
+                .open_store()?
+                .get_issue_events(issue_id)?
+                .iter()
+                .map(EventJson::from)
+                .collect()),
+            Source::Daemon { ctx, client } => {
+                let json = Self::request(
+                    ctx,
+                    client,
+                    IpcCommand::IssueEvents {
+                        issue_id: id_to_hex(issue_id),
+                        offset: 0,
+                        limit: TIMELINE_LIMIT,
+                    },
+                )?;
+                Ok(serde_json::from_value(json["events"].clone())?)
+            }
+        }
+    }
+
+    fn create(&mut self, title: String) -> Result<(), GriteError> {
+        match self {
+            Source::Local(ctx) => {
+                let labels = load_repo_config(&ctx.git_dir)?
+                    .unwrap_or_default()
+                    .issue_labels(&[])?;
+                let kind = EventKind::IssueCreated {
+                    title,
+                    body: String::new(),
+                    labels,
+                };
+                Self::append_local(ctx, generate_issue_id(), kind)
+            }
+            Source::Daemon { ctx, client } => {
+                Self::request(
+                    ctx,
+                    client,
+                    IpcCommand::IssueCreate {
+                        title,
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                )?;
+                Ok(())
+            }
+        }
+    }
+
+    fn close(&mut self, issue_id: &IssueId) -> Result<(), GriteError> {
+        match self {
+            Source::Local(ctx) => Self::append_local(
+                ctx,
+                *issue_id,
+                EventKind::StateChanged {
+                    state: IssueState::Closed,
+                },
+            ),
+            Source::Daemon { ctx, client } => {
+                Self::request(
+                    ctx,
+                    client,
+                    IpcCommand::IssueClose {
+                        issue_id: id_to_hex(issue_id),
+                    },
+                )?;
+                Ok(())
+            }
+        }
+    }
+
+    fn append_local(
+        ctx: &GriteContext,
+        issue_id: IssueId,
+        kind: EventKind,
+    ) -> Result<(), GriteError> {
+        let store = ctx.open_store()?;
+        let wal = ctx.open_wal()?;
+        let actor = ctx.actor_config.actor_id_bytes()?;
+
+        let ts = chrono::Utc::now().timestamp_millis() as u64;
+        let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+        let event = ctx.sign_event(Event::new(event_id, issue_id, actor, ts, None, kind));
+        insert_and_append(&store, &wal, &actor, &event)?;
+        Ok(())
+    }
+}
+
+/// Parse one entry of the daemon's `issues` list
+fn summary_from_json(json: &serde_json::Value) -> Option<IssueSummary> {
+    let strings = |key: &str| -> Vec<String> {
+        json[key]
+            .as_array()
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Some(IssueSummary {
+        issue_id: hex_to_id(json["issue_id"].as_str()?).ok()?,
+        title: json["title"].as_str()?.to_string(),
+        state: match json["state"].as_str()? {
+            "closed" => IssueState::Closed,
+            _ => IssueState::Open,
+        },
+        labels: strings("labels"),
+        assignees: strings("assignees"),
+        created_ts: json["created_ts"].as_u64().unwrap_or(0),
+        created_by: json["created_by"].as_str().and_then(|v| hex_to_id(v).ok()),
+        updated_ts: json["updated_ts"].as_u64().unwrap_or(0),
+        comment_count: json["comment_count"].as_u64().unwrap_or(0) as usize,
+        blocks_count: json["blocks_count"].as_u64().unwrap_or(0) as usize,
+        deleted: json["deleted"].as_bool().unwrap_or(false),
+    })
+}
+
+/// What the main area shows
+enum View {
+    List,
+    Issue {
+        issue_id: IssueId,
+        events: Vec<EventJson>,
+        scroll: u16,
+    },
+}
+
+/// A line of text being typed at the bottom of the screen
+struct Input {
+    purpose: InputPurpose,
+    text: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum InputPurpose {
+    NewIssue,
+    LabelFilter,
+}
+
+/// Work requested by a key press that needs the data source
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    None,
+    Reload,
+    Open(IssueId),
+    Create(String),
+    Close(IssueId),
+}
+
+/// TUI state, kept apart from the terminal so it can be tested
+struct App {
+    id_len: usize,
+    issues: Vec<IssueSummary>,
+    selected: usize,
+    state_filter: Option<IssueState>,
+    label_filter: Option<String>,
+    view: View,
+    input: Option<Input>,
+    status: String,
+    should_quit: bool,
+}
+
+impl App {
+    fn new(id_len: usize) -> Self {
+        Self {
+            id_len,
+            issues: Vec::new(),
+            selected: 0,
+            state_filter: None,
+            label_filter: None,
+            view: View::List,
+            input: None,
+            status: String::new(),
+            should_quit: false,
+        }
+    }
+
+    fn filter(&self) -> IssueFilter {
+        IssueFilter {
+            state: self.state_filter,
+            label: self.label_filter.clone(),
+            ..IssueFilter::default()
+        }
+    }
+
+    fn set_issues(&mut self, issues: Vec<IssueSummary>) {
+        self.issues = issues;
+        self.selected = self.selected.min(self.issues.len().saturating_sub(1));
+    }
+
+    fn selected_issue(&self) -> Option<&IssueSummary> {
+        self.issues.get(self.selected)
+    }
+
+    /// Reload the list and, if one is open, the issue's timeline
+    fn reload(&mut self, source: &mut Source) {
+        match source.list(&self.filter()) {
+            Ok(issues) => self.set_issues(issues),
+            Err(e) => self.status = format!("error: {}", e),
+        }
+        if let View::Issue {
+            issue_id, events, ..
+        } = &mut self.view
+        {
+            match source.timeline(issue_id) {
+                Ok(fresh) => *events = fresh,
+                Err(e) => self.status = format!("error: {}", e),
+            }
+        }
+    }
+
+    fn perform(&mut self, source: &mut Source, action: Action) {
+        let result = match action {
+            Action::None => return,
+            Action::Reload => Ok(()),
+            Action::Open(issue_id) => source.timeline(&issue_id).map(|events| {
+                self.view = View::Issue {
+                    issue_id,
+                    events,
+                    scroll: 0,
+                };
+            }),
+            Action::Create(title) => source.create(title).map(|()| {
+                self.status = "Created issue".to_string();
+            }),
+            Action::Close(issue_id) => source.close(&issue_id).map(|()| {
+                self.status = format!("Closed {}", short_id(&id_to_hex(&issue_id), self.id_len));
+            }),
+        };
+        match result {
+            Ok(()) => self.reload(source),
+            Err(e) => self.status = format!("error: {}", e),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return Action::None;
+        }
+        if self.input.is_some() {
+            return self.handle_input_key(key);
+        }
+        match self.view {
+            View::List => self.handle_list_key(key),
+            View::Issue { .. } => self.handle_issue_key(key),
+        }
+    }
+
+    fn handle_list_key(&mut self, key: KeyEvent) -> Action {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.issues.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Enter => {
+                if let Some(issue) = self.selected_issue() {
+                    return Action::Open(issue.issue_id);
+                }
+            }
+            KeyCode::Char('s') => {
+                self.state_filter = match self.state_filter {
+                    None => Some(IssueState::Open),
+                    Some(IssueState::Open) => Some(IssueState::Closed),
+                    Some(IssueState::Closed) => None,
+                };
+                self.selected = 0;
+                return Action::Reload;
+            }
+            KeyCode::Char('l') => self.start_input(InputPurpose::LabelFilter),
+            KeyCode::Char('n') => self.start_input(InputPurpose::NewIssue),
+            KeyCode::Char('c') => {
+                if let Some(issue) = self.selected_issue() {
+                    return Action::Close(issue.issue_id);
+                }
+            }
+            KeyCode::Char('r') => return Action::Reload,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn handle_issue_key(&mut self, key: KeyEvent) -> Action {
+        let View::Issue {
+            issue_id, scroll, ..
+        } = &mut self.view
+        else {
+            return Action::None;
+        };
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.view = View::List,
+            KeyCode::Down | KeyCode::Char('j') => *scroll = scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => *scroll = scroll.saturating_sub(1),
+            KeyCode::Char('c') => return Action::Close(*issue_id),
+            KeyCode::Char('r') => return Action::Reload,
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn start_input(&mut self, purpose: InputPurpose) {
+        let text = match purpose {
+            InputPurpose::LabelFilter => self.label_filter.clone().unwrap_or_default(),
+            InputPurpose::NewIssue => String::new(),
+        };
+        self.input = Some(Input { purpose, text });
+    }
+
+    fn handle_input_key(&mut self, key: KeyEvent) -> Action {
+        let Some(input) = self.input.as_mut() else {
+            return Action::None;
+        };
+        match key.code {
+            KeyCode::Esc => self.input = None,
+            KeyCode::Backspace => {
+                input.text.pop();
+            }
+            KeyCode::Char(c) => input.text.push(c),
+            KeyCode::Enter => {
+                let Input { purpose, text } = self.input.take().expect("input is active");
+                let text = text.trim().to_string();
+                match purpose {
+                    InputPurpose::LabelFilter => {
+                        self.label_filter = (!text.is_empty()).then_some(text);
+                        self.selected = 0;
+                        return Action::Reload;
+                    }
+                    InputPurpose::NewIssue if !text.is_empty() => return Action::Create(text),
+                    InputPurpose::NewIssue => {}
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [header, main, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let state = app.state_filter.map_or("all", |s| s.as_str());
+    let label = app.label_filter.as_deref().unwrap_or("any");
+    frame.render_widget(
+        Paragraph::new(format!(
+            "grite  state: {}  label: {}  {} issues",
+            state,
+            label,
+            app.issues.len()
+        ))
+        .style(Style::default().add_modifier(Modifier::BOLD)),
+        header,
+    );
+
+    match &app.view {
+        View::List => draw_list(frame, app, main),
+        View::Issue {
+            issue_id,
+            events,
+            scroll,
+        } => draw_issue(frame, app, issue_id, events, *scroll, main),
+    }
+
+    let footer_text = match &app.input {
+        Some(input) => {
+            let prompt = match input.purpose {
+                InputPurpose::NewIssue => "New issue title",
+                InputPurpose::LabelFilter => "Label (empty for any)",
+            };
+            format!("{}: {}_", prompt, input.text)
+        }
+        None if !app.status.is_empty() => app.status.clone(),
+        None => match app.view {
+            View::List => {
+                "j/k move  enter open  s state  l label  n new  c close  r reload  q quit"
+                    .to_string()
+            }
+            View::Issue { .. } => "j/k scroll  c close  r reload  esc back".to_string(),
+        },
+    };
+    frame.render_widget(Paragraph::new(footer_text), footer);
+}
+
+fn draw_list(frame: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .issues
+        .iter()
+        .map(|issue| {
+            let state_style = match issue.state {
+                IssueState::Open => Style::default().fg(Color::Blue),
+                IssueState::Closed => Style::default().fg(Color::Yellow),
+            };
+            let mut spans = vec![
+                Span::raw(format!(
+                    "{}  ",
+                    short_id(&id_to_hex(&issue.issue_id), app.id_len)
+                )),
+                Span::styled(format!("{:<6}  ", issue.state.as_str()), state_style),
+                Span::raw(issue.title.clone()),
+            ];
+            if !issue.labels.is_empty() {
+                spans.push(Span::styled(
+                    format!("  [{}]", issue.labels.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Issues"))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_issue(
+    frame: &mut Frame,
+    app: &App,
+    issue_id: &IssueId,
+    events: &[EventJson],
+    scroll: u16,
+    area: Rect,
+) {
+    let lines: Vec<Line> = events
+        .iter()
+        .map(|event| {
+            let (kind, detail) = match event.kind.as_object().and_then(|o| o.iter().next()) {
+                Some((name, payload)) => (name.clone(), payload.to_string()),
+                None => (event.kind.to_string(), String::new()),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{}  ", format_local_date(event.ts_unix_ms)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(format!("{}  ", short_id(&event.actor, app.id_len))),
+                Span::styled(kind, Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw(format!("  {}", detail)),
+            ])
+        })
+        .collect();
+
+    let title = format!("Issue {}", id_to_hex(issue_id));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0)),
+        area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libgrite_core::GriteStore;
+    use ratatui::backend::TestBackend;
+    use tempfile::TempDir;
+
+    fn create(store: &GriteStore, title: &str, labels: &[&str], ts: u64) -> IssueId {
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        let kind = EventKind::IssueCreated {
+            title: title.to_string(),
+            body: String::new(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+        };
+        let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+        store
+            .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+            .unwrap();
+        issue_id
+    }
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_issue_list_renders_from_store() {
+        let temp = TempDir::new().unwrap();
+        let store = GriteStore::open(temp.path()).unwrap();
+        let crash = create(&store, "Crash on startup", &["bug"], 1000);
+        create(&store, "Add dark mode", &[], 2000);
+
+        let mut app = App::new(8);
+        app.set_issues(store.list_issues(&app.filter()).unwrap());
+        let screen = render(&app);
+        assert!(screen.contains("2 issues"), "{}", screen);
+        assert!(screen.contains("Crash on startup"));
+        assert!(screen.contains("[bug]"));
+        assert!(screen.contains("Add dark mode"));
+
+        // Label filters go through the store's issue filter
+        app.handle_key(key(KeyCode::Char('l')));
+        for c in "bug".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::Reload);
+        app.set_issues(store.list_issues(&app.filter()).unwrap());
+        let screen = render(&app);
+        assert!(screen.contains("label: bug  1 issues"), "{}", screen);
+        assert!(!screen.contains("Add dark mode"));
+
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Action::Open(crash));
+        assert_eq!(
+            app.handle_key(key(KeyCode::Char('c'))),
+            Action::Close(crash)
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::cli::Cli;
 use git2::Repository;
 use libgrite_core::{
     config::{
-        actor_dir, list_actors, load_actor_config, load_repo_config, load_signing_key,
-        repo_sled_path, save_actor_config, save_repo_config, RepoConfig,
+        actor_dir, auto_init_disabled_by_env, list_actors, load_actor_config, load_repo_config,
+        load_signing_key, load_store_options, repo_sled_path, save_actor_config,
+        update_repo_config,
     },
     lock::{LockCheckResult, LockPolicy},
     signing::SigningKeyPair,
@@ -15,7 +17,7 @@ use libgrite_core::{
     GriteError, GriteStore, LockedStore,
 };
 use libgrite_git::{GitError, LockManager, SnapshotManager, SyncManager, WalManager};
-use libgrite_ipc::{DaemonLock, IpcClient};
+use libgrite_ipc::{DaemonLock, DaemonRegistry, IpcClient};
 
 /// Source of actor selection
 #[derive(Debug, Clone, Copy)]
@@ -73,6 +75,8 @@ pub struct GriteContext {
     pub actor_config: ActorConfig,
     pub data_dir: PathBuf,
     pub source: ActorSource,
+    /// How long `open_store` retries while another process holds the lock
+    pub store_wait: Duration,
 }
 
 impl GriteContext {
@@ -122,9 +126,15 @@ impl GriteContext {
     /// 1. --data-dir or GRITE_HOME
     /// 2. --actor <id>
     /// 3. Repo default in .git/grite/config.toml
-    /// 4. Auto-init a new actor if none exists
+    /// 4. Auto-init a new actor if none exists, unless --no-auto-init or
+    ///    GRITE_NO_AUTO_INIT is set
     pub fn resolve(cli: &Cli) -> Result<Self, GriteError> {
-        let git_dir = Self::find_git_dir()?;
+        Self::resolve_at(Self::find_git_dir()?, cli)
+    }
+
+    /// Resolve the actor context for a known git directory
+    fn resolve_at(git_dir: PathBuf, cli: &Cli) -> Result<Self, GriteError> {
+        let store_wait = store_wait(cli, &git_dir);
 
         // 1. Check --data-dir or GRITE_HOME
         if let Some(ref data_dir) = cli.data_dir {
@@ -135,6 +145,7 @@ impl GriteContext {
                 actor_config: config,
                 data_dir: data_dir.clone(),
                 source: ActorSource::DataDir,
+                store_wait,
             });
         }
 
@@ -147,6 +158,7 @@ impl GriteContext {
                 actor_config: config,
                 data_dir,
                 source: ActorSource::DataDir,
+                store_wait,
             });
         }
 
@@ -160,6 +172,7 @@ impl GriteContext {
                 actor_config: config,
                 data_dir,
                 source: ActorSource::Flag,
+                store_wait,
             });
         }
 
@@ -174,6 +187,7 @@ impl GriteContext {
                         actor_config: config,
                         data_dir,
                         source: ActorSource::RepoDefault,
+                        store_wait,
                     });
                 }
             }
@@ -189,10 +203,18 @@ impl GriteContext {
                 actor_config: first_actor.clone(),
                 data_dir,
                 source: ActorSource::Auto,
+                store_wait,
             });
         }
 
-        // No actors exist - auto-init
+        // No actors exist - auto-init unless disabled
+        if cli.no_auto_init || auto_init_disabled_by_env() {
+            return Err(GriteError::NotFound(
+                "No actor configured for this repository. Run 'grite init' or 'grite actor init' to create one"
+                    .to_string(),
+            ));
+        }
+
         let actor_id = generate_actor_id();
         let actor_id_hex = id_to_hex(&actor_id);
         let data_dir = actor_dir(&git_dir, &actor_id_hex);
@@ -201,12 +223,10 @@ impl GriteContext {
         // Create actor directory and config
         save_actor_config(&data_dir, &config)?;
 
-        // Set as repo default
-        let repo_config = RepoConfig {
-            default_actor: Some(actor_id_hex.clone()),
-            ..Default::default()
-        };
-        save_repo_config(&git_dir, &repo_config)?;
+        // Set as repo default, keeping any other repo settings
+        update_repo_config(&git_dir, |cfg| {
+            cfg.default_actor = Some(actor_id_hex.clone())
+        })?;
 
         Ok(Self {
             git_dir,
@@ -214,14 +234,17 @@ impl GriteContext {
             actor_config: config,
             data_dir,
             source: ActorSource::Auto,
+            store_wait,
         })
     }
 
     /// Open the store for this context with exclusive filesystem lock.
     ///
-    /// Returns `GriteError::DbBusy` if another process holds the lock.
+    /// While another process holds the lock, retries with backoff for up to
+    /// `store_wait` (`--wait`). Returns `GriteError::DbBusy` if the lock is
+    /// still held after that, or at once when `store_wait` is zero.
     pub fn open_store(&self) -> Result<LockedStore, GriteError> {
-        GriteStore::open_locked(&repo_sled_path(&self.git_dir))
+        open_store_at(&self.git_dir, self.store_wait)
     }
 
     /// Get the sled database path
@@ -315,54 +338,132 @@ impl GriteContext {
         event
     }
 
+    /// Endpoint of the live daemon registered for this repo and actor
+    pub fn registered_endpoint(&self, registry: &DaemonRegistry) -> Option<String> {
+        registry
+            .lookup(&self.repo_root().to_string_lossy(), &self.actor_id)
+            .ok()
+            .flatten()
+            .map(|entry| entry.endpoint)
+    }
+
     /// Determine execution mode (local vs daemon)
     ///
     /// Resolution order:
     /// 1. If --no-daemon flag is set, always use Local
-    /// 2. Check for daemon.lock file in data directory
-    /// 3. If lock exists and is valid, try to connect to daemon
-    /// 4. If connection succeeds, return Daemon mode
-    /// 5. If lock is valid but connection fails, return Blocked
-    /// 6. If no lock or lock is expired, return Local
+    /// 2. If the discovery registry lists a reachable daemon for this
+    ///    repo and actor, return Daemon mode
+    /// 3. Check for daemon.lock file in data directory
+    /// 4. If lock exists and is valid, try to connect to daemon
+    /// 5. If connection succeeds, return Daemon mode
+    /// 6. If lock is valid but connection fails, return Blocked
+    /// 7. If no lock or lock is expired, return Local
     pub fn execution_mode(&self, no_daemon: bool) -> ExecutionMode {
+        self.execution_mode_traced(no_daemon, &mut Vec::new())
+    }
+
+    /// Determine execution mode, appending one line per decision to `trace`
+    ///
+    /// Same resolution as [`GriteContext::execution_mode`]; used by
+    /// `--explain-routing`.
+    pub fn execution_mode_traced(&self, no_daemon: bool, trace: &mut Vec<String>) -> ExecutionMode {
         // 1. Check --no-daemon flag
         if no_daemon {
+            trace.push("--no-daemon set → local".to_string());
             return ExecutionMode::Local;
         }
 
-        // 2. Check for daemon lock
+        // 2. Check the discovery registry
+        match self.registered_endpoint(&DaemonRegistry::default()) {
+            Some(endpoint) => match IpcClient::connect(&endpoint) {
+                Ok(client) => {
+                    trace.push(format!("registry: connected to {} → daemon", endpoint));
+                    return ExecutionMode::Daemon { endpoint, client };
+                }
+                Err(e) => trace.push(format!(
+                    "registry: connecting to {} failed: {}",
+                    endpoint, e
+                )),
+            },
+            None => trace.push("registry: no daemon registered".to_string()),
+        }
+
+        // 3. Check for daemon lock
         match DaemonLock::read(&self.git_dir.join("grite")) {
             Ok(Some(lock)) => {
-                // 3. Check if lock is still valid
+                trace.push(format!("daemon lock: found (PID {})", lock.pid));
+
+                // 4. Check if lock is still valid
                 if lock.is_expired() {
                     // Lock expired, can execute locally
+                    trace.push("lock expired → local".to_string());
                     return ExecutionMode::Local;
                 }
 
-                // 4. Try to connect to daemon
+                // 5. Try to connect to daemon
                 match IpcClient::connect(&lock.ipc_endpoint) {
-                    Ok(client) => ExecutionMode::Daemon {
-                        endpoint: lock.ipc_endpoint.clone(),
-                        client,
-                    },
-                    Err(_) => {
-                        // 5. Lock valid but can't connect - blocked
+                    Ok(client) => {
+                        trace.push(format!("connected to {} → daemon", lock.ipc_endpoint));
+                        ExecutionMode::Daemon {
+                            endpoint: lock.ipc_endpoint.clone(),
+                            client,
+                        }
+                    }
+                    Err(e) => {
+                        // 6. Lock valid but can't connect - blocked
+                        trace.push(format!(
+                            "connecting to {} failed ({}) while the lock is live → blocked",
+                            lock.ipc_endpoint, e
+                        ));
                         ExecutionMode::Blocked { lock }
                     }
                 }
             }
             Ok(None) => {
                 // No lock file, execute locally
+                trace.push("daemon lock: none → local".to_string());
                 ExecutionMode::Local
             }
-            Err(_) => {
+            Err(e) => {
                 // Error reading lock, execute locally
+                trace.push(format!("daemon lock: unreadable ({}) → local", e));
                 ExecutionMode::Local
             }
         }
     }
 }
 
+/// Store wait when `--wait` is not given and no daemon is running
+const DEFAULT_STORE_WAIT_SECS: u64 = 2;
+
+/// How long to retry opening a busy store
+///
+/// An explicit `--wait` wins. Otherwise a running daemon holds the store for
+/// as long as it runs, so waiting would only delay the `db_busy` error.
+pub fn store_wait(cli: &Cli, git_dir: &Path) -> Duration {
+    let secs = cli
+        .wait
+        .unwrap_or_else(|| match DaemonLock::read(&git_dir.join("grite")) {
+            Ok(Some(lock)) if !lock.is_expired() => 0,
+            _ => DEFAULT_STORE_WAIT_SECS,
+        });
+    Duration::from_secs(secs)
+}
+
+/// Open the store in `git_dir`, retrying for up to `wait` while it is locked.
+///
+/// For commands that need the store but not an actor; see
+/// [`GriteContext::open_store`].
+pub fn open_store_at(git_dir: &Path, wait: Duration) -> Result<LockedStore, GriteError> {
+    let path = repo_sled_path(git_dir);
+    let options = load_store_options(git_dir)?;
+    if wait.is_zero() {
+        GriteStore::open_locked_with_options(&path, &options)
+    } else {
+        GriteStore::open_locked_blocking_with_options(&path, wait, &options)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -468,4 +569,129 @@ mod tests {
             temp.path().join(".git").canonicalize().unwrap()
         );
     }
+
+    #[test]
+    fn test_registered_endpoint_per_repo() {
+        use libgrite_ipc::RegistryEntry;
+
+        let temp = TempDir::new().unwrap();
+        let registry = DaemonRegistry::new(temp.path().join("daemons.json"));
+        let actor_id = "00112233445566778899aabbccddeeff".to_string();
+        let context_for = |repo: &str| GriteContext {
+            git_dir: temp.path().join(repo).join(".git"),
+            actor_id: actor_id.clone(),
+            actor_config: ActorConfig {
+                actor_id: actor_id.clone(),
+                label: None,
+                created_ts: None,
+                public_key: None,
+                key_scheme: None,
+            },
+            data_dir: temp
+                .path()
+                .join(repo)
+                .join(".git/grite/actors")
+                .join(&actor_id),
+            source: ActorSource::Auto,
+            store_wait: Duration::ZERO,
+        };
+        let repo_a = context_for("a");
+        let repo_b = context_for("b");
+
+        for (ctx, endpoint) in [(&repo_a, "/tmp/a.sock"), (&repo_b, "/tmp/b.sock")] {
+            registry
+                .register(RegistryEntry {
+                    repo_root: ctx.repo_root().to_string_lossy().to_string(),
+                    actor_id: actor_id.clone(),
+                    endpoint: endpoint.to_string(),
+                    pid: std::process::id(),
+                })
+                .unwrap();
+        }
+
+        assert_eq!(
+            repo_a.registered_endpoint(&registry).as_deref(),
+            Some("/tmp/a.sock")
+        );
+        assert_eq!(
+            repo_b.registered_endpoint(&registry).as_deref(),
+            Some("/tmp/b.sock")
+        );
+        assert!(context_for("c").registered_endpoint(&registry).is_none());
+    }
+
+    #[test]
+    fn test_no_auto_init_errors_without_actors() {
+        use clap::Parser;
+
+        let temp = TempDir::new().unwrap();
+        assert!(git(&["init"], temp.path()));
+        let git_dir = temp.path().join(".git");
+
+        let cli = Cli::try_parse_from(["grite", "--no-auto-init", "issue", "list"]).unwrap();
+        let err = GriteContext::resolve_at(git_dir.clone(), &cli)
+            .err()
+            .expect("resolution should fail without an actor");
+        assert!(matches!(err, GriteError::NotFound(_)), "{err:?}");
+        assert!(list_actors(&git_dir).unwrap().is_empty());
+        assert!(load_repo_config(&git_dir).unwrap().is_none());
+
+        // Without the flag the same command provisions an actor
+        let cli = Cli::try_parse_from(["grite", "issue", "list"]).unwrap();
+        let ctx = GriteContext::resolve_at(git_dir.clone(), &cli).unwrap();
+        assert_eq!(list_actors(&git_dir).unwrap().len(), 1);
+        assert!(matches!(ctx.source, ActorSource::Auto));
+    }
+
+    #[test]
+    fn test_open_store_waits_for_lock() {
+        use clap::Parser;
+
+        let temp = TempDir::new().unwrap();
+        assert!(git(&["init"], temp.path()));
+        let git_dir = temp.path().join(".git");
+
+        let cli = Cli::try_parse_from(["grite", "--wait", "0", "issue", "list"]).unwrap();
+        let mut ctx = GriteContext::resolve_at(git_dir.clone(), &cli).unwrap();
+        assert!(ctx.store_wait.is_zero());
+
+        // Another process briefly holds the lock
+        let held = GriteStore::open_locked(&repo_sled_path(&git_dir)).unwrap();
+        assert!(matches!(ctx.open_store(), Err(GriteError::DbBusy(_))));
+        let holder = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            drop(held);
+        });
+
+        ctx.store_wait = Duration::from_secs(5);
+        assert!(ctx.open_store().is_ok());
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn test_store_wait_defaults_to_zero_under_a_daemon() {
+        use clap::Parser;
+
+        let temp = TempDir::new().unwrap();
+        let git_dir = temp.path().join(".git");
+        let grite_dir = git_dir.join("grite");
+        std::fs::create_dir_all(&grite_dir).unwrap();
+        let cli = Cli::try_parse_from(["grite", "issue", "list"]).unwrap();
+        assert_eq!(store_wait(&cli, &git_dir), Duration::from_secs(2));
+
+        DaemonLock::new(
+            1,
+            temp.path().to_string_lossy().to_string(),
+            "actor".to_string(),
+            "host".to_string(),
+            "ipc:///tmp/grite-test.sock".to_string(),
+        )
+        .write(&grite_dir)
+        .unwrap();
+        assert!(store_wait(&cli, &git_dir).is_zero());
+
+        // An explicit --wait still applies
+        let cli = Cli::try_parse_from(["grite", "--wait", "3", "issue", "list"]).unwrap();
+        assert_eq!(store_wait(&cli, &git_dir), Duration::from_secs(3));
+    }
 }
@@ -1,8 +1,19 @@
 //! Helper for inserting events into both sled store and Git WAL
 
+use std::sync::OnceLock;
+
+use libgrite_core::audit::AuditLog;
 use libgrite_core::{types::event::Event, types::ids::ActorId, GriteError, GriteStore};
 use libgrite_git::{GitError, WalManager};
 
+/// Command being run, as recorded in the audit log
+static COMMAND_NAME: OnceLock<String> = OnceLock::new();
+
+/// Set the command name recorded in the audit log (e.g. `issue create`)
+pub fn set_command_name(name: String) {
+    let _ = COMMAND_NAME.set(name);
+}
+
 /// Result of inserting an event
 pub struct InsertResult {
     /// The WAL commit OID (hex string), if WAL append succeeded
@@ -16,7 +27,8 @@ pub struct InsertResult {
 /// 2. Appends the event to the Git WAL (for durability and sync)
 ///
 /// If WAL append fails, the event is still persisted in sled and
-/// an error is logged but not returned.
+/// an error is logged but not returned. The same goes for the audit log,
+/// when the repo config enables it.
 pub fn insert_and_append(
     store: &GriteStore,
     wal: &WalManager,
@@ -36,10 +48,50 @@ pub fn insert_and_append(
             None
         }
     };
+    audit(wal, std::slice::from_ref(event));
 
     Ok(InsertResult { wal_head })
 }
 
+/// Insert several events into the sled store and append them to the Git WAL
+/// as a single commit
+///
+/// Same failure handling as [`insert_and_append`].
+pub fn insert_all_and_append(
+    store: &GriteStore,
+    wal: &WalManager,
+    actor: &ActorId,
+    events: &[Event],
+) -> Result<InsertResult, GriteError> {
+    for event in events {
+        store.insert_event(event)?;
+    }
+    store.flush()?;
+
+    let wal_head = match wal.append(actor, events) {
+        Ok(oid) => Some(oid.to_string()),
+        Err(e) => {
+            eprintln!("Warning: Failed to append to WAL: {}", e);
+            None
+        }
+    };
+    audit(wal, events);
+
+    Ok(InsertResult { wal_head })
+}
+
+/// Append events to the audit log if the repo config enables it
+///
+/// Failures are reported as warnings; the events are already persisted.
+fn audit(wal: &WalManager, events: &[Event]) {
+    let command = COMMAND_NAME.get().map(String::as_str).unwrap_or("unknown");
+    let result = AuditLog::for_repo(wal.git_dir(), command)
+        .and_then(|log| log.map_or(Ok(()), |log| log.record(events)));
+    if let Err(e) = result {
+        eprintln!("Warning: Failed to write audit log: {}", e);
+    }
+}
+
 /// Try to append to WAL without inserting to store
 /// Useful for batch operations or when store is already updated
 #[allow(dead_code)]
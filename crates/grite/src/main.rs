@@ -6,14 +6,35 @@ mod event_helper;
 mod output;
 mod router;
 
-use clap::Parser;
+use std::io::Write;
+
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
 use cli::{Cli, Command};
+use libgrite_core::types::ids::hex_to_id;
 use libgrite_core::GriteError;
 
 fn main() {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    event_helper::set_command_name(subcommand_path(&matches));
+
+    // Deterministic test mode resumes where the last run in this repo stopped
+    if libgrite_core::test_state::is_enabled() {
+        if let Ok(git_dir) = context::GriteContext::find_git_dir() {
+            libgrite_core::test_state::persist_in(&git_dir.join("grite"));
+        }
+    }
 
-    let result = run_command(&cli);
+    if cli.explain_routing {
+        for line in router::explain_routing(&cli) {
+            eprintln!("routing: {}", line);
+        }
+        cli.no_daemon = true;
+    }
+
+    let result = output::apply_id_len_config(&mut cli)
+        .and_then(|()| commands::issue::apply_template(&mut cli))
+        .and_then(|()| run_command(&cli));
 
     if let Err(e) = result {
         output::output_error(&cli, &e);
@@ -21,9 +42,20 @@ fn main() {
     }
 }
 
+/// Full subcommand path as typed, e.g. `issue label add`
+fn subcommand_path(matches: &ArgMatches) -> String {
+    let mut names = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        names.push(name);
+        current = sub;
+    }
+    names.join(" ")
+}
+
 fn run_command(cli: &Cli) -> Result<(), GriteError> {
     // Check if this command can be routed through daemon
-    if router::should_route_through_daemon(&cli.command) {
+    if router::should_route_through_daemon(&cli.command, router::local_commands()) {
         if let Some(ipc_cmd) = router::cli_to_ipc_command(&cli.command) {
             // Try to route through daemon
             if let Some(result) = try_route_through_daemon(cli, ipc_cmd)? {
@@ -39,18 +71,54 @@ fn run_command(cli: &Cli) -> Result<(), GriteError> {
         Command::Actor { cmd } => commands::actor::run(cli, cmd.clone()),
         Command::Issue { cmd } => commands::issue::run(cli, cmd.clone()),
         Command::Db { cmd } => commands::db::run(cli, cmd.clone()),
-        Command::Export { format, since } => {
-            commands::export::run(cli, format.clone(), since.clone())
-        }
-        Command::Rebuild { from_snapshot } => commands::rebuild::run(cli, *from_snapshot),
-        Command::Sync { remote, pull, push } => {
-            commands::sync::run(cli, remote.clone(), *pull, *push)
-        }
+        Command::Export {
+            format,
+            since,
+            order,
+            issue,
+            kinds,
+            count_only,
+            include_attachments,
+            anonymize,
+            out,
+        } => commands::export::run(
+            cli,
+            *format,
+            since.clone(),
+            *order,
+            issue.clone(),
+            kinds.clone(),
+            *count_only,
+            *include_attachments,
+            *anonymize,
+            out.clone(),
+        ),
+        Command::Rebuild {
+            from_snapshot,
+            dry_run,
+            skip_errors,
+        } => commands::rebuild::run(cli, *from_snapshot, *dry_run, *skip_errors),
+        Command::Sync {
+            check_compat: Some(compat_remote),
+            ..
+        } => commands::sync::run_check_compat(cli, compat_remote),
+        Command::Sync {
+            remote,
+            preview: true,
+            ..
+        } => commands::sync::run_preview(cli, remote),
+        Command::Sync {
+            remote, pull, push, ..
+        } => commands::sync::run(cli, remote.clone(), *pull, *push),
         Command::Snapshot { cmd } => commands::snapshot::run(cli, cmd.clone()),
         Command::Daemon { cmd } => commands::daemon::run(cli, cmd.clone()),
         Command::Lock { cmd } => commands::lock::run(cli, cmd.clone()),
         Command::Doctor { fix } => commands::doctor::run(cli, *fix),
+        Command::Milestone { cmd } => commands::milestone::run(cli, cmd.clone()),
+        Command::Report { cmd } => commands::report::run(cli, cmd.clone()),
+        Command::Tui => commands::tui::run(cli),
         Command::Context { cmd } => commands::context::run(cli, cmd.clone()),
+        Command::Errors => commands::errors::run(cli),
         Command::InstallSkill { global, force } => {
             commands::install_skill::run(cli, *global, *force)
         }
@@ -99,7 +167,7 @@ fn handle_daemon_response(
             if cli.json {
                 // Data is already JSON, just print it
                 if !cli.quiet {
-                    println!("{}", data);
+                    writeln!(output::stdout_writer(cli), "{}", data)?;
                 }
             } else {
                 // Try to format nicely
@@ -114,18 +182,17 @@ fn handle_daemon_response(
             None => ("unknown".to_string(), "Unknown error".to_string()),
         };
 
-        // Map error code to GriteError
-        match code.as_str() {
-            "not_found" => Err(GriteError::NotFound(message)),
-            "invalid_input" | "invalid_args" => Err(GriteError::InvalidArgs(message)),
-            "conflict" => Err(GriteError::Conflict(message)),
-            "db_busy" => Err(GriteError::DbBusy(message)),
-            "ipc_error" => Err(GriteError::Ipc(message)),
-            _ => Err(GriteError::Internal(message)),
-        }
+        Err(router::daemon_error(code, message))
     }
 }
 
+/// Actor labels for rendering daemon responses, empty if unavailable
+fn daemon_actor_names() -> std::collections::HashMap<libgrite_core::types::ids::ActorId, String> {
+    context::GriteContext::find_git_dir()
+        .and_then(|git_dir| output::actor_names(&git_dir))
+        .unwrap_or_default()
+}
+
 /// Output daemon response data in human-readable format
 fn output_daemon_data(cli: &Cli, data: &str) -> Result<(), GriteError> {
     if cli.quiet {
@@ -135,9 +202,16 @@ fn output_daemon_data(cli: &Cli, data: &str) -> Result<(), GriteError> {
     // Try to parse as JSON and format nicely
     if let Ok(json) = serde_json::from_str::<serde_json::Value>(data) {
         // Handle known response types
-        if let Some(issues) = json.get("issues") {
+        if let (Some(events), Some(issues)) = (
+            json.get("events").and_then(|v| v.as_u64()),
+            json.get("issues").and_then(|v| v.as_u64()),
+        ) {
+            // Export --count-only response
+            println!("{} events, {} issues", events, issues);
+        } else if let Some(issues) = json.get("issues") {
             // Issue list response
             if let Some(arr) = issues.as_array() {
+                let names = daemon_actor_names();
                 let rows: Vec<output::IssueRow> = arr
                     .iter()
                     .map(|issue| {
@@ -147,19 +221,40 @@ fn output_daemon_data(cli: &Cli, data: &str) -> Result<(), GriteError> {
                             .unwrap_or("?");
                         let state = issue.get("state").and_then(|v| v.as_str()).unwrap_or("?");
                         let title = issue.get("title").and_then(|v| v.as_str()).unwrap_or("?");
+                        let deleted = issue
+                            .get("deleted")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false);
                         let created_ts = issue
                             .get("created_ts")
                             .and_then(|v| v.as_u64())
                             .unwrap_or(0);
+                        let author = issue
+                            .get("created_by")
+                            .and_then(|v| v.as_str())
+                            .and_then(|v| hex_to_id::<16>(v).ok())
+                            .map_or_else(
+                                || "?".to_string(),
+                                |actor| output::actor_display(&names, &actor, output::id_len(cli)),
+                            );
                         output::IssueRow {
                             id: id.to_string(),
                             state: state.to_string(),
-                            title: title.to_string(),
+                            title: if deleted {
+                                format!("[deleted] {}", title)
+                            } else {
+                                title.to_string()
+                            },
                             created_ts,
+                            author,
                         }
                     })
                     .collect();
-                println!("{}", output::format_issue_table(&rows));
+                output::write_issue_table(
+                    &mut output::stdout_writer(cli),
+                    &rows,
+                    output::id_len(cli),
+                )?;
             }
         } else if let Some(action) = json.get("action").and_then(|v| v.as_str()) {
             let issue_id = json.get("issue_id").and_then(|v| v.as_str()).unwrap_or("?");
@@ -176,13 +271,102 @@ fn output_daemon_data(cli: &Cli, data: &str) -> Result<(), GriteError> {
             let title = json.get("title").and_then(|v| v.as_str()).unwrap_or("?");
             let state = json.get("state").and_then(|v| v.as_str()).unwrap_or("?");
             let body = json.get("body").and_then(|v| v.as_str()).unwrap_or("");
-            println!("{} [{}] {}", &id[..8.min(id.len())], state, title);
+            println!(
+                "{} [{}] {}",
+                output::short_id(id, output::id_len(cli)),
+                state,
+                title
+            );
+            if let Some(candidates) = json.get("title_conflict").and_then(|v| v.as_array()) {
+                let candidates: Vec<&str> = candidates.iter().filter_map(|v| v.as_str()).collect();
+                println!("  title conflict: {}", candidates.join(" | "));
+            }
+            if let Some(fields) = json.get("fields").and_then(|v| v.as_object()) {
+                for (key, value) in fields {
+                    println!("  {}: {}", key, value.as_str().unwrap_or("?"));
+                }
+            }
+            let logged = json
+                .get("logged_minutes")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let estimate = json.get("estimate_minutes").and_then(|v| v.as_u64());
+            if logged > 0 || estimate.is_some() {
+                let estimate = estimate.map_or_else(|| "none".to_string(), |m| format!("{}m", m));
+                println!("  time: {}m logged, estimate {}", logged, estimate);
+            }
+            if let Some(milestone) = json.get("milestone").and_then(|v| v.as_str()) {
+                println!("  milestone: {}", milestone);
+            }
+            if let Some(severity) = json.get("severity").and_then(|v| v.as_str()) {
+                println!("  severity: {}", severity);
+            }
+            if let Some(actor) = json
+                .get("created_by")
+                .and_then(|v| v.as_str())
+                .and_then(|v| hex_to_id::<16>(v).ok())
+            {
+                let names = daemon_actor_names();
+                println!(
+                    "  created by: {}",
+                    output::actor_display(&names, &actor, output::id_len(cli))
+                );
+            }
+            if let Some(ms) = json.get("first_response_ms").and_then(|v| v.as_u64()) {
+                println!("  first response: {}", output::format_duration_ms(ms));
+            }
+            let deps = |key: &str| -> Vec<output::DependencyRow> {
+                json.get(key)
+                    .and_then(|v| v.as_array())
+                    .map(|arr| {
+                        arr.iter()
+                            .map(|d| output::DependencyRow {
+                                id: d["target"].as_str().unwrap_or("?").to_string(),
+                                dep_type: d["dep_type"].as_str().unwrap_or("?").to_string(),
+                                title: d["title"].as_str().map(str::to_string),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            let sections = output::format_dependency_sections(
+                &deps("dependencies"),
+                &deps("dependents"),
+                output::id_len(cli),
+            );
+            if !sections.is_empty() {
+                println!("\n{}", sections);
+            }
             if !body.is_empty() {
                 println!("\n{}", body);
             }
+        } else if let (Some(id), Some(paths)) = (
+            json.get("issue_id").and_then(|v| v.as_str()),
+            json.get("paths"),
+        ) {
+            // Issue why-blocked response
+            let paths: Vec<Vec<output::BlockerRow>> = serde_json::from_value(paths.clone())?;
+            println!(
+                "{}",
+                output::format_blocking_chain(id, &paths, output::id_len(cli))
+            );
+        } else if json.get("children").is_some() {
+            // Issue tree response
+            let tree: output::IssueTreeNode = serde_json::from_value(json)?;
+            println!(
+                "{}",
+                output::format_issue_tree(&tree, output::id_len(cli), output::color_enabled())
+            );
+        } else if json.get("pulled").is_some() {
+            // Sync response
+            let report: libgrite_git::SyncReport = serde_json::from_value(json)?;
+            println!("{}", commands::sync::format_sync_report(&report));
         } else if json.get("path").is_some() {
             // DB stats response (has "path" field)
             println!("{}", serde_json::to_string_pretty(&json)?);
+        } else if json.get("output_path").is_some() {
+            // Export --out response, printed like the local summary
+            println!("{}", serde_json::to_string_pretty(&json)?);
         } else if json.get("event_count").is_some() {
             // Rebuild response (only has event_count/issue_count, no "path")
             let events = json
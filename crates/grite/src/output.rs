@@ -1,11 +1,18 @@
 use crate::cli::Cli;
+use crate::context::GriteContext;
 use comfy_table::{
     modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement,
     Table,
 };
+use crossterm::style::Stylize;
+use libgrite_core::config::{list_actors, load_repo_config};
+use libgrite_core::types::ids::{hex_to_id, id_to_hex, ActorId};
 use libgrite_core::GriteError;
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 
 /// JSON response envelope (from cli-json.md)
 #[derive(Serialize)]
@@ -26,6 +33,59 @@ pub struct JsonError {
     pub details: serde_json::Value,
 }
 
+/// Stdout for output rendered in many pieces, such as lists.
+///
+/// Buffered, output is written in large blocks, so a long list costs a few
+/// writes instead of one per line. With `--no-buffer`, each line reaches
+/// stdout as soon as it is complete. Whatever was written is flushed when the
+/// writer is dropped, so output before an error is never lost.
+pub struct OutputWriter<W: Write> {
+    sink: OutputSink<W>,
+}
+
+enum OutputSink<W: Write> {
+    Buffered(BufWriter<W>),
+    Direct(W),
+}
+
+impl<W: Write> OutputWriter<W> {
+    pub fn new(inner: W, buffered: bool) -> Self {
+        let sink = if buffered {
+            OutputSink::Buffered(BufWriter::with_capacity(64 * 1024, inner))
+        } else {
+            OutputSink::Direct(inner)
+        };
+        Self { sink }
+    }
+}
+
+impl<W: Write> Write for OutputWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.sink {
+            OutputSink::Buffered(w) => w.write(buf),
+            OutputSink::Direct(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.sink {
+            OutputSink::Buffered(w) => w.flush(),
+            OutputSink::Direct(w) => w.flush(),
+        }
+    }
+}
+
+impl<W: Write> Drop for OutputWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Stdout writer honouring `--no-buffer`
+pub fn stdout_writer(cli: &Cli) -> OutputWriter<io::Stdout> {
+    OutputWriter::new(io::stdout(), !cli.no_buffer)
+}
+
 /// Output a successful result
 pub fn output_success<T: Serialize>(cli: &Cli, data: T) {
     if cli.json {
@@ -35,16 +95,33 @@ pub fn output_success<T: Serialize>(cli: &Cli, data: T) {
             data: Some(data),
             error: None,
         };
-        match serde_json::to_string_pretty(&response) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("error: failed to serialize response: {}", e),
-        }
+        write_json(cli, &response, "response");
     } else if !cli.quiet {
         // For human output, serialize to JSON and print nicely
-        match serde_json::to_string_pretty(&data) {
-            Ok(json) => println!("{}", json),
-            Err(e) => eprintln!("error: failed to serialize output: {}", e),
-        }
+        write_json(cli, &data, "output");
+    }
+}
+
+/// Pretty-print `value` to stdout
+///
+/// Buffered, the JSON is streamed into the buffer rather than built as a
+/// string. With `--no-buffer` it is rendered first and written in one go, so
+/// the document does not reach stdout a token at a time.
+fn write_json<T: Serialize>(cli: &Cli, value: &T, what: &str) {
+    let buffered = !cli.no_buffer;
+    if let Err(e) = render_json(&mut stdout_writer(cli), value, buffered) {
+        eprintln!("error: failed to write {}: {}", what, e);
+    }
+}
+
+fn render_json<T: Serialize>(out: &mut impl Write, value: &T, buffered: bool) -> io::Result<()> {
+    if buffered {
+        serde_json::to_writer_pretty(&mut *out, value)?;
+        writeln!(out)
+    } else {
+        let mut json = serde_json::to_string_pretty(value)?;
+        json.push('\n');
+        out.write_all(json.as_bytes())
     }
 }
 
@@ -110,17 +187,81 @@ fn strip_markdown(input: &str) -> String {
     re_heading.replace_all(&s, "").to_string()
 }
 
+/// ID length shown in human output when neither `--id-len` nor the config set one
+pub const DEFAULT_ID_LEN: usize = 8;
+
+/// Allowed values for `--id-len` and the `id_len` config key
+const ID_LEN_RANGE: std::ops::RangeInclusive<usize> = 4..=32;
+
+/// Shorten an ID to `len` characters; shorter IDs are shown in full.
+pub fn short_id(id: &str, len: usize) -> &str {
+    &id[..len.min(id.len())]
+}
+
+/// ID length for human output: `--id-len`, else `id_len` from the repo config.
+pub fn id_len(cli: &Cli) -> usize {
+    cli.id_len.map_or(DEFAULT_ID_LEN, usize::from)
+}
+
+/// Fill in `--id-len` from the repo config when it was not given.
+///
+/// Runs before routing so local and daemon output agree. Outside a repo the
+/// default applies.
+pub fn apply_id_len_config(cli: &mut Cli) -> Result<(), GriteError> {
+    if cli.id_len.is_some() {
+        return Ok(());
+    }
+    let Some(len) = GriteContext::find_git_dir()
+        .ok()
+        .and_then(|git_dir| load_repo_config(&git_dir).ok().flatten())
+        .and_then(|config| config.id_len)
+    else {
+        return Ok(());
+    };
+    if !ID_LEN_RANGE.contains(&len) {
+        return Err(GriteError::InvalidArgs(format!(
+            "id_len in config must be between {} and {}, got {}",
+            ID_LEN_RANGE.start(),
+            ID_LEN_RANGE.end(),
+            len
+        )));
+    }
+    cli.id_len = Some(len as u8);
+    Ok(())
+}
+
+/// Labels of this repo's actors, for showing actors by name
+pub fn actor_names(git_dir: &Path) -> Result<HashMap<ActorId, String>, GriteError> {
+    Ok(list_actors(git_dir)?
+        .into_iter()
+        .filter_map(|a| {
+            let id = hex_to_id::<16>(&a.actor_id).ok()?;
+            Some((id, a.label?))
+        })
+        .collect())
+}
+
+/// An actor's label, or its short ID if it has none
+pub fn actor_display(names: &HashMap<ActorId, String>, actor: &ActorId, id_len: usize) -> String {
+    match names.get(actor) {
+        Some(name) => name.clone(),
+        None => short_id(&id_to_hex(actor), id_len).to_string(),
+    }
+}
+
 /// A single issue row for table formatting.
 pub struct IssueRow {
     pub id: String,
     pub state: String,
     pub title: String,
     pub created_ts: u64,
+    /// Creator, already rendered by [`actor_display`]
+    pub author: String,
 }
 
 /// Format a Unix millisecond timestamp as local date/time.
 /// Example: "Apr 5, 26 2:35 pm"
-fn format_local_date(ts_ms: u64) -> String {
+pub fn format_local_date(ts_ms: u64) -> String {
     let secs = (ts_ms / 1000) as i64;
     chrono::DateTime::from_timestamp(secs, 0)
         .map(|dt| {
@@ -131,14 +272,41 @@ fn format_local_date(ts_ms: u64) -> String {
         .unwrap_or_else(|| ts_ms.to_string())
 }
 
-/// Format a list of issues as a colored table.
-pub fn format_issue_table(issues: &[IssueRow]) -> String {
+/// Format an optional "last seen" timestamp, or "never".
+pub fn format_last_seen(ts_ms: Option<u64>) -> String {
+    ts_ms
+        .map(format_local_date)
+        .unwrap_or_else(|| "never".to_string())
+}
+
+/// Format a duration in milliseconds with its two largest units.
+/// Example: "2h 5m"
+pub fn format_duration_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    let (days, hours, mins) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Write a list of issues to `out` as a colored table, one row at a time.
+pub fn write_issue_table(
+    out: &mut impl Write,
+    issues: &[IssueRow],
+    id_len: usize,
+) -> io::Result<()> {
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
         .apply_modifier(UTF8_ROUND_CORNERS)
         .set_content_arrangement(ContentArrangement::Dynamic)
-        .set_header(vec!["ID", "Status", "Created", "Title"]);
+        .set_header(vec!["ID", "Status", "Created", "Author", "Title"]);
 
     for issue in issues {
         let state_cell = match issue.state.as_str() {
@@ -150,9 +318,10 @@ pub fn format_issue_table(issues: &[IssueRow]) -> String {
 
         let title = strip_markdown(&issue.title);
         table.add_row(vec![
-            Cell::new(&issue.id[..8.min(issue.id.len())]),
+            Cell::new(short_id(&issue.id, id_len)),
             state_cell,
             Cell::new(format_local_date(issue.created_ts)),
+            Cell::new(&issue.author),
             Cell::new(title),
         ]);
     }
@@ -160,14 +329,405 @@ pub fn format_issue_table(issues: &[IssueRow]) -> String {
     let open_count = issues.iter().filter(|i| i.state == "open").count();
     let closed_count = issues.len() - open_count;
 
-    let mut table_str = table.to_string();
+    for line in table.lines() {
+        writeln!(out, "{}", line)?;
+    }
     if !issues.is_empty() {
-        table_str.push_str(&format!(
-            "\n{} issues total ({} open, {} closed)",
+        writeln!(
+            out,
+            "{} issues total ({} open, {} closed)",
             issues.len(),
             open_count,
             closed_count,
+        )?;
+    }
+    Ok(())
+}
+
+/// One dependency edge shown by `issue show`
+pub struct DependencyRow {
+    /// The other issue
+    pub id: String,
+    /// "blocks", "depends_on" or "related_to"
+    pub dep_type: String,
+    /// None if the other issue no longer exists
+    pub title: Option<String>,
+}
+
+/// Format "Blocked by", "Blocks" and "Related" sections for an issue.
+///
+/// `dependencies` are the issue's own edges and `dependents` the edges other
+/// issues point at it. Empty sections are omitted.
+pub fn format_dependency_sections(
+    dependencies: &[DependencyRow],
+    dependents: &[DependencyRow],
+    id_len: usize,
+) -> String {
+    let mut blocked_by = Vec::new();
+    let mut blocks = Vec::new();
+    let mut related = Vec::new();
+    // An incoming edge reads the other way round: if X blocks this issue,
+    // this issue is blocked by X
+    let edges = dependencies
+        .iter()
+        .map(|d| (d, false))
+        .chain(dependents.iter().map(|d| (d, true)));
+    for (dep, incoming) in edges {
+        match (dep.dep_type.as_str(), incoming) {
+            ("blocks", false) | ("depends_on", true) => blocks.push(dep),
+            ("depends_on", false) | ("blocks", true) => blocked_by.push(dep),
+            _ => related.push(dep),
+        }
+    }
+
+    let mut out = String::new();
+    for (heading, deps) in [
+        ("Blocked by", blocked_by),
+        ("Blocks", blocks),
+        ("Related", related),
+    ] {
+        if deps.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(heading);
+        out.push(':');
+        for dep in deps {
+            let title = dep
+                .title
+                .as_deref()
+                .map(strip_markdown)
+                .unwrap_or_else(|| "(unknown)".to_string());
+            out.push_str(&format!("\n  {} {}", short_id(&dep.id, id_len), title));
+        }
+    }
+    out
+}
+
+/// One open prerequisite in a `why-blocked` chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockerRow {
+    pub issue_id: String,
+    /// None if the issue no longer exists
+    pub title: Option<String>,
+}
+
+/// Format the chains of open blockers behind an issue, one per line, each
+/// read from the direct blocker outward
+pub fn format_blocking_chain(issue_id: &str, paths: &[Vec<BlockerRow>], id_len: usize) -> String {
+    if paths.is_empty() {
+        return format!("{} is ready", short_id(issue_id, id_len));
+    }
+    let mut out = format!("{} is blocked by:", short_id(issue_id, id_len));
+    for path in paths {
+        let chain: Vec<String> = path
+            .iter()
+            .map(|b| {
+                let title = b
+                    .title
+                    .as_deref()
+                    .map(strip_markdown)
+                    .unwrap_or_else(|| "(unknown)".to_string());
+                format!("{} {}", short_id(&b.issue_id, id_len), title)
+            })
+            .collect();
+        out.push_str(&format!("\n  {}", chain.join(" <- ")));
+    }
+    out
+}
+
+/// Whether colored output is allowed (see <https://no-color.org>)
+pub fn color_enabled() -> bool {
+    std::env::var_os("NO_COLOR").map_or(true, |v| v.is_empty())
+}
+
+/// A node of `issue tree` output, as produced by
+/// `DependencyTreeNode::to_json`
+#[derive(Debug, Clone, Deserialize)]
+pub struct IssueTreeNode {
+    pub issue_id: String,
+    pub dep_type: Option<String>,
+    pub title: Option<String>,
+    pub state: Option<String>,
+    pub cycle: bool,
+    pub truncated: bool,
+    pub children: Vec<IssueTreeNode>,
+}
+
+/// Render a dependency tree with box-drawing characters.
+///
+/// Closed issues are marked `[closed]` and dimmed when `color` is set;
+/// issues that close a cycle or sit at the depth limit are annotated.
+pub fn format_issue_tree(root: &IssueTreeNode, id_len: usize, color: bool) -> String {
+    let mut lines = vec![tree_label(root, id_len, color)];
+    push_tree_children(&root.children, "", id_len, color, &mut lines);
+    lines.join("\n")
+}
+
+fn push_tree_children(
+    children: &[IssueTreeNode],
+    prefix: &str,
+    id_len: usize,
+    color: bool,
+    lines: &mut Vec<String>,
+) {
+    for (i, child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        let (branch, indent) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        lines.push(format!(
+            "{}{}{}",
+            prefix,
+            branch,
+            tree_label(child, id_len, color)
         ));
+        let child_prefix = format!("{}{}", prefix, indent);
+        push_tree_children(&child.children, &child_prefix, id_len, color, lines);
+    }
+}
+
+fn tree_label(node: &IssueTreeNode, id_len: usize, color: bool) -> String {
+    let title = node
+        .title
+        .as_deref()
+        .map(strip_markdown)
+        .unwrap_or_else(|| "(unknown)".to_string());
+    let mut label = format!("{} {}", short_id(&node.issue_id, id_len), title);
+    if let Some(dep_type) = &node.dep_type {
+        label = format!("{} {}", dep_type, label);
+    }
+    let closed = node.state.as_deref() == Some("closed");
+    if closed {
+        label.push_str(" [closed]");
+    }
+    if color && closed {
+        label = label.dark_grey().to_string();
+    }
+
+    let note = if node.cycle {
+        Some("(cycle)")
+    } else if node.truncated {
+        Some("(depth limit reached)")
+    } else {
+        None
+    };
+    match note {
+        Some(note) if color => format!("{} {}", label, note.yellow()),
+        Some(note) => format!("{} {}", label, note),
+        None => label,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records what reaches it and how many write calls it took
+    #[derive(Default)]
+    struct CountingWriter {
+        data: Vec<u8>,
+        writes: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            self.data.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn issue_rows(count: usize) -> Vec<IssueRow> {
+        (0..count)
+            .map(|i| IssueRow {
+                id: format!("{:032x}", i),
+                state: "open".to_string(),
+                title: format!("Issue number {}", i),
+                created_ts: 0,
+                author: "alice".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_issue_table_is_buffered() {
+        let rows = issue_rows(2000);
+        let mut buffered = CountingWriter::default();
+        write_issue_table(&mut OutputWriter::new(&mut buffered, true), &rows, 8).unwrap();
+        let mut direct = CountingWriter::default();
+        write_issue_table(&mut OutputWriter::new(&mut direct, false), &rows, 8).unwrap();
+
+        assert_eq!(buffered.data, direct.data);
+        let text = String::from_utf8(buffered.data).unwrap();
+        assert!(text.contains("Issue number 1999"));
+        assert_eq!(
+            text.lines().last(),
+            Some("2000 issues total (2000 open, 0 closed)")
+        );
+
+        // Unbuffered output costs at least one write per row
+        assert!(direct.writes >= 2000, "{} writes", direct.writes);
+        assert!(buffered.writes <= 20, "{} writes", buffered.writes);
+    }
+
+    #[test]
+    fn test_output_writer_flushes_on_error() {
+        let mut sink = CountingWriter::default();
+        let render = |sink: &mut CountingWriter| -> io::Result<()> {
+            let mut out = OutputWriter::new(sink, true);
+            write_issue_table(&mut out, &issue_rows(3), 8)?;
+            Err(io::Error::other("render failed"))
+        };
+        assert!(render(&mut sink).is_err());
+        let text = String::from_utf8(sink.data).unwrap();
+        assert!(text.ends_with("3 issues total (3 open, 0 closed)\n"));
+    }
+
+    #[test]
+    fn test_unbuffered_json_is_one_write() {
+        let titles: Vec<String> = (0..500).map(|i| format!("Issue number {}", i)).collect();
+        let value = serde_json::json!({ "ok": true, "titles": titles });
+        let mut buffered = CountingWriter::default();
+        render_json(&mut OutputWriter::new(&mut buffered, true), &value, true).unwrap();
+        let mut direct = CountingWriter::default();
+        render_json(&mut OutputWriter::new(&mut direct, false), &value, false).unwrap();
+
+        assert_eq!(buffered.data, direct.data);
+        assert_eq!(direct.writes, 1);
+    }
+
+    fn row(id: &str, dep_type: &str, title: Option<&str>) -> DependencyRow {
+        DependencyRow {
+            id: id.to_string(),
+            dep_type: dep_type.to_string(),
+            title: title.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_format_dependency_sections() {
+        let dependencies = vec![
+            row("aaaaaaaa11", "blocks", Some("Ship it")),
+            row("bbbbbbbb22", "related_to", None),
+        ];
+        let dependents = vec![row("cccccccc33", "blocks", Some("Fix **build**"))];
+
+        assert_eq!(
+            format_dependency_sections(&dependencies, &dependents, DEFAULT_ID_LEN),
+            "Blocked by:\n  cccccccc Fix build\nBlocks:\n  aaaaaaaa Ship it\nRelated:\n  bbbbbbbb (unknown)"
+        );
+        assert_eq!(format_dependency_sections(&[], &[], DEFAULT_ID_LEN), "");
+    }
+
+    #[test]
+    fn test_format_issue_tree() {
+        let node = |id: &str, dep_type: Option<&str>, state: &str, children| IssueTreeNode {
+            issue_id: id.to_string(),
+            dep_type: dep_type.map(str::to_string),
+            title: Some(format!("Issue {}", &id[..1])),
+            state: Some(state.to_string()),
+            cycle: false,
+            truncated: false,
+            children,
+        };
+        let mut cycle = node("aaaaaaaa11", Some("depends_on"), "open", vec![]);
+        cycle.cycle = true;
+        let root = node(
+            "aaaaaaaa11",
+            None,
+            "open",
+            vec![
+                node(
+                    "bbbbbbbb22",
+                    Some("depends_on"),
+                    "open",
+                    vec![cycle, node("dddddddd44", Some("blocks"), "open", vec![])],
+                ),
+                node("cccccccc33", Some("depends_on"), "closed", vec![]),
+            ],
+        );
+
+        assert_eq!(
+            format_issue_tree(&root, DEFAULT_ID_LEN, false),
+            "aaaaaaaa Issue a\n\
+             ├── depends_on bbbbbbbb Issue b\n\
+             │   ├── depends_on aaaaaaaa Issue a (cycle)\n\
+             │   └── blocks dddddddd Issue d\n\
+             └── depends_on cccccccc Issue c [closed]"
+        );
+    }
+
+    #[test]
+    fn test_format_blocking_chain() {
+        let blocker = |id: &str, title: Option<&str>| BlockerRow {
+            issue_id: id.to_string(),
+            title: title.map(str::to_string),
+        };
+        let paths = vec![
+            vec![
+                blocker("bbbbbbbb22", Some("Middle")),
+                blocker("aaaaaaaa11", Some("Root")),
+            ],
+            vec![blocker("dddddddd44", None)],
+        ];
+        assert_eq!(
+            format_blocking_chain("cccccccc33", &paths, DEFAULT_ID_LEN),
+            "cccccccc is blocked by:\n  bbbbbbbb Middle <- aaaaaaaa Root\n  dddddddd (unknown)"
+        );
+        assert_eq!(
+            format_blocking_chain("cccccccc33", &[], DEFAULT_ID_LEN),
+            "cccccccc is ready"
+        );
+    }
+
+    #[test]
+    fn test_id_len_controls_rendered_ids() {
+        use clap::Parser;
+
+        let id = "0123456789abcdef0123456789abcdef";
+        let cli = Cli::try_parse_from(["grite", "--id-len", "12", "issue", "list"]).unwrap();
+        let rows = [IssueRow {
+            id: id.to_string(),
+            state: "open".to_string(),
+            title: "Wide".to_string(),
+            created_ts: 0,
+            author: "alice".to_string(),
+        }];
+        let mut out = Vec::new();
+        write_issue_table(&mut out, &rows, id_len(&cli)).unwrap();
+        let table = String::from_utf8(out).unwrap();
+        assert!(table.contains("0123456789ab "));
+        assert!(!table.contains("0123456789abc"));
+
+        assert_eq!(short_id("abc", 12), "abc");
+        assert_eq!(
+            id_len(&Cli::try_parse_from(["grite", "issue", "list"]).unwrap()),
+            8
+        );
+        assert!(Cli::try_parse_from(["grite", "--id-len", "3", "issue", "list"]).is_err());
+        assert!(Cli::try_parse_from(["grite", "--id-len", "33", "issue", "list"]).is_err());
+    }
+
+    #[test]
+    fn test_format_last_seen() {
+        assert_eq!(format_last_seen(None), "never");
+        assert_eq!(format_last_seen(Some(0)), format_local_date(0));
+    }
+
+    #[test]
+    fn test_format_duration_ms() {
+        assert_eq!(format_duration_ms(999), "0s");
+        assert_eq!(format_duration_ms(61_000), "1m 1s");
+        assert_eq!(format_duration_ms(7_500_000), "2h 5m");
+        assert_eq!(format_duration_ms(90_000_000), "1d 1h");
     }
-    table_str
 }
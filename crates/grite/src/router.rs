@@ -3,10 +3,14 @@
 //! This module handles the decision of whether to route a command
 //! through the daemon or execute it locally.
 
+use std::sync::OnceLock;
+
+use clap::CommandFactory;
+use libgrite_core::config::load_repo_config;
 use libgrite_core::GriteError;
-use libgrite_ipc::{IpcClient, IpcCommand, IpcRequest, IpcResponse};
+use libgrite_ipc::{DataEncoding, IpcClient, IpcCommand, IpcRequest, IpcResponse};
 
-use crate::cli::Cli;
+use crate::cli::{Cli, Command};
 use crate::commands::daemon::ensure_daemon_running;
 use crate::context::{ExecutionMode, GriteContext};
 
@@ -62,11 +66,59 @@ pub fn route_command(
     }
 }
 
+/// Describe how `route_command` would handle `cli`, one decision per line
+///
+/// Opens a connection to probe the daemon but sends nothing, and never
+/// auto-spawns a daemon.
+pub fn explain_routing(cli: &Cli) -> Vec<String> {
+    let name = command_name(&cli.command);
+    let mut lines = Vec::new();
+
+    if local_commands().iter().any(|c| c == name) {
+        lines.push(format!(
+            "command: {} (not routable: listed in {} / local_commands)",
+            name, LOCAL_COMMANDS_ENV
+        ));
+    } else if !should_route_through_daemon(&cli.command, &[])
+        || cli_to_ipc_command(&cli.command).is_none()
+    {
+        lines.push(format!("command: {} (not routable: local-only)", name));
+    } else {
+        lines.push(format!("command: {} (routable)", name));
+        match GriteContext::resolve(cli) {
+            Ok(ctx) => {
+                let mode = ctx.execution_mode_traced(cli.no_daemon, &mut lines);
+                if matches!(mode, ExecutionMode::Local) && !cli.no_daemon {
+                    lines.push("no daemon reachable: would auto-spawn one".to_string());
+                }
+                lines.push(format!("decision: {:?}", mode));
+            }
+            Err(e) => lines.push(format!("context unavailable ({}) → local", e)),
+        }
+    }
+
+    lines.push("execution mode: local (forced by --explain-routing)".to_string());
+    lines
+}
+
 /// Send a command to the daemon
-fn send_to_daemon(
+pub fn send_to_daemon(
     ctx: &GriteContext,
     client: &mut IpcClient,
     command: IpcCommand,
+) -> Result<IpcResponse, GriteError> {
+    send_to_daemon_encoded(ctx, client, command, DataEncoding::Json)
+}
+
+/// Send a command to the daemon, asking for response data in `encoding`
+///
+/// Plain-text data stays in `data` whatever the encoding, so read the data
+/// with [`IpcResponse::decode_data`].
+pub fn send_to_daemon_encoded(
+    ctx: &GriteContext,
+    client: &mut IpcClient,
+    command: IpcCommand,
+    encoding: DataEncoding,
 ) -> Result<IpcResponse, GriteError> {
     let request = IpcRequest::new(
         uuid::Uuid::new_v4().to_string(),
@@ -74,18 +126,95 @@ fn send_to_daemon(
         ctx.actor_id.clone(),
         ctx.data_dir.to_string_lossy().to_string(),
         command,
-    );
+    )
+    .with_encoding(encoding);
 
     client
         .send_with_retry(&request, 3)
         .map_err(|e| GriteError::Internal(format!("IPC error: {}", e)))
 }
 
+/// Convert an error code and message from the daemon to a `GriteError`
+///
+/// Codes without a variant of their own keep the daemon's code verbatim.
+pub fn daemon_error(code: String, message: String) -> GriteError {
+    match code.as_str() {
+        "not_found" => GriteError::NotFound(message),
+        "invalid_input" | "invalid_args" => GriteError::InvalidArgs(message),
+        "conflict" => GriteError::Conflict(message),
+        "ambiguous" => GriteError::Ambiguous(message),
+        "db_busy" => GriteError::DbBusy(message),
+        "ipc_error" => GriteError::Ipc(message),
+        "internal" | "internal_error" => GriteError::Internal(message),
+        _ => GriteError::Daemon { code, message },
+    }
+}
+
+/// Environment variable listing top-level commands that always run locally,
+/// comma-separated (e.g. `sync,rebuild`)
+pub const LOCAL_COMMANDS_ENV: &str = "GRITE_LOCAL_CMDS";
+
+/// Commands forced local by `GRITE_LOCAL_CMDS` and the repo config's
+/// `local_commands`.
+///
+/// Read once per process. Unknown command names are skipped with a warning.
+pub fn local_commands() -> &'static [String] {
+    static LOCAL_COMMANDS: OnceLock<Vec<String>> = OnceLock::new();
+    LOCAL_COMMANDS.get_or_init(|| {
+        let mut names: Vec<String> = std::env::var(LOCAL_COMMANDS_ENV)
+            .map(|raw| raw.split(',').map(str::to_string).collect())
+            .unwrap_or_default();
+        if let Some(config) = GriteContext::find_git_dir()
+            .ok()
+            .and_then(|git_dir| load_repo_config(&git_dir).ok().flatten())
+        {
+            names.extend(config.local_commands);
+        }
+
+        let (known, unknown) = parse_local_commands(&names);
+        for name in unknown {
+            eprintln!(
+                "warning: ignoring unknown command '{}' in {} / local_commands",
+                name, LOCAL_COMMANDS_ENV
+            );
+        }
+        known
+    })
+}
+
+/// Split command names into known top-level commands and unknown names.
+///
+/// Names are trimmed and lowercased; empty entries are dropped.
+fn parse_local_commands(names: &[String]) -> (Vec<String>, Vec<String>) {
+    let cli = Cli::command();
+    let mut known = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names {
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        if cli.find_subcommand(&name).is_some() {
+            if !known.contains(&name) {
+                known.push(name);
+            }
+        } else {
+            unknown.push(name);
+        }
+    }
+    (known, unknown)
+}
+
 /// Check if a command should use daemon routing
 ///
-/// Some commands (like init, actor management) should always run locally.
-pub fn should_route_through_daemon(cmd: &crate::cli::Command) -> bool {
-    use crate::cli::{Command, DbCommand};
+/// Some commands (like init, actor management) should always run locally,
+/// as do any listed in `local_commands`.
+pub fn should_route_through_daemon(cmd: &Command, local_commands: &[String]) -> bool {
+    use crate::cli::{AttachmentCommand, DbCommand, IssueCommand, SnapshotCommand};
+
+    if local_commands.iter().any(|name| name == command_name(cmd)) {
+        return false;
+    }
 
     match cmd {
         // Always local - these manage the grite setup itself
@@ -101,44 +230,148 @@ pub fn should_route_through_daemon(cmd: &crate::cli::Command) -> bool {
             DbCommand::Stats => true,
             DbCommand::Check { .. } => false, // Integrity check is local
             DbCommand::Verify { .. } => false, // Signature verify is local
+            DbCommand::Events { .. } => false, // Full event scan is local
+            DbCommand::SignHistory => false,  // Rewrites stored events
+            DbCommand::Archive { .. } | DbCommand::Restore { .. } => false, // File I/O is local
+            DbCommand::EventPreimage { .. } => false, // Debugging aid, reads one event
+            DbCommand::ImportAttachments { .. } => false, // File I/O is local
         },
 
         // Doctor is local-only (health checks)
         Command::Doctor { .. } => false,
 
+        // Reports scan the whole event log locally
+        Command::Report { .. } => false,
+        Command::Milestone { .. } => false,
+
+        // The TUI picks its own data source
+        Command::Tui => false,
+
         // Context commands are local-only (need filesystem access)
         Command::Context { .. } => false,
 
         // Install-skill is local-only
         Command::InstallSkill { .. } => false,
+        // Static table, no repo needed
+        Command::Errors => false,
+
+        // Storing attachment bytes reads a local file
+        Command::Issue {
+            cmd:
+                IssueCommand::Attachment {
+                    cmd: AttachmentCommand::Add { file: Some(_), .. },
+                },
+        } => false,
+
+        // Simulated writes print the event instead of sending it; update and
+        // comment look the issue up through the daemon themselves
+        Command::Issue {
+            cmd:
+                IssueCommand::Create { simulate: true, .. }
+                | IssueCommand::Update { simulate: true, .. }
+                | IssueCommand::Comment { simulate: true, .. },
+        } => false,
 
-        // All issue commands (including dep) route through daemon
+        // All other issue commands (including dep) route through daemon
         Command::Issue { .. } => true,
         Command::Export { .. } => true,
         Command::Rebuild { .. } => false, // Handled specially in rebuild.rs with a longer IPC timeout
+        // Reads local WAL chunk headers, no store access
+        Command::Sync {
+            check_compat: Some(_),
+            ..
+        } => false,
+        // Fetches into a side ref and reads WALs, no store access
+        Command::Sync { preview: true, .. } => false,
         Command::Sync { .. } => true,
-        Command::Snapshot { .. } => true,
+        // Pinning rewrites a snapshot ref, like lock commands; signing and
+        // verifying need the actor's key; the daemon only writes full snapshots
+        Command::Snapshot { cmd: snap_cmd } => !matches!(
+            snap_cmd,
+            SnapshotCommand::Pin { .. }
+                | SnapshotCommand::Unpin { .. }
+                | SnapshotCommand::Create { sign: true, .. }
+                | SnapshotCommand::Create { delta: true, .. }
+                | SnapshotCommand::Verify { .. }
+        ),
+    }
+}
+
+/// Name of a top-level command as typed on the command line
+pub fn command_name(cmd: &Command) -> &'static str {
+    match cmd {
+        Command::Init { .. } => "init",
+        Command::Actor { .. } => "actor",
+        Command::Issue { .. } => "issue",
+        Command::Db { .. } => "db",
+        Command::Export { .. } => "export",
+        Command::Rebuild { .. } => "rebuild",
+        Command::Sync { .. } => "sync",
+        Command::Snapshot { .. } => "snapshot",
+        Command::Daemon { .. } => "daemon",
+        Command::Lock { .. } => "lock",
+        Command::Doctor { .. } => "doctor",
+        Command::Milestone { .. } => "milestone",
+        Command::Report { .. } => "report",
+        Command::Tui => "tui",
+        Command::Context { .. } => "context",
+        Command::Errors => "errors",
+        Command::InstallSkill { .. } => "install-skill",
     }
 }
 
 /// Convert a CLI command to an IPC command
 ///
 /// Returns None for commands that should always run locally.
-pub fn cli_to_ipc_command(cmd: &crate::cli::Command) -> Option<IpcCommand> {
-    use crate::cli::{Command, ExportFormat};
+pub fn cli_to_ipc_command(cmd: &Command) -> Option<IpcCommand> {
+    use crate::cli::{ExportFormat, ExportOrder};
 
     match cmd {
         Command::Issue { cmd: issue_cmd } => Some(issue_to_ipc(issue_cmd)),
         Command::Db { cmd: db_cmd } => Some(db_to_ipc(db_cmd)),
-        Command::Export { format, since } => Some(IpcCommand::Export {
+        Command::Export {
+            format,
+            since,
+            order,
+            issue,
+            kinds,
+            count_only,
+            include_attachments,
+            anonymize,
+            out,
+        } => Some(IpcCommand::Export {
             format: match format {
-                ExportFormat::Json => "json".to_string(),
-                ExportFormat::Md => "md".to_string(),
+                Some(ExportFormat::Md) => "md".to_string(),
+                Some(ExportFormat::Json) | None => "json".to_string(),
             },
             since: since.clone(),
+            order: match order {
+                ExportOrder::ByIssue => "by-issue".to_string(),
+                ExportOrder::Chronological => "chronological".to_string(),
+            },
+            issue: issue.clone(),
+            kinds: kinds.clone(),
+            count_only: *count_only,
+            include_attachments: *include_attachments,
+            anonymize: *anonymize,
+            // The daemon writes the file, so resolve against our directory
+            out: out.as_ref().map(|path| {
+                std::env::current_dir()
+                    .map(|cwd| cwd.join(path))
+                    .unwrap_or_else(|_| path.clone())
+                    .to_string_lossy()
+                    .to_string()
+            }),
         }),
         Command::Rebuild { .. } => None, // Always local
-        Command::Sync { remote, pull, push } => Some(IpcCommand::Sync {
+        Command::Sync {
+            check_compat: Some(_),
+            ..
+        } => None,
+        Command::Sync { preview: true, .. } => None,
+        Command::Sync {
+            remote, pull, push, ..
+        } => Some(IpcCommand::Sync {
             remote: remote.clone(),
             pull: *pull,
             push: *push,
@@ -150,23 +383,52 @@ pub fn cli_to_ipc_command(cmd: &crate::cli::Command) -> Option<IpcCommand> {
         | Command::Daemon { .. }
         | Command::Lock { .. }
         | Command::Doctor { .. }
+        | Command::Milestone { .. }
+        | Command::Report { .. }
+        | Command::Tui
         | Command::Context { .. }
+        | Command::Errors
         | Command::InstallSkill { .. } => None,
     }
 }
 
 fn issue_to_ipc(cmd: &crate::cli::IssueCommand) -> IpcCommand {
-    use crate::cli::{AssigneeCommand, AttachmentCommand, IssueCommand, LabelCommand, LinkCommand};
+    use crate::cli::{
+        AssigneeCommand, AttachmentCommand, FieldCommand, IssueCommand, IssueSort, LabelCommand,
+        LinkCommand,
+    };
 
     match cmd {
-        IssueCommand::Create { title, body, label } => IpcCommand::IssueCreate {
+        IssueCommand::Create {
+            title, body, label, ..
+        } => IpcCommand::IssueCreate {
             title: title.clone(),
             body: body.clone(),
             labels: label.clone(),
         },
-        IssueCommand::List { state, label } => IpcCommand::IssueList {
+        IssueCommand::List {
+            state,
+            label,
+            assignee,
+            unassigned,
+            field,
+            milestone,
+            severity,
+            include_deleted,
+            sort,
+        } => IpcCommand::IssueList {
             state: state.clone(),
             label: label.clone(),
+            assignee: assignee.clone(),
+            unassigned: *unassigned,
+            field: field.clone(),
+            milestone: milestone.clone(),
+            severity: severity.map(|s| s.as_str().to_string()),
+            include_deleted: *include_deleted,
+            sort: match sort {
+                IssueSort::Created => "created".to_string(),
+                IssueSort::MostBlocking => "most-blocking".to_string(),
+            },
         },
         IssueCommand::Show { id } => IpcCommand::IssueShow {
             issue_id: id.clone(),
@@ -178,9 +440,12 @@ fn issue_to_ipc(cmd: &crate::cli::IssueCommand) -> IpcCommand {
             title: title.clone(),
             body: body.clone(),
         },
-        IssueCommand::Comment { id, body, .. } => IpcCommand::IssueComment {
+        IssueCommand::Comment {
+            id, body, reply_to, ..
+        } => IpcCommand::IssueComment {
             issue_id: id.clone(),
             body: body.clone(),
+            reply_to: reply_to.clone(),
         },
         IssueCommand::Close { id, .. } => IpcCommand::IssueClose {
             issue_id: id.clone(),
@@ -189,25 +454,25 @@ fn issue_to_ipc(cmd: &crate::cli::IssueCommand) -> IpcCommand {
             issue_id: id.clone(),
         },
         IssueCommand::Label { cmd: label_cmd } => match label_cmd {
-            LabelCommand::Add { id, label, .. } => IpcCommand::IssueLabel {
-                issue_id: id.clone(),
+            LabelCommand::Add { ids, label, .. } => IpcCommand::IssueLabel {
+                issue_ids: ids.clone(),
                 add: vec![label.clone()],
                 remove: vec![],
             },
-            LabelCommand::Remove { id, label, .. } => IpcCommand::IssueLabel {
-                issue_id: id.clone(),
+            LabelCommand::Remove { ids, label, .. } => IpcCommand::IssueLabel {
+                issue_ids: ids.clone(),
                 add: vec![],
                 remove: vec![label.clone()],
             },
         },
         IssueCommand::Assignee { cmd: assign_cmd } => match assign_cmd {
-            AssigneeCommand::Add { id, user, .. } => IpcCommand::IssueAssign {
-                issue_id: id.clone(),
+            AssigneeCommand::Add { ids, user, .. } => IpcCommand::IssueAssign {
+                issue_ids: ids.clone(),
                 add: vec![user.clone()],
                 remove: vec![],
             },
-            AssigneeCommand::Remove { id, user, .. } => IpcCommand::IssueAssign {
-                issue_id: id.clone(),
+            AssigneeCommand::Remove { ids, user, .. } => IpcCommand::IssueAssign {
+                issue_ids: ids.clone(),
                 add: vec![],
                 remove: vec![user.clone()],
             },
@@ -219,6 +484,62 @@ fn issue_to_ipc(cmd: &crate::cli::IssueCommand) -> IpcCommand {
                 note: note.clone(),
             },
         },
+        IssueCommand::LinkIssue {
+            id,
+            target,
+            rel,
+            remove,
+            ..
+        } => IpcCommand::IssueLinkIssue {
+            issue_id: id.clone(),
+            target_id: target.clone(),
+            relation: rel.clone(),
+            remove: *remove,
+        },
+        IssueCommand::Dup { id, of, .. } => IpcCommand::IssueDup {
+            issue_id: id.clone(),
+            target_id: of.clone(),
+        },
+        IssueCommand::Subscribe { id, user } => IpcCommand::IssueSubscribe {
+            issue_id: id.clone(),
+            user: user.clone(),
+            remove: false,
+        },
+        IssueCommand::Unsubscribe { id, user } => IpcCommand::IssueSubscribe {
+            issue_id: id.clone(),
+            user: user.clone(),
+            remove: true,
+        },
+        IssueCommand::Estimate { id, minutes } => IpcCommand::IssueEstimate {
+            issue_id: id.clone(),
+            minutes: *minutes,
+        },
+        IssueCommand::LogTime { id, minutes, note } => IpcCommand::IssueLogTime {
+            issue_id: id.clone(),
+            minutes: *minutes,
+            note: note.clone(),
+        },
+        IssueCommand::Milestone { id, milestone } => IpcCommand::IssueMilestone {
+            issue_id: id.clone(),
+            milestone: (milestone != "clear").then(|| milestone.clone()),
+        },
+        IssueCommand::Severity { id, severity } => IpcCommand::IssueSeverity {
+            issue_id: id.clone(),
+            severity: severity.as_str().to_string(),
+        },
+        IssueCommand::Delete { id } => IpcCommand::IssueDelete {
+            issue_id: id.clone(),
+        },
+        IssueCommand::Undelete { id } => IpcCommand::IssueUndelete {
+            issue_id: id.clone(),
+        },
+        IssueCommand::WhyBlocked { id } => IpcCommand::IssueWhyBlocked {
+            issue_id: id.clone(),
+        },
+        IssueCommand::Tree { id, depth } => IpcCommand::IssueTree {
+            issue_id: id.clone(),
+            depth: *depth,
+        },
         IssueCommand::Attachment { cmd: attach_cmd } => match attach_cmd {
             AttachmentCommand::Add {
                 id,
@@ -228,10 +549,22 @@ fn issue_to_ipc(cmd: &crate::cli::IssueCommand) -> IpcCommand {
                 ..
             } => IpcCommand::IssueAttach {
                 issue_id: id.clone(),
-                file_path: format!("{}:{}:{}", name, sha256, mime),
+                file_path: format!("{}:{}:{}", name, sha256.as_deref().unwrap_or(""), mime),
             },
         },
         IssueCommand::Dep { cmd: dep_cmd } => dep_to_ipc(dep_cmd),
+        IssueCommand::Field { cmd: field_cmd } => match field_cmd {
+            FieldCommand::Set { id, key, value, .. } => IpcCommand::IssueField {
+                issue_id: id.clone(),
+                key: key.clone(),
+                value: Some(value.clone()),
+            },
+            FieldCommand::Clear { id, key, .. } => IpcCommand::IssueField {
+                issue_id: id.clone(),
+                key: key.clone(),
+                value: None,
+            },
+        },
     }
 }
 
@@ -240,8 +573,15 @@ fn db_to_ipc(cmd: &crate::cli::DbCommand) -> IpcCommand {
 
     match cmd {
         DbCommand::Stats => IpcCommand::DbStats,
-        // Check and Verify are local-only, shouldn't reach here
-        DbCommand::Check { .. } | DbCommand::Verify { .. } => IpcCommand::DbStats,
+        // The remaining db commands are local-only, shouldn't reach here
+        DbCommand::Check { .. }
+        | DbCommand::Verify { .. }
+        | DbCommand::Events { .. }
+        | DbCommand::SignHistory
+        | DbCommand::Archive { .. }
+        | DbCommand::Restore { .. }
+        | DbCommand::EventPreimage { .. }
+        | DbCommand::ImportAttachments { .. } => IpcCommand::DbStats,
     }
 }
 
@@ -278,8 +618,52 @@ fn snapshot_to_ipc(cmd: &crate::cli::SnapshotCommand) -> IpcCommand {
     use crate::cli::SnapshotCommand;
 
     match cmd {
-        SnapshotCommand::Create => IpcCommand::SnapshotCreate,
-        SnapshotCommand::List => IpcCommand::SnapshotList,
+        SnapshotCommand::Create { .. } => IpcCommand::SnapshotCreate,
+        SnapshotCommand::List
+        | SnapshotCommand::Pin { .. }
+        | SnapshotCommand::Unpin { .. }
+        | SnapshotCommand::Verify { .. } => IpcCommand::SnapshotList,
         SnapshotCommand::Gc { keep } => IpcCommand::SnapshotGc { keep: *keep as u32 },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn command(args: &[&str]) -> Command {
+        Cli::parse_from(std::iter::once("grite").chain(args.iter().copied())).command
+    }
+
+    #[test]
+    fn test_local_commands_skip_daemon() {
+        let names: Vec<String> = ["Sync", " rebuild", "bogus", ""]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (local, unknown) = parse_local_commands(&names);
+        assert_eq!(local, vec!["sync", "rebuild"]);
+        assert_eq!(unknown, vec!["bogus"]);
+
+        let sync = command(&["sync"]);
+        let list = command(&["issue", "list"]);
+        assert!(should_route_through_daemon(&sync, &[]));
+        assert!(!should_route_through_daemon(&sync, &local));
+        // Commands not in the list keep routing through the daemon
+        assert!(should_route_through_daemon(&list, &local));
+    }
+
+    #[test]
+    fn test_daemon_error_keeps_unknown_code() {
+        let err = daemon_error("quota_exceeded".to_string(), "Too many".to_string());
+        assert_eq!(err.error_code(), "quota_exceeded");
+        assert!(err.to_string().contains("Too many"));
+        assert_eq!(err.exit_code(), 1);
+
+        let err = daemon_error("not_found".to_string(), "Issue abc".to_string());
+        assert!(matches!(err, GriteError::NotFound(_)));
+        let err = daemon_error("internal".to_string(), "boom".to_string());
+        assert_eq!(err.error_code(), "internal_error");
+    }
+}
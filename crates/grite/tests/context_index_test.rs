@@ -0,0 +1,77 @@
+//! End-to-end test for `grite context index`
+//!
+//! Indexing walks tracked and untracked files, skips anything listed in
+//! `.gritignore`, and only creates context for source files it understands.
+
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::Command as GriteCommand;
+use tempfile::tempdir;
+
+fn grite(repo: &Path, args: &[&str]) -> std::process::Output {
+    GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .args(["--json", "--no-daemon"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn grite_ok(repo: &Path, args: &[&str]) -> serde_json::Value {
+    let output = grite(repo, args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["data"].clone()
+}
+
+fn write(repo: &Path, path: &str, content: &[u8]) {
+    let path = repo.join(path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_index_creates_context_for_source_files_only() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(repo)
+        .status()
+        .unwrap()
+        .success());
+    grite_ok(repo, &["init", "--no-agents-md"]);
+
+    // Nothing is committed: the index covers untracked files too
+    write(repo, "src/lib.rs", b"pub fn parse() {}\n");
+    write(repo, "tools/build.py", b"def build():\n    pass\n");
+    write(repo, "README.md", b"# Project\n");
+    write(repo, "assets/logo.rs", b"\x00\x01binary\x00");
+    write(repo, "vendor/dep.rs", b"pub fn vendored() {}\n");
+    write(repo, ".gritignore", b"# third-party code\nvendor/\n");
+
+    let result = grite_ok(repo, &["context", "index"]);
+    assert_eq!(result["indexed"], 2, "{}", result);
+
+    for path in ["src/lib.rs", "tools/build.py"] {
+        let shown = grite_ok(repo, &["context", "show", path]);
+        assert_eq!(shown["path"], path);
+    }
+    for path in ["README.md", "assets/logo.rs", "vendor/dep.rs"] {
+        assert!(
+            !grite(repo, &["context", "show", path]).status.success(),
+            "{} should not be indexed",
+            path
+        );
+    }
+
+    // Unchanged files are skipped on the next run
+    let result = grite_ok(repo, &["context", "index"]);
+    assert_eq!(result["indexed"], 0, "{}", result);
+}
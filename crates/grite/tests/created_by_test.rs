@@ -0,0 +1,51 @@
+//! End-to-end test for the issue creator in `issue show` and `issue list`
+
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::Command as GriteCommand;
+use tempfile::tempdir;
+
+fn grite_ok(repo: &Path, args: &[&str]) -> serde_json::Value {
+    let output = GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .args(["--json", "--no-daemon"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["data"].clone()
+}
+
+#[test]
+fn test_created_by_is_authoring_actor() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(repo)
+        .status()
+        .unwrap()
+        .success());
+    grite_ok(repo, &["init", "--no-agents-md"]);
+
+    let alice = grite_ok(repo, &["actor", "init", "--label", "alice"]);
+    let alice_id = alice["actor_id"].as_str().unwrap().to_string();
+    grite_ok(repo, &["actor", "use", &alice_id]);
+
+    let created = grite_ok(repo, &["issue", "create", "--title", "Mine"]);
+    let issue_id = created["issue_id"].as_str().unwrap();
+
+    let shown = grite_ok(repo, &["issue", "show", issue_id]);
+    assert_eq!(shown["issue"]["created_by"], alice_id.as_str());
+    assert_eq!(shown["issue"]["created_by_name"], "alice");
+
+    let listed = grite_ok(repo, &["issue", "list"]);
+    assert_eq!(listed["issues"][0]["created_by"], alice_id.as_str());
+}
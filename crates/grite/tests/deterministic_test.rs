@@ -0,0 +1,100 @@
+//! End-to-end test for the deterministic test mode
+//!
+//! With `GRITE_TEST_SEED` and `GRITE_TEST_CLOCK` set, running the same
+//! commands in two fresh repos must produce the same IDs.
+
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::Command as GriteCommand;
+use tempfile::tempdir;
+
+fn grite(repo: &Path, args: &[&str]) -> serde_json::Value {
+    let output = GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .env("GRITE_TEST_SEED", "7")
+        .env("GRITE_TEST_CLOCK", "1700000000000")
+        .args(["--json", "--no-daemon"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["data"].clone()
+}
+
+/// Run the same commands in a fresh repo and collect the IDs they print
+fn run_once() -> Vec<String> {
+    let temp = tempdir().unwrap();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(temp.path())
+        .status()
+        .unwrap()
+        .success());
+
+    let init = grite(temp.path(), &["init", "--no-agents-md"]);
+    let created = grite(
+        temp.path(),
+        &["issue", "create", "--title", "First", "--body", "body"],
+    );
+    let commented = grite(
+        temp.path(),
+        &[
+            "issue",
+            "comment",
+            created["issue_id"].as_str().unwrap(),
+            "--body",
+            "hello",
+        ],
+    );
+
+    vec![
+        init["actor_id"].as_str().unwrap().to_string(),
+        created["issue_id"].as_str().unwrap().to_string(),
+        created["event_id"].as_str().unwrap().to_string(),
+        commented["event_id"].as_str().unwrap().to_string(),
+    ]
+}
+
+#[test]
+fn test_seeded_runs_produce_identical_ids() {
+    let first = run_once();
+    let second = run_once();
+    assert_eq!(first, second);
+
+    // The first issue ID must not reuse the first actor ID
+    assert_ne!(first[0], first[1]);
+}
+
+#[test]
+fn test_later_runs_continue_the_sequence() {
+    let temp = tempdir().unwrap();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(temp.path())
+        .status()
+        .unwrap()
+        .success());
+    grite(temp.path(), &["init", "--no-agents-md"]);
+
+    // Each create is a separate process
+    let first = grite(temp.path(), &["issue", "create", "--title", "One"]);
+    let second = grite(temp.path(), &["issue", "create", "--title", "Two"]);
+    assert_ne!(first["issue_id"], second["issue_id"]);
+
+    let show = |created: &serde_json::Value| {
+        grite(
+            temp.path(),
+            &["issue", "show", created["issue_id"].as_str().unwrap()],
+        )
+    };
+    let first_ts = show(&first)["issue"]["created_ts"].as_u64().unwrap();
+    let second_ts = show(&second)["issue"]["created_ts"].as_u64().unwrap();
+    assert!(second_ts > first_ts, "{} <= {}", second_ts, first_ts);
+}
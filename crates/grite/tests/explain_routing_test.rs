@@ -0,0 +1,78 @@
+//! End-to-end test for `--explain-routing`
+//!
+//! The flag reports each routing decision on stderr and then runs the
+//! command locally, without contacting or spawning a daemon.
+
+use std::process::Command;
+
+use assert_cmd::Command as GriteCommand;
+use tempfile::tempdir;
+
+#[test]
+fn test_explain_routing_reports_expired_lock() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(repo)
+        .status()
+        .unwrap()
+        .success());
+    let init = GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .args(["--json", "--no-daemon", "init", "--no-agents-md"])
+        .output()
+        .unwrap();
+    assert!(init.status.success());
+
+    // A lock left behind by a daemon whose lease ran out long ago
+    let lock = serde_json::json!({
+        "pid": 999999,
+        "started_ts": 1000,
+        "repo_root": repo.to_string_lossy(),
+        "actor_id": "00000000000000000000000000000000",
+        "host_id": "test-host",
+        "ipc_endpoint": repo.join("gone.sock").to_string_lossy(),
+        "lease_ms": 30000,
+        "last_heartbeat_ts": 1000,
+        "expires_ts": 31000,
+    });
+    std::fs::write(
+        repo.join(".git/grite/daemon.lock"),
+        serde_json::to_string(&lock).unwrap(),
+    )
+    .unwrap();
+
+    // Keep the discovery registry away from any daemon on this machine
+    let output = GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .env("XDG_RUNTIME_DIR", repo)
+        .args(["--json", "--explain-routing", "issue", "list"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+
+    assert!(
+        stderr.contains("routing: command: issue (routable)"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("daemon lock: found (PID 999999)"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("lock expired → local"), "{}", stderr);
+    assert!(
+        stderr.contains("execution mode: local (forced by --explain-routing)"),
+        "{}",
+        stderr
+    );
+
+    // The command itself still ran
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["data"]["total"], 0, "{}", json);
+}
@@ -0,0 +1,70 @@
+//! End-to-end test for `grite issue dup`
+//!
+//! Marking an issue a duplicate links it to the original and closes it.
+
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::Command as GriteCommand;
+use tempfile::tempdir;
+
+fn grite(repo: &Path, args: &[&str]) -> std::process::Output {
+    GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .args(["--json", "--no-daemon"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn grite_ok(repo: &Path, args: &[&str]) -> serde_json::Value {
+    let output = grite(repo, args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["data"].clone()
+}
+
+fn create(repo: &Path, title: &str) -> String {
+    let created = grite_ok(repo, &["issue", "create", "--title", title]);
+    created["issue_id"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_dup_links_and_closes() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(repo)
+        .status()
+        .unwrap()
+        .success());
+    grite_ok(repo, &["init", "--no-agents-md"]);
+
+    let a = create(repo, "Crash on start (again)");
+    let b = create(repo, "Crash on start");
+
+    let result = grite_ok(repo, &["issue", "dup", &a, "--of", &b]);
+    assert_eq!(result["event_ids"].as_array().unwrap().len(), 2);
+
+    let shown = grite_ok(repo, &["issue", "show", &a]);
+    assert_eq!(shown["issue"]["state"], "closed");
+    assert_eq!(
+        shown["issue_links"],
+        serde_json::json!([{ "target": b, "relation": "duplicate-of" }])
+    );
+
+    // The original is untouched
+    let shown = grite_ok(repo, &["issue", "show", &b]);
+    assert_eq!(shown["issue"]["state"], "open");
+
+    let output = grite(repo, &["issue", "dup", &b, "--of", &b]);
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["code"], "invalid_args");
+}
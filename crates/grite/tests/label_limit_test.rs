@@ -0,0 +1,83 @@
+//! End-to-end test for `max_labels_per_issue`
+//!
+//! Once an issue carries as many labels as the repo config allows, adding
+//! another one fails, while removing one makes room again. A new issue may
+//! not start with more labels than the limit, default labels included.
+
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::Command as GriteCommand;
+use tempfile::tempdir;
+
+fn grite(repo: &Path, args: &[&str]) -> std::process::Output {
+    GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .args(["--json", "--no-daemon"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn grite_ok(repo: &Path, args: &[&str]) -> serde_json::Value {
+    let output = grite(repo, args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["data"].clone()
+}
+
+#[test]
+fn test_label_add_rejected_at_limit() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(repo)
+        .status()
+        .unwrap()
+        .success());
+    grite_ok(repo, &["init", "--no-agents-md"]);
+
+    let config_path = repo.join(".git/grite/config.toml");
+    let mut config = std::fs::read_to_string(&config_path).unwrap_or_default();
+    config.push_str("max_labels_per_issue = 2\n");
+    std::fs::write(&config_path, config).unwrap();
+
+    let output = grite(
+        repo,
+        &[
+            "issue", "create", "--title", "Crowded", "--label", "a", "--label", "b", "--label", "c",
+        ],
+    );
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["code"], "conflict", "{}", json);
+
+    let created = grite_ok(repo, &["issue", "create", "--title", "Limited"]);
+    let id = created["issue_id"].as_str().unwrap().to_string();
+
+    grite_ok(repo, &["issue", "label", "add", &id, "--label", "bug"]);
+    grite_ok(repo, &["issue", "label", "add", &id, "--label", "p1"]);
+
+    // Re-adding a label the issue already has does not grow it
+    grite_ok(repo, &["issue", "label", "add", &id, "--label", "bug"]);
+
+    let output = grite(repo, &["issue", "label", "add", &id, "--label", "ui"]);
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(json["error"]["code"], "conflict", "{}", json);
+    let message = json["error"]["message"].as_str().unwrap();
+    assert!(message.contains("max_labels_per_issue = 2"), "{}", message);
+
+    let shown = grite_ok(repo, &["issue", "show", &id]);
+    assert_eq!(shown["issue"]["labels"], serde_json::json!(["bug", "p1"]));
+
+    // Removing one makes room again
+    grite_ok(repo, &["issue", "label", "remove", &id, "--label", "p1"]);
+    grite_ok(repo, &["issue", "label", "add", &id, "--label", "ui"]);
+}
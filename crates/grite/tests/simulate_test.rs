@@ -0,0 +1,137 @@
+//! End-to-end test for `--simulate` on issue writes
+//!
+//! A simulated command prints the event it would write, with a valid ID and
+//! its canonical preimage, and leaves the store untouched.
+
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::Command as GriteCommand;
+use libgrite_core::hash::{build_canonical_cbor, compute_event_id};
+use libgrite_core::types::event::EventKind;
+use libgrite_core::types::ids::hex_to_id;
+use tempfile::tempdir;
+
+fn grite(repo: &Path, args: &[&str]) -> std::process::Output {
+    GriteCommand::cargo_bin("grite")
+        .unwrap()
+        .current_dir(repo)
+        .args(["--json", "--no-daemon"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn grite_ok(repo: &Path, args: &[&str]) -> serde_json::Value {
+    let output = grite(repo, args);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    json["data"].clone()
+}
+
+#[test]
+fn test_simulated_create_prints_event_without_storing_it() {
+    let temp = tempdir().unwrap();
+    let repo = temp.path();
+    assert!(Command::new("git")
+        .args(["init", "-q"])
+        .current_dir(repo)
+        .status()
+        .unwrap()
+        .success());
+    grite_ok(repo, &["init", "--no-agents-md"]);
+
+    let result = grite_ok(
+        repo,
+        &[
+            "issue",
+            "create",
+            "--title",
+            "Preview",
+            "--body",
+            "Not stored",
+            "--simulate",
+        ],
+    );
+    assert_eq!(result["simulated"], true);
+    let event = &result["event"];
+
+    // The printed ID is the hash of the printed preimage
+    let issue_id = hex_to_id(event["issue_id"].as_str().unwrap()).unwrap();
+    let actor = hex_to_id(event["actor"].as_str().unwrap()).unwrap();
+    let ts = event["ts_unix_ms"].as_u64().unwrap();
+    let kind = EventKind::IssueCreated {
+        title: "Preview".to_string(),
+        body: "Not stored".to_string(),
+        labels: vec![],
+    };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    assert_eq!(
+        event["event_id"].as_str().unwrap(),
+        hex::encode(event_id),
+        "{}",
+        result
+    );
+    assert_eq!(
+        result["preimage_hex"].as_str().unwrap(),
+        hex::encode(build_canonical_cbor(&issue_id, &actor, ts, None, &kind))
+    );
+
+    let stats = grite_ok(repo, &["db", "stats"]);
+    assert_eq!(stats["event_count"], 0, "{}", stats);
+    let listed = grite_ok(repo, &["issue", "list"]);
+    assert_eq!(listed["total"], 0);
+
+    // Simulating an update still checks that the issue exists
+    let output = grite(
+        repo,
+        &["issue", "update", "ffff", "--title", "Nope", "--simulate"],
+    );
+    assert!(!output.status.success());
+
+    // ...and resolves the issue and reply prefixes it is given
+    let created = grite_ok(repo, &["issue", "create", "--title", "Real"]);
+    let issue_id = created["issue_id"].as_str().unwrap().to_string();
+    let comment = grite_ok(repo, &["issue", "comment", &issue_id[..8], "--body", "Hi"]);
+    let comment_id = comment["event_id"].as_str().unwrap().to_string();
+    let reply = grite_ok(
+        repo,
+        &[
+            "issue",
+            "comment",
+            &issue_id[..8],
+            "--body",
+            "Reply",
+            "--reply-to",
+            &comment_id[..8],
+            "--simulate",
+        ],
+    );
+    assert_eq!(reply["event"]["issue_id"], issue_id.as_str());
+    let kind: EventKind = serde_json::from_value(reply["event"]["kind"].clone()).unwrap();
+    assert_eq!(
+        kind,
+        EventKind::CommentAdded {
+            body: "Reply".to_string(),
+            parent_event: Some(hex_to_id(&comment_id).unwrap()),
+        }
+    );
+    let output = grite(
+        repo,
+        &[
+            "issue",
+            "comment",
+            &issue_id,
+            "--body",
+            "Reply",
+            "--reply-to",
+            "ffff",
+            "--simulate",
+        ],
+    );
+    assert!(!output.status.success());
+}
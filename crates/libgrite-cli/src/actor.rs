@@ -1,15 +1,20 @@
 use libgrite_core::{
+    audit::AuditLog,
     config::{
-        actor_dir, list_actors, load_actor_config, load_repo_config, save_actor_config,
-        save_repo_config,
+        actor_dir, list_actors, load_actor_config, save_actor_config, save_signing_key,
+        update_repo_config,
     },
-    signing::SigningKeyPair,
+    context::PROJECT_CONTEXT_ISSUE_ID,
+    hash::compute_event_id,
+    signing::{actor_key_context_key, SigningKeyPair, ACTOR_KEY_CONTEXT_PREFIX},
     types::actor::ActorConfig,
-    types::ids::{generate_actor_id, id_to_hex},
+    types::event::{Event, EventKind},
+    types::ids::{generate_actor_id, hex_to_id, id_to_hex},
     GriteError,
 };
 
 use crate::context::GriteContext;
+use crate::event_helper::insert_and_append;
 use crate::types::*;
 
 /// Create a new actor.
@@ -30,28 +35,7 @@ pub fn actor_init(opts: &ActorInitOptions) -> Result<ActorInitResult, GriteError
         config.public_key = public_key.clone();
         config.key_scheme = Some("ed25519".to_string());
 
-        std::fs::create_dir_all(&data_dir)?;
-        let signing_key_path = data_dir.join("signing_key");
-
-        #[cfg(unix)]
-        {
-            use std::fs::OpenOptions;
-            use std::io::Write;
-            use std::os::unix::fs::OpenOptionsExt;
-
-            let mut file = OpenOptions::new()
-                .write(true)
-                .create(true)
-                .truncate(true)
-                .mode(0o600)
-                .open(&signing_key_path)?;
-            file.write_all(keypair.seed_hex().as_bytes())?;
-        }
-
-        #[cfg(not(unix))]
-        {
-            std::fs::write(&signing_key_path, keypair.seed_hex())?;
-        }
+        save_signing_key(&git_dir, &actor_id_hex, &keypair.seed_hex())?;
     }
 
     save_actor_config(&data_dir, &config)?;
@@ -111,10 +95,82 @@ pub fn actor_use(opts: &ActorUseOptions) -> Result<(), GriteError> {
     let _config = load_actor_config(&data_dir)?;
 
     // Update repo config
-    let mut repo_config = load_repo_config(&git_dir)?.unwrap_or_default();
-    repo_config.default_actor = Some(opts.id.clone());
-
-    save_repo_config(&git_dir, &repo_config)?;
+    update_repo_config(&git_dir, |cfg| cfg.default_actor = Some(opts.id.clone()))?;
 
     Ok(())
 }
+
+/// Replace the current actor's signing key.
+///
+/// The new public key is recorded in project context with its effective
+/// timestamp so events signed with earlier keys stay verifiable.
+pub fn actor_rotate_key(ctx: &GriteContext) -> Result<ActorRotateKeyResult, GriteError> {
+    let data_dir = actor_dir(&ctx.git_dir, &ctx.actor_id);
+    let mut config = load_actor_config(&data_dir)?;
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+
+    let actor_id_bytes = hex_to_id::<16>(&ctx.actor_id)
+        .map_err(|e| GriteError::InvalidArgs(format!("Invalid actor ID: {}", e)))?;
+    let effective_ts = libgrite_core::clock::now_ms();
+    let keypair = SigningKeyPair::generate();
+    let previous_public_key = config.public_key.clone();
+
+    let mut records = Vec::new();
+    if let Some(old_key) = &previous_public_key {
+        let history_prefix = format!("{}{}/", ACTOR_KEY_CONTEXT_PREFIX, ctx.actor_id);
+        let has_history = store
+            .list_project_context()?
+            .iter()
+            .any(|(key, _)| key.starts_with(&history_prefix));
+        if !has_history {
+            records.push((actor_key_context_key(&ctx.actor_id, 0), old_key.clone()));
+        }
+    }
+    records.push((
+        actor_key_context_key(&ctx.actor_id, effective_ts),
+        keypair.public_key_hex(),
+    ));
+
+    // Records are signed with the outgoing key, vouching for its successor
+    let mut events = Vec::new();
+    for (key, value) in records {
+        let kind = EventKind::ProjectContextUpdated { key, value };
+        let event_id = compute_event_id(
+            &PROJECT_CONTEXT_ISSUE_ID,
+            &actor_id_bytes,
+            effective_ts,
+            None,
+            &kind,
+        );
+        let event = ctx.sign_event(Event::new(
+            event_id,
+            PROJECT_CONTEXT_ISSUE_ID,
+            actor_id_bytes,
+            effective_ts,
+            None,
+            kind,
+        ));
+        insert_and_append(&store, &wal, &actor_id_bytes, &event)?;
+        events.push(event);
+    }
+
+    // Key changes are always worth an audit record when auditing is on
+    let audited = AuditLog::for_repo(&ctx.git_dir, "actor rotate-key")
+        .and_then(|log| log.map_or(Ok(()), |log| log.record(&events)));
+    if let Err(e) = audited {
+        eprintln!("Warning: Failed to write audit log: {}", e);
+    }
+
+    save_signing_key(&ctx.git_dir, &ctx.actor_id, &keypair.seed_hex())?;
+    config.public_key = Some(keypair.public_key_hex());
+    config.key_scheme = Some("ed25519".to_string());
+    save_actor_config(&data_dir, &config)?;
+
+    Ok(ActorRotateKeyResult {
+        actor_id: ctx.actor_id.clone(),
+        public_key: keypair.public_key_hex(),
+        previous_public_key,
+        effective_ts,
+    })
+}
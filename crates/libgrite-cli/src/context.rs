@@ -4,8 +4,9 @@ use crate::types::ResolveOptions;
 use git2::Repository;
 use libgrite_core::{
     config::{
-        actor_dir, list_actors, load_actor_config, load_repo_config, load_signing_key,
-        repo_sled_path, save_actor_config, save_repo_config, RepoConfig,
+        actor_dir, auto_init_disabled_by_env, list_actors, load_actor_config, load_repo_config,
+        load_signing_key, load_store_options, repo_sled_path, save_actor_config,
+        update_repo_config,
     },
     lock::{LockCheckResult, LockPolicy},
     signing::SigningKeyPair,
@@ -15,7 +16,7 @@ use libgrite_core::{
     GriteError, GriteStore, LockedStore,
 };
 use libgrite_git::{GitError, LockManager, SnapshotManager, SyncManager, WalManager};
-use libgrite_ipc::{DaemonLock, IpcClient};
+use libgrite_ipc::{DaemonLock, DaemonRegistry, IpcClient};
 
 /// Source of actor selection
 #[derive(Debug, Clone, Copy)]
@@ -186,7 +187,14 @@ impl GriteContext {
             });
         }
 
-        // No actors exist - auto-init
+        // No actors exist - auto-init unless disabled
+        if opts.no_auto_init || auto_init_disabled_by_env() {
+            return Err(GriteError::NotFound(
+                "No actor configured for this repository. Run 'grite init' or 'grite actor init' to create one"
+                    .to_string(),
+            ));
+        }
+
         let actor_id = generate_actor_id();
         let actor_id_hex = id_to_hex(&actor_id);
         let data_dir = actor_dir(&git_dir, &actor_id_hex);
@@ -195,12 +203,10 @@ impl GriteContext {
         // Create actor directory and config
         save_actor_config(&data_dir, &config)?;
 
-        // Set as repo default
-        let repo_config = RepoConfig {
-            default_actor: Some(actor_id_hex.clone()),
-            ..Default::default()
-        };
-        save_repo_config(&git_dir, &repo_config)?;
+        // Set as repo default, keeping any other repo settings
+        update_repo_config(&git_dir, |cfg| {
+            cfg.default_actor = Some(actor_id_hex.clone())
+        })?;
 
         Ok(Self {
             git_dir,
@@ -213,7 +219,10 @@ impl GriteContext {
 
     /// Open the store for this context with exclusive filesystem lock.
     pub fn open_store(&self) -> Result<LockedStore, GriteError> {
-        GriteStore::open_locked(&repo_sled_path(&self.git_dir))
+        GriteStore::open_locked_with_options(
+            &repo_sled_path(&self.git_dir),
+            &load_store_options(&self.git_dir)?,
+        )
     }
 
     /// Get the sled database path
@@ -301,12 +310,27 @@ impl GriteContext {
         event
     }
 
+    /// Endpoint of the live daemon registered for this repo and actor
+    pub fn registered_endpoint(&self, registry: &DaemonRegistry) -> Option<String> {
+        registry
+            .lookup(&self.repo_root().to_string_lossy(), &self.actor_id)
+            .ok()
+            .flatten()
+            .map(|entry| entry.endpoint)
+    }
+
     /// Determine execution mode (local vs daemon)
     pub fn execution_mode(&self, no_daemon: bool) -> ExecutionMode {
         if no_daemon {
             return ExecutionMode::Local;
         }
 
+        if let Some(endpoint) = self.registered_endpoint(&DaemonRegistry::default()) {
+            if let Ok(client) = IpcClient::connect(&endpoint) {
+                return ExecutionMode::Daemon { endpoint, client };
+            }
+        }
+
         match DaemonLock::read(&self.git_dir.join("grite")) {
             Ok(Some(lock)) => {
                 if lock.is_expired() {
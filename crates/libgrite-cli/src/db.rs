@@ -1,8 +1,8 @@
-use std::collections::HashMap;
-
 use libgrite_core::{
     config::list_actors,
-    integrity::{check_store_integrity, verify_store_signatures, CorruptionKind},
+    integrity::{
+        check_store_integrity, load_key_histories, verify_store_signatures, CorruptionKind,
+    },
     types::ids::id_to_hex,
     GriteError,
 };
@@ -26,6 +26,9 @@ pub fn db_stats(ctx: &GriteContext) -> Result<DbStatsResult, GriteError> {
         events_since_rebuild: stats.events_since_rebuild,
         days_since_rebuild: stats.days_since_rebuild,
         rebuild_recommended: stats.rebuild_recommended,
+        context_file_count: stats.context_file_count,
+        context_symbol_count: stats.context_symbol_count,
+        last_context_update_ts: stats.last_context_update_ts,
     })
 }
 
@@ -82,16 +85,16 @@ pub fn db_verify(
 ) -> Result<DbVerifyResult, GriteError> {
     let store = ctx.open_store()?;
 
+    // Build each actor's key history (rotations plus configured keys)
     let actors = list_actors(&ctx.git_dir)?;
-    let mut public_keys: HashMap<String, String> = HashMap::new();
-
-    for actor in &actors {
-        if let Some(pk) = &actor.public_key {
-            public_keys.insert(actor.actor_id.clone(), pk.clone());
-        }
-    }
-
-    let get_public_key = |actor_id: &str| -> Option<String> { public_keys.get(actor_id).cloned() };
+    let key_histories = load_key_histories(&store, &actors)?;
+
+    let get_public_key = |actor_id: &str, ts: u64| -> Option<String> {
+        key_histories
+            .get(actor_id)
+            .and_then(|h| h.key_at(ts))
+            .map(str::to_string)
+    };
 
     let report = verify_store_signatures(&store, get_public_key)?;
 
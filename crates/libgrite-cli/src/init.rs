@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use libgrite_core::{
     config::{
         actor_dir, load_actor_config, load_repo_config, repo_sled_path, save_actor_config,
-        save_repo_config, RepoConfig,
+        update_repo_config,
     },
     types::actor::ActorConfig,
     types::ids::{generate_actor_id, id_to_hex},
@@ -58,11 +58,9 @@ pub fn init(opts: &InitOptions) -> Result<InitResult, GriteError> {
             let sled_path = repo_sled_path(&git_dir);
             let _store = GriteStore::open_locked(&sled_path)?;
 
-            let repo_config = RepoConfig {
-                default_actor: Some(actor_id_hex.clone()),
-                ..Default::default()
-            };
-            save_repo_config(&git_dir, &repo_config)?;
+            update_repo_config(&git_dir, |cfg| {
+                cfg.default_actor = Some(actor_id_hex.clone())
+            })?;
 
             (actor_id_hex, data_dir, true)
         }
@@ -1,9 +1,11 @@
 use libgrite_core::{
+    config::{load_issue_template, load_repo_config},
     hash::compute_event_id,
     lock::LockCheckResult,
     store::IssueFilter,
-    types::event::{Event, EventKind, IssueState},
+    types::event::{Event, EventKind, IssueState, Severity},
     types::ids::{generate_issue_id, id_to_hex},
+    types::issue::IssueLink,
     GriteError,
 };
 
@@ -12,10 +14,7 @@ use crate::event_helper::insert_and_append;
 use crate::types::*;
 
 fn current_ts() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+    libgrite_core::clock::now_ms()
 }
 
 /// RAII guard for auto-releasing locks
@@ -94,12 +93,22 @@ pub fn issue_create(
     let wal = ctx.open_wal()?;
     let actor = ctx.actor_config.actor_id_bytes()?;
 
+    let (title, body, labels) = match &opts.template {
+        Some(name) => {
+            load_issue_template(&ctx.git_dir, name)?.apply(&opts.title, &opts.body, &opts.labels)
+        }
+        None => (opts.title.clone(), opts.body.clone(), opts.labels.clone()),
+    };
+    let labels = load_repo_config(&ctx.git_dir)?
+        .unwrap_or_default()
+        .issue_labels(&labels)?;
+
     let issue_id = generate_issue_id();
     let ts = current_ts();
     let kind = EventKind::IssueCreated {
-        title: opts.title.clone(),
-        body: opts.body.clone(),
-        labels: opts.labels.clone(),
+        title,
+        body,
+        labels,
     };
     let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
     let event = Event::new(event_id, issue_id, actor, ts, None, kind);
@@ -132,6 +141,16 @@ pub fn issue_list(
     let filter = IssueFilter {
         state: state_filter,
         label: opts.label.clone(),
+        assignee: opts.assignee.clone(),
+        unassigned: opts.unassigned,
+        field: opts
+            .field
+            .as_deref()
+            .map(IssueFilter::parse_field)
+            .transpose()?,
+        milestone: opts.milestone.clone(),
+        severity: opts.severity.as_deref().map(parse_severity).transpose()?,
+        include_deleted: opts.include_deleted,
     };
 
     let issues = store.list_issues(&filter)?;
@@ -177,9 +196,12 @@ pub fn issue_update(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
 
     let title = opts.title.clone();
     let body = opts.body.clone();
@@ -210,13 +232,19 @@ pub fn issue_comment(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
+    let existing = store
         .get_issue(&issue_id)?
         .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    let parent_event = opts
+        .reply_to
+        .as_deref()
+        .map(|prefix| existing.resolve_comment(prefix))
+        .transpose()?;
 
     let ts = current_ts();
     let kind = EventKind::CommentAdded {
         body: opts.body.clone(),
+        parent_event,
     };
     let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
     let event = Event::new(event_id, issue_id, actor, ts, None, kind);
@@ -242,9 +270,12 @@ pub fn issue_close(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
 
     let ts = current_ts();
     let kind = EventKind::StateChanged {
@@ -275,9 +306,12 @@ pub fn issue_reopen(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
 
     let ts = current_ts();
     let kind = EventKind::StateChanged {
@@ -308,9 +342,12 @@ pub fn issue_label(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
 
     let ts = current_ts();
     let kind = if !opts.add.is_empty() {
@@ -326,6 +363,13 @@ pub fn issue_label(
             "No labels to add or remove".to_string(),
         ));
     };
+    if let EventKind::LabelAdded { label } = &kind {
+        if let Some(proj) = store.get_issue(&issue_id)? {
+            load_repo_config(&ctx.git_dir)?
+                .unwrap_or_default()
+                .check_label_limit(&proj, std::slice::from_ref(label), &[])?;
+        }
+    }
 
     let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
     let event = Event::new(event_id, issue_id, actor, ts, None, kind);
@@ -351,9 +395,12 @@ pub fn issue_assign(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
 
     let ts = current_ts();
     let kind = if !opts.add.is_empty() {
@@ -394,9 +441,12 @@ pub fn issue_link(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
 
     let ts = current_ts();
     let kind = EventKind::LinkAdded {
@@ -415,6 +465,153 @@ pub fn issue_link(
     })
 }
 
+/// Add or remove a typed link to another issue.
+pub fn issue_link_issue(
+    ctx: &GriteContext,
+    opts: &IssueLinkIssueOptions,
+) -> Result<IssueLinkIssueResult, GriteError> {
+    let relation = IssueLink::normalize_relation(&opts.relation)
+        .ok_or_else(|| GriteError::InvalidArgs(format!("Invalid relation: '{}'", opts.relation)))?;
+
+    let _guard = LockGuard::acquire(ctx, &opts.issue_id, opts.acquire_lock)?;
+
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&opts.issue_id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
+    let target = store.resolve_issue_id(&opts.target_id)?;
+    if !store.issue_exists(&target)? {
+        return Err(GriteError::NotFound(format!(
+            "Target {} not found",
+            opts.target_id
+        )));
+    }
+    if issue_id == target {
+        return Err(GriteError::InvalidArgs(
+            "Cannot link an issue to itself".to_string(),
+        ));
+    }
+
+    let ts = current_ts();
+    let kind = if opts.remove {
+        EventKind::IssueUnlinked { target, relation }
+    } else {
+        EventKind::IssueLinked { target, relation }
+    };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    insert_and_append(&store, &wal, &actor, &event)?;
+
+    Ok(IssueLinkIssueResult {
+        issue_id: id_to_hex(&issue_id),
+        event_id: id_to_hex(&event_id),
+    })
+}
+
+/// Subscribe to or unsubscribe from an issue.
+pub fn issue_subscribe(
+    ctx: &GriteContext,
+    opts: &IssueSubscribeOptions,
+) -> Result<IssueSubscribeResult, GriteError> {
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+    let user = opts.user.clone().unwrap_or_else(|| ctx.actor_id.clone());
+
+    let issue_id = store.resolve_issue_id(&opts.issue_id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
+
+    let ts = current_ts();
+    let kind = if opts.remove {
+        EventKind::Unsubscribed { user }
+    } else {
+        EventKind::Subscribed { user }
+    };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    insert_and_append(&store, &wal, &actor, &event)?;
+
+    Ok(IssueSubscribeResult {
+        issue_id: id_to_hex(&issue_id),
+        event_id: id_to_hex(&event_id),
+    })
+}
+
+/// Set an issue's estimated effort; the latest estimate wins.
+pub fn issue_estimate(
+    ctx: &GriteContext,
+    opts: &IssueEstimateOptions,
+) -> Result<IssueTimeResult, GriteError> {
+    record_time(
+        ctx,
+        &opts.issue_id,
+        EventKind::TimeEstimated {
+            minutes: opts.minutes,
+        },
+    )
+}
+
+/// Log time spent on an issue; logged minutes add up.
+pub fn issue_log_time(
+    ctx: &GriteContext,
+    opts: &IssueLogTimeOptions,
+) -> Result<IssueTimeResult, GriteError> {
+    record_time(
+        ctx,
+        &opts.issue_id,
+        EventKind::TimeLogged {
+            minutes: opts.minutes,
+            note: opts.note.clone(),
+        },
+    )
+}
+
+fn record_time(
+    ctx: &GriteContext,
+    issue_id: &str,
+    kind: EventKind,
+) -> Result<IssueTimeResult, GriteError> {
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let resolved = store.resolve_issue_id(issue_id)?;
+    if !store.issue_exists(&resolved)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            issue_id
+        )));
+    }
+
+    let ts = current_ts();
+    let event_id = compute_event_id(&resolved, &actor, ts, None, &kind);
+    let event = Event::new(event_id, resolved, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    insert_and_append(&store, &wal, &actor, &event)?;
+
+    Ok(IssueTimeResult {
+        issue_id: id_to_hex(&resolved),
+        event_id: id_to_hex(&event_id),
+    })
+}
+
 /// Add an attachment.
 pub fn issue_attach(
     ctx: &GriteContext,
@@ -427,9 +624,12 @@ pub fn issue_attach(
     let actor = ctx.actor_config.actor_id_bytes()?;
 
     let issue_id = store.resolve_issue_id(&opts.issue_id)?;
-    let _existing = store
-        .get_issue(&issue_id)?
-        .ok_or_else(|| GriteError::NotFound(format!("Issue {} not found", opts.issue_id)))?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
 
     let ts = current_ts();
     let sha256_bytes = hex::decode(&opts.sha256)
@@ -452,3 +652,152 @@ pub fn issue_attach(
         event_id: id_to_hex(&event_id),
     })
 }
+
+/// Set or clear a custom field.
+pub fn issue_field(
+    ctx: &GriteContext,
+    opts: &IssueFieldOptions,
+) -> Result<IssueFieldResult, GriteError> {
+    let _guard = LockGuard::acquire(ctx, &opts.issue_id, opts.acquire_lock)?;
+
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&opts.issue_id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
+
+    let ts = current_ts();
+    let kind = EventKind::FieldSet {
+        key: opts.key.clone(),
+        value: opts.value.clone(),
+    };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    insert_and_append(&store, &wal, &actor, &event)?;
+
+    Ok(IssueFieldResult {
+        issue_id: id_to_hex(&issue_id),
+        event_id: id_to_hex(&event_id),
+    })
+}
+
+/// Set or clear an issue's milestone; the latest wins.
+pub fn issue_milestone(
+    ctx: &GriteContext,
+    opts: &IssueMilestoneOptions,
+) -> Result<IssueMilestoneResult, GriteError> {
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&opts.issue_id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
+
+    let ts = current_ts();
+    let kind = EventKind::MilestoneSet {
+        milestone: opts.milestone.clone(),
+    };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    insert_and_append(&store, &wal, &actor, &event)?;
+
+    Ok(IssueMilestoneResult {
+        issue_id: id_to_hex(&issue_id),
+        event_id: id_to_hex(&event_id),
+    })
+}
+
+/// Set or clear an issue's severity; the latest wins.
+pub fn issue_severity(
+    ctx: &GriteContext,
+    opts: &IssueSeverityOptions,
+) -> Result<IssueSeverityResult, GriteError> {
+    let severity = parse_severity(&opts.severity)?;
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(&opts.issue_id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!(
+            "Issue {} not found",
+            opts.issue_id
+        )));
+    }
+
+    let ts = current_ts();
+    let kind = EventKind::SeveritySet { severity };
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    insert_and_append(&store, &wal, &actor, &event)?;
+
+    Ok(IssueSeverityResult {
+        issue_id: id_to_hex(&issue_id),
+        event_id: id_to_hex(&event_id),
+    })
+}
+
+fn parse_severity(s: &str) -> Result<Severity, GriteError> {
+    Severity::from_str(s).ok_or_else(|| GriteError::InvalidArgs(format!("Invalid severity: {}", s)))
+}
+
+/// Soft-delete an issue, hiding it from lists; the latest of delete and
+/// undelete wins.
+pub fn issue_delete(
+    ctx: &GriteContext,
+    opts: &IssueDeleteOptions,
+) -> Result<IssueDeleteResult, GriteError> {
+    record_deletion(ctx, &opts.issue_id, EventKind::IssueDeleted {})
+}
+
+/// Restore a soft-deleted issue.
+pub fn issue_undelete(
+    ctx: &GriteContext,
+    opts: &IssueDeleteOptions,
+) -> Result<IssueDeleteResult, GriteError> {
+    record_deletion(ctx, &opts.issue_id, EventKind::IssueUndeleted {})
+}
+
+fn record_deletion(
+    ctx: &GriteContext,
+    id: &str,
+    kind: EventKind,
+) -> Result<IssueDeleteResult, GriteError> {
+    let store = ctx.open_store()?;
+    let wal = ctx.open_wal()?;
+    let actor = ctx.actor_config.actor_id_bytes()?;
+
+    let issue_id = store.resolve_issue_id(id)?;
+    if !store.issue_exists(&issue_id)? {
+        return Err(GriteError::NotFound(format!("Issue {} not found", id)));
+    }
+
+    let ts = current_ts();
+    let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+    let event = Event::new(event_id, issue_id, actor, ts, None, kind);
+    let event = ctx.sign_event(event);
+
+    insert_and_append(&store, &wal, &actor, &event)?;
+
+    Ok(IssueDeleteResult {
+        issue_id: id_to_hex(&issue_id),
+        event_id: id_to_hex(&event_id),
+    })
+}
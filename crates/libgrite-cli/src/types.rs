@@ -9,6 +9,10 @@ pub struct ResolveOptions {
 
     /// Override the actor ID
     pub actor: Option<String>,
+
+    /// Fail instead of creating an actor when none exists
+    #[serde(default)]
+    pub no_auto_init: bool,
 }
 
 /// Options for `grite init`.
@@ -32,6 +36,8 @@ pub struct IssueCreateOptions {
     pub title: String,
     pub body: String,
     pub labels: Vec<String>,
+    /// Template to prefill the title prefix, body and labels from
+    pub template: Option<String>,
 }
 
 /// Result of creating an issue.
@@ -46,6 +52,17 @@ pub struct IssueCreateResult {
 pub struct IssueListOptions {
     pub state: Option<String>,
     pub label: Option<String>,
+    pub assignee: Option<String>,
+    pub unassigned: bool,
+    /// Custom field filter as `key=value`
+    pub field: Option<String>,
+    /// Only issues in this milestone
+    pub milestone: Option<String>,
+    /// Only issues with this severity: `sev1`..`sev4`, or `none` for issues
+    /// without one
+    pub severity: Option<String>,
+    /// Also list soft-deleted issues
+    pub include_deleted: bool,
 }
 
 /// Result of listing issues.
@@ -88,6 +105,9 @@ pub struct IssueUpdateResult {
 pub struct IssueCommentOptions {
     pub issue_id: String,
     pub body: String,
+    /// Event ID (or unique prefix) of the comment being replied to
+    #[serde(default)]
+    pub reply_to: Option<String>,
     pub acquire_lock: bool,
 }
 
@@ -161,6 +181,64 @@ pub struct IssueLinkResult {
     pub event_id: String,
 }
 
+/// Options for adding or removing a typed link to another issue.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueLinkIssueOptions {
+    pub issue_id: String,
+    pub target_id: String,
+    /// Relation, e.g. "duplicate-of", "relates", "parent"
+    pub relation: String,
+    /// Remove the link instead of adding it
+    pub remove: bool,
+    pub acquire_lock: bool,
+}
+
+/// Result of adding or removing a typed issue link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueLinkIssueResult {
+    pub issue_id: String,
+    pub event_id: String,
+}
+
+/// Options for subscribing to or unsubscribing from an issue.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueSubscribeOptions {
+    pub issue_id: String,
+    /// User to (un)subscribe; defaults to the context's actor ID
+    pub user: Option<String>,
+    /// Unsubscribe instead of subscribing
+    pub remove: bool,
+}
+
+/// Result of subscribing or unsubscribing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueSubscribeResult {
+    pub issue_id: String,
+    pub event_id: String,
+}
+
+/// Options for setting an issue's time estimate.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueEstimateOptions {
+    pub issue_id: String,
+    pub minutes: u32,
+}
+
+/// Options for logging time on an issue.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueLogTimeOptions {
+    pub issue_id: String,
+    pub minutes: u32,
+    pub note: Option<String>,
+}
+
+/// Result of recording an estimate or logged time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueTimeResult {
+    pub issue_id: String,
+    pub event_id: String,
+}
+
 /// Options for adding an attachment.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct IssueAttachOptions {
@@ -178,6 +256,66 @@ pub struct IssueAttachResult {
     pub event_id: String,
 }
 
+/// Options for setting or clearing a custom field.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueFieldOptions {
+    pub issue_id: String,
+    pub key: String,
+    /// New value, or `None` to clear the field
+    pub value: Option<String>,
+    pub acquire_lock: bool,
+}
+
+/// Result of setting or clearing a custom field.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueFieldResult {
+    pub issue_id: String,
+    pub event_id: String,
+}
+
+/// Options for setting or clearing an issue's milestone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueMilestoneOptions {
+    pub issue_id: String,
+    /// Milestone name, or `None` to clear it
+    pub milestone: Option<String>,
+}
+
+/// Result of setting or clearing a milestone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueMilestoneResult {
+    pub issue_id: String,
+    pub event_id: String,
+}
+
+/// Options for setting or clearing an issue's severity.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueSeverityOptions {
+    pub issue_id: String,
+    /// `sev1`..`sev4`, or `none` to clear it
+    pub severity: String,
+}
+
+/// Result of setting or clearing a severity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueSeverityResult {
+    pub issue_id: String,
+    pub event_id: String,
+}
+
+/// Options for soft-deleting or restoring an issue.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IssueDeleteOptions {
+    pub issue_id: String,
+}
+
+/// Result of soft-deleting or restoring an issue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueDeleteResult {
+    pub issue_id: String,
+    pub event_id: String,
+}
+
 /// Options for dependency operations.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DepAddOptions {
@@ -260,6 +398,15 @@ pub struct ActorShowOptions {
     pub id: Option<String>,
 }
 
+/// Result of actor rotate-key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorRotateKeyResult {
+    pub actor_id: String,
+    pub public_key: String,
+    pub previous_public_key: Option<String>,
+    pub effective_ts: u64,
+}
+
 /// Options for actor use.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ActorUseOptions {
@@ -294,6 +441,9 @@ pub struct DbStatsResult {
     pub events_since_rebuild: usize,
     pub days_since_rebuild: Option<u32>,
     pub rebuild_recommended: bool,
+    pub context_file_count: usize,
+    pub context_symbol_count: usize,
+    pub last_context_update_ts: Option<u64>,
 }
 
 /// Options for DB check.
@@ -336,6 +486,9 @@ pub enum ExportFormat {
 pub struct ExportOptions {
     pub format: ExportFormat,
     pub since: Option<String>,
+    pub issue: Option<String>,
+    /// Comma-separated event kinds to export
+    pub kinds: Option<String>,
 }
 
 /// Result of export.
@@ -519,6 +672,7 @@ pub struct ContextIndexResult {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ContextQueryOptions {
     pub query: String,
+    pub limit: Option<usize>,
 }
 
 /// Result of context query.
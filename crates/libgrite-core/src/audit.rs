@@ -0,0 +1,158 @@
+//! Append-only audit log of mutating commands
+//!
+//! When the repo config sets `audit = true`, every event written by a command
+//! is recorded as one JSON line in `.git/grite/audit.log`. The log is local to
+//! the clone and separate from the event log; nothing reads it back.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::load_repo_config;
+use crate::error::GriteError;
+use crate::types::event::Event;
+use crate::types::ids::id_to_hex;
+
+/// Path of the audit log for a repository
+pub fn audit_log_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("grite").join("audit.log")
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// When the command wrote the event (Unix timestamp in ms)
+    pub ts: u64,
+    /// Actor ID (hex)
+    pub actor: String,
+    /// Command that wrote the event, e.g. `issue create`
+    pub command: String,
+    pub issue_id: String,
+    pub event_id: String,
+}
+
+/// Audit log for one command against one repository
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    command: String,
+}
+
+impl AuditLog {
+    /// The audit log for `command`, or `None` if the repo config leaves
+    /// auditing off
+    pub fn for_repo(git_dir: &Path, command: &str) -> Result<Option<Self>, GriteError> {
+        let enabled = load_repo_config(git_dir)?.is_some_and(|config| config.audit);
+        Ok(enabled.then(|| Self {
+            path: audit_log_path(git_dir),
+            command: command.to_string(),
+        }))
+    }
+
+    /// Append one record per event
+    ///
+    /// All records go out in a single append, so concurrent writers never
+    /// interleave within a line.
+    pub fn record(&self, events: &[Event]) -> Result<(), GriteError> {
+        let ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let mut buf = Vec::new();
+        for event in events {
+            let record = AuditRecord {
+                ts,
+                actor: id_to_hex(&event.actor),
+                command: self.command.clone(),
+                issue_id: id_to_hex(&event.issue_id),
+                event_id: id_to_hex(&event.event_id),
+            };
+            serde_json::to_writer(&mut buf, &record)?;
+            buf.push(b'\n');
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&buf)?;
+        Ok(())
+    }
+}
+
+/// Read every record in the audit log; a missing log reads as empty
+pub fn read_audit_log(git_dir: &Path) -> Result<Vec<AuditRecord>, GriteError> {
+    let content = match std::fs::read_to_string(audit_log_path(git_dir)) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{save_repo_config, RepoConfig};
+    use crate::hash::compute_event_id;
+    use crate::types::event::EventKind;
+    use crate::types::ids::generate_issue_id;
+    use tempfile::tempdir;
+
+    fn make_event() -> Event {
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        let kind = EventKind::IssueCreated {
+            title: "Audited".to_string(),
+            body: String::new(),
+            labels: vec![],
+        };
+        let event_id = compute_event_id(&issue_id, &actor, 1000, None, &kind);
+        Event::new(event_id, issue_id, actor, 1000, None, kind)
+    }
+
+    #[test]
+    fn test_audit_disabled_by_default() {
+        let dir = tempdir().unwrap();
+        assert!(AuditLog::for_repo(dir.path(), "issue create")
+            .unwrap()
+            .is_none());
+
+        save_repo_config(dir.path(), &RepoConfig::default()).unwrap();
+        assert!(AuditLog::for_repo(dir.path(), "issue create")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_audit_appends_records() {
+        let dir = tempdir().unwrap();
+        let config = RepoConfig {
+            audit: true,
+            ..Default::default()
+        };
+        save_repo_config(dir.path(), &config).unwrap();
+
+        let log = AuditLog::for_repo(dir.path(), "issue create")
+            .unwrap()
+            .unwrap();
+        let first = make_event();
+        let second = make_event();
+        log.record(std::slice::from_ref(&first)).unwrap();
+        log.record(std::slice::from_ref(&second)).unwrap();
+
+        let records = read_audit_log(dir.path()).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].command, "issue create");
+        assert_eq!(records[0].actor, id_to_hex(&first.actor));
+        assert_eq!(records[0].issue_id, id_to_hex(&first.issue_id));
+        assert_eq!(records[0].event_id, id_to_hex(&first.event_id));
+        assert_eq!(records[1].event_id, id_to_hex(&second.event_id));
+    }
+}
@@ -0,0 +1,43 @@
+//! Wall-clock source for event timestamps
+//!
+//! Timestamps normally come from the system clock. Setting `GRITE_TEST_CLOCK`
+//! to a Unix timestamp in milliseconds replaces it: the first reading is that
+//! value and each later reading is one millisecond later, so end-to-end tests
+//! get the same timestamps on every run. With [`crate::test_state`] set up, a
+//! new process continues after the last reading of the previous one.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use crate::test_state;
+
+/// Environment variable holding the start of the test clock (Unix ms)
+pub const TEST_CLOCK_ENV: &str = "GRITE_TEST_CLOCK";
+
+/// Current time as a Unix timestamp in milliseconds
+pub fn now_ms() -> u64 {
+    match test_clock() {
+        Some(next) => {
+            let ts = next.fetch_add(1, Ordering::SeqCst);
+            test_state::update(|state| state.clock_next = state.clock_next.max(ts + 1));
+            ts
+        }
+        None => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+    }
+}
+
+/// Next test clock reading, if `GRITE_TEST_CLOCK` holds a timestamp
+fn test_clock() -> Option<&'static AtomicU64> {
+    static CLOCK: OnceLock<Option<AtomicU64>> = OnceLock::new();
+    CLOCK
+        .get_or_init(|| {
+            std::env::var(TEST_CLOCK_ENV)
+                .ok()
+                .and_then(|raw| raw.trim().parse::<u64>().ok())
+                .map(|start| AtomicU64::new(start.max(test_state::load().clock_next)))
+        })
+        .as_ref()
+}
@@ -1,9 +1,16 @@
 use crate::error::GriteError;
 use crate::lock::LockPolicy;
 use crate::signing::VerificationPolicy;
+use crate::store::{StoreOptions, DEFAULT_LARGE_EVENT_WARN_BYTES};
 use crate::types::actor::ActorConfig;
+use crate::types::ids::id_to_hex;
+use crate::types::issue::IssueProjection;
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Repo-level configuration stored in .git/grite/config.toml
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -17,9 +24,178 @@ pub struct RepoConfig {
     /// Signature verification policy: "off", "warn", or "require"
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verify_signatures: Option<String>,
+    /// Let the daemon rebuild the local database when a rebuild is recommended
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_rebuild: bool,
     /// Snapshot configuration
     #[serde(skip_serializing_if = "Option::is_none")]
     pub snapshot: Option<SnapshotConfig>,
+    /// sled page cache size in MiB (unset = sled default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sled_cache_mb: Option<u64>,
+    /// sled background flush interval in milliseconds (unset = sled default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sled_flush_every_ms: Option<u64>,
+    /// Stored event size in bytes above which `grite doctor` warns
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub large_event_warn_bytes: Option<u64>,
+    /// Superseded bodies each issue projection keeps (unset = none)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_history: Option<usize>,
+    /// Characters of each ID shown in human output (unset = 8)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_len: Option<usize>,
+    /// Append a record of every written event to `.git/grite/audit.log`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub audit: bool,
+    /// Commands a daemon worker runs at once for this repo (unset = daemon default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_commands: Option<usize>,
+    /// Attempts a daemon worker makes to take the daemon lock (unset = daemon default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_acquire_attempts: Option<u32>,
+    /// Top-level commands that always run locally instead of via the daemon
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub local_commands: Vec<String>,
+    /// Labels added to every new issue
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_labels: Vec<String>,
+    /// Most labels an issue may carry (unset = unlimited)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_labels_per_issue: Option<usize>,
+    /// Most events per WAL chunk; larger appends span several commits
+    /// (unset = one chunk per append)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_events_per_chunk: Option<usize>,
+    /// Deflate large events in the local database
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compress_events: bool,
+    /// Write new WAL chunks zstd-compressed (`cbor-zstd-v1`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compress_chunks: bool,
+    /// Issue templates defined inline (`[templates.<name>]`)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub templates: BTreeMap<String, IssueTemplate>,
+}
+
+impl RepoConfig {
+    /// Store tuning options taken from this config
+    pub fn store_options(&self) -> StoreOptions {
+        StoreOptions {
+            cache_mb: self.sled_cache_mb,
+            flush_every_ms: self.sled_flush_every_ms,
+            body_history: self.body_history,
+            compress_events: self.compress_events,
+        }
+    }
+
+    /// Size threshold for the doctor's large event check
+    pub fn large_event_warn_bytes(&self) -> u64 {
+        self.large_event_warn_bytes
+            .unwrap_or(DEFAULT_LARGE_EVENT_WARN_BYTES)
+    }
+
+    /// Labels for a new issue: the explicit labels followed by any
+    /// `default_labels` not already among them
+    ///
+    /// Fails if the issue would start with more distinct labels than
+    /// `max_labels_per_issue` allows.
+    pub fn issue_labels(&self, labels: &[String]) -> Result<Vec<String>, GriteError> {
+        let mut all_labels = labels.to_vec();
+        for label in &self.default_labels {
+            if !all_labels.contains(label) {
+                all_labels.push(label.clone());
+            }
+        }
+        if let Some(max) = self.max_labels_per_issue {
+            let distinct: HashSet<&String> = all_labels.iter().collect();
+            if distinct.len() > max {
+                return Err(GriteError::Conflict(format!(
+                    "new issue would have {} labels (max_labels_per_issue = {})",
+                    distinct.len(),
+                    max
+                )));
+            }
+        }
+        Ok(all_labels)
+    }
+
+    /// Check that `issue` stays within `max_labels_per_issue` once `remove`
+    /// and `add` are applied.
+    ///
+    /// Only adding a label the issue does not have yet can fail, so an issue
+    /// already over a lowered limit can still drop labels.
+    pub fn check_label_limit(
+        &self,
+        issue: &IssueProjection,
+        add: &[String],
+        remove: &[String],
+    ) -> Result<(), GriteError> {
+        let Some(max) = self.max_labels_per_issue else {
+            return Ok(());
+        };
+        let mut labels = issue.labels.clone();
+        for label in remove {
+            labels.remove(label);
+        }
+        let mut grows = false;
+        for label in add {
+            grows |= labels.insert(label.clone());
+        }
+        if grows && labels.len() > max {
+            return Err(GriteError::Conflict(format!(
+                "issue {} already has {} labels (max_labels_per_issue = {})",
+                id_to_hex(&issue.issue_id),
+                issue.labels.len(),
+                max
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Issue template applied by `grite issue create --template <name>`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueTemplate {
+    /// Prefix prepended to the issue title
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_prefix: Option<String>,
+    /// Body skeleton used when no body is given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Labels added to the issue
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+}
+
+impl IssueTemplate {
+    /// Fill in issue fields from this template.
+    ///
+    /// A non-empty `body` overrides the skeleton; `labels` are added to the
+    /// template's labels.
+    pub fn apply(
+        &self,
+        title: &str,
+        body: &str,
+        labels: &[String],
+    ) -> (String, String, Vec<String>) {
+        let title = match &self.title_prefix {
+            Some(prefix) if !title.starts_with(prefix.as_str()) => format!("{}{}", prefix, title),
+            _ => title.to_string(),
+        };
+        let body = if body.is_empty() {
+            self.body.clone().unwrap_or_default()
+        } else {
+            body.to_string()
+        };
+        let mut all_labels = self.labels.clone();
+        for label in labels {
+            if !all_labels.contains(label) {
+                all_labels.push(label.clone());
+            }
+        }
+        (title, body, all_labels)
+    }
 }
 
 /// Snapshot policy configuration
@@ -31,6 +207,9 @@ pub struct SnapshotConfig {
     /// Create snapshot when last snapshot is older than this many days
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_age_days: Option<u32>,
+    /// Snapshot and prune the WAL once it holds more commits than this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wal_commits: Option<u32>,
 }
 
 impl Default for SnapshotConfig {
@@ -38,6 +217,7 @@ impl Default for SnapshotConfig {
         Self {
             max_events: Some(10000),
             max_age_days: Some(7),
+            max_wal_commits: None,
         }
     }
 }
@@ -67,20 +247,166 @@ pub fn load_repo_config(git_dir: &Path) -> Result<Option<RepoConfig>, GriteError
         return Ok(None);
     }
     let content = std::fs::read_to_string(&config_path)?;
-    let config: RepoConfig = toml::from_str(&content)?;
-    Ok(Some(config))
+    Ok(Some(parse_repo_config(&content)?))
+}
+
+/// Parse repo config from TOML text
+pub fn parse_repo_config(content: &str) -> Result<RepoConfig, GriteError> {
+    Ok(toml::from_str(content)?)
 }
 
 /// Save repo config to .git/grite/config.toml
+///
+/// The file is replaced atomically, so a crash never leaves a partial config.
+/// Use [`update_repo_config`] for read-modify-write changes.
 pub fn save_repo_config(git_dir: &Path, config: &RepoConfig) -> Result<(), GriteError> {
     let grit_dir = git_dir.join("grite");
     std::fs::create_dir_all(&grit_dir)?;
     let config_path = grit_dir.join("config.toml");
     let content = toml::to_string_pretty(config)?;
-    std::fs::write(&config_path, content)?;
+    write_atomic(&config_path, &content)
+}
+
+/// Load, modify and save the repo config under an exclusive file lock.
+///
+/// Concurrent updaters (threads or processes) are serialized on
+/// `.git/grite/config.lock`, so each sees the others' changes. A missing
+/// config starts from the defaults. Returns the saved config.
+pub fn update_repo_config<F>(git_dir: &Path, update: F) -> Result<RepoConfig, GriteError>
+where
+    F: FnOnce(&mut RepoConfig),
+{
+    let grit_dir = git_dir.join("grite");
+    std::fs::create_dir_all(&grit_dir)?;
+    let lock_file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(grit_dir.join("config.lock"))?;
+    lock_file.lock_exclusive()?;
+
+    let mut config = load_repo_config(git_dir)?.unwrap_or_default();
+    update(&mut config);
+    save_repo_config(git_dir, &config)?;
+
+    // Lock released when `lock_file` is dropped
+    Ok(config)
+}
+
+/// Replace `path` with `content` by writing a sibling temp file and renaming
+/// it into place
+fn write_atomic(path: &Path, content: &str) -> Result<(), GriteError> {
+    static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp_path = path.with_file_name(format!(
+        ".{}.tmp.{}.{}",
+        file_name,
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    })();
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
     Ok(())
 }
 
+/// Load store tuning options from the repo config, falling back to defaults
+pub fn load_store_options(git_dir: &Path) -> Result<StoreOptions, GriteError> {
+    Ok(load_repo_config(git_dir)?
+        .map(|c| c.store_options())
+        .unwrap_or_default())
+}
+
+/// Get the issue templates directory (.git/grite/templates)
+pub fn templates_dir(git_dir: &Path) -> std::path::PathBuf {
+    git_dir.join("grite").join("templates")
+}
+
+/// List available issue template names, from the templates directory and
+/// the repo config
+pub fn list_issue_templates(git_dir: &Path) -> Result<Vec<String>, GriteError> {
+    let mut names: Vec<String> = load_repo_config(git_dir)?
+        .map(|c| c.templates.into_keys().collect())
+        .unwrap_or_default();
+
+    let dir = templates_dir(git_dir);
+    if dir.exists() {
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "toml") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    names.push(stem.to_string());
+                }
+            }
+        }
+    }
+
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// Load an issue template by name.
+///
+/// `.git/grite/templates/<name>.toml` takes precedence over a
+/// `[templates.<name>]` table in the repo config. Names containing a path
+/// separator or `..` are rejected, so a template never reads outside the
+/// templates directory.
+pub fn load_issue_template(git_dir: &Path, name: &str) -> Result<IssueTemplate, GriteError> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(GriteError::InvalidArgs(format!(
+            "Invalid template name '{}'",
+            name
+        )));
+    }
+
+    let path = templates_dir(git_dir).join(format!("{}.toml", name));
+    if path.exists() {
+        let content = std::fs::read_to_string(&path)?;
+        return Ok(toml::from_str(&content)?);
+    }
+
+    if let Some(template) = load_repo_config(git_dir)?.and_then(|mut c| c.templates.remove(name)) {
+        return Ok(template);
+    }
+
+    let available = list_issue_templates(git_dir)?;
+    Err(GriteError::NotFound(format!(
+        "Unknown template '{}'. Available templates: {}",
+        name,
+        if available.is_empty() {
+            "(none)".to_string()
+        } else {
+            available.join(", ")
+        }
+    )))
+}
+
+/// Environment variable that disables auto-creating an actor during context
+/// resolution (same effect as `--no-auto-init`)
+pub const NO_AUTO_INIT_ENV: &str = "GRITE_NO_AUTO_INIT";
+
+/// Whether `GRITE_NO_AUTO_INIT` is set to a truthy value.
+///
+/// Empty, `0` and `false` count as unset.
+pub fn auto_init_disabled_by_env() -> bool {
+    std::env::var(NO_AUTO_INIT_ENV)
+        .map(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "" | "0" | "false"))
+        .unwrap_or(false)
+}
+
 /// Load actor config from .git/grite/actors/<actor_id>/config.toml
 pub fn load_actor_config(actor_dir: &Path) -> Result<ActorConfig, GriteError> {
     let config_path = actor_dir.join("config.toml");
@@ -96,12 +422,13 @@ pub fn load_actor_config(actor_dir: &Path) -> Result<ActorConfig, GriteError> {
 }
 
 /// Save actor config to .git/grite/actors/<actor_id>/config.toml
+///
+/// The file is replaced atomically, so a crash never leaves a partial config.
 pub fn save_actor_config(actor_dir: &Path, config: &ActorConfig) -> Result<(), GriteError> {
     std::fs::create_dir_all(actor_dir)?;
     let config_path = actor_dir.join("config.toml");
     let content = toml::to_string_pretty(config)?;
-    std::fs::write(&config_path, content)?;
-    Ok(())
+    write_atomic(&config_path, &content)
 }
 
 /// List all actors in .git/grite/actors/
@@ -162,6 +489,36 @@ pub fn load_signing_key(git_dir: &Path, actor_id: &str) -> Option<String> {
     std::fs::read_to_string(key_path).ok()
 }
 
+/// Save an actor's signing key seed (hex-encoded), replacing any existing one
+///
+/// On Unix the file is created with 0600 permissions to protect the key.
+pub fn save_signing_key(git_dir: &Path, actor_id: &str, seed_hex: &str) -> Result<(), GriteError> {
+    std::fs::create_dir_all(actor_dir(git_dir, actor_id))?;
+    let key_path = actor_signing_key_path(git_dir, actor_id);
+
+    #[cfg(unix)]
+    {
+        use std::fs::OpenOptions;
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&key_path)?;
+        file.write_all(seed_hex.as_bytes())?;
+    }
+
+    #[cfg(not(unix))]
+    {
+        std::fs::write(&key_path, seed_hex)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,10 +533,27 @@ mod tests {
             default_actor: Some("00112233445566778899aabbccddeeff".to_string()),
             lock_policy: Some("warn".to_string()),
             verify_signatures: Some("warn".to_string()),
+            auto_rebuild: true,
             snapshot: Some(SnapshotConfig {
                 max_events: Some(5000),
                 max_age_days: Some(3),
+                max_wal_commits: Some(500),
             }),
+            sled_cache_mb: Some(256),
+            sled_flush_every_ms: Some(1000),
+            large_event_warn_bytes: Some(4096),
+            body_history: Some(2),
+            id_len: Some(12),
+            audit: true,
+            max_concurrent_commands: Some(4),
+            lock_acquire_attempts: Some(3),
+            local_commands: vec!["sync".to_string()],
+            default_labels: vec!["needs-triage".to_string()],
+            max_labels_per_issue: Some(5),
+            max_events_per_chunk: Some(1000),
+            compress_events: true,
+            compress_chunks: true,
+            templates: BTreeMap::new(),
         };
 
         save_repo_config(git_dir, &config).unwrap();
@@ -187,6 +561,137 @@ mod tests {
 
         assert_eq!(loaded.default_actor, config.default_actor);
         assert_eq!(loaded.lock_policy, config.lock_policy);
+        assert!(loaded.auto_rebuild);
+        assert_eq!(loaded.snapshot.as_ref().unwrap().max_wal_commits, Some(500));
+        assert_eq!(
+            loaded.store_options(),
+            StoreOptions {
+                cache_mb: Some(256),
+                flush_every_ms: Some(1000),
+                body_history: Some(2),
+                compress_events: true,
+            }
+        );
+        assert_eq!(loaded.large_event_warn_bytes(), 4096);
+        assert_eq!(loaded.default_labels, vec!["needs-triage".to_string()]);
+        assert_eq!(loaded.id_len, Some(12));
+        assert!(loaded.audit);
+        assert_eq!(loaded.max_concurrent_commands, Some(4));
+        assert_eq!(loaded.max_labels_per_issue, Some(5));
+        assert_eq!(loaded.max_events_per_chunk, Some(1000));
+        assert!(loaded.compress_chunks);
+        assert_eq!(
+            RepoConfig::default().large_event_warn_bytes(),
+            DEFAULT_LARGE_EVENT_WARN_BYTES
+        );
+    }
+
+    #[test]
+    fn test_concurrent_repo_config_updates() {
+        let dir = tempdir().unwrap();
+        let git_dir = dir.path().to_path_buf();
+        save_repo_config(
+            &git_dir,
+            &RepoConfig {
+                default_actor: Some("actor".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // Each thread repeatedly adds its own templates; a lost update would
+        // drop some of the other thread's entries
+        let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+        let handles: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|prefix| {
+                let git_dir = git_dir.clone();
+                let barrier = barrier.clone();
+                std::thread::spawn(move || {
+                    barrier.wait();
+                    for i in 0..20 {
+                        update_repo_config(&git_dir, |cfg| {
+                            cfg.templates
+                                .insert(format!("{}{}", prefix, i), IssueTemplate::default());
+                            if prefix == "a" {
+                                cfg.lock_policy = Some("warn".to_string());
+                            } else {
+                                cfg.sled_cache_mb = Some(64);
+                            }
+                        })
+                        .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let config = load_repo_config(&git_dir).unwrap().unwrap();
+        assert_eq!(config.templates.len(), 40);
+        assert_eq!(config.lock_policy.as_deref(), Some("warn"));
+        assert_eq!(config.sled_cache_mb, Some(64));
+        assert_eq!(config.default_actor.as_deref(), Some("actor"));
+
+        // No temp files are left behind
+        let leftovers: Vec<_> = std::fs::read_dir(git_dir.join("grite"))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".tmp."))
+            .collect();
+        assert!(leftovers.is_empty());
+    }
+
+    #[test]
+    fn test_issue_template_from_dir_and_config() {
+        let dir = tempdir().unwrap();
+        let git_dir = dir.path();
+
+        std::fs::create_dir_all(templates_dir(git_dir)).unwrap();
+        std::fs::write(
+            templates_dir(git_dir).join("bug.toml"),
+            "title_prefix = \"[bug] \"\nbody = \"## Steps\\n\\n## Expected\"\nlabels = [\"bug\"]\n",
+        )
+        .unwrap();
+        let mut config = RepoConfig::default();
+        config.templates.insert(
+            "task".to_string(),
+            IssueTemplate {
+                labels: vec!["task".to_string()],
+                ..Default::default()
+            },
+        );
+        save_repo_config(git_dir, &config).unwrap();
+
+        let bug = load_issue_template(git_dir, "bug").unwrap();
+        let (title, body, labels) = bug.apply("Crash on start", "", &["p0".to_string()]);
+        assert_eq!(title, "[bug] Crash on start");
+        assert_eq!(body, "## Steps\n\n## Expected");
+        assert_eq!(labels, vec!["bug".to_string(), "p0".to_string()]);
+
+        // An explicit body overrides the skeleton
+        let (_, body, _) = bug.apply("Crash", "Details", &[]);
+        assert_eq!(body, "Details");
+
+        let task = load_issue_template(git_dir, "task").unwrap();
+        assert_eq!(task.labels, vec!["task".to_string()]);
+
+        let err = load_issue_template(git_dir, "feature").unwrap_err();
+        assert!(err.to_string().contains("bug, task"), "{}", err);
+
+        // Names cannot reach outside the templates directory
+        std::fs::write(git_dir.join("grite").join("outside.toml"), "body = \"x\"\n").unwrap();
+        for name in ["../outside", "..", "a/b", "a\\b", ""] {
+            assert!(
+                matches!(
+                    load_issue_template(git_dir, name),
+                    Err(GriteError::InvalidArgs(_))
+                ),
+                "{}",
+                name
+            );
+        }
     }
 
     #[test]
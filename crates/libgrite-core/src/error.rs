@@ -1,3 +1,4 @@
+use serde::Serialize;
 use thiserror::Error;
 
 /// Main error type for grite operations
@@ -12,6 +13,9 @@ pub enum GriteError {
     #[error("conflict: {0}")]
     Conflict(String),
 
+    #[error("ambiguous: {0}")]
+    Ambiguous(String),
+
     #[error("database busy: {0}")]
     DbBusy(String),
 
@@ -38,15 +42,20 @@ pub enum GriteError {
 
     #[error("IPC error: {0}")]
     Ipc(String),
+
+    /// An error from the daemon whose code has no variant of its own
+    #[error("daemon error ({code}): {message}")]
+    Daemon { code: String, message: String },
 }
 
 impl GriteError {
     /// Get the error code for JSON output (from cli-json.md)
-    pub fn error_code(&self) -> &'static str {
+    pub fn error_code(&self) -> &str {
         match self {
             GriteError::InvalidArgs(_) => "invalid_args",
             GriteError::NotFound(_) => "not_found",
             GriteError::Conflict(_) => "conflict",
+            GriteError::Ambiguous(_) => "ambiguous",
             GriteError::DbBusy(_) => "db_busy",
             GriteError::Io(_) => "io_error",
             GriteError::Sled(_) => "db_error",
@@ -56,6 +65,7 @@ impl GriteError {
             GriteError::IdParse(_) => "invalid_args",
             GriteError::Internal(_) => "internal_error",
             GriteError::Ipc(_) => "ipc_error",
+            GriteError::Daemon { code, .. } => code,
         }
     }
 
@@ -63,14 +73,19 @@ impl GriteError {
     pub fn exit_code(&self) -> i32 {
         match self {
             GriteError::InvalidArgs(_) => 2,
+            GriteError::Ambiguous(_) => 2,
             GriteError::NotFound(_) => 3,
             GriteError::Conflict(_) => 4,
             GriteError::DbBusy(_) => 5,
             GriteError::Io(_) => 5,
             GriteError::Sled(_) => 5,
             GriteError::IdParse(_) => 2,
+            GriteError::TomlParse(_) => 2,
             GriteError::Ipc(_) => 6,
-            _ => 1,
+            GriteError::Json(_) => 1,
+            GriteError::TomlSerialize(_) => 1,
+            GriteError::Internal(_) => 1,
+            GriteError::Daemon { .. } => 1,
         }
     }
 
@@ -100,6 +115,7 @@ impl GriteError {
                 "Or use 'grite --no-daemon <command>' to bypass IPC",
             ],
             GriteError::Conflict(_) => vec!["Run 'grite sync' to pull latest changes"],
+            GriteError::Ambiguous(_) => vec!["Type more characters of the ID to pick one match"],
             GriteError::IdParse(_) => vec![
                 "IDs should be hex strings (e.g., 'abc123...')",
                 "Use 'grite issue list' to see valid issue IDs",
@@ -129,3 +145,98 @@ impl GriteError {
         GriteError::DbBusy(msg)
     }
 }
+
+/// Exit code and JSON error code of one `GriteError` variant, as listed by
+/// `grite errors`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorCodeInfo {
+    pub variant: &'static str,
+    pub exit_code: i32,
+    pub error_code: String,
+}
+
+/// Defines `variant_name` and `all_variants` from one list, so the
+/// exhaustive match in `variant_name` fails to compile until a new variant is
+/// listed, which also adds it to `all_variants`.
+macro_rules! error_variants {
+    ($($name:ident $pattern:tt => $example:expr,)*) => {
+        impl GriteError {
+            /// Name of this error's variant, e.g. `NotFound`
+            pub fn variant_name(&self) -> &'static str {
+                match self {
+                    $(GriteError::$name $pattern => stringify!($name),)*
+                }
+            }
+
+            /// One placeholder error of every variant, in declaration order
+            pub fn all_variants() -> Vec<GriteError> {
+                vec![$($example,)*]
+            }
+        }
+    };
+}
+
+error_variants! {
+    InvalidArgs(_) => GriteError::InvalidArgs(String::new()),
+    NotFound(_) => GriteError::NotFound(String::new()),
+    Conflict(_) => GriteError::Conflict(String::new()),
+    Ambiguous(_) => GriteError::Ambiguous(String::new()),
+    DbBusy(_) => GriteError::DbBusy(String::new()),
+    Io(_) => GriteError::Io(std::io::Error::other("")),
+    Sled(_) => GriteError::Sled(sled::Error::Unsupported(String::new())),
+    Json(_) => GriteError::Json(serde::de::Error::custom("")),
+    TomlParse(_) => GriteError::TomlParse(serde::de::Error::custom("")),
+    TomlSerialize(_) => GriteError::TomlSerialize(serde::ser::Error::custom("")),
+    IdParse(_) => GriteError::IdParse(crate::types::ids::IdParseError::InvalidLength {
+        expected: 0,
+        actual: 0,
+    }),
+    Internal(_) => GriteError::Internal(String::new()),
+    Ipc(_) => GriteError::Ipc(String::new()),
+    Daemon { .. } => GriteError::Daemon {
+        code: "*".to_string(),
+        message: String::new(),
+    },
+}
+
+impl GriteError {
+    /// Exit code and error code of every variant.
+    ///
+    /// `Daemon` is listed with error code `*`: it carries whatever code the
+    /// daemon reported.
+    pub fn code_table() -> Vec<ErrorCodeInfo> {
+        Self::all_variants()
+            .iter()
+            .map(|e| ErrorCodeInfo {
+                variant: e.variant_name(),
+                exit_code: e.exit_code(),
+                error_code: e.error_code().to_string(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_stable_and_distinct() {
+        let codes = [
+            GriteError::InvalidArgs(String::new()).exit_code(),
+            GriteError::NotFound(String::new()).exit_code(),
+            GriteError::Conflict(String::new()).exit_code(),
+            GriteError::DbBusy(String::new()).exit_code(),
+        ];
+        assert_eq!(codes, [2, 3, 4, 5]);
+
+        let table = GriteError::code_table();
+        assert_eq!(table.len(), GriteError::all_variants().len());
+        for (info, e) in table.iter().zip(GriteError::all_variants()) {
+            assert_eq!(info.variant, e.variant_name());
+        }
+        let not_found = table.iter().find(|i| i.variant == "NotFound").unwrap();
+        assert_eq!(not_found.exit_code, 3);
+        assert_eq!(not_found.error_code, "not_found");
+    }
+}
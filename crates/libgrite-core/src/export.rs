@@ -1,9 +1,17 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
 use crate::error::GriteError;
 use crate::store::{GriteStore, IssueFilter};
 use crate::types::event::{Event, EventKind};
-use crate::types::ids::{id_to_hex, EventId};
+use crate::types::ids::{id_to_hex, ActorId, EventId, IssueId};
 use crate::types::issue::IssueSummary;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Export metadata
 #[derive(Debug, Serialize)]
@@ -21,6 +29,19 @@ pub struct JsonExport {
     pub meta: ExportMeta,
     pub issues: Vec<IssueSummaryJson>,
     pub events: Vec<EventJson>,
+    /// Bytes of the attachments the events reference, with `include_attachments`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<AttachmentJson>,
+}
+
+/// Attachment bytes embedded in a JSON export
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttachmentJson {
+    pub sha256: String,
+    pub name: String,
+    pub mime: String,
+    /// Attachment bytes, base64-encoded (standard alphabet, padded)
+    pub data_base64: String,
 }
 
 /// Issue summary for JSON export
@@ -52,7 +73,7 @@ impl From<&IssueSummary> for IssueSummaryJson {
 }
 
 /// Event for JSON export
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct EventJson {
     pub event_id: String,
     pub issue_id: String,
@@ -99,12 +120,12 @@ fn event_kind_to_json(kind: &EventKind) -> serde_json::Value {
                 }
             })
         }
-        EventKind::CommentAdded { body } => {
-            serde_json::json!({
-                "CommentAdded": {
-                    "body": body
-                }
-            })
+        EventKind::CommentAdded { body, parent_event } => {
+            let mut comment = serde_json::json!({ "body": body });
+            if let Some(parent) = parent_event {
+                comment["parent_event"] = serde_json::Value::String(id_to_hex(parent));
+            }
+            serde_json::json!({ "CommentAdded": comment })
         }
         EventKind::LabelAdded { label } => {
             serde_json::json!({
@@ -149,6 +170,51 @@ fn event_kind_to_json(kind: &EventKind) -> serde_json::Value {
                 }
             })
         }
+        EventKind::Subscribed { user } => {
+            serde_json::json!({
+                "Subscribed": {
+                    "user": user
+                }
+            })
+        }
+        EventKind::Unsubscribed { user } => {
+            serde_json::json!({
+                "Unsubscribed": {
+                    "user": user
+                }
+            })
+        }
+        EventKind::TimeEstimated { minutes } => {
+            serde_json::json!({
+                "TimeEstimated": {
+                    "minutes": minutes
+                }
+            })
+        }
+        EventKind::TimeLogged { minutes, note } => {
+            serde_json::json!({
+                "TimeLogged": {
+                    "minutes": minutes,
+                    "note": note
+                }
+            })
+        }
+        EventKind::MilestoneSet { milestone } => {
+            serde_json::json!({
+                "MilestoneSet": {
+                    "milestone": milestone
+                }
+            })
+        }
+        EventKind::IssueDeleted {} => serde_json::json!({ "IssueDeleted": {} }),
+        EventKind::IssueUndeleted {} => serde_json::json!({ "IssueUndeleted": {} }),
+        EventKind::SeveritySet { severity } => {
+            serde_json::json!({
+                "SeveritySet": {
+                    "severity": severity.as_str()
+                }
+            })
+        }
         EventKind::AttachmentAdded { name, sha256, mime } => {
             serde_json::json!({
                 "AttachmentAdded": {
@@ -174,6 +240,22 @@ fn event_kind_to_json(kind: &EventKind) -> serde_json::Value {
                 }
             })
         }
+        EventKind::IssueLinked { target, relation } => {
+            serde_json::json!({
+                "IssueLinked": {
+                    "target": id_to_hex(target),
+                    "relation": relation
+                }
+            })
+        }
+        EventKind::IssueUnlinked { target, relation } => {
+            serde_json::json!({
+                "IssueUnlinked": {
+                    "target": id_to_hex(target),
+                    "relation": relation
+                }
+            })
+        }
         EventKind::ContextUpdated {
             path,
             language,
@@ -199,6 +281,14 @@ fn event_kind_to_json(kind: &EventKind) -> serde_json::Value {
                 }
             })
         }
+        EventKind::FieldSet { key, value } => {
+            serde_json::json!({
+                "FieldSet": {
+                    "key": key,
+                    "value": value
+                }
+            })
+        }
     }
 }
 
@@ -208,123 +298,634 @@ pub enum ExportSince {
     EventId(EventId),
 }
 
+/// Event ordering for exports
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportOrder {
+    /// Grouped by issue: `(issue_id, ts_unix_ms, actor, event_id)`
+    #[default]
+    ByIssue,
+    /// Strictly by time: `(ts_unix_ms, actor, event_id)`
+    Chronological,
+}
+
+impl ExportOrder {
+    /// Parse from string
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "by-issue" => Some(ExportOrder::ByIssue),
+            "chronological" => Some(ExportOrder::Chronological),
+            _ => None,
+        }
+    }
+
+    /// Convert to string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportOrder::ByIssue => "by-issue",
+            ExportOrder::Chronological => "chronological",
+        }
+    }
+}
+
+/// Options controlling export output
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions {
+    pub order: ExportOrder,
+    /// Export only this issue and its events
+    pub issue: Option<IssueId>,
+    /// Export only events with these kind tags; `None` exports every kind
+    pub kinds: Option<HashSet<u32>>,
+    /// Embed the bytes of referenced attachments in JSON exports
+    pub include_attachments: bool,
+    /// Replace actor IDs and user names with pseudonyms
+    pub anonymize: bool,
+}
+
+/// Pseudonyms for one anonymized export
+///
+/// Every export draws a fresh random salt, so an actor or user keeps one
+/// pseudonym throughout an export but gets an unrelated one in the next, and
+/// pseudonyms cannot be matched by hashing candidate names. Event IDs are
+/// replaced too: the real ones hash the actor ID and would give it away.
+pub struct Anonymizer {
+    salt: [u8; 32],
+}
+
+impl Anonymizer {
+    /// Pseudonyms under a new random salt
+    pub fn new() -> Self {
+        Self {
+            salt: rand::random(),
+        }
+    }
+
+    /// Stand-in for an actor ID
+    pub fn actor(&self, actor: &ActorId) -> String {
+        format!("actor-{}", &hex::encode(self.digest("actor", actor))[..12])
+    }
+
+    /// Stand-in for a user name (assignee or subscriber)
+    pub fn user(&self, user: &str) -> String {
+        format!(
+            "user-{}",
+            &hex::encode(self.digest("user", user.as_bytes()))[..12]
+        )
+    }
+
+    /// Stand-in for an event ID, in the same 64-hex form
+    pub fn event_id(&self, event_id: &EventId) -> String {
+        hex::encode(self.digest("event", event_id))
+    }
+
+    fn digest(&self, domain: &str, data: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt);
+        hasher.update(domain.as_bytes());
+        hasher.update([0]);
+        hasher.update(data);
+        hasher.finalize().into()
+    }
+
+    /// Copy of `event` with its actor, user names and event IDs replaced
+    fn event_json(&self, event: &Event) -> EventJson {
+        let kind = match &event.kind {
+            EventKind::AssigneeAdded { user } => EventKind::AssigneeAdded {
+                user: self.user(user),
+            },
+            EventKind::AssigneeRemoved { user } => EventKind::AssigneeRemoved {
+                user: self.user(user),
+            },
+            EventKind::Subscribed { user } => EventKind::Subscribed {
+                user: self.user(user),
+            },
+            EventKind::Unsubscribed { user } => EventKind::Unsubscribed {
+                user: self.user(user),
+            },
+            kind => kind.clone(),
+        };
+        let mut kind = event_kind_to_json(&kind);
+        if let EventKind::CommentAdded {
+            parent_event: Some(parent),
+            ..
+        } = &event.kind
+        {
+            kind["CommentAdded"]["parent_event"] = self.event_id(parent).into();
+        }
+        EventJson {
+            event_id: self.event_id(&event.event_id),
+            actor: self.actor(&event.actor),
+            parent: event.parent.as_ref().map(|p| self.event_id(p)),
+            kind,
+            ..EventJson::from(event)
+        }
+    }
+}
+
+impl Default for Anonymizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse a comma-separated list of event kind names into kind tags.
+///
+/// Names are matched as in [`EventKind::tag_for_name`]. An empty list yields
+/// an empty set, which selects no events.
+pub fn parse_kinds(list: &str) -> Result<HashSet<u32>, GriteError> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            EventKind::tag_for_name(name).ok_or_else(|| {
+                GriteError::InvalidArgs(format!(
+                    "Unknown event kind '{}'. Valid kinds: {}",
+                    name,
+                    EventKind::names().join(", ")
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Keep only events whose kind tag is in `kinds`; `None` keeps every event
+pub fn retain_kinds(events: &mut Vec<Event>, kinds: Option<&HashSet<u32>>) {
+    if let Some(kinds) = kinds {
+        events.retain(|e| kinds.contains(&e.kind.kind_tag()));
+    }
+}
+
+/// Summaries of the issues selected by `options`
+///
+/// Errors with `NotFound` when a single issue was requested but does not exist.
+fn selected_issues(
+    store: &GriteStore,
+    options: &ExportOptions,
+) -> Result<Vec<IssueSummary>, GriteError> {
+    match options.issue {
+        Some(issue_id) => {
+            let proj = store
+                .get_issue(&issue_id)?
+                .ok_or_else(|| GriteError::issue_not_found(&id_to_hex(&issue_id)))?;
+            Ok(vec![store.issue_summary(&proj)?])
+        }
+        // Soft-deleted issues are exported too, so an export is complete
+        None => store.list_issues(&IssueFilter {
+            include_deleted: true,
+            ..IssueFilter::default()
+        }),
+    }
+}
+
 /// Export to JSON format
 pub fn export_json(
     store: &GriteStore,
     since: Option<ExportSince>,
+    options: &ExportOptions,
 ) -> Result<JsonExport, GriteError> {
+    let anonymizer = options.anonymize.then(Anonymizer::new);
+    let parts = json_export_parts(store, since, options, anonymizer.as_ref())?;
+    let events = parts
+        .event_ids
+        .iter()
+        .map(|event_id| {
+            Ok(event_json(
+                &load_event(store, event_id)?,
+                anonymizer.as_ref(),
+            ))
+        })
+        .collect::<Result<_, GriteError>>()?;
+    Ok(JsonExport {
+        meta: parts.meta,
+        issues: parts.issues,
+        events,
+        attachments: parts.attachments,
+    })
+}
+
+/// Stream a JSON export to `w`, returning the number of events written
+///
+/// Writes the same document as serializing [`export_json`]'s result, but
+/// holds only the selected event IDs and loads, converts and writes events
+/// one at a time. A failed write is returned as an `Io` error; whatever was
+/// written before it is left to the caller to discard.
+pub fn export_json_to<W: Write>(
+    store: &GriteStore,
+    since: Option<ExportSince>,
+    options: &ExportOptions,
+    mut w: W,
+) -> Result<usize, GriteError> {
+    let anonymizer = options.anonymize.then(Anonymizer::new);
+    let parts = json_export_parts(store, since, options, anonymizer.as_ref())?;
+    let doc = JsonExportStream {
+        meta: &parts.meta,
+        issues: &parts.issues,
+        events: EventsJson {
+            store,
+            event_ids: &parts.event_ids,
+            anonymizer: anonymizer.as_ref(),
+        },
+        attachments: &parts.attachments,
+    };
+    serde_json::to_writer_pretty(&mut w, &doc).map_err(|e| {
+        if e.is_io() {
+            GriteError::Io(e.into())
+        } else {
+            GriteError::Json(e)
+        }
+    })?;
+    w.flush()?;
+    Ok(parts.meta.event_count)
+}
+
+/// Stream an export into `path` through a `.partial` file next to it
+///
+/// `path` only changes once the export is complete; if writing fails, the
+/// partial file is removed and any earlier export is left in place.
+pub fn write_streamed(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<usize, GriteError>,
+) -> Result<usize, GriteError> {
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    let result = File::create(&partial)
+        .map_err(GriteError::from)
+        .and_then(|file| write(&mut BufWriter::new(file)))
+        .and_then(|count| {
+            std::fs::rename(&partial, path)?;
+            Ok(count)
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Everything in a JSON export except the events, which are only referenced
+/// by ID until they are written
+struct JsonExportParts {
+    meta: ExportMeta,
+    issues: Vec<IssueSummaryJson>,
+    event_ids: Vec<EventId>,
+    attachments: Vec<AttachmentJson>,
+}
+
+fn json_export_parts(
+    store: &GriteStore,
+    since: Option<ExportSince>,
+    options: &ExportOptions,
+    anonymizer: Option<&Anonymizer>,
+) -> Result<JsonExportParts, GriteError> {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64;
 
-    // Get all issues
-    let issues: Vec<IssueSummaryJson> = store
-        .list_issues(&IssueFilter::default())?
+    let issues: Vec<IssueSummaryJson> = selected_issues(store, options)?
         .iter()
-        .map(IssueSummaryJson::from)
+        .map(|summary| {
+            let mut json = IssueSummaryJson::from(summary);
+            if let Some(anonymizer) = anonymizer {
+                json.assignees = json.assignees.iter().map(|a| anonymizer.user(a)).collect();
+            }
+            json
+        })
         .collect();
+    let selected = selected_events(store, since, options)?;
+    let attachments = if options.include_attachments {
+        export_attachments(store, &selected.attachments)?
+    } else {
+        Vec::new()
+    };
 
-    // Get all events
-    let mut events = store.get_all_events()?;
+    Ok(JsonExportParts {
+        meta: ExportMeta {
+            schema_version: 1,
+            generated_ts: now,
+            wal_head: None, // M1 has no WAL
+            event_count: selected.event_ids.len(),
+        },
+        issues,
+        event_ids: selected.event_ids,
+        attachments,
+    })
+}
 
-    // Apply since filter
-    if let Some(since_filter) = since {
-        events.retain(|e| match &since_filter {
-            ExportSince::Timestamp(ts) => e.ts_unix_ms > *ts,
-            ExportSince::EventId(event_id) => {
+/// Load an event selected for export
+fn load_event(store: &GriteStore, event_id: &EventId) -> Result<Event, GriteError> {
+    store.get_event(event_id)?.ok_or_else(|| {
+        GriteError::Internal(format!(
+            "event {} disappeared during export",
+            id_to_hex(event_id)
+        ))
+    })
+}
+
+fn event_json(event: &Event, anonymizer: Option<&Anonymizer>) -> EventJson {
+    match anonymizer {
+        Some(anonymizer) => anonymizer.event_json(event),
+        None => EventJson::from(event),
+    }
+}
+
+/// Borrowed form of [`JsonExport`] that serializes to the same document
+#[derive(Serialize)]
+struct JsonExportStream<'a> {
+    meta: &'a ExportMeta,
+    issues: &'a [IssueSummaryJson],
+    events: EventsJson<'a>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    attachments: &'a [AttachmentJson],
+}
+
+/// Events loaded from the store and serialized as [`EventJson`] one at a time
+struct EventsJson<'a> {
+    store: &'a GriteStore,
+    event_ids: &'a [EventId],
+    anonymizer: Option<&'a Anonymizer>,
+}
+
+impl Serialize for EventsJson<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{Error, SerializeSeq};
+
+        let mut seq = serializer.serialize_seq(Some(self.event_ids.len()))?;
+        for event_id in self.event_ids {
+            let event = load_event(self.store, event_id).map_err(S::Error::custom)?;
+            seq.serialize_element(&event_json(&event, self.anonymizer))?;
+        }
+        seq.end()
+    }
+}
+
+/// Bytes of every attachment in `refs`, which hold each SHA-256 once
+///
+/// Errors with `NotFound` naming every attachment missing from the store, and
+/// with `Internal` if stored bytes no longer match their hash.
+fn export_attachments(
+    store: &GriteStore,
+    refs: &[AttachmentRef],
+) -> Result<Vec<AttachmentJson>, GriteError> {
+    let mut attachments = Vec::new();
+    let mut missing = Vec::new();
+    for AttachmentRef { name, sha256, mime } in refs {
+        let Some(data) = store.get_attachment(sha256)? else {
+            missing.push(format!("{} ({})", name, id_to_hex(sha256)));
+            continue;
+        };
+        if Sha256::digest(&data).as_slice() != sha256 {
+            return Err(GriteError::Internal(format!(
+                "stored attachment {} does not match its sha256 {}",
+                name,
+                id_to_hex(sha256)
+            )));
+        }
+        attachments.push(AttachmentJson {
+            sha256: id_to_hex(sha256),
+            name: name.clone(),
+            mime: mime.clone(),
+            data_base64: base64::engine::general_purpose::STANDARD.encode(&data),
+        });
+    }
+
+    if !missing.is_empty() {
+        return Err(GriteError::NotFound(format!(
+            "attachments missing from the store: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(attachments)
+}
+
+/// Store the attachments embedded in a JSON export, returning how many were stored
+///
+/// Every attachment is decoded and checked against its `sha256` before any is
+/// written, so a bad export leaves the store untouched.
+pub fn import_attachments(
+    store: &GriteStore,
+    attachments: &[AttachmentJson],
+) -> Result<usize, GriteError> {
+    let mut decoded = Vec::with_capacity(attachments.len());
+    for attachment in attachments {
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(&attachment.data_base64)
+            .map_err(|e| {
+                GriteError::InvalidArgs(format!(
+                    "attachment {} is not valid base64: {}",
+                    attachment.name, e
+                ))
+            })?;
+        if hex::encode(Sha256::digest(&data)) != attachment.sha256.to_lowercase() {
+            return Err(GriteError::InvalidArgs(format!(
+                "attachment {} does not match its sha256 {}",
+                attachment.name, attachment.sha256
+            )));
+        }
+        decoded.push(data);
+    }
+
+    for data in &decoded {
+        store.put_attachment(data)?;
+    }
+    Ok(decoded.len())
+}
+
+/// Number of events and issues a JSON export would contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ExportCounts {
+    pub events: usize,
+    pub issues: usize,
+}
+
+/// Count what [`export_json`] would emit without serializing anything
+pub fn export_counts(
+    store: &GriteStore,
+    since: Option<ExportSince>,
+    options: &ExportOptions,
+) -> Result<ExportCounts, GriteError> {
+    Ok(ExportCounts {
+        events: selected_events(store, since, options)?.event_ids.len(),
+        issues: selected_issues(store, options)?.len(),
+    })
+}
+
+/// An attachment referenced by an exported `AttachmentAdded` event
+struct AttachmentRef {
+    name: String,
+    sha256: [u8; 32],
+    mime: String,
+}
+
+/// The events an export contains, as IDs in export order, and the
+/// attachments they reference in the same order, once per SHA-256
+struct SelectedEvents {
+    event_ids: Vec<EventId>,
+    attachments: Vec<AttachmentRef>,
+}
+
+/// Where an event sorts in by-issue order
+type EventKey = (IssueId, u64, ActorId, EventId);
+
+/// Select the events a JSON export contains, filtered and in export order
+///
+/// Events are read from the store one at a time and only their sort keys are
+/// kept, so memory grows with the number of events, not their size.
+fn selected_events(
+    store: &GriteStore,
+    since: Option<ExportSince>,
+    options: &ExportOptions,
+) -> Result<SelectedEvents, GriteError> {
+    let events: Box<dyn Iterator<Item = Result<Event, GriteError>>> = match options.issue {
+        Some(issue_id) => Box::new(store.get_issue_events(&issue_id)?.into_iter().map(Ok)),
+        None => Box::new(store.iter_events()),
+    };
+
+    let mut keys: Vec<EventKey> = Vec::new();
+    let mut attachments: Vec<(EventKey, AttachmentRef)> = Vec::new();
+    for event in events {
+        let e = event?;
+        if options
+            .kinds
+            .as_ref()
+            .is_some_and(|kinds| !kinds.contains(&e.kind.kind_tag()))
+        {
+            continue;
+        }
+        let after_since = match &since {
+            None => true,
+            Some(ExportSince::Timestamp(ts)) => e.ts_unix_ms > *ts,
+            Some(ExportSince::EventId(event_id)) => {
                 // Include events after the given event_id in sort order
                 (&e.issue_id, e.ts_unix_ms, &e.actor, &e.event_id)
                     > (&e.issue_id, e.ts_unix_ms, &e.actor, event_id)
             }
-        });
+        };
+        if !after_since {
+            continue;
+        }
+        let key = (e.issue_id, e.ts_unix_ms, e.actor, e.event_id);
+        if let EventKind::AttachmentAdded { name, sha256, mime } = e.kind {
+            attachments.push((key, AttachmentRef { name, sha256, mime }));
+        }
+        keys.push(key);
     }
 
-    let event_jsons: Vec<EventJson> = events.iter().map(EventJson::from).collect();
+    let export_order = |a: &EventKey, b: &EventKey| match options.order {
+        ExportOrder::ByIssue => a.cmp(b),
+        ExportOrder::Chronological => (a.1, &a.2, &a.3).cmp(&(b.1, &b.2, &b.3)),
+    };
+    keys.sort_by(export_order);
+    attachments.sort_by(|(a, _), (b, _)| export_order(a, b));
 
-    Ok(JsonExport {
-        meta: ExportMeta {
-            schema_version: 1,
-            generated_ts: now,
-            wal_head: None, // M1 has no WAL
-            event_count: event_jsons.len(),
-        },
-        issues,
-        events: event_jsons,
+    let mut seen = HashSet::new();
+    Ok(SelectedEvents {
+        event_ids: keys
+            .into_iter()
+            .map(|(_, _, _, event_id)| event_id)
+            .collect(),
+        attachments: attachments
+            .into_iter()
+            .map(|(_, attachment)| attachment)
+            .filter(|attachment| seen.insert(attachment.sha256))
+            .collect(),
     })
 }
 
 /// Export to Markdown format
 pub fn export_markdown(
     store: &GriteStore,
-    _since: Option<ExportSince>,
+    since: Option<ExportSince>,
+    options: &ExportOptions,
 ) -> Result<String, GriteError> {
-    let mut md = String::new();
+    let mut md = Vec::new();
+    export_markdown_to(store, since, options, &mut md)?;
+    String::from_utf8(md).map_err(|e| GriteError::Internal(e.to_string()))
+}
 
-    md.push_str("# grite Export\n\n");
+/// Stream a Markdown export to `w`, returning the number of issues written
+///
+/// A failed write is returned as an `Io` error, as for [`export_json_to`].
+pub fn export_markdown_to<W: Write>(
+    store: &GriteStore,
+    _since: Option<ExportSince>,
+    options: &ExportOptions,
+    mut w: W,
+) -> Result<usize, GriteError> {
+    writeln!(w, "# grite Export\n")?;
 
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
         .as_millis() as u64;
-    md.push_str(&format!("Generated: {}\n\n", now));
+    writeln!(w, "Generated: {}\n", now)?;
 
-    // List issues
-    let issues = store.list_issues(&IssueFilter::default())?;
+    let issues = selected_issues(store, options)?;
+    let anonymizer = options.anonymize.then(Anonymizer::new);
 
     if issues.is_empty() {
-        md.push_str("No issues found.\n");
-        return Ok(md);
+        writeln!(w, "No issues found.")?;
+        w.flush()?;
+        return Ok(0);
     }
 
-    md.push_str("## Issues\n\n");
+    writeln!(w, "## Issues\n")?;
 
     for summary in &issues {
         let issue_id_hex = id_to_hex(&summary.issue_id);
         let state_str = format!("{:?}", summary.state).to_lowercase();
 
-        md.push_str(&format!("### {} [{}]\n\n", summary.title, state_str));
-        md.push_str(&format!("**ID:** `{}`\n\n", issue_id_hex));
+        writeln!(w, "### {} [{}]\n", summary.title, state_str)?;
+        writeln!(w, "**ID:** `{}`\n", issue_id_hex)?;
 
         if !summary.labels.is_empty() {
-            md.push_str(&format!("**Labels:** {}\n\n", summary.labels.join(", ")));
+            writeln!(w, "**Labels:** {}\n", summary.labels.join(", "))?;
         }
 
         if !summary.assignees.is_empty() {
-            md.push_str(&format!(
-                "**Assignees:** {}\n\n",
-                summary.assignees.join(", ")
-            ));
+            let assignees: Vec<String> = match &anonymizer {
+                Some(anonymizer) => summary
+                    .assignees
+                    .iter()
+                    .map(|a| anonymizer.user(a))
+                    .collect(),
+                None => summary.assignees.clone(),
+            };
+            writeln!(w, "**Assignees:** {}\n", assignees.join(", "))?;
         }
 
         if summary.comment_count > 0 {
-            md.push_str(&format!("**Comments:** {}\n\n", summary.comment_count));
+            writeln!(w, "**Comments:** {}\n", summary.comment_count)?;
         }
 
         // Get full issue for body and comments
         if let Some(proj) = store.get_issue(&summary.issue_id)? {
             if !proj.body.is_empty() {
-                md.push_str(&format!("{}\n\n", proj.body));
+                writeln!(w, "{}\n", proj.body)?;
             }
 
             if !proj.comments.is_empty() {
-                md.push_str("#### Comments\n\n");
+                writeln!(w, "#### Comments\n")?;
                 for comment in &proj.comments {
-                    let actor_hex = id_to_hex(&comment.actor);
-                    md.push_str(&format!(
-                        "> **{}** at {}:\n> {}\n\n",
-                        &actor_hex[..8],
-                        comment.ts_unix_ms,
-                        comment.body
-                    ));
+                    let author = match &anonymizer {
+                        Some(anonymizer) => anonymizer.actor(&comment.actor),
+                        None => id_to_hex(&comment.actor)[..8].to_string(),
+                    };
+                    writeln!(
+                        w,
+                        "> **{}** at {}:\n> {}\n",
+                        author, comment.ts_unix_ms, comment.body
+                    )?;
                 }
             }
         }
 
-        md.push_str("---\n\n");
+        writeln!(w, "---\n")?;
     }
 
-    Ok(md)
+    w.flush()?;
+    Ok(issues.len())
 }
 
 #[cfg(test)]
@@ -350,13 +951,61 @@ mod tests {
         let event = Event::new(event_id, issue_id, actor, 1000, None, kind);
         store.insert_event(&event).unwrap();
 
-        let export = export_json(&store, None).unwrap();
+        let export = export_json(&store, None, &ExportOptions::default()).unwrap();
         assert_eq!(export.meta.schema_version, 1);
         assert_eq!(export.issues.len(), 1);
         assert_eq!(export.events.len(), 1);
         assert_eq!(export.issues[0].title, "Test");
     }
 
+    #[test]
+    fn test_export_json_chronological_order() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_a = generate_issue_id();
+        let issue_b = generate_issue_id();
+        // Interleave the two issues' histories
+        for (issue_id, ts) in [
+            (issue_a, 1000),
+            (issue_b, 2000),
+            (issue_a, 3000),
+            (issue_b, 4000),
+        ] {
+            let kind = if ts < 2500 {
+                EventKind::IssueCreated {
+                    title: "Test".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                }
+            } else {
+                EventKind::CommentAdded {
+                    body: format!("at {}", ts),
+                    parent_event: None,
+                }
+            };
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            store
+                .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                .unwrap();
+        }
+
+        let options = ExportOptions {
+            order: ExportOrder::Chronological,
+            ..Default::default()
+        };
+        let export = export_json(&store, None, &options).unwrap();
+        let timestamps: Vec<u64> = export.events.iter().map(|e| e.ts_unix_ms).collect();
+        assert_eq!(timestamps, vec![1000, 2000, 3000, 4000]);
+
+        // The default groups events by issue instead
+        let export = export_json(&store, None, &ExportOptions::default()).unwrap();
+        let issue_ids: Vec<&str> = export.events.iter().map(|e| e.issue_id.as_str()).collect();
+        assert_eq!(issue_ids[0], issue_ids[1]);
+        assert_eq!(issue_ids[2], issue_ids[3]);
+    }
+
     #[test]
     fn test_export_markdown() {
         let dir = tempdir().unwrap();
@@ -373,9 +1022,434 @@ mod tests {
         let event = Event::new(event_id, issue_id, actor, 1000, None, kind);
         store.insert_event(&event).unwrap();
 
-        let md = export_markdown(&store, None).unwrap();
+        let md = export_markdown(&store, None, &ExportOptions::default()).unwrap();
         assert!(md.contains("# grite Export"));
         assert!(md.contains("Test Issue"));
         assert!(md.contains("bug"));
     }
+
+    #[test]
+    fn test_streaming_export_matches_in_memory() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        for (ts, kind) in [
+            (
+                1000,
+                EventKind::IssueCreated {
+                    title: "Streamed".to_string(),
+                    body: "Body".to_string(),
+                    labels: vec!["bug".to_string()],
+                },
+            ),
+            (
+                2000,
+                EventKind::CommentAdded {
+                    body: "A comment".to_string(),
+                    parent_event: None,
+                },
+            ),
+        ] {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            store
+                .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                .unwrap();
+        }
+        let options = ExportOptions::default();
+
+        // Only the generation time may differ between the two
+        let without_ts = |mut json: serde_json::Value| {
+            json["meta"]["generated_ts"] = 0.into();
+            json
+        };
+        let path = dir.path().join("export.json");
+        let file = std::fs::File::create(&path).unwrap();
+        assert_eq!(export_json_to(&store, None, &options, file).unwrap(), 2);
+        let streamed: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let in_memory = serde_json::to_value(export_json(&store, None, &options).unwrap()).unwrap();
+        assert_eq!(without_ts(streamed), without_ts(in_memory));
+
+        let without_generated = |md: &str| {
+            md.lines()
+                .filter(|line| !line.starts_with("Generated: "))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let path = dir.path().join("export.md");
+        let file = std::fs::File::create(&path).unwrap();
+        assert_eq!(export_markdown_to(&store, None, &options, file).unwrap(), 1);
+        let streamed = std::fs::read_to_string(&path).unwrap();
+        let in_memory = export_markdown(&store, None, &options).unwrap();
+        assert_eq!(without_generated(&streamed), without_generated(&in_memory));
+    }
+
+    #[test]
+    fn test_streaming_export_surfaces_write_errors() {
+        struct FailingWriter;
+        impl Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk full"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let options = ExportOptions::default();
+        assert!(matches!(
+            export_json_to(&store, None, &options, FailingWriter),
+            Err(GriteError::Io(_))
+        ));
+        assert!(matches!(
+            export_markdown_to(&store, None, &options, FailingWriter),
+            Err(GriteError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_single_issue() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let wanted = generate_issue_id();
+        let other = generate_issue_id();
+        for (issue_id, ts, title) in [(wanted, 1000, "Wanted"), (other, 2000, "Other")] {
+            for (offset, kind) in [
+                EventKind::IssueCreated {
+                    title: title.to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+                EventKind::CommentAdded {
+                    body: format!("Comment on {}", title),
+                    parent_event: None,
+                },
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let ts = ts + offset as u64;
+                let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+                store
+                    .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                    .unwrap();
+            }
+        }
+
+        let options = ExportOptions {
+            issue: Some(wanted),
+            ..Default::default()
+        };
+        let export = export_json(&store, None, &options).unwrap();
+        let wanted_hex = id_to_hex(&wanted);
+        assert_eq!(export.meta.event_count, 2);
+        assert_eq!(export.issues.len(), 1);
+        assert_eq!(export.issues[0].title, "Wanted");
+        assert!(export.events.iter().all(|e| e.issue_id == wanted_hex));
+
+        let md = export_markdown(&store, None, &options).unwrap();
+        assert!(md.contains("Comment on Wanted"));
+        assert!(!md.contains("Other"));
+
+        let missing = ExportOptions {
+            issue: Some(generate_issue_id()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            export_json(&store, None, &missing),
+            Err(GriteError::NotFound(_))
+        ));
+        assert!(matches!(
+            export_markdown(&store, None, &missing),
+            Err(GriteError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_kinds_filter() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        for (ts, kind) in [
+            (
+                1000,
+                EventKind::IssueCreated {
+                    title: "Filtered".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ),
+            (
+                2000,
+                EventKind::CommentAdded {
+                    body: "Kept".to_string(),
+                    parent_event: None,
+                },
+            ),
+            (
+                3000,
+                EventKind::StateChanged {
+                    state: crate::types::event::IssueState::Closed,
+                },
+            ),
+        ] {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            store
+                .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                .unwrap();
+        }
+
+        let options = ExportOptions {
+            kinds: Some(parse_kinds("comment_added").unwrap()),
+            ..Default::default()
+        };
+        let export = export_json(&store, None, &options).unwrap();
+        assert_eq!(export.meta.event_count, 1);
+        assert!(export.events[0].kind.get("CommentAdded").is_some());
+
+        assert_eq!(
+            export_counts(&store, None, &options).unwrap(),
+            ExportCounts {
+                events: 1,
+                issues: 1
+            }
+        );
+        assert_eq!(
+            export_counts(
+                &store,
+                Some(ExportSince::Timestamp(1000)),
+                &ExportOptions::default()
+            )
+            .unwrap()
+            .events,
+            2
+        );
+
+        // An explicit empty list selects nothing
+        let options = ExportOptions {
+            kinds: Some(parse_kinds("").unwrap()),
+            ..Default::default()
+        };
+        assert!(export_json(&store, None, &options)
+            .unwrap()
+            .events
+            .is_empty());
+
+        assert_eq!(
+            parse_kinds("IssueCreated, state_changed").unwrap(),
+            HashSet::from([1, 6])
+        );
+        assert!(matches!(
+            parse_kinds("comment_added,bogus"),
+            Err(GriteError::InvalidArgs(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_includes_attachment_bytes() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        let bytes = b"\x89PNG not really an image".to_vec();
+        let sha256 = store.put_attachment(&bytes).unwrap();
+        for (ts, kind) in [
+            (
+                1000,
+                EventKind::IssueCreated {
+                    title: "With attachment".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ),
+            (
+                2000,
+                EventKind::AttachmentAdded {
+                    name: "screenshot.png".to_string(),
+                    sha256,
+                    mime: "image/png".to_string(),
+                },
+            ),
+        ] {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            store
+                .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                .unwrap();
+        }
+
+        // Off by default
+        let export = export_json(&store, None, &ExportOptions::default()).unwrap();
+        assert!(export.attachments.is_empty());
+
+        let options = ExportOptions {
+            issue: Some(issue_id),
+            include_attachments: true,
+            ..Default::default()
+        };
+        let export = export_json(&store, None, &options).unwrap();
+        assert_eq!(export.attachments.len(), 1);
+        assert_eq!(export.attachments[0].name, "screenshot.png");
+        assert_eq!(export.attachments[0].sha256, id_to_hex(&sha256));
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(&export.attachments[0].data_base64)
+            .unwrap();
+        assert_eq!(decoded, bytes);
+
+        // Importing restores the bytes into another store
+        let other_dir = tempdir().unwrap();
+        let other = GriteStore::open(other_dir.path()).unwrap();
+        assert_eq!(import_attachments(&other, &export.attachments).unwrap(), 1);
+        assert_eq!(other.get_attachment(&sha256).unwrap(), Some(bytes));
+
+        // Tampered data is rejected without storing anything
+        let mut tampered = export.attachments.clone();
+        tampered[0].data_base64 = base64::engine::general_purpose::STANDARD.encode(b"other");
+        let fresh_dir = tempdir().unwrap();
+        let fresh = GriteStore::open(fresh_dir.path()).unwrap();
+        assert!(matches!(
+            import_attachments(&fresh, &tampered),
+            Err(GriteError::InvalidArgs(_))
+        ));
+        assert_eq!(fresh.get_attachment(&sha256).unwrap(), None);
+    }
+
+    #[test]
+    fn test_export_reports_missing_attachment() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        for (ts, kind) in [
+            (
+                1000,
+                EventKind::IssueCreated {
+                    title: "Missing attachment".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ),
+            (
+                2000,
+                EventKind::AttachmentAdded {
+                    name: "lost.log".to_string(),
+                    sha256: [7u8; 32],
+                    mime: "text/plain".to_string(),
+                },
+            ),
+        ] {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            store
+                .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                .unwrap();
+        }
+
+        let options = ExportOptions {
+            include_attachments: true,
+            ..Default::default()
+        };
+        match export_json(&store, None, &options) {
+            Err(GriteError::NotFound(msg)) => assert!(msg.contains("lost.log")),
+            other => panic!("expected NotFound, got {:?}", other.map(|e| e.events.len())),
+        }
+    }
+
+    #[test]
+    fn test_export_anonymize() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let alice = [1u8; 16];
+        let bob = [2u8; 16];
+        let issue_id = generate_issue_id();
+        let events = [
+            (
+                alice,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Private".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ),
+            (
+                bob,
+                2000,
+                EventKind::AssigneeAdded {
+                    user: "carol".to_string(),
+                },
+            ),
+            (
+                alice,
+                3000,
+                EventKind::CommentAdded {
+                    body: "Thanks".to_string(),
+                    parent_event: None,
+                },
+            ),
+        ];
+        let mut event_ids = Vec::new();
+        for (actor, ts, kind) in events {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            store
+                .insert_event(&Event::new(event_id, issue_id, actor, ts, None, kind))
+                .unwrap();
+            event_ids.push(event_id);
+        }
+        let reply = EventKind::CommentAdded {
+            body: "Reply".to_string(),
+            parent_event: Some(event_ids[2]),
+        };
+        let reply_id = compute_event_id(&issue_id, &bob, 4000, None, &reply);
+        store
+            .insert_event(&Event::new(reply_id, issue_id, bob, 4000, None, reply))
+            .unwrap();
+
+        let options = ExportOptions {
+            anonymize: true,
+            ..Default::default()
+        };
+        let export = export_json(&store, None, &options).unwrap();
+        let actors: Vec<&str> = export.events.iter().map(|e| e.actor.as_str()).collect();
+        assert_eq!(actors[0], actors[2]);
+        assert_ne!(actors[0], actors[1]);
+        assert!(actors[0].starts_with("actor-"), "{}", actors[0]);
+
+        let carol = export.issues[0].assignees[0].clone();
+        assert!(carol.starts_with("user-"), "{}", carol);
+        assert_eq!(export.events[1].kind["AssigneeAdded"]["user"], carol);
+
+        // Event IDs are replaced consistently, including references
+        assert_eq!(
+            export.events[3].kind["CommentAdded"]["parent_event"],
+            export.events[2].event_id
+        );
+
+        let json = serde_json::to_string(&export).unwrap();
+        assert!(!json.contains(&id_to_hex(&alice)));
+        assert!(!json.contains("carol"));
+        for event_id in event_ids.iter().chain([&reply_id]) {
+            assert!(!json.contains(&id_to_hex(event_id)));
+        }
+
+        // Each export draws its own salt
+        let again = export_json(&store, None, &options).unwrap();
+        assert_ne!(again.events[0].actor, actors[0]);
+        assert_ne!(again.issues[0].assignees[0], carol);
+
+        let md = export_markdown(&store, None, &options).unwrap();
+        assert!(md.contains("user-"));
+        assert!(md.contains("actor-"));
+        assert!(!md.contains("carol"));
+        assert!(!md.contains(&id_to_hex(&alice)[..8]));
+    }
 }
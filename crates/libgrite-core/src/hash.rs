@@ -5,9 +5,62 @@ use ciborium::Value;
 use crate::types::event::EventKind;
 use crate::types::ids::{ActorId, EventId, IssueId};
 
-/// Schema version for event hashing
+/// Schema version for event hashing with the default [`HashAlgo`]
 pub const SCHEMA_VERSION: u8 = 1;
 
+/// Hash algorithm used to derive event IDs
+///
+/// Each algorithm has its own preimage schema version, so IDs from different
+/// algorithms can never collide. Only BLAKE2b-256 exists today; the id is
+/// recorded in chunk headers so another algorithm can be added later without
+/// reinterpreting old data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum HashAlgo {
+    #[default]
+    Blake2b256,
+}
+
+impl HashAlgo {
+    /// Stable numeric id stored in chunk headers
+    pub fn id(&self) -> u8 {
+        match self {
+            HashAlgo::Blake2b256 => 1,
+        }
+    }
+
+    /// Look up an algorithm by its chunk header id
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(HashAlgo::Blake2b256),
+            _ => None,
+        }
+    }
+
+    /// Schema version written into the canonical preimage
+    pub fn schema_version(&self) -> u8 {
+        match self {
+            HashAlgo::Blake2b256 => SCHEMA_VERSION,
+        }
+    }
+
+    /// Convert to string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashAlgo::Blake2b256 => "blake2b-256",
+        }
+    }
+
+    fn digest(&self, data: &[u8]) -> [u8; 32] {
+        match self {
+            HashAlgo::Blake2b256 => {
+                let mut hasher = Blake2b::<U32>::new();
+                hasher.update(data);
+                hasher.finalize().into()
+            }
+        }
+    }
+}
+
 /// Compute the event_id from event fields using canonical CBOR + BLAKE2b-256
 pub fn compute_event_id(
     issue_id: &IssueId,
@@ -16,13 +69,30 @@ pub fn compute_event_id(
     parent: Option<&EventId>,
     kind: &EventKind,
 ) -> EventId {
-    let preimage = build_canonical_cbor(issue_id, actor, ts_unix_ms, parent, kind);
-    let mut hasher = Blake2b::<U32>::new();
-    hasher.update(&preimage);
-    hasher.finalize().into()
+    compute_event_id_with(
+        HashAlgo::default(),
+        issue_id,
+        actor,
+        ts_unix_ms,
+        parent,
+        kind,
+    )
+}
+
+/// Compute the event_id with a specific hash algorithm
+pub fn compute_event_id_with(
+    algo: HashAlgo,
+    issue_id: &IssueId,
+    actor: &ActorId,
+    ts_unix_ms: u64,
+    parent: Option<&EventId>,
+    kind: &EventKind,
+) -> EventId {
+    let preimage = build_canonical_cbor_with(algo, issue_id, actor, ts_unix_ms, parent, kind);
+    algo.digest(&preimage)
 }
 
-/// Build the canonical CBOR preimage for hashing
+/// Build the canonical CBOR preimage for hashing with the default algorithm
 /// Format: [schema_version, issue_id, actor, ts_unix_ms, parent, kind_tag, kind_payload]
 pub fn build_canonical_cbor(
     issue_id: &IssueId,
@@ -30,6 +100,25 @@ pub fn build_canonical_cbor(
     ts_unix_ms: u64,
     parent: Option<&EventId>,
     kind: &EventKind,
+) -> Vec<u8> {
+    build_canonical_cbor_with(
+        HashAlgo::default(),
+        issue_id,
+        actor,
+        ts_unix_ms,
+        parent,
+        kind,
+    )
+}
+
+/// Build the canonical CBOR preimage using `algo`'s schema version
+pub fn build_canonical_cbor_with(
+    algo: HashAlgo,
+    issue_id: &IssueId,
+    actor: &ActorId,
+    ts_unix_ms: u64,
+    parent: Option<&EventId>,
+    kind: &EventKind,
 ) -> Vec<u8> {
     let (kind_tag, kind_payload) = kind_to_tag_and_payload(kind);
 
@@ -39,7 +128,7 @@ pub fn build_canonical_cbor(
     };
 
     let array = Value::Array(vec![
-        Value::Integer(SCHEMA_VERSION.into()),
+        Value::Integer(algo.schema_version().into()),
         Value::Bytes(issue_id.to_vec()),
         Value::Bytes(actor.to_vec()),
         Value::Integer(ts_unix_ms.into()),
@@ -89,7 +178,15 @@ pub fn kind_to_tag_and_payload(kind: &EventKind) -> (u32, ciborium::Value) {
             };
             (2, Value::Array(vec![title_value, body_value]))
         }
-        EventKind::CommentAdded { body } => (3, Value::Array(vec![Value::Text(body.clone())])),
+        EventKind::CommentAdded { body, parent_event } => {
+            // Top-level comments keep the original one-field payload so their
+            // event IDs are unchanged
+            let mut payload = vec![Value::Text(body.clone())];
+            if let Some(parent) = parent_event {
+                payload.push(Value::Bytes(parent.to_vec()));
+            }
+            (3, Value::Array(payload))
+        }
         EventKind::LabelAdded { label } => (4, Value::Array(vec![Value::Text(label.clone())])),
         EventKind::LabelRemoved { label } => (5, Value::Array(vec![Value::Text(label.clone())])),
         EventKind::StateChanged { state } => (
@@ -165,13 +262,77 @@ pub fn kind_to_tag_and_payload(kind: &EventKind) -> (u32, ciborium::Value) {
             14,
             Value::Array(vec![Value::Text(key.clone()), Value::Text(value.clone())]),
         ),
+        EventKind::FieldSet { key, value } => {
+            let value_value = match value {
+                Some(v) => Value::Text(v.clone()),
+                None => Value::Null,
+            };
+            (
+                15,
+                Value::Array(vec![Value::Text(key.clone()), value_value]),
+            )
+        }
+        EventKind::IssueLinked { target, relation } => (
+            16,
+            Value::Array(vec![
+                Value::Bytes(target.to_vec()),
+                Value::Text(relation.clone()),
+            ]),
+        ),
+        EventKind::IssueUnlinked { target, relation } => (
+            17,
+            Value::Array(vec![
+                Value::Bytes(target.to_vec()),
+                Value::Text(relation.clone()),
+            ]),
+        ),
+        EventKind::Subscribed { user } => (18, Value::Array(vec![Value::Text(user.clone())])),
+        EventKind::Unsubscribed { user } => (19, Value::Array(vec![Value::Text(user.clone())])),
+        EventKind::TimeEstimated { minutes } => {
+            (20, Value::Array(vec![Value::Integer((*minutes).into())]))
+        }
+        EventKind::TimeLogged { minutes, note } => {
+            let note_value = match note {
+                Some(n) => Value::Text(n.clone()),
+                None => Value::Null,
+            };
+            (
+                21,
+                Value::Array(vec![Value::Integer((*minutes).into()), note_value]),
+            )
+        }
+        EventKind::MilestoneSet { milestone } => {
+            let milestone_value = match milestone {
+                Some(m) => Value::Text(m.clone()),
+                None => Value::Null,
+            };
+            (22, Value::Array(vec![milestone_value]))
+        }
+        EventKind::IssueDeleted {} => (23, Value::Array(vec![])),
+        EventKind::IssueUndeleted {} => (24, Value::Array(vec![])),
+        EventKind::SeveritySet { severity } => (
+            25,
+            Value::Array(vec![Value::Text(severity.as_str().to_string())]),
+        ),
+    }
+}
+
+/// Compute a digest of an issue's history: BLAKE2b-256 over its event IDs
+/// in sorted order, so the result does not depend on arrival order
+pub fn compute_issue_digest(event_ids: impl IntoIterator<Item = EventId>) -> [u8; 32] {
+    let mut ids: Vec<EventId> = event_ids.into_iter().collect();
+    ids.sort_unstable();
+    let mut hasher = Blake2b::<U32>::new();
+    for id in &ids {
+        hasher.update(id);
     }
+    hasher.finalize().into()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::event::IssueState;
+    use crate::types::event::{IssueState, Severity};
     use crate::types::ids::hex_to_id;
 
     // Test vectors from docs/hash-vectors.md
@@ -241,6 +402,7 @@ mod tests {
         let parent = Some(&parent_bytes);
         let kind = EventKind::CommentAdded {
             body: "Looks good".to_string(),
+            parent_event: None,
         };
 
         let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, parent, &kind);
@@ -575,4 +737,191 @@ mod tests {
         let id3 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &kind2);
         assert_ne!(id1, id3);
     }
+
+    #[test]
+    fn test_vector_15_field_set() {
+        let issue_id: IssueId = hex_to_id("000102030405060708090a0b0c0d0e0f").unwrap();
+        let actor: ActorId = hex_to_id("101112131415161718191a1b1c1d1e1f").unwrap();
+        let ts_unix_ms: u64 = 1700000013000;
+        let set = EventKind::FieldSet {
+            key: "severity".to_string(),
+            value: Some("high".to_string()),
+        };
+        let clear = EventKind::FieldSet {
+            key: "severity".to_string(),
+            value: None,
+        };
+
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &clear);
+        assert!(
+            hex::encode(&cbor).ends_with("0f82687365766572697479f6"),
+            "clearing a field encodes value as null"
+        );
+
+        let id1 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &set);
+        let id2 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &clear);
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn test_vector_16_issue_linked() {
+        let issue_id: IssueId = hex_to_id("000102030405060708090a0b0c0d0e0f").unwrap();
+        let actor: ActorId = hex_to_id("101112131415161718191a1b1c1d1e1f").unwrap();
+        let ts_unix_ms: u64 = 1700000014000;
+        let target: IssueId = hex_to_id("aabbccddeeff00112233445566778899").unwrap();
+        let linked = EventKind::IssueLinked {
+            target,
+            relation: "duplicate-of".to_string(),
+        };
+        let unlinked = EventKind::IssueUnlinked {
+            target,
+            relation: "duplicate-of".to_string(),
+        };
+
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &linked);
+        assert!(
+            hex::encode(&cbor)
+                .ends_with("108250aabbccddeeff001122334455667788996c6475706c69636174652d6f66"),
+            "payload is [target_bytes, relation]"
+        );
+
+        // Different relation, and link vs unlink, produce different hashes
+        let relates = EventKind::IssueLinked {
+            target,
+            relation: "relates".to_string(),
+        };
+        let id1 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &linked);
+        let id2 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &relates);
+        let id3 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &unlinked);
+        assert_ne!(id1, id2);
+        assert_ne!(id1, id3);
+    }
+
+    #[test]
+    fn test_vector_18_subscribed() {
+        let issue_id: IssueId = hex_to_id("000102030405060708090a0b0c0d0e0f").unwrap();
+        let actor: ActorId = hex_to_id("101112131415161718191a1b1c1d1e1f").unwrap();
+        let ts_unix_ms: u64 = 1700000015000;
+        let subscribed = EventKind::Subscribed {
+            user: "alice".to_string(),
+        };
+        let unsubscribed = EventKind::Unsubscribed {
+            user: "alice".to_string(),
+        };
+
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &subscribed);
+        assert!(
+            hex::encode(&cbor).ends_with("128165616c696365"),
+            "payload is [user]"
+        );
+
+        let id1 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &subscribed);
+        let id2 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &unsubscribed);
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn test_vector_20_time_tracking() {
+        let issue_id: IssueId = hex_to_id("000102030405060708090a0b0c0d0e0f").unwrap();
+        let actor: ActorId = hex_to_id("101112131415161718191a1b1c1d1e1f").unwrap();
+        let ts_unix_ms: u64 = 1700000016000;
+        let estimated = EventKind::TimeEstimated { minutes: 90 };
+        let logged = EventKind::TimeLogged {
+            minutes: 90,
+            note: None,
+        };
+
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &estimated);
+        assert!(
+            hex::encode(&cbor).ends_with("1481185a"),
+            "payload is [minutes]"
+        );
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &logged);
+        assert!(
+            hex::encode(&cbor).ends_with("1582185af6"),
+            "payload is [minutes, note_opt]"
+        );
+
+        let noted = EventKind::TimeLogged {
+            minutes: 90,
+            note: Some("review".to_string()),
+        };
+        let id1 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &estimated);
+        let id2 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &logged);
+        let id3 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &noted);
+        assert_ne!(id1, id2);
+        assert_ne!(id2, id3);
+    }
+
+    #[test]
+    fn test_vector_22_milestone() {
+        let issue_id: IssueId = hex_to_id("000102030405060708090a0b0c0d0e0f").unwrap();
+        let actor: ActorId = hex_to_id("101112131415161718191a1b1c1d1e1f").unwrap();
+        let ts_unix_ms: u64 = 1700000017000;
+        let set = EventKind::MilestoneSet {
+            milestone: Some("v1".to_string()),
+        };
+        let clear = EventKind::MilestoneSet { milestone: None };
+
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &set);
+        assert!(
+            hex::encode(&cbor).ends_with("1681627631"),
+            "payload is [milestone_opt]"
+        );
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &clear);
+        assert!(hex::encode(&cbor).ends_with("1681f6"), "clear is [null]");
+
+        let id1 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &set);
+        let id2 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &clear);
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn test_vector_23_soft_delete() {
+        let issue_id: IssueId = hex_to_id("000102030405060708090a0b0c0d0e0f").unwrap();
+        let actor: ActorId = hex_to_id("101112131415161718191a1b1c1d1e1f").unwrap();
+        let ts_unix_ms: u64 = 1700000018000;
+        let deleted = EventKind::IssueDeleted {};
+        let undeleted = EventKind::IssueUndeleted {};
+
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &deleted);
+        assert!(hex::encode(&cbor).ends_with("1780"), "payload is []");
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &undeleted);
+        assert!(
+            hex::encode(&cbor).ends_with("181880"),
+            "tag 24 takes a one-byte argument"
+        );
+
+        let id1 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &deleted);
+        let id2 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &undeleted);
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn test_vector_25_severity() {
+        let issue_id: IssueId = hex_to_id("000102030405060708090a0b0c0d0e0f").unwrap();
+        let actor: ActorId = hex_to_id("101112131415161718191a1b1c1d1e1f").unwrap();
+        let ts_unix_ms: u64 = 1700000019000;
+        let set = EventKind::SeveritySet {
+            severity: Severity::Sev2,
+        };
+        let clear = EventKind::SeveritySet {
+            severity: Severity::None,
+        };
+
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &set);
+        assert!(
+            hex::encode(&cbor).ends_with("1819816473657632"),
+            "payload is [severity]"
+        );
+        let cbor = build_canonical_cbor(&issue_id, &actor, ts_unix_ms, None, &clear);
+        assert!(
+            hex::encode(&cbor).ends_with("181981646e6f6e65"),
+            "clear is [\"none\"]"
+        );
+
+        let id1 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &set);
+        let id2 = compute_event_id(&issue_id, &actor, ts_unix_ms, None, &clear);
+        assert_ne!(id1, id2);
+    }
 }
@@ -2,11 +2,14 @@
 //!
 //! Provides verification of event hashes, signatures, and projection consistency.
 
+use std::collections::HashMap;
+
 use crate::hash::compute_event_id;
-use crate::signing::verify_signature;
-use crate::store::GriteStore;
+use crate::signing::{verify_signature, KeyHistory, SigningKeyPair, ACTOR_KEY_CONTEXT_PREFIX};
+use crate::store::{GriteStore, IssueFilter};
+use crate::types::actor::ActorConfig;
 use crate::types::event::Event;
-use crate::types::ids::{id_to_hex, EventId};
+use crate::types::ids::{id_to_hex, ActorId, EventId};
 use crate::GriteError;
 
 /// Result of an integrity check
@@ -103,7 +106,10 @@ pub fn check_store_integrity(
     let mut report = IntegrityReport::default();
 
     // Get all events from all issues
-    let issues = store.list_issues(&Default::default())?;
+    let issues = store.list_issues(&IssueFilter {
+        include_deleted: true,
+        ..IssueFilter::default()
+    })?;
 
     for issue_summary in &issues {
         let events = store.get_issue_events(&issue_summary.issue_id)?;
@@ -148,19 +154,58 @@ pub fn check_store_integrity(
     Ok(report)
 }
 
+/// Load each actor's signing key history.
+///
+/// Rotations recorded in project context take precedence; an actor with no
+/// recorded rotation falls back to the public key in its config. Only
+/// rotations the actor itself wrote and signed with its outgoing key count
+/// (see [`KeyHistory::from_project_context`]).
+pub fn load_key_histories(
+    store: &GriteStore,
+    actors: &[ActorConfig],
+) -> Result<HashMap<String, KeyHistory>, GriteError> {
+    let mut records = Vec::new();
+    for (key, entry) in store.list_project_context()? {
+        if !key.starts_with(ACTOR_KEY_CONTEXT_PREFIX) {
+            continue;
+        }
+        if let Some(event) = store.get_event(&entry.version.event_id)? {
+            records.push((key, entry.value, event));
+        }
+    }
+    let mut histories = KeyHistory::from_project_context(
+        records
+            .iter()
+            .map(|(key, value, event)| (key.as_str(), value.as_str(), event)),
+    );
+    for actor in actors {
+        if let Some(pk) = &actor.public_key {
+            let history = histories.entry(actor.actor_id.clone()).or_default();
+            if history.is_empty() {
+                history.add(0, pk.clone());
+            }
+        }
+    }
+    Ok(histories)
+}
+
 /// Verify signatures on all events in the store
 ///
-/// Requires a function to look up public keys by actor ID.
+/// Requires a function to look up the public key an actor (by ID) used at
+/// a given event timestamp.
 pub fn verify_store_signatures<F>(
     store: &GriteStore,
     get_public_key: F,
 ) -> Result<IntegrityReport, GriteError>
 where
-    F: Fn(&str) -> Option<String>,
+    F: Fn(&str, u64) -> Option<String>,
 {
     let mut report = IntegrityReport::default();
 
-    let issues = store.list_issues(&Default::default())?;
+    let issues = store.list_issues(&IssueFilter {
+        include_deleted: true,
+        ..IssueFilter::default()
+    })?;
 
     for issue_summary in &issues {
         let events = store.get_issue_events(&issue_summary.issue_id)?;
@@ -182,7 +227,7 @@ where
 
             // Look up public key
             let actor_hex = id_to_hex(&event.actor);
-            let public_key = match get_public_key(&actor_hex) {
+            let public_key = match get_public_key(&actor_hex, event.ts_unix_ms) {
                 Some(pk) => pk,
                 None => {
                     report.signature_errors.push(SignatureError {
@@ -214,6 +259,76 @@ where
     Ok(report)
 }
 
+/// Result of backfilling signatures with [`sign_unsigned_events`]
+#[derive(Debug, Default)]
+pub struct SignHistoryReport {
+    /// Events that were unsigned and are now signed
+    pub events_signed: usize,
+    /// The actor's events that already had a signature
+    pub already_signed: usize,
+    /// Events by other actors, which were skipped
+    pub other_actors: usize,
+    /// Unsigned events skipped because a different key was in effect at
+    /// their timestamp, so a new signature would not verify
+    pub key_mismatch: usize,
+}
+
+/// Sign an actor's stored events that have no signature.
+///
+/// `key_at` returns the public key recorded for the actor at a timestamp;
+/// events are signed when that key is `keypair`'s or no key is recorded.
+/// Event IDs are unchanged since signatures are not part of the hash.
+///
+/// The signed events are handed to `publish` (e.g. to append them to the
+/// WAL so other clones receive the signatures) before the store is updated;
+/// if it fails, nothing is changed.
+pub fn sign_unsigned_events<F, P>(
+    store: &GriteStore,
+    actor: &ActorId,
+    keypair: &SigningKeyPair,
+    key_at: F,
+    publish: P,
+) -> Result<SignHistoryReport, GriteError>
+where
+    F: Fn(u64) -> Option<String>,
+    P: FnOnce(&[Event]) -> Result<(), GriteError>,
+{
+    let mut report = SignHistoryReport::default();
+    let public_key = keypair.public_key_hex();
+
+    let mut signed = Vec::new();
+    for mut event in store.get_all_events()? {
+        if event.actor != *actor {
+            report.other_actors += 1;
+            continue;
+        }
+        if event.sig.is_some() {
+            report.already_signed += 1;
+            continue;
+        }
+        if key_at(event.ts_unix_ms).is_some_and(|key| key != public_key) {
+            report.key_mismatch += 1;
+            continue;
+        }
+        event.sig = Some(keypair.sign_event(&event));
+        signed.push(event);
+    }
+
+    if signed.is_empty() {
+        return Ok(report);
+    }
+    publish(&signed)?;
+    for event in signed {
+        if let Some(sig) = event.sig {
+            if store.set_event_signature(&event.event_id, sig)? {
+                report.events_signed += 1;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,4 +385,195 @@ mod tests {
         });
         assert!(!report_with_error.is_healthy());
     }
+
+    #[test]
+    fn test_verify_signatures_across_key_rotation() {
+        use crate::signing::{actor_key_context_key, SigningKeyPair};
+        use crate::types::event::EventKind;
+        use crate::types::ids::generate_issue_id;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [2u8; 16];
+        let actor_hex = id_to_hex(&actor);
+        let old_key = SigningKeyPair::generate();
+        let new_key = SigningKeyPair::generate();
+
+        let signed_event = |issue_id, ts, kind: EventKind, key: &SigningKeyPair| {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            let mut event = Event::new(event_id, issue_id, actor, ts, None, kind);
+            event.sig = Some(key.sign_event(&event));
+            event
+        };
+
+        // Rotation records, as written by `grite actor rotate-key`
+        let project_issue = [0u8; 16];
+        for (effective_ts, key) in [(0, &old_key), (2000, &new_key)] {
+            let kind = EventKind::ProjectContextUpdated {
+                key: actor_key_context_key(&actor_hex, effective_ts),
+                value: key.public_key_hex(),
+            };
+            store
+                .insert_event(&signed_event(project_issue, 2000, kind, &old_key))
+                .unwrap();
+        }
+
+        let issue_id = generate_issue_id();
+        let created = EventKind::IssueCreated {
+            title: "Before rotation".to_string(),
+            body: String::new(),
+            labels: vec![],
+        };
+        let comment = EventKind::CommentAdded {
+            body: "After rotation".to_string(),
+            parent_event: None,
+        };
+        store
+            .insert_event(&signed_event(issue_id, 1000, created, &old_key))
+            .unwrap();
+        store
+            .insert_event(&signed_event(issue_id, 3000, comment, &new_key))
+            .unwrap();
+
+        let actors = vec![ActorConfig {
+            actor_id: actor_hex.clone(),
+            label: None,
+            created_ts: None,
+            public_key: Some(new_key.public_key_hex()),
+            key_scheme: Some("ed25519".to_string()),
+        }];
+        let histories = load_key_histories(&store, &actors).unwrap();
+        let report = verify_store_signatures(&store, |actor_id, ts| {
+            histories
+                .get(actor_id)
+                .and_then(|h| h.key_at(ts))
+                .map(str::to_string)
+        })
+        .unwrap();
+        assert_eq!(report.signatures_checked, 2);
+        assert_eq!(report.signatures_valid, 2, "{:?}", report.signature_errors);
+
+        // Checking every event against the current key alone fails the old one
+        let current = new_key.public_key_hex();
+        let report = verify_store_signatures(&store, |_, _| Some(current.clone())).unwrap();
+        assert_eq!(report.signatures_valid, 1);
+    }
+
+    #[test]
+    fn test_sign_unsigned_events_backfills_own_events() {
+        use crate::signing::SigningKeyPair;
+        use crate::types::event::EventKind;
+        use crate::types::ids::generate_issue_id;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let me = [2u8; 16];
+        let other = [3u8; 16];
+        let unsigned_event = |actor, issue_id, ts, kind: EventKind| {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            Event::new(event_id, issue_id, actor, ts, None, kind)
+        };
+
+        // History written before signing was enabled
+        let issue_id = generate_issue_id();
+        let created = unsigned_event(
+            me,
+            issue_id,
+            1000,
+            EventKind::IssueCreated {
+                title: "Old issue".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        );
+        let mine = unsigned_event(
+            me,
+            issue_id,
+            2000,
+            EventKind::CommentAdded {
+                body: "Mine".to_string(),
+                parent_event: None,
+            },
+        );
+        let theirs = unsigned_event(
+            other,
+            issue_id,
+            3000,
+            EventKind::CommentAdded {
+                body: "Theirs".to_string(),
+                parent_event: None,
+            },
+        );
+        for event in [&created, &mine, &theirs] {
+            store.insert_event(event).unwrap();
+        }
+        let projection_before = store.get_issue(&issue_id).unwrap();
+
+        // Enable signing
+        let keypair = SigningKeyPair::generate();
+        let actors = vec![ActorConfig {
+            actor_id: id_to_hex(&me),
+            label: None,
+            created_ts: None,
+            public_key: Some(keypair.public_key_hex()),
+            key_scheme: Some("ed25519".to_string()),
+        }];
+        let histories = load_key_histories(&store, &actors).unwrap();
+        let key_at = |actor_id: &str, ts: u64| {
+            histories
+                .get(actor_id)
+                .and_then(|h| h.key_at(ts))
+                .map(str::to_string)
+        };
+
+        let mut published = Vec::new();
+        let report = sign_unsigned_events(
+            &store,
+            &me,
+            &keypair,
+            |ts| key_at(&id_to_hex(&me), ts),
+            |events| {
+                published.extend_from_slice(events);
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(report.events_signed, 2);
+        assert_eq!(published.len(), 2);
+        assert!(published.iter().all(|e| e.sig.is_some()));
+        assert_eq!(report.other_actors, 1);
+        assert_eq!(report.already_signed, 0);
+
+        // Event IDs and projections are unchanged; only signatures were added
+        let stored = store.get_event(&mine.event_id).unwrap().unwrap();
+        assert!(stored.sig.is_some());
+        assert!(verify_event_hash(&stored).is_ok());
+        assert!(store
+            .get_event(&theirs.event_id)
+            .unwrap()
+            .unwrap()
+            .sig
+            .is_none());
+        assert_eq!(store.get_issue(&issue_id).unwrap(), projection_before);
+
+        let report = verify_store_signatures(&store, key_at).unwrap();
+        assert_eq!(report.signatures_valid, 2, "{:?}", report.signature_errors);
+        assert_eq!(report.signature_errors.len(), 1, "only the other actor's");
+
+        // A second run has nothing left to sign
+        let report = sign_unsigned_events(
+            &store,
+            &me,
+            &keypair,
+            |ts| key_at(&id_to_hex(&me), ts),
+            |_| panic!("nothing to publish"),
+        )
+        .unwrap();
+        assert_eq!(report.events_signed, 0);
+        assert_eq!(report.already_signed, 2);
+    }
 }
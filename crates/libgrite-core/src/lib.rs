@@ -21,6 +21,8 @@
 //! - **Commutative sets** for labels, assignees, dependencies
 //! - **Append-only** for comments, links, attachments
 
+pub mod audit;
+pub mod clock;
 pub mod config;
 pub mod context;
 pub mod error;
@@ -31,24 +33,35 @@ pub mod lock;
 pub mod projection;
 pub mod signing;
 pub mod store;
+pub mod test_state;
 pub mod types;
 
 pub use config::{
     actor_dir, list_actors, load_repo_config, load_signing_key, repo_sled_path, save_repo_config,
-    RepoConfig,
+    update_repo_config, RepoConfig,
+};
+pub use error::{ErrorCodeInfo, GriteError};
+pub use export::{
+    export_counts, export_json, export_json_to, export_markdown, export_markdown_to,
+    import_attachments, parse_kinds, retain_kinds, AttachmentJson, ExportCounts, ExportOptions,
+    ExportOrder, ExportSince,
 };
-pub use error::GriteError;
-pub use export::{export_json, export_markdown, ExportSince};
 pub use integrity::{
-    check_store_integrity, verify_event_hash, verify_store_signatures, CorruptEvent,
-    CorruptionKind, IntegrityReport, SignatureError,
+    check_store_integrity, load_key_histories, verify_event_hash, verify_store_signatures,
+    CorruptEvent, CorruptionKind, IntegrityReport, SignatureError,
 };
 pub use lock::{resource_hash, Lock, LockCheckResult, LockPolicy, LockStatus, DEFAULT_LOCK_TTL_MS};
-pub use signing::{verify_signature, SigningError, SigningKeyPair, VerificationPolicy};
-pub use store::{DbStats, GriteStore, IssueFilter, LockedStore, RebuildStats};
+pub use signing::{verify_signature, KeyHistory, SigningError, SigningKeyPair, VerificationPolicy};
+pub use store::{
+    DbStats, DependencyRef, DependencyTreeNode, EventPreimage, EventSize, GriteStore, IssueDrift,
+    IssueEventsReindex, IssueFilter, IssueSort, LockedStore, MilestoneRollup, ProjectionDrift,
+    RebuildPlan, RebuildStats, ResponseTimeStats, StoreOptions,
+};
 pub use types::actor::ActorConfig;
 pub use types::context::{FileContext, ProjectContext, ProjectContextEntry};
-pub use types::event::{DependencyType, Event, EventKind, IssueState, SymbolInfo};
+pub use types::event::{
+    canonical_order, DependencyType, Event, EventKind, IssueState, Severity, SymbolInfo,
+};
 pub use types::ids::{generate_actor_id, generate_issue_id, hex_to_id, id_to_hex};
 pub use types::issue::{IssueProjection, IssueSummary, Version};
 pub use types::{ActorId, EventId, IssueId};
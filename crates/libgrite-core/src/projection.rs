@@ -1,10 +1,26 @@
 use crate::error::GriteError;
-use crate::types::event::{Event, EventKind};
-use crate::types::issue::{Attachment, Comment, Dependency, IssueProjection, Link, Version};
+use crate::types::event::{Event, EventKind, Severity};
+use crate::types::ids::{id_to_hex, EventId};
+use crate::types::issue::{
+    Attachment, BodyRevision, Comment, CommentThread, Dependency, IssueLink, IssueProjection, Link,
+    Version,
+};
 
 impl IssueProjection {
-    /// Apply an event to update this projection
+    /// Apply an event to update this projection, keeping only the winning body
     pub fn apply(&mut self, event: &Event) -> Result<(), GriteError> {
+        self.apply_keeping_bodies(event, 0)
+    }
+
+    /// Apply an event, keeping up to `body_history` superseded bodies
+    ///
+    /// The kept bodies are the newest ones by version, so the result does
+    /// not depend on the order events are applied in.
+    pub fn apply_keeping_bodies(
+        &mut self,
+        event: &Event,
+        body_history: usize,
+    ) -> Result<(), GriteError> {
         let new_version = Version::new(event.ts_unix_ms, event.actor, event.event_id);
 
         match &event.kind {
@@ -18,27 +34,65 @@ impl IssueProjection {
             EventKind::IssueUpdated { title, body } => {
                 // LWW for title
                 if let Some(new_title) = title {
+                    // Same timestamp from another actor: LWW still picks a
+                    // winner, but keep every candidate so a human can choose
+                    if new_version.ts_unix_ms == self.title_version.ts_unix_ms
+                        && new_version.actor != self.title_version.actor
+                        && *new_title != self.title
+                    {
+                        let candidates = self
+                            .title_conflict
+                            .get_or_insert_with(|| vec![self.title.clone()]);
+                        if !candidates.contains(new_title) {
+                            candidates.push(new_title.clone());
+                            candidates.sort();
+                        }
+                    }
                     if new_version.is_newer_than(&self.title_version) {
+                        // A strictly later edit resolves any conflict
+                        if new_version.ts_unix_ms > self.title_version.ts_unix_ms {
+                            self.title_conflict = None;
+                        }
                         self.title = new_title.clone();
                         self.title_version = new_version.clone();
                     }
                 }
                 // LWW for body
                 if let Some(new_body) = body {
-                    if new_version.is_newer_than(&self.body_version) {
-                        self.body = new_body.clone();
-                        self.body_version = new_version.clone();
+                    // Counted whether or not the edit wins, so the count
+                    // does not depend on event order
+                    self.body_revisions += 1;
+                    let superseded = if new_version.is_newer_than(&self.body_version) {
+                        BodyRevision {
+                            body: std::mem::replace(&mut self.body, new_body.clone()),
+                            version: std::mem::replace(&mut self.body_version, new_version.clone()),
+                        }
+                    } else {
+                        BodyRevision {
+                            body: new_body.clone(),
+                            version: new_version.clone(),
+                        }
+                    };
+                    if body_history > 0 {
+                        let at = self
+                            .body_history
+                            .iter()
+                            .position(|r| superseded.version.is_newer_than(&r.version))
+                            .unwrap_or(self.body_history.len());
+                        self.body_history.insert(at, superseded);
+                        self.body_history.truncate(body_history);
                     }
                 }
             }
 
-            EventKind::CommentAdded { body } => {
+            EventKind::CommentAdded { body, parent_event } => {
                 // Append-only
                 self.comments.push(Comment {
                     event_id: event.event_id,
                     actor: event.actor,
                     ts_unix_ms: event.ts_unix_ms,
                     body: body.clone(),
+                    parent_event: *parent_event,
                 });
             }
 
@@ -105,12 +159,124 @@ impl IssueProjection {
                 });
             }
 
+            EventKind::IssueLinked { target, relation } => {
+                // Commutative add to issue link set
+                self.issue_links.insert(IssueLink {
+                    target: *target,
+                    relation: relation.clone(),
+                });
+            }
+
+            EventKind::IssueUnlinked { target, relation } => {
+                // Commutative remove from issue link set
+                self.issue_links.remove(&IssueLink {
+                    target: *target,
+                    relation: relation.clone(),
+                });
+            }
+
+            EventKind::Subscribed { user } => {
+                // Commutative add
+                self.subscribers.insert(user.clone());
+            }
+
+            EventKind::Unsubscribed { user } => {
+                // Commutative remove; a no-op for non-subscribers
+                self.subscribers.remove(user);
+            }
+
+            EventKind::FieldSet { key, value } => {
+                // LWW per key; a clear is recorded so older sets cannot resurrect it
+                let is_newer = self
+                    .field_versions
+                    .get(key)
+                    .map_or(true, |v| new_version.is_newer_than(v));
+                if is_newer {
+                    match value {
+                        Some(v) => {
+                            self.custom_fields.insert(key.clone(), v.clone());
+                        }
+                        None => {
+                            self.custom_fields.remove(key);
+                        }
+                    }
+                    self.field_versions.insert(key.clone(), new_version.clone());
+                }
+            }
+
+            EventKind::TimeEstimated { minutes } => {
+                // LWW: a re-estimate replaces the previous one
+                let is_newer = self
+                    .estimate_version
+                    .as_ref()
+                    .map_or(true, |v| new_version.is_newer_than(v));
+                if is_newer {
+                    self.estimate_minutes = Some(*minutes);
+                    self.estimate_version = Some(new_version.clone());
+                }
+            }
+
+            EventKind::TimeLogged { minutes, .. } => {
+                // Commutative sum
+                self.logged_minutes += u64::from(*minutes);
+            }
+
+            EventKind::MilestoneSet { milestone } => {
+                // LWW; a clear is recorded so older sets cannot resurrect it
+                let is_newer = self
+                    .milestone_version
+                    .as_ref()
+                    .map_or(true, |v| new_version.is_newer_than(v));
+                if is_newer {
+                    self.milestone = milestone.clone();
+                    self.milestone_version = Some(new_version.clone());
+                }
+            }
+
+            EventKind::SeveritySet { severity } => {
+                // LWW; a clear is recorded so older sets cannot resurrect it
+                let is_newer = self
+                    .severity_version
+                    .as_ref()
+                    .map_or(true, |v| new_version.is_newer_than(v));
+                if is_newer {
+                    self.severity = Some(*severity).filter(|s| *s != Severity::None);
+                    self.severity_version = Some(new_version.clone());
+                }
+            }
+
+            EventKind::IssueDeleted {} | EventKind::IssueUndeleted {} => {
+                // LWW across deletes and undeletes
+                let is_newer = self
+                    .deleted_version
+                    .as_ref()
+                    .map_or(true, |v| new_version.is_newer_than(v));
+                if is_newer {
+                    self.deleted = matches!(event.kind, EventKind::IssueDeleted {});
+                    self.deleted_version = Some(new_version.clone());
+                }
+            }
+
             EventKind::ContextUpdated { .. } | EventKind::ProjectContextUpdated { .. } => {
                 // Context events are handled by the context store, not issue projections
                 return Ok(());
             }
         }
 
+        // Earliest response wins, whatever order events are applied in
+        let is_response = matches!(
+            event.kind,
+            EventKind::CommentAdded { .. } | EventKind::AssigneeAdded { .. }
+        );
+        if is_response
+            && self
+                .created_by
+                .is_some_and(|creator| creator != event.actor)
+        {
+            let delta = event.ts_unix_ms.saturating_sub(self.created_ts);
+            self.first_response_ms = Some(self.first_response_ms.map_or(delta, |d| d.min(delta)));
+        }
+
         // Update the updated_ts to the latest event timestamp
         if event.ts_unix_ms > self.updated_ts {
             self.updated_ts = event.ts_unix_ms;
@@ -140,6 +306,63 @@ impl IssueProjection {
             )),
         }
     }
+
+    /// Arrange comments into reply threads, in event order.
+    ///
+    /// Replies whose parent is not a comment on this issue (for example one
+    /// not yet synced) are shown as top-level threads.
+    pub fn comment_threads(&self) -> Vec<CommentThread> {
+        fn build(comments: &[Comment], comment: &Comment) -> CommentThread {
+            let replies = comments
+                .iter()
+                .filter(|c| c.parent_event == Some(comment.event_id))
+                .map(|c| build(comments, c))
+                .collect();
+            CommentThread {
+                comment: comment.clone(),
+                replies,
+            }
+        }
+
+        self.comments
+            .iter()
+            .filter(|c| match c.parent_event {
+                Some(parent) => !self.comments.iter().any(|p| p.event_id == parent),
+                None => true,
+            })
+            .map(|c| build(&self.comments, c))
+            .collect()
+    }
+
+    /// Resolve a comment's event ID from a hex prefix
+    pub fn resolve_comment(&self, hex_prefix: &str) -> Result<EventId, GriteError> {
+        let ids: Vec<EventId> = self.comments.iter().map(|c| c.event_id).collect();
+        resolve_comment_prefix(&ids, hex_prefix)
+    }
+}
+
+/// Resolve a hex prefix against the event IDs of an issue's comments
+///
+/// For callers that only have the IDs, such as a daemon response.
+pub fn resolve_comment_prefix(
+    comment_ids: &[EventId],
+    hex_prefix: &str,
+) -> Result<EventId, GriteError> {
+    let prefix = hex_prefix.to_ascii_lowercase();
+    let mut matches = comment_ids
+        .iter()
+        .filter(|id| id_to_hex(id).starts_with(&prefix));
+    match (matches.next(), matches.next()) {
+        (Some(id), None) if !prefix.is_empty() => Ok(*id),
+        (Some(_), Some(_)) => Err(GriteError::InvalidArgs(format!(
+            "ambiguous comment prefix '{}'",
+            hex_prefix
+        ))),
+        _ => Err(GriteError::NotFound(format!(
+            "No comment matching '{}' on this issue",
+            hex_prefix
+        ))),
+    }
 }
 
 #[cfg(test)]
@@ -147,7 +370,7 @@ mod tests {
     use super::*;
     use crate::hash::compute_event_id;
     use crate::types::event::IssueState;
-    use crate::types::ids::generate_issue_id;
+    use crate::types::ids::{generate_issue_id, IssueId};
 
     fn make_event(issue_id: [u8; 16], actor: [u8; 16], ts: u64, kind: EventKind) -> Event {
         let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
@@ -188,6 +411,53 @@ mod tests {
         assert_eq!(proj.body, "Body"); // Unchanged
     }
 
+    #[test]
+    fn test_body_history_ignores_event_order() {
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        let create_event = make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Bodies".to_string(),
+                body: "Draft 0".to_string(),
+                labels: vec![],
+            },
+        );
+        let updates: Vec<Event> = (1..=3)
+            .map(|i| {
+                make_event(
+                    issue_id,
+                    actor,
+                    1000 + i,
+                    EventKind::IssueUpdated {
+                        title: None,
+                        body: Some(format!("Draft {}", i)),
+                    },
+                )
+            })
+            .collect();
+
+        let fold = |order: &[usize]| {
+            let mut proj = IssueProjection::from_event(&create_event).unwrap();
+            for &i in order {
+                proj.apply_keeping_bodies(&updates[i], 2).unwrap();
+            }
+            proj
+        };
+        let in_order = fold(&[0, 1, 2]);
+        assert_eq!(in_order.body, "Draft 3");
+        let history: Vec<&str> = in_order
+            .body_history
+            .iter()
+            .map(|r| r.body.as_str())
+            .collect();
+        assert_eq!(history, ["Draft 2", "Draft 1"]);
+        assert_eq!(fold(&[2, 0, 1]), in_order);
+        assert_eq!(fold(&[1, 2, 0]), in_order);
+    }
+
     #[test]
     fn test_apply_lww_older_update_ignored() {
         let issue_id = generate_issue_id();
@@ -221,6 +491,110 @@ mod tests {
         assert_eq!(proj.title, "Original"); // Unchanged because update was older
     }
 
+    #[test]
+    fn test_apply_field_set_and_clear() {
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+
+        let create_event = make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Test".to_string(),
+                body: "Body".to_string(),
+                labels: vec![],
+            },
+        );
+        let mut proj = IssueProjection::from_event(&create_event).unwrap();
+
+        let field_event = |ts: u64, key: &str, value: Option<&str>| {
+            make_event(
+                issue_id,
+                actor,
+                ts,
+                EventKind::FieldSet {
+                    key: key.to_string(),
+                    value: value.map(str::to_string),
+                },
+            )
+        };
+
+        proj.apply(&field_event(2000, "severity", Some("high")))
+            .unwrap();
+        proj.apply(&field_event(2001, "customer", Some("acme")))
+            .unwrap();
+        proj.apply(&field_event(2002, "severity", None)).unwrap();
+        // Clearing a field that was never set is a no-op
+        proj.apply(&field_event(2003, "missing", None)).unwrap();
+        // An older set does not resurrect a cleared field
+        proj.apply(&field_event(1500, "severity", Some("low")))
+            .unwrap();
+
+        assert_eq!(proj.custom_fields.len(), 1);
+        assert_eq!(
+            proj.custom_fields.get("customer").map(String::as_str),
+            Some("acme")
+        );
+    }
+
+    #[test]
+    fn test_concurrent_title_edits_record_conflict() {
+        let issue_id = generate_issue_id();
+        let actor_a = [1u8; 16];
+        let actor_b = [2u8; 16];
+        let rename = |actor, ts, title: &str| {
+            make_event(
+                issue_id,
+                actor,
+                ts,
+                EventKind::IssueUpdated {
+                    title: Some(title.to_string()),
+                    body: None,
+                },
+            )
+        };
+        let create = make_event(
+            issue_id,
+            actor_a,
+            1000,
+            EventKind::IssueCreated {
+                title: "Original".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        );
+        let edit_a = rename(actor_a, 2000, "Title from A");
+        let edit_b = rename(actor_b, 2000, "Title from B");
+
+        // The marker is the same whichever edit is applied first
+        let mut projections = Vec::new();
+        for order in [[&edit_a, &edit_b], [&edit_b, &edit_a]] {
+            let mut proj = IssueProjection::from_event(&create).unwrap();
+            for event in order {
+                proj.apply(event).unwrap();
+            }
+            assert_eq!(
+                proj.title_conflict,
+                Some(vec!["Title from A".to_string(), "Title from B".to_string()])
+            );
+            projections.push(proj);
+        }
+        assert_eq!(projections[0].title, projections[1].title);
+
+        // A later edit resolves the conflict
+        let mut proj = projections.pop().unwrap();
+        proj.apply(&rename(actor_a, 3000, "Agreed title")).unwrap();
+        assert_eq!(proj.title, "Agreed title");
+        assert_eq!(proj.title_conflict, None);
+
+        // Same-timestamp edits from one actor are not a conflict
+        let mut proj = IssueProjection::from_event(&create).unwrap();
+        proj.apply(&rename(actor_a, 2000, "One")).unwrap();
+        proj.apply(&rename(actor_a, 2000, "Two")).unwrap();
+        assert_eq!(proj.title_conflict, None);
+    }
+
     #[test]
     fn test_apply_comment_added() {
         let issue_id = generate_issue_id();
@@ -246,6 +620,7 @@ mod tests {
             2000,
             EventKind::CommentAdded {
                 body: "Nice work!".to_string(),
+                parent_event: None,
             },
         );
 
@@ -254,6 +629,61 @@ mod tests {
         assert_eq!(proj.comments[0].body, "Nice work!");
     }
 
+    #[test]
+    fn test_comment_reply_threads() {
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        let comment = |ts, body: &str, parent_event| {
+            make_event(
+                issue_id,
+                actor,
+                ts,
+                EventKind::CommentAdded {
+                    body: body.to_string(),
+                    parent_event,
+                },
+            )
+        };
+
+        let mut proj = IssueProjection::from_event(&make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Test".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        ))
+        .unwrap();
+
+        let first = comment(2000, "Should we ship?", None);
+        let reply = comment(3000, "Yes", Some(first.event_id));
+        let orphan = comment(4000, "Lost reply", Some([9u8; 32]));
+        for event in [&first, &reply, &orphan] {
+            proj.apply(event).unwrap();
+        }
+
+        assert_eq!(proj.comments[1].parent_event, Some(first.event_id));
+        // The reply's parent is part of its hash
+        assert_ne!(
+            reply.event_id,
+            comment(3000, "Yes", None).event_id,
+            "parent changes the event ID"
+        );
+
+        let threads = proj.comment_threads();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].comment.event_id, first.event_id);
+        assert_eq!(threads[0].replies.len(), 1);
+        assert_eq!(threads[0].replies[0].comment.event_id, reply.event_id);
+        assert_eq!(threads[1].comment.event_id, orphan.event_id);
+
+        let prefix = &id_to_hex(&first.event_id)[..10];
+        assert_eq!(proj.resolve_comment(prefix).unwrap(), first.event_id);
+        assert!(proj.resolve_comment("zz").is_err());
+    }
+
     #[test]
     fn test_apply_labels_commutative() {
         let issue_id = generate_issue_id();
@@ -299,6 +729,210 @@ mod tests {
         assert!(proj.labels.contains("bug"));
     }
 
+    #[test]
+    fn test_apply_issue_linked_and_unlinked() {
+        let actor = [1u8; 16];
+        let create = |issue_id, title: &str| {
+            make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: title.to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            )
+        };
+        let issue_a = generate_issue_id();
+        let issue_b = generate_issue_id();
+        let mut proj_a = IssueProjection::from_event(&create(issue_a, "A")).unwrap();
+        let proj_b = IssueProjection::from_event(&create(issue_b, "B")).unwrap();
+
+        let link = |ts, relation: &str| {
+            make_event(
+                issue_a,
+                actor,
+                ts,
+                EventKind::IssueLinked {
+                    target: proj_b.issue_id,
+                    relation: relation.to_string(),
+                },
+            )
+        };
+        proj_a.apply(&link(2000, "duplicate-of")).unwrap();
+        proj_a.apply(&link(3000, "relates")).unwrap();
+
+        let links: Vec<(IssueId, &str)> = proj_a
+            .issue_links
+            .iter()
+            .map(|l| (l.target, l.relation.as_str()))
+            .collect();
+        assert_eq!(links, vec![(issue_b, "duplicate-of"), (issue_b, "relates")]);
+        assert!(proj_a.dependencies.is_empty(), "links are not dependencies");
+
+        // Unlinking removes only the matching relation
+        let unlink = make_event(
+            issue_a,
+            actor,
+            4000,
+            EventKind::IssueUnlinked {
+                target: issue_b,
+                relation: "relates".to_string(),
+            },
+        );
+        proj_a.apply(&unlink).unwrap();
+        assert_eq!(proj_a.issue_links.len(), 1);
+        assert_eq!(
+            proj_a.issue_links.iter().next().unwrap().relation,
+            "duplicate-of"
+        );
+    }
+
+    #[test]
+    fn test_apply_subscribed_and_unsubscribed() {
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+
+        let create_event = make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Test".to_string(),
+                body: "Body".to_string(),
+                labels: vec![],
+            },
+        );
+        let mut proj = IssueProjection::from_event(&create_event).unwrap();
+
+        let watch = |ts, kind| make_event(issue_id, actor, ts, kind);
+        for (ts, user) in [(2000, "bob"), (3000, "alice")] {
+            proj.apply(&watch(
+                ts,
+                EventKind::Subscribed {
+                    user: user.to_string(),
+                },
+            ))
+            .unwrap();
+        }
+        proj.apply(&watch(
+            4000,
+            EventKind::Unsubscribed {
+                user: "bob".to_string(),
+            },
+        ))
+        .unwrap();
+        // Unsubscribing a non-subscriber changes nothing
+        proj.apply(&watch(
+            5000,
+            EventKind::Unsubscribed {
+                user: "carol".to_string(),
+            },
+        ))
+        .unwrap();
+
+        let subscribers: Vec<&str> = proj.subscribers.iter().map(String::as_str).collect();
+        assert_eq!(subscribers, vec!["alice"]);
+    }
+
+    #[test]
+    fn test_apply_time_tracking() {
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+
+        let create_event = make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Test".to_string(),
+                body: "Body".to_string(),
+                labels: vec![],
+            },
+        );
+        let mut proj = IssueProjection::from_event(&create_event).unwrap();
+        assert_eq!(proj.estimate_minutes, None);
+        assert_eq!(proj.logged_minutes, 0);
+
+        let time = |ts, kind| make_event(issue_id, actor, ts, kind);
+        proj.apply(&time(2000, EventKind::TimeEstimated { minutes: 60 }))
+            .unwrap();
+        proj.apply(&time(
+            3000,
+            EventKind::TimeLogged {
+                minutes: 30,
+                note: Some("spike".to_string()),
+            },
+        ))
+        .unwrap();
+        proj.apply(&time(4000, EventKind::TimeEstimated { minutes: 120 }))
+            .unwrap();
+        proj.apply(&time(
+            5000,
+            EventKind::TimeLogged {
+                minutes: 45,
+                note: None,
+            },
+        ))
+        .unwrap();
+        // An older estimate arriving late does not win
+        proj.apply(&time(1500, EventKind::TimeEstimated { minutes: 10 }))
+            .unwrap();
+
+        assert_eq!(proj.logged_minutes, 75);
+        assert_eq!(proj.estimate_minutes, Some(120));
+    }
+
+    #[test]
+    fn test_first_response_ms() {
+        let issue_id = generate_issue_id();
+        let creator = [1u8; 16];
+        let responder = [2u8; 16];
+
+        let create_event = make_event(
+            issue_id,
+            creator,
+            1000,
+            EventKind::IssueCreated {
+                title: "Test".to_string(),
+                body: "Body".to_string(),
+                labels: vec![],
+            },
+        );
+        let mut proj = IssueProjection::from_event(&create_event).unwrap();
+        let comment = |actor, ts| {
+            make_event(
+                issue_id,
+                actor,
+                ts,
+                EventKind::CommentAdded {
+                    body: "Looking".to_string(),
+                    parent_event: None,
+                },
+            )
+        };
+
+        // The creator commenting on their own issue is not a response
+        proj.apply(&comment(creator, 1500)).unwrap();
+        assert_eq!(proj.first_response_ms, None);
+
+        proj.apply(&comment(responder, 4000)).unwrap();
+        assert_eq!(proj.first_response_ms, Some(3000));
+
+        // An earlier assignment synced late still counts as the first response
+        proj.apply(&make_event(
+            issue_id,
+            responder,
+            2500,
+            EventKind::AssigneeAdded {
+                user: "bob".to_string(),
+            },
+        ))
+        .unwrap();
+        assert_eq!(proj.first_response_ms, Some(1500));
+    }
+
     #[test]
     fn test_apply_state_changed() {
         let issue_id = generate_issue_id();
@@ -355,6 +989,7 @@ mod tests {
                 2000,
                 EventKind::CommentAdded {
                     body: "Comment 1".to_string(),
+                    parent_event: None,
                 },
             ),
             make_event(
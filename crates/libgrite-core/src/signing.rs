@@ -6,6 +6,7 @@
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use thiserror::Error;
 
 use crate::types::event::Event;
@@ -69,6 +70,102 @@ impl SigningKeyPair {
     }
 }
 
+/// Project context key prefix under which actor key rotations are recorded
+pub const ACTOR_KEY_CONTEXT_PREFIX: &str = "actor_key/";
+
+/// Project context key recording the key an actor signs with from `effective_ts` on.
+///
+/// The timestamp is zero-padded so an actor's keys sort chronologically.
+pub fn actor_key_context_key(actor_id_hex: &str, effective_ts: u64) -> String {
+    format!(
+        "{}{}/{:020}",
+        ACTOR_KEY_CONTEXT_PREFIX, actor_id_hex, effective_ts
+    )
+}
+
+/// Public keys an actor has signed with, each with the time it took effect
+#[derive(Debug, Clone, Default)]
+pub struct KeyHistory {
+    /// `(effective_ts, public_key_hex)`, sorted by timestamp
+    keys: Vec<(u64, String)>,
+}
+
+impl KeyHistory {
+    /// Record a key that is in effect from `effective_ts` on
+    pub fn add(&mut self, effective_ts: u64, public_key_hex: String) {
+        let pos = self.keys.partition_point(|(ts, _)| *ts <= effective_ts);
+        self.keys.insert(pos, (effective_ts, public_key_hex));
+    }
+
+    /// Whether no keys are recorded
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// The key in effect at `ts`: the latest one that took effect at or before it
+    ///
+    /// No other key was in effect before the first one, so the first key
+    /// also covers earlier timestamps; events an actor wrote before it
+    /// recorded a key can then be signed after the fact.
+    pub fn key_at(&self, ts: u64) -> Option<&str> {
+        self.keys
+            .iter()
+            .rev()
+            .find(|(effective_ts, _)| *effective_ts <= ts)
+            .or(self.keys.first())
+            .map(|(_, key)| key.as_str())
+    }
+
+    /// Collect key histories from project context entries, keyed by actor ID (hex).
+    ///
+    /// Each entry comes with the event that wrote it. A key record is only
+    /// accepted when that event was authored by the actor the record names,
+    /// and, after an actor's first record, when it is signed by the key in
+    /// effect before it, so one actor cannot rotate another's key. The first
+    /// record is trusted on first use. Entries that are not key rotation
+    /// records are ignored.
+    pub fn from_project_context<'a>(
+        entries: impl IntoIterator<Item = (&'a str, &'a str, &'a Event)>,
+    ) -> HashMap<String, KeyHistory> {
+        let mut records: HashMap<String, Vec<(u64, &'a str, &'a Event)>> = HashMap::new();
+        for (key, value, event) in entries {
+            let Some(rest) = key.strip_prefix(ACTOR_KEY_CONTEXT_PREFIX) else {
+                continue;
+            };
+            let Some((actor_id, ts)) = rest.split_once('/') else {
+                continue;
+            };
+            let Ok(effective_ts) = ts.parse::<u64>() else {
+                continue;
+            };
+            if crate::types::ids::id_to_hex(&event.actor) != actor_id {
+                continue;
+            }
+            records
+                .entry(actor_id.to_string())
+                .or_default()
+                .push((effective_ts, value, event));
+        }
+
+        let mut histories = HashMap::new();
+        for (actor_id, mut records) in records {
+            records.sort_by_key(|(effective_ts, _, _)| *effective_ts);
+            let mut history = KeyHistory::default();
+            for (effective_ts, value, event) in records {
+                let vouched = match history.keys.last() {
+                    Some((_, previous)) => verify_signature(event, previous).is_ok(),
+                    None => true,
+                };
+                if vouched {
+                    history.add(effective_ts, value.to_string());
+                }
+            }
+            histories.insert(actor_id, history);
+        }
+        histories
+    }
+}
+
 /// Signature verification policy
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -209,6 +306,7 @@ pub fn verify_raw(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hash::compute_event_id;
     use crate::types::event::EventKind;
 
     #[test]
@@ -223,6 +321,101 @@ mod tests {
         assert_eq!(pk.len(), 64);
     }
 
+    /// A project context event writing `key = value`, signed with `signer`
+    fn key_record(actor: [u8; 16], key: &str, value: &str, signer: &SigningKeyPair) -> Event {
+        let kind = EventKind::ProjectContextUpdated {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+        let event_id = compute_event_id(&[0u8; 16], &actor, 2000, None, &kind);
+        let mut event = Event::new(event_id, [0u8; 16], actor, 2000, None, kind);
+        event.sig = Some(signer.sign_event(&event));
+        event
+    }
+
+    #[test]
+    fn test_key_history_picks_key_per_era() {
+        let old = SigningKeyPair::generate();
+        let new = SigningKeyPair::generate();
+        let (old_key, new_key) = (old.public_key_hex(), new.public_key_hex());
+        let actor = [0x11u8; 16];
+        let actor_hex = crate::types::ids::id_to_hex(&actor);
+
+        let entries = [
+            (
+                actor_key_context_key(&actor_hex, 2000),
+                new_key.clone(),
+                &old,
+            ),
+            (
+                actor_key_context_key(&actor_hex, 1000),
+                old_key.clone(),
+                &old,
+            ),
+            ("unrelated".to_string(), "value".to_string(), &old),
+        ];
+        let events: Vec<Event> = entries
+            .iter()
+            .map(|(k, v, signer)| key_record(actor, k, v, signer))
+            .collect();
+        let histories = KeyHistory::from_project_context(
+            entries
+                .iter()
+                .zip(&events)
+                .map(|((k, v, _), e)| (k.as_str(), v.as_str(), e)),
+        );
+
+        assert_eq!(histories.len(), 1);
+        let history = &histories[&actor_hex];
+        assert_eq!(history.key_at(1999), Some(old_key.as_str()));
+        // The first key also covers the time before it was recorded
+        assert_eq!(history.key_at(0), Some(old_key.as_str()));
+        assert_eq!(history.key_at(2000), Some(new_key.as_str()));
+        assert_eq!(history.key_at(5000), Some(new_key.as_str()));
+    }
+
+    #[test]
+    fn test_key_history_rejects_unauthorized_rotation() {
+        let old = SigningKeyPair::generate();
+        let attacker = SigningKeyPair::generate();
+        let actor = [0x11u8; 16];
+        let other = [0x22u8; 16];
+        let actor_hex = crate::types::ids::id_to_hex(&actor);
+        let attacker_key = attacker.public_key_hex();
+
+        let original = key_record(
+            actor,
+            &actor_key_context_key(&actor_hex, 0),
+            &old.public_key_hex(),
+            &old,
+        );
+        // Written by another actor in the victim's name
+        let foreign = key_record(
+            other,
+            &actor_key_context_key(&actor_hex, 3000),
+            &attacker_key,
+            &attacker,
+        );
+        // Claims the victim as author, but the outgoing key never signed it
+        let unvouched = key_record(
+            actor,
+            &actor_key_context_key(&actor_hex, 4000),
+            &attacker_key,
+            &attacker,
+        );
+        let histories = KeyHistory::from_project_context(
+            [&original, &foreign, &unvouched].into_iter().map(|e| {
+                let EventKind::ProjectContextUpdated { key, value } = &e.kind else {
+                    unreachable!()
+                };
+                (key.as_str(), value.as_str(), e)
+            }),
+        );
+
+        let old_key = old.public_key_hex();
+        assert_eq!(histories[&actor_hex].key_at(5000), Some(old_key.as_str()));
+    }
+
     #[test]
     fn test_keypair_from_seed() {
         let keypair1 = SigningKeyPair::generate();
@@ -285,6 +478,7 @@ mod tests {
             None,
             EventKind::CommentAdded {
                 body: "test".to_string(),
+                parent_event: None,
             },
         );
 
@@ -304,6 +498,7 @@ mod tests {
             None,
             EventKind::CommentAdded {
                 body: "test".to_string(),
+                parent_event: None,
             },
         );
 
@@ -327,6 +522,7 @@ mod tests {
             None,
             EventKind::CommentAdded {
                 body: "test".to_string(),
+                parent_event: None,
             },
         );
 
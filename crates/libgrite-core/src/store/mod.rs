@@ -1,5 +1,6 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
+use std::ops::ControlFlow;
 use std::path::Path;
 use std::time::{Duration, Instant};
 
@@ -7,9 +8,9 @@ use fs2::FileExt;
 
 use crate::error::GriteError;
 use crate::types::context::{FileContext, ProjectContextEntry};
-use crate::types::event::IssueState;
-use crate::types::event::{DependencyType, Event, EventKind};
-use crate::types::ids::{EventId, IssueId};
+use crate::types::event::{canonical_order, DependencyType, Event, EventKind};
+use crate::types::event::{IssueState, Severity};
+use crate::types::ids::{id_to_hex, ActorId, EventId, IssueId};
 use crate::types::issue::Version;
 use crate::types::issue::{IssueProjection, IssueSummary};
 
@@ -19,11 +20,131 @@ pub const DEFAULT_REBUILD_EVENTS_THRESHOLD: usize = 10000;
 /// Default threshold for days since rebuild before recommending rebuild
 pub const DEFAULT_REBUILD_DAYS_THRESHOLD: u32 = 7;
 
+/// Size in bytes above which serialized events are compressed, when
+/// `compress_events` is on
+pub const DEFAULT_EVENT_COMPRESSION_THRESHOLD: usize = 1024;
+
+/// Default stored event size in bytes above which `grite doctor` warns
+pub const DEFAULT_LARGE_EVENT_WARN_BYTES: u64 = 64 * 1024;
+
+/// Header byte for event values stored as plain JSON behind a header; no
+/// longer written, since bare JSON is what older versions read
+const EVENT_VALUE_RAW: u8 = 0x00;
+
+/// Header byte for event values stored as deflate-compressed JSON
+const EVENT_VALUE_DEFLATE: u8 = 0x01;
+
 /// Filter for listing issues
 #[derive(Debug, Default)]
 pub struct IssueFilter {
     pub state: Option<IssueState>,
     pub label: Option<String>,
+    /// Only issues assigned to this user
+    pub assignee: Option<String>,
+    /// Only issues with no assignees (contradicts `assignee`)
+    pub unassigned: bool,
+    /// Only issues whose custom field `key` equals `value`
+    pub field: Option<(String, String)>,
+    /// Only issues in this milestone
+    pub milestone: Option<String>,
+    /// Only issues with this severity; `Severity::None` selects issues
+    /// without one
+    pub severity: Option<Severity>,
+    /// Also return soft-deleted issues
+    pub include_deleted: bool,
+}
+
+impl IssueFilter {
+    /// Parse a `key=value` field filter
+    pub fn parse_field(s: &str) -> Result<(String, String), GriteError> {
+        match s.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+            _ => Err(GriteError::InvalidArgs(format!(
+                "Invalid field filter '{}': expected key=value",
+                s
+            ))),
+        }
+    }
+
+    /// Whether a projection passes every filter
+    fn matches(&self, proj: &IssueProjection) -> bool {
+        if proj.deleted && !self.include_deleted {
+            return false;
+        }
+        if let Some(state) = self.state {
+            if proj.state != state {
+                return false;
+            }
+        }
+        if let Some(ref label) = self.label {
+            if !proj.labels.contains(label) {
+                return false;
+            }
+        }
+        if let Some(ref assignee) = self.assignee {
+            if !proj.assignees.contains(assignee) {
+                return false;
+            }
+        }
+        if self.unassigned && !proj.assignees.is_empty() {
+            return false;
+        }
+        if let Some((ref key, ref value)) = self.field {
+            if proj.custom_fields.get(key) != Some(value) {
+                return false;
+            }
+        }
+        if let Some(ref milestone) = self.milestone {
+            if proj.milestone.as_ref() != Some(milestone) {
+                return false;
+            }
+        }
+        if let Some(severity) = self.severity {
+            if proj.severity.unwrap_or(Severity::None) != severity {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Order of the issues returned by [`GriteStore::list_issues_sorted`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IssueSort {
+    /// Oldest first
+    #[default]
+    Created,
+    /// Highest `blocks_count` first, then oldest first
+    MostBlocking,
+}
+
+impl IssueSort {
+    /// Parse from string
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "created" => Some(IssueSort::Created),
+            "most-blocking" => Some(IssueSort::MostBlocking),
+            _ => None,
+        }
+    }
+
+    /// Convert to string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IssueSort::Created => "created",
+            IssueSort::MostBlocking => "most-blocking",
+        }
+    }
+
+    fn sort(&self, summaries: &mut [IssueSummary]) {
+        match self {
+            IssueSort::Created => summaries.sort_by_key(|s| s.created_ts),
+            IssueSort::MostBlocking => {
+                summaries.sort_by_key(|s| (std::cmp::Reverse(s.blocks_count), s.created_ts))
+            }
+        }
+    }
 }
 
 /// Statistics about the database
@@ -40,6 +161,12 @@ pub struct DbStats {
     pub days_since_rebuild: Option<u32>,
     /// Whether rebuild is recommended based on thresholds
     pub rebuild_recommended: bool,
+    /// Files in the context index
+    pub context_file_count: usize,
+    /// Symbol entries in the context index (one per symbol per file)
+    pub context_symbol_count: usize,
+    /// Timestamp of the newest `ContextUpdated` event applied to the index
+    pub last_context_update_ts: Option<u64>,
 }
 
 /// Statistics from a rebuild operation
@@ -47,6 +174,167 @@ pub struct DbStats {
 pub struct RebuildStats {
     pub event_count: usize,
     pub issue_count: usize,
+    /// Events that failed to apply, with the error, when errors are skipped
+    pub skipped: Vec<(EventId, String)>,
+}
+
+/// Entries of the `issue_events` index that disagree with the events tree,
+/// as found or repaired by [`GriteStore::reindex_issue_events`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IssueEventsReindex {
+    /// Events that had no index entry
+    pub missing: usize,
+    /// Entries pointing at no stored event, or duplicating an event under the
+    /// wrong issue or timestamp
+    pub stale: usize,
+}
+
+impl IssueEventsReindex {
+    pub fn is_clean(&self) -> bool {
+        self.missing == 0 && self.stale == 0
+    }
+}
+
+/// Stored size of one event, as reported by [`GriteStore::largest_events`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventSize {
+    pub event_id: EventId,
+    pub issue_id: IssueId,
+    /// Event kind name, e.g. `CommentAdded`
+    pub kind: &'static str,
+    /// Bytes the event occupies in the events tree (after compression)
+    pub size_bytes: u64,
+}
+
+/// Canonical hash preimage of a stored event, as reported by
+/// [`GriteStore::event_preimage`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventPreimage {
+    /// ID the event is stored under
+    pub event_id: EventId,
+    /// ID recomputed from the preimage
+    pub computed_id: EventId,
+    /// Canonical CBOR bytes that are hashed into the ID
+    pub preimage: Vec<u8>,
+}
+
+/// Open and closed issue counts for one milestone, as reported by
+/// [`GriteStore::milestone_rollups`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MilestoneRollup {
+    pub milestone: String,
+    pub open: usize,
+    pub closed: usize,
+}
+
+/// First-response times across issues, as reported by
+/// [`GriteStore::response_time_stats`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseTimeStats {
+    /// Issues someone other than the creator has responded to
+    pub responded: usize,
+    /// Issues with no response yet
+    pub unanswered: usize,
+    /// Mean first-response time, `None` if nothing was responded to
+    pub mean_ms: Option<u64>,
+    /// Median first-response time (lower middle for an even count)
+    pub median_ms: Option<u64>,
+}
+
+/// One side of a dependency edge, with the other issue's title resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyRef {
+    /// The other issue
+    pub issue_id: IssueId,
+    pub dep_type: DependencyType,
+    /// Title of the other issue, `None` if it no longer exists
+    pub title: Option<String>,
+}
+
+/// A node of the tree built by [`GriteStore::dependency_tree`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyTreeNode {
+    pub issue_id: IssueId,
+    /// Type of the edge from the parent, `None` for the root
+    pub dep_type: Option<DependencyType>,
+    /// `None` if the issue no longer exists
+    pub title: Option<String>,
+    pub state: Option<IssueState>,
+    /// The issue already appears on the path from the root, so it is not
+    /// expanded again
+    pub cycle: bool,
+    /// The depth limit was reached before this issue's dependencies
+    pub truncated: bool,
+    pub children: Vec<DependencyTreeNode>,
+}
+
+impl DependencyTreeNode {
+    /// JSON form shared by the CLI and the daemon
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "issue_id": id_to_hex(&self.issue_id),
+            "dep_type": self.dep_type.map(|t| t.as_str()),
+            "title": self.title,
+            "state": self.state.map(|s| s.as_str()),
+            "cycle": self.cycle,
+            "truncated": self.truncated,
+            "children": self.children.iter().map(Self::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// What a rebuild would process, computed without writing anything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RebuildPlan {
+    pub event_count: usize,
+    /// Issues that would have a projection after the rebuild
+    pub issue_count: usize,
+    /// Event count per kind name, e.g. "IssueCreated"
+    pub kinds: BTreeMap<&'static str, usize>,
+}
+
+impl RebuildPlan {
+    /// Plan a rebuild over the given events
+    pub fn from_events(events: &[Event]) -> Self {
+        let mut issues = HashSet::new();
+        let mut kinds = BTreeMap::new();
+        for event in events {
+            *kinds.entry(event.kind.kind_name()).or_insert(0) += 1;
+            if !matches!(
+                event.kind,
+                EventKind::ContextUpdated { .. } | EventKind::ProjectContextUpdated { .. }
+            ) {
+                issues.insert(event.issue_id);
+            }
+        }
+        Self {
+            event_count: events.len(),
+            issue_count: issues.len(),
+            kinds,
+        }
+    }
+}
+
+/// How a stored issue projection differs from one rebuilt from a snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProjectionDrift {
+    /// The store has events for the issue that the snapshot lacks, so a
+    /// difference is expected
+    NewerThanSnapshot,
+    /// Top-level projection fields whose stored value differs, sorted
+    Mismatch(Vec<String>),
+    /// The snapshot has the issue but the store has no projection for it
+    Missing,
+    /// The store has a projection for an issue that is in neither the
+    /// snapshot nor the store's own events
+    Unexpected,
+}
+
+/// One issue reported by [`GriteStore::diff_against_snapshot`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IssueDrift {
+    pub issue_id: IssueId,
+    pub drift: ProjectionDrift,
 }
 
 /// A GriteStore with filesystem-level exclusive lock.
@@ -90,6 +378,34 @@ impl std::ops::DerefMut for LockedStore {
     }
 }
 
+/// Tuning applied when opening a store.
+///
+/// Unset sled values keep sled's own defaults.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StoreOptions {
+    /// Page cache capacity in MiB
+    pub cache_mb: Option<u64>,
+    /// Background flush interval in milliseconds
+    pub flush_every_ms: Option<u64>,
+    /// Superseded bodies each issue projection keeps (unset = none)
+    pub body_history: Option<usize>,
+    /// Deflate stored events larger than `DEFAULT_EVENT_COMPRESSION_THRESHOLD`
+    pub compress_events: bool,
+}
+
+impl StoreOptions {
+    fn sled_config(&self, path: &Path) -> sled::Config {
+        let mut config = sled::Config::new().path(path);
+        if let Some(mb) = self.cache_mb {
+            config = config.cache_capacity(mb * 1024 * 1024);
+        }
+        if let Some(ms) = self.flush_every_ms {
+            config = config.flush_every_ms(Some(ms));
+        }
+        config
+    }
+}
+
 /// Main storage interface backed by sled
 pub struct GriteStore {
     db: sled::Db,
@@ -97,28 +413,52 @@ pub struct GriteStore {
     issue_states: sled::Tree,
     issue_events: sled::Tree,
     label_index: sled::Tree,
+    field_index: sled::Tree,
+    /// Issue IDs keyed by milestone name
+    milestone_index: sled::Tree,
+    /// Issue IDs keyed by severity
+    severity_index: sled::Tree,
     metadata: sled::Tree,
     dep_forward: sled::Tree,
     dep_reverse: sled::Tree,
     context_files: sled::Tree,
     context_symbols: sled::Tree,
     context_project: sled::Tree,
+    /// Attachment bytes keyed by their SHA-256
+    attachments: sled::Tree,
+    /// Integration scratch space keyed by `namespace \0 key`, kept apart
+    /// from the internal `metadata` tree
+    integration_meta: sled::Tree,
+    /// Serialized events larger than this are compressed (None = never)
+    event_compression_threshold: Option<usize>,
+    /// Superseded bodies kept in each issue projection
+    body_history: usize,
 }
 
 impl GriteStore {
     /// Open or create a store at the given path
     pub fn open(path: &Path) -> Result<Self, GriteError> {
-        let db = sled::open(path)?;
+        Self::open_with_options(path, &StoreOptions::default())
+    }
+
+    /// Open or create a store at the given path with sled tuning options
+    pub fn open_with_options(path: &Path, options: &StoreOptions) -> Result<Self, GriteError> {
+        let db = options.sled_config(path).open()?;
         let events = db.open_tree("events")?;
         let issue_states = db.open_tree("issue_states")?;
         let issue_events = db.open_tree("issue_events")?;
         let label_index = db.open_tree("label_index")?;
+        let field_index = db.open_tree("field_index")?;
+        let milestone_index = db.open_tree("milestone_index")?;
+        let severity_index = db.open_tree("severity_index")?;
         let metadata = db.open_tree("metadata")?;
         let dep_forward = db.open_tree("dep_forward")?;
         let dep_reverse = db.open_tree("dep_reverse")?;
         let context_files = db.open_tree("context_files")?;
         let context_symbols = db.open_tree("context_symbols")?;
         let context_project = db.open_tree("context_project")?;
+        let attachments = db.open_tree("attachments")?;
+        let integration_meta = db.open_tree("integration_meta")?;
 
         Ok(Self {
             db,
@@ -126,12 +466,21 @@ impl GriteStore {
             issue_states,
             issue_events,
             label_index,
+            field_index,
+            milestone_index,
+            severity_index,
             metadata,
             dep_forward,
             dep_reverse,
             context_files,
             context_symbols,
             context_project,
+            attachments,
+            integration_meta,
+            event_compression_threshold: options
+                .compress_events
+                .then_some(DEFAULT_EVENT_COMPRESSION_THRESHOLD),
+            body_history: options.body_history.unwrap_or(0),
         })
     }
 
@@ -140,6 +489,14 @@ impl GriteStore {
     /// Lock file is created at `<path>.lock` (e.g., `.git/grite/actors/<id>/sled.lock`).
     /// Returns `GriteError::DbBusy` if another process holds the lock.
     pub fn open_locked(path: &Path) -> Result<LockedStore, GriteError> {
+        Self::open_locked_with_options(path, &StoreOptions::default())
+    }
+
+    /// Like [`GriteStore::open_locked`], with sled tuning options.
+    pub fn open_locked_with_options(
+        path: &Path,
+        options: &StoreOptions,
+    ) -> Result<LockedStore, GriteError> {
         let lock_path = path.with_extension("lock");
 
         // Create/open lock file
@@ -151,7 +508,7 @@ impl GriteStore {
         })?;
 
         // Now safe to open sled
-        let store = Self::open(path)?;
+        let store = Self::open_with_options(path, options)?;
 
         Ok(LockedStore {
             _lock_file: lock_file,
@@ -164,6 +521,15 @@ impl GriteStore {
     /// Retries with exponential backoff until the lock is acquired or timeout is reached.
     /// Returns `GriteError::DbBusy` if timeout expires before acquiring the lock.
     pub fn open_locked_blocking(path: &Path, timeout: Duration) -> Result<LockedStore, GriteError> {
+        Self::open_locked_blocking_with_options(path, timeout, &StoreOptions::default())
+    }
+
+    /// Like [`GriteStore::open_locked_blocking`], with sled tuning options.
+    pub fn open_locked_blocking_with_options(
+        path: &Path,
+        timeout: Duration,
+        options: &StoreOptions,
+    ) -> Result<LockedStore, GriteError> {
         let lock_path = path.with_extension("lock");
         let lock_file = File::create(&lock_path)?;
 
@@ -186,7 +552,7 @@ impl GriteStore {
             }
         }
 
-        let store = Self::open(path)?;
+        let store = Self::open_with_options(path, options)?;
         Ok(LockedStore {
             _lock_file: lock_file,
             store,
@@ -197,8 +563,8 @@ impl GriteStore {
     pub fn insert_event(&self, event: &Event) -> Result<(), GriteError> {
         // Store the event
         let event_key = event_key(&event.event_id);
-        let event_json = serde_json::to_vec(event)?;
-        self.events.insert(&event_key, event_json)?;
+        let event_value = encode_event_value(event, self.event_compression_threshold)?;
+        self.events.insert(&event_key, event_value)?;
 
         // Index by issue
         let issue_events_key = issue_events_key(&event.issue_id, event.ts_unix_ms, &event.event_id);
@@ -230,6 +596,79 @@ impl GriteStore {
         Ok(())
     }
 
+    /// Recompute the events_since_rebuild counter from the stored events.
+    ///
+    /// Counts events stamped after the last rebuild (every event if the store
+    /// was never rebuilt) and writes the result back, repairing a counter that
+    /// drifted after a crash or a manual replay.
+    pub fn recount_since_rebuild(&self) -> Result<usize, GriteError> {
+        let last_rebuild_ts = self.metadata.get("last_rebuild_ts")?.map(|bytes| {
+            let arr: [u8; 8] = bytes.as_ref().try_into().unwrap_or([0; 8]);
+            u64::from_le_bytes(arr)
+        });
+
+        let count = match last_rebuild_ts {
+            Some(ts) => self
+                .get_all_events()?
+                .iter()
+                .filter(|e| e.ts_unix_ms > ts)
+                .count(),
+            None => self.events.len(),
+        };
+
+        self.metadata
+            .insert("events_since_rebuild", &(count as u64).to_le_bytes())?;
+        Ok(count)
+    }
+
+    /// Rebuild the `issue_events` index from the events tree.
+    ///
+    /// Adds an entry for every event that lost one and removes entries that
+    /// do not match a stored event, so each event is indexed exactly once.
+    /// Projections are left alone; they are folded from the events tree.
+    pub fn reindex_issue_events(&self) -> Result<IssueEventsReindex, GriteError> {
+        self.reconcile_issue_events(true)
+    }
+
+    /// Count the `issue_events` entries [`Self::reindex_issue_events`] would
+    /// add or remove, without changing anything
+    pub fn check_issue_events_index(&self) -> Result<IssueEventsReindex, GriteError> {
+        self.reconcile_issue_events(false)
+    }
+
+    fn reconcile_issue_events(&self, apply: bool) -> Result<IssueEventsReindex, GriteError> {
+        let mut expected = HashSet::new();
+        for result in self.events.iter() {
+            let (_, value) = result?;
+            let event = decode_event_value(&value)?;
+            expected.insert(issue_events_key(
+                &event.issue_id,
+                event.ts_unix_ms,
+                &event.event_id,
+            ));
+        }
+
+        let mut report = IssueEventsReindex::default();
+        for result in self.issue_events.iter() {
+            let (key, _) = result?;
+            if !expected.remove(key.as_ref()) {
+                report.stale += 1;
+                if apply {
+                    self.issue_events.remove(key)?;
+                }
+            }
+        }
+
+        // What is left in `expected` has no entry yet
+        report.missing = expected.len();
+        if apply {
+            for key in expected {
+                self.issue_events.insert(key, &[])?;
+            }
+        }
+        Ok(report)
+    }
+
     /// Update the issue projection for an event
     fn update_projection(&self, event: &Event) -> Result<(), GriteError> {
         // Handle context events separately (they don't have issue projections)
@@ -266,9 +705,17 @@ impl GriteStore {
             }
         };
 
+        // Remember the previous field value so its index entry can be dropped
+        let old_field_value = match &event.kind {
+            EventKind::FieldSet { key, .. } => projection.custom_fields.get(key).cloned(),
+            _ => None,
+        };
+        let old_milestone = projection.milestone.clone();
+        let old_severity = projection.severity;
+
         // Apply event if not IssueCreated (which created the projection)
         if self.issue_states.get(&issue_key)?.is_some() {
-            projection.apply(event)?;
+            projection.apply_keeping_bodies(event, self.body_history)?;
         }
 
         // Update label index
@@ -291,6 +738,39 @@ impl GriteStore {
                 let rev = dep_reverse_key(target, &event.issue_id, dep_type);
                 self.dep_reverse.remove(&rev)?;
             }
+            EventKind::FieldSet { key, .. } => {
+                let new_value = projection.custom_fields.get(key);
+                if old_field_value.as_ref() != new_value {
+                    if let Some(old) = &old_field_value {
+                        self.field_index
+                            .remove(field_index_key(key, old, &event.issue_id))?;
+                    }
+                    if let Some(new) = new_value {
+                        self.field_index
+                            .insert(field_index_key(key, new, &event.issue_id), &[])?;
+                    }
+                }
+            }
+            EventKind::MilestoneSet { .. } if old_milestone != projection.milestone => {
+                if let Some(old) = &old_milestone {
+                    self.milestone_index
+                        .remove(milestone_index_key(old, &event.issue_id))?;
+                }
+                if let Some(new) = &projection.milestone {
+                    self.milestone_index
+                        .insert(milestone_index_key(new, &event.issue_id), &[])?;
+                }
+            }
+            EventKind::SeveritySet { .. } if old_severity != projection.severity => {
+                if let Some(old) = old_severity {
+                    self.severity_index
+                        .remove(severity_index_key(old, &event.issue_id))?;
+                }
+                if let Some(new) = projection.severity {
+                    self.severity_index
+                        .insert(severity_index_key(new, &event.issue_id), &[])?;
+                }
+            }
             _ => {}
         }
 
@@ -391,16 +871,125 @@ impl GriteStore {
     pub fn get_event(&self, event_id: &EventId) -> Result<Option<Event>, GriteError> {
         let key = event_key(event_id);
         match self.events.get(&key)? {
-            Some(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Some(bytes) => Ok(Some(decode_event_value(&bytes)?)),
             None => Ok(None),
         }
     }
 
+    /// Store attachment bytes, returning their SHA-256
+    ///
+    /// Content-addressed, so storing the same bytes twice is a no-op.
+    pub fn put_attachment(&self, data: &[u8]) -> Result<[u8; 32], GriteError> {
+        use sha2::{Digest, Sha256};
+
+        let sha256: [u8; 32] = Sha256::digest(data).into();
+        self.attachments.insert(sha256, data)?;
+        Ok(sha256)
+    }
+
+    /// Get attachment bytes by SHA-256
+    pub fn get_attachment(&self, sha256: &[u8; 32]) -> Result<Option<Vec<u8>>, GriteError> {
+        Ok(self.attachments.get(sha256)?.map(|bytes| bytes.to_vec()))
+    }
+
+    /// Get the bytes of every stored attachment, in SHA-256 order
+    pub fn get_all_attachments(&self) -> Result<Vec<Vec<u8>>, GriteError> {
+        let mut attachments = Vec::new();
+        for result in self.attachments.iter() {
+            let (_, value) = result?;
+            attachments.push(value.to_vec());
+        }
+        Ok(attachments)
+    }
+
+    /// Store a value for an integration under `namespace`/`key`
+    ///
+    /// Integration values live in their own tree, so they can never shadow
+    /// or overwrite internal metadata such as `last_rebuild_ts`, and they
+    /// survive rebuilds.
+    pub fn put_meta(&self, namespace: &str, key: &str, value: &[u8]) -> Result<(), GriteError> {
+        self.integration_meta
+            .insert(integration_meta_key(namespace, key)?, value)?;
+        Ok(())
+    }
+
+    /// Get an integration value stored under `namespace`/`key`
+    pub fn get_meta(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>, GriteError> {
+        Ok(self
+            .integration_meta
+            .get(integration_meta_key(namespace, key)?)?
+            .map(|bytes| bytes.to_vec()))
+    }
+
+    /// List all key/value pairs stored under `namespace`, sorted by key
+    pub fn list_meta(&self, namespace: &str) -> Result<Vec<(String, Vec<u8>)>, GriteError> {
+        let prefix = integration_meta_prefix(namespace)?;
+        let mut entries = Vec::new();
+        for item in self.integration_meta.scan_prefix(&prefix) {
+            let (key, value) = item?;
+            let key = String::from_utf8_lossy(&key[prefix.len()..]).into_owned();
+            entries.push((key, value.to_vec()));
+        }
+        Ok(entries)
+    }
+
+    /// Build the canonical CBOR preimage of a stored event and recompute its ID
+    ///
+    /// A `computed_id` that differs from `event_id` means the stored event no
+    /// longer hashes to its ID.
+    pub fn event_preimage(&self, event_id: &EventId) -> Result<EventPreimage, GriteError> {
+        let event = self
+            .get_event(event_id)?
+            .ok_or_else(|| GriteError::NotFound(format!("event {}", id_to_hex(event_id))))?;
+        let preimage = crate::hash::build_canonical_cbor(
+            &event.issue_id,
+            &event.actor,
+            event.ts_unix_ms,
+            event.parent.as_ref(),
+            &event.kind,
+        );
+        let computed_id = crate::hash::compute_event_id(
+            &event.issue_id,
+            &event.actor,
+            event.ts_unix_ms,
+            event.parent.as_ref(),
+            &event.kind,
+        );
+        Ok(EventPreimage {
+            event_id: event.event_id,
+            computed_id,
+            preimage,
+        })
+    }
+
+    /// Attach a signature to a stored event, returning false if it is missing.
+    ///
+    /// The signature is not part of the event ID, so only the stored event is
+    /// rewritten; projections and the rebuild counter are left alone.
+    pub fn set_event_signature(
+        &self,
+        event_id: &EventId,
+        sig: Vec<u8>,
+    ) -> Result<bool, GriteError> {
+        let key = event_key(event_id);
+        let Some(bytes) = self.events.get(&key)? else {
+            return Ok(false);
+        };
+        let mut event = decode_event_value(&bytes)?;
+        event.sig = Some(sig);
+        self.events.insert(
+            &key,
+            encode_event_value(&event, self.event_compression_threshold)?,
+        )?;
+        Ok(true)
+    }
+
     /// Resolve a hex prefix to a full issue ID
     ///
     /// If the prefix is already a full 32-char hex ID, parses it directly.
     /// Otherwise, scans the issue_states tree for matching prefixes.
-    /// Returns an error if the prefix is ambiguous (matches multiple issues).
+    /// Returns `Ambiguous`, listing every candidate, if the prefix matches
+    /// more than one issue.
     pub fn resolve_issue_id(&self, hex_prefix: &str) -> Result<IssueId, GriteError> {
         use crate::types::ids::{hex_to_id, id_to_hex};
 
@@ -455,13 +1044,42 @@ impl GriteStore {
                 hex_prefix
             ))),
             1 => Ok(matches[0]),
-            n => Err(GriteError::InvalidArgs(format!(
-                "ambiguous prefix {} matches {} issues",
-                hex_prefix, n
+            n => Err(GriteError::Ambiguous(format!(
+                "prefix {} matches {} issues: {}",
+                hex_prefix,
+                n,
+                matches.iter().map(id_to_hex).collect::<Vec<_>>().join(", ")
             ))),
         }
     }
 
+    /// Resolve several ID prefixes to existing issues, all or nothing.
+    ///
+    /// Every ID is checked before returning, so the `NotFound` error lists
+    /// each ID that matches no issue. Repeated IDs are resolved once.
+    pub fn resolve_existing_issues(&self, ids: &[String]) -> Result<Vec<IssueId>, GriteError> {
+        let mut resolved = Vec::with_capacity(ids.len());
+        let mut missing = Vec::new();
+        for id in ids {
+            match self.resolve_issue_id(id) {
+                Ok(issue_id) if self.issue_exists(&issue_id)? => {
+                    if !resolved.contains(&issue_id) {
+                        resolved.push(issue_id);
+                    }
+                }
+                Ok(_) | Err(GriteError::NotFound(_)) => missing.push(id.as_str()),
+                Err(e) => return Err(e),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(GriteError::NotFound(format!(
+                "Issues not found: {}",
+                missing.join(", ")
+            )));
+        }
+        Ok(resolved)
+    }
+
     /// Get an issue projection by ID
     pub fn get_issue(&self, issue_id: &IssueId) -> Result<Option<IssueProjection>, GriteError> {
         let key = issue_state_key(issue_id);
@@ -471,33 +1089,208 @@ impl GriteStore {
         }
     }
 
+    /// Shortest hex prefix that tells every stored issue ID apart
+    ///
+    /// Keys are sorted, so the longest shared prefix is always between
+    /// neighbours. Returns 1 when there are fewer than two issues.
+    pub fn min_unique_id_prefix(&self) -> Result<usize, GriteError> {
+        let mut longest_shared = 0;
+        let mut previous: Option<sled::IVec> = None;
+        for key in self.issue_states.iter().keys() {
+            let key = key?;
+            if let Some(previous) = &previous {
+                longest_shared = longest_shared.max(shared_hex_prefix(previous, &key));
+            }
+            previous = Some(key);
+        }
+        Ok(longest_shared + 1)
+    }
+
+    /// Whether an issue exists, without deserializing its projection
+    pub fn issue_exists(&self, issue_id: &IssueId) -> Result<bool, GriteError> {
+        Ok(self.issue_states.contains_key(issue_state_key(issue_id))?)
+    }
+
     /// List issues with optional filtering
     pub fn list_issues(&self, filter: &IssueFilter) -> Result<Vec<IssueSummary>, GriteError> {
+        self.list_issues_sorted(filter, IssueSort::Created)
+    }
+
+    /// List issues matching `filter` in the order given by `sort`
+    pub fn list_issues_sorted(
+        &self,
+        filter: &IssueFilter,
+        sort: IssueSort,
+    ) -> Result<Vec<IssueSummary>, GriteError> {
         let mut summaries = Vec::new();
+        self.for_each_issue(filter, |summary| {
+            summaries.push(summary);
+            ControlFlow::Continue(())
+        })?;
+        sort.sort(&mut summaries);
+        Ok(summaries)
+    }
 
-        for result in self.issue_states.iter() {
-            let (_, value) = result?;
-            let proj: IssueProjection = serde_json::from_slice(&value)?;
+    /// Summary of a projection, with `blocks_count` filled in from the
+    /// dependency indexes
+    pub fn issue_summary(&self, proj: &IssueProjection) -> Result<IssueSummary, GriteError> {
+        let mut summary = IssueSummary::from(proj);
+        summary.blocks_count = self.blocks_count(&proj.issue_id)?;
+        Ok(summary)
+    }
+
+    /// Number of distinct issues this one blocks
+    ///
+    /// Counts both `blocks` edges from this issue and `depends_on` edges
+    /// pointing at it, whatever the state of either issue.
+    pub fn blocks_count(&self, issue_id: &IssueId) -> Result<usize, GriteError> {
+        let blocked: HashSet<IssueId> = self
+            .get_dependencies(issue_id)?
+            .into_iter()
+            .filter(|(_, dep_type)| *dep_type == DependencyType::Blocks)
+            .chain(
+                self.get_dependents(issue_id)?
+                    .into_iter()
+                    .filter(|(_, dep_type)| *dep_type == DependencyType::DependsOn),
+            )
+            .map(|(other, _)| other)
+            .collect();
+        Ok(blocked.len())
+    }
+
+    /// Pass each issue matching `filter` to `f` as it is read
+    ///
+    /// Issues arrive in storage order, not by creation time. Returning
+    /// `ControlFlow::Break` from `f` stops the scan.
+    pub fn for_each_issue(
+        &self,
+        filter: &IssueFilter,
+        mut f: impl FnMut(IssueSummary) -> ControlFlow<()>,
+    ) -> Result<(), GriteError> {
+        if filter.unassigned && filter.assignee.is_some() {
+            return Err(GriteError::InvalidArgs(
+                "--unassigned cannot be combined with --assignee".to_string(),
+            ));
+        }
+
+        let mut visit = |proj: IssueProjection| -> Result<ControlFlow<()>, GriteError> {
+            if filter.matches(&proj) {
+                Ok(f(self.issue_summary(&proj)?))
+            } else {
+                Ok(ControlFlow::Continue(()))
+            }
+        };
 
-            // Apply filters
-            if let Some(state) = filter.state {
-                if proj.state != state {
-                    continue;
+        if let Some((ref key, ref value)) = filter.field {
+            // Use the field index to find candidates instead of scanning
+            for result in self.field_index.scan_prefix(field_index_prefix(key, value)) {
+                let (index_key, _) = result?;
+                let issue_id = extract_issue_id_from_field_index_key(&index_key)?;
+                if let Some(proj) = self.get_issue(&issue_id)? {
+                    if visit(proj)?.is_break() {
+                        break;
+                    }
                 }
             }
-            if let Some(ref label) = filter.label {
-                if !proj.labels.contains(label) {
-                    continue;
+        } else if let Some(ref milestone) = filter.milestone {
+            for issue_id in self.milestone_issue_ids(milestone)? {
+                if let Some(proj) = self.get_issue(&issue_id)? {
+                    if visit(proj)?.is_break() {
+                        break;
+                    }
+                }
+            }
+        } else if let Some(severity) = filter.severity.filter(|s| *s != Severity::None) {
+            // Issues without a severity are not indexed and need a full scan
+            for result in self
+                .severity_index
+                .scan_prefix(severity_index_prefix(severity))
+            {
+                let (index_key, _) = result?;
+                let issue_id = extract_issue_id_from_field_index_key(&index_key)?;
+                if let Some(proj) = self.get_issue(&issue_id)? {
+                    if visit(proj)?.is_break() {
+                        break;
+                    }
+                }
+            }
+        } else {
+            for result in self.issue_states.iter() {
+                let (_, value) = result?;
+                let proj: IssueProjection = serde_json::from_slice(&value)?;
+                if visit(proj)?.is_break() {
+                    break;
                 }
             }
-
-            summaries.push(IssueSummary::from(&proj));
         }
 
-        // Sort by creation time (oldest first)
-        summaries.sort_by_key(|s| s.created_ts);
+        Ok(())
+    }
 
-        Ok(summaries)
+    /// IDs of the issues in a milestone, from the milestone index
+    fn milestone_issue_ids(&self, milestone: &str) -> Result<Vec<IssueId>, GriteError> {
+        self.milestone_index
+            .scan_prefix(milestone_index_prefix(milestone))
+            .map(|result| {
+                let (index_key, _) = result?;
+                extract_issue_id_from_field_index_key(&index_key)
+            })
+            .collect()
+    }
+
+    /// Open and closed issue counts per milestone, sorted by milestone name
+    ///
+    /// Milestones with no issues left in them are not listed.
+    pub fn milestone_rollups(&self) -> Result<Vec<MilestoneRollup>, GriteError> {
+        let mut rollups: BTreeMap<String, MilestoneRollup> = BTreeMap::new();
+        for result in self.milestone_index.iter() {
+            let (index_key, _) = result?;
+            let issue_id = extract_issue_id_from_field_index_key(&index_key)?;
+            let Some(proj) = self.get_issue(&issue_id)?.filter(|p| !p.deleted) else {
+                continue;
+            };
+            let Some(milestone) = proj.milestone else {
+                continue;
+            };
+            let rollup = rollups
+                .entry(milestone.clone())
+                .or_insert_with(|| MilestoneRollup {
+                    milestone,
+                    open: 0,
+                    closed: 0,
+                });
+            match proj.state {
+                IssueState::Open => rollup.open += 1,
+                IssueState::Closed => rollup.closed += 1,
+            }
+        }
+        Ok(rollups.into_values().collect())
+    }
+
+    /// Aggregate `first_response_ms` over all issues that are not deleted
+    pub fn response_time_stats(&self) -> Result<ResponseTimeStats, GriteError> {
+        let mut times = Vec::new();
+        let mut unanswered = 0;
+        for result in self.issue_states.iter() {
+            let (_, value) = result?;
+            let proj: IssueProjection = serde_json::from_slice(&value)?;
+            if proj.deleted {
+                continue;
+            }
+            match proj.first_response_ms {
+                Some(ms) => times.push(ms),
+                None => unanswered += 1,
+            }
+        }
+        times.sort_unstable();
+        let mean_ms = (!times.is_empty()).then(|| times.iter().sum::<u64>() / times.len() as u64);
+        let median_ms = (!times.is_empty()).then(|| times[(times.len() - 1) / 2]);
+        Ok(ResponseTimeStats {
+            responded: times.len(),
+            unanswered,
+            mean_ms,
+            median_ms,
+        })
     }
 
     /// Get all events for an issue, sorted by (ts, actor, event_id)
@@ -514,39 +1307,186 @@ impl GriteStore {
             }
         }
 
-        // Sort by (ts, actor, event_id)
-        events.sort_by(|a, b| {
-            (a.ts_unix_ms, &a.actor, &a.event_id).cmp(&(b.ts_unix_ms, &b.actor, &b.event_id))
-        });
+        canonical_order(&mut events);
 
         Ok(events)
     }
 
+    /// Digest of an issue's event IDs, for cheap change detection
+    ///
+    /// Reads only the issue's event index, not the events themselves. Any new
+    /// event changes the digest. Errors with `NotFound` if the issue has no
+    /// events.
+    pub fn issue_digest(&self, issue_id: &IssueId) -> Result<[u8; 32], GriteError> {
+        let mut event_ids = Vec::new();
+        for result in self.issue_events.scan_prefix(issue_events_prefix(issue_id)) {
+            let (key, _) = result?;
+            event_ids.push(extract_event_id_from_issue_events_key(&key)?);
+        }
+        if event_ids.is_empty() {
+            return Err(GriteError::issue_not_found(&crate::types::ids::id_to_hex(
+                issue_id,
+            )));
+        }
+        Ok(crate::hash::compute_issue_digest(event_ids))
+    }
+
+    /// Get a page of events for an issue, along with the total event count.
+    ///
+    /// Events are ordered as in [`GriteStore::get_issue_events`]. An `offset`
+    /// past the end yields an empty page.
+    pub fn get_issue_events_page(
+        &self,
+        issue_id: &IssueId,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<Event>, usize), GriteError> {
+        let events = self.get_issue_events(issue_id)?;
+        let total = events.len();
+        let page = events.into_iter().skip(offset).take(limit).collect();
+        Ok((page, total))
+    }
+
     /// Get all events in the store
     pub fn get_all_events(&self) -> Result<Vec<Event>, GriteError> {
         let mut events = Vec::new();
         for result in self.events.iter() {
             let (_, value) = result?;
-            let event: Event = serde_json::from_slice(&value)?;
-            events.push(event);
+            events.push(decode_event_value(&value)?);
         }
-        // Sort by (issue_id, ts, actor, event_id)
-        events.sort_by(|a, b| {
-            (&a.issue_id, a.ts_unix_ms, &a.actor, &a.event_id).cmp(&(
-                &b.issue_id,
-                b.ts_unix_ms,
-                &b.actor,
-                &b.event_id,
-            ))
-        });
+        canonical_order(&mut events);
+        Ok(events)
+    }
+
+    /// Iterate over every stored event in event ID order, decoding one at a
+    /// time
+    pub fn iter_events(&self) -> impl Iterator<Item = Result<Event, GriteError>> + '_ {
+        self.events.iter().map(|result| {
+            let (_, value) = result?;
+            decode_event_value(&value)
+        })
+    }
+
+    /// Timestamp of each actor's newest event
+    ///
+    /// Scans the whole events tree. Actors with no events are absent.
+    pub fn actor_last_activity(&self) -> Result<HashMap<ActorId, u64>, GriteError> {
+        let mut last_seen = HashMap::new();
+        for result in self.events.iter() {
+            let (_, value) = result?;
+            let event = decode_event_value(&value)?;
+            let ts = last_seen.entry(event.actor).or_insert(event.ts_unix_ms);
+            *ts = (*ts).max(event.ts_unix_ms);
+        }
+        Ok(last_seen)
+    }
+
+    /// Total `TimeLogged` minutes per actor across all issues
+    ///
+    /// Actors are shown by their entry in `names`, or by the first 8 hex
+    /// characters of their ID if they have none. Sorted by total, largest
+    /// first, then by name.
+    pub fn time_by_assignee(
+        &self,
+        names: &HashMap<ActorId, String>,
+    ) -> Result<Vec<(String, u64)>, GriteError> {
+        let mut totals: HashMap<String, u64> = HashMap::new();
+        for result in self.events.iter() {
+            let (_, value) = result?;
+            let event = decode_event_value(&value)?;
+            if let EventKind::TimeLogged { minutes, .. } = event.kind {
+                let name = names
+                    .get(&event.actor)
+                    .cloned()
+                    .unwrap_or_else(|| id_to_hex(&event.actor)[..8].to_string());
+                *totals.entry(name).or_default() += u64::from(minutes);
+            }
+        }
+        let mut totals: Vec<(String, u64)> = totals.into_iter().collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(totals)
+    }
+
+    /// Get all events of one kind, ordered as in [`GriteStore::get_all_events`].
+    ///
+    /// `tag` is an [`EventKind::kind_tag`] value; this scans the whole
+    /// events tree.
+    pub fn events_of_kind(&self, tag: u32) -> Result<Vec<Event>, GriteError> {
+        let mut events = Vec::new();
+        for result in self.events.iter() {
+            let (_, value) = result?;
+            let event = decode_event_value(&value)?;
+            if event.kind.kind_tag() == tag {
+                events.push(event);
+            }
+        }
+        canonical_order(&mut events);
         Ok(events)
     }
 
+    /// Find the `limit` largest events by stored size, largest first.
+    ///
+    /// Sizes come from the stored value lengths, so only the events returned
+    /// are deserialized. Ties are broken by event ID.
+    pub fn largest_events(&self, limit: usize) -> Result<Vec<EventSize>, GriteError> {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        // Min-heap of the largest (size, key) pairs seen so far
+        let mut heap = BinaryHeap::with_capacity(limit + 1);
+        for result in self.events.iter() {
+            let (key, value) = result?;
+            heap.push(Reverse((value.len() as u64, key)));
+            if heap.len() > limit {
+                heap.pop();
+            }
+        }
+
+        let mut largest: Vec<_> = heap.into_iter().map(|Reverse(entry)| entry).collect();
+        largest.sort_by(|a, b| b.cmp(a));
+
+        let mut sizes = Vec::with_capacity(largest.len());
+        for (size_bytes, key) in largest {
+            let Some(value) = self.events.get(&key)? else {
+                continue;
+            };
+            let event = decode_event_value(&value)?;
+            sizes.push(EventSize {
+                event_id: event.event_id,
+                issue_id: event.issue_id,
+                kind: event.kind.kind_name(),
+                size_bytes,
+            });
+        }
+        Ok(sizes)
+    }
+
     /// Rebuild all projections from events
+    ///
+    /// Fails on the first event that cannot be applied.
     pub fn rebuild(&self) -> Result<RebuildStats, GriteError> {
+        self.rebuild_events(false)
+    }
+
+    /// Rebuild all projections from events, skipping events that fail to apply
+    ///
+    /// Skipped events are listed in [`RebuildStats::skipped`]. Storage errors
+    /// still abort the rebuild.
+    pub fn rebuild_skipping_errors(&self) -> Result<RebuildStats, GriteError> {
+        self.rebuild_events(true)
+    }
+
+    fn rebuild_events(&self, skip_errors: bool) -> Result<RebuildStats, GriteError> {
         // Clear existing projections and indexes
         self.issue_states.clear()?;
         self.label_index.clear()?;
+        self.field_index.clear()?;
+        self.milestone_index.clear()?;
+        self.severity_index.clear()?;
         self.dep_forward.clear()?;
         self.dep_reverse.clear()?;
         self.context_files.clear()?;
@@ -556,19 +1496,12 @@ impl GriteStore {
         // Collect all events
         let mut events = self.get_all_events()?;
 
-        // Sort events by (issue_id, ts, actor, event_id) for deterministic ordering
-        events.sort_by(|a, b| {
-            (&a.issue_id, a.ts_unix_ms, &a.actor, &a.event_id).cmp(&(
-                &b.issue_id,
-                b.ts_unix_ms,
-                &b.actor,
-                &b.event_id,
-            ))
-        });
+        canonical_order(&mut events);
 
         // Rebuild projections
+        let mut skipped = Vec::new();
         for event in &events {
-            self.update_projection(event)?;
+            self.rebuild_projection(event, skip_errors, &mut skipped)?;
         }
 
         let issue_count = self.issue_states.len();
@@ -586,17 +1519,45 @@ impl GriteStore {
         Ok(RebuildStats {
             event_count: events.len(),
             issue_count,
+            skipped,
         })
     }
 
+    /// Report what [`GriteStore::rebuild`] would process, without clearing or
+    /// writing any trees. Rebuild metadata is left untouched.
+    pub fn rebuild_plan(&self) -> Result<RebuildPlan, GriteError> {
+        Ok(RebuildPlan::from_events(&self.get_all_events()?))
+    }
+
     /// Rebuild all projections from provided events (for snapshot-based rebuild)
     ///
     /// This is useful when rebuilding from a snapshot + WAL combination,
     /// where events come from external sources rather than the local store.
     pub fn rebuild_from_events(&self, events: &[Event]) -> Result<RebuildStats, GriteError> {
+        self.rebuild_from_event_list(events, false)
+    }
+
+    /// Like [`GriteStore::rebuild_from_events`], but events that fail to
+    /// apply are stored and listed in [`RebuildStats::skipped`] instead of
+    /// aborting the rebuild
+    pub fn rebuild_from_events_skipping_errors(
+        &self,
+        events: &[Event],
+    ) -> Result<RebuildStats, GriteError> {
+        self.rebuild_from_event_list(events, true)
+    }
+
+    fn rebuild_from_event_list(
+        &self,
+        events: &[Event],
+        skip_errors: bool,
+    ) -> Result<RebuildStats, GriteError> {
         // Clear existing projections, indexes, and events
         self.issue_states.clear()?;
         self.label_index.clear()?;
+        self.field_index.clear()?;
+        self.milestone_index.clear()?;
+        self.severity_index.clear()?;
         self.dep_forward.clear()?;
         self.dep_reverse.clear()?;
         self.context_files.clear()?;
@@ -604,30 +1565,34 @@ impl GriteStore {
         self.context_project.clear()?;
         self.events.clear()?;
 
-        // Sort events by (issue_id, ts, actor, event_id) for deterministic ordering
         let mut sorted_events: Vec<_> = events.to_vec();
-        sorted_events.sort_by(|a, b| {
-            (&a.issue_id, a.ts_unix_ms, &a.actor, &a.event_id).cmp(&(
-                &b.issue_id,
-                b.ts_unix_ms,
-                &b.actor,
-                &b.event_id,
-            ))
+        canonical_order(&mut sorted_events);
+        // `db sign-history` appends signed copies of existing events, so an
+        // event can appear twice; apply it once, keeping a signature
+        sorted_events.dedup_by(|later, earlier| {
+            if later.event_id != earlier.event_id {
+                return false;
+            }
+            if earlier.sig.is_none() {
+                earlier.sig = later.sig.take();
+            }
+            true
         });
 
         // Insert events and rebuild projections
+        let mut skipped = Vec::new();
         for event in &sorted_events {
             // Insert event into store
             let ev_key = event_key(&event.event_id);
-            let event_json = serde_json::to_vec(event)?;
-            self.events.insert(&ev_key, event_json)?;
+            let event_value = encode_event_value(event, self.event_compression_threshold)?;
+            self.events.insert(&ev_key, event_value)?;
 
             // Index by issue
             let ie_key = issue_events_key(&event.issue_id, event.ts_unix_ms, &event.event_id);
             self.issue_events.insert(&ie_key, &[])?;
 
             // Rebuild projection (handles deps, context, labels)
-            self.update_projection(event)?;
+            self.rebuild_projection(event, skip_errors, &mut skipped)?;
         }
 
         let issue_count = self.issue_states.len();
@@ -645,9 +1610,114 @@ impl GriteStore {
         Ok(RebuildStats {
             event_count: sorted_events.len(),
             issue_count,
+            skipped,
         })
     }
 
+    /// Apply one event during a rebuild. With `skip_errors`, an event that
+    /// fails to apply is recorded in `skipped` instead of failing the rebuild;
+    /// storage errors are always returned.
+    fn rebuild_projection(
+        &self,
+        event: &Event,
+        skip_errors: bool,
+        skipped: &mut Vec<(EventId, String)>,
+    ) -> Result<(), GriteError> {
+        match self.update_projection(event) {
+            Err(e) if skip_errors && !matches!(e, GriteError::Sled(_) | GriteError::Io(_)) => {
+                skipped.push((event.event_id, e.to_string()));
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Build issue projections from events in memory, without touching the store.
+    ///
+    /// Events are folded in the same order as [`GriteStore::rebuild_from_events`],
+    /// so the result matches what a real rebuild would write, given the
+    /// store's `body_history`. Context events are skipped since they do not
+    /// produce issue projections.
+    pub fn projections_from_events(
+        events: &[Event],
+        body_history: usize,
+    ) -> Result<HashMap<IssueId, IssueProjection>, GriteError> {
+        let mut sorted_events: Vec<_> = events.to_vec();
+        canonical_order(&mut sorted_events);
+
+        let mut projections: HashMap<IssueId, IssueProjection> = HashMap::new();
+        for event in &sorted_events {
+            if matches!(
+                event.kind,
+                EventKind::ContextUpdated { .. } | EventKind::ProjectContextUpdated { .. }
+            ) {
+                continue;
+            }
+            match projections.get_mut(&event.issue_id) {
+                Some(projection) => projection.apply_keeping_bodies(event, body_history)?,
+                None => {
+                    projections.insert(event.issue_id, IssueProjection::from_event(event)?);
+                }
+            }
+        }
+
+        Ok(projections)
+    }
+
+    /// Compare stored projections with ones rebuilt from snapshot events
+    ///
+    /// Issues that match are not reported. An issue with events the snapshot
+    /// does not contain is reported as newer rather than compared; any other
+    /// difference, including an unreadable projection, is a mismatch.
+    /// Results are sorted by issue ID.
+    pub fn diff_against_snapshot(&self, events: &[Event]) -> Result<Vec<IssueDrift>, GriteError> {
+        let mut expected = Self::projections_from_events(events, self.body_history)?;
+        let snapshot_ids: HashSet<EventId> = events.iter().map(|e| e.event_id).collect();
+        let mut drifts = Vec::new();
+
+        for result in self.issue_states.iter() {
+            let (key, value) = result?;
+            let issue_id = extract_issue_id_from_issue_state_key(&key)?;
+            let snapshot_proj = expected.remove(&issue_id);
+
+            let mut has_newer = false;
+            for result in self
+                .issue_events
+                .scan_prefix(issue_events_prefix(&issue_id))
+            {
+                let (key, _) = result?;
+                if !snapshot_ids.contains(&extract_event_id_from_issue_events_key(&key)?) {
+                    has_newer = true;
+                    break;
+                }
+            }
+            let drift = if has_newer {
+                Some(ProjectionDrift::NewerThanSnapshot)
+            } else {
+                match (serde_json::from_slice(&value), snapshot_proj) {
+                    (_, None) => Some(ProjectionDrift::Unexpected),
+                    (Ok(stored), Some(proj)) => {
+                        let fields = differing_fields(&stored, &serde_json::to_value(&proj)?);
+                        (!fields.is_empty()).then_some(ProjectionDrift::Mismatch(fields))
+                    }
+                    (Err(_), Some(_)) => {
+                        Some(ProjectionDrift::Mismatch(vec!["projection".to_string()]))
+                    }
+                }
+            };
+            if let Some(drift) = drift {
+                drifts.push(IssueDrift { issue_id, drift });
+            }
+        }
+
+        drifts.extend(expected.into_keys().map(|issue_id| IssueDrift {
+            issue_id,
+            drift: ProjectionDrift::Missing,
+        }));
+        drifts.sort_by_key(|d| d.issue_id);
+        Ok(drifts)
+    }
+
     /// Get database statistics
     pub fn stats(&self, path: &Path) -> Result<DbStats, GriteError> {
         let event_count = self.events.len();
@@ -687,6 +1757,14 @@ impl GriteStore {
                 .map(|d| d > DEFAULT_REBUILD_DAYS_THRESHOLD)
                 .unwrap_or(false);
 
+        // Each indexed file keeps the version of the event that last set it
+        let mut last_context_update_ts = None;
+        for result in self.context_files.iter() {
+            let (_, value) = result?;
+            let ctx: FileContext = serde_json::from_slice(&value)?;
+            last_context_update_ts = last_context_update_ts.max(Some(ctx.version.ts_unix_ms));
+        }
+
         Ok(DbStats {
             path: path.to_string_lossy().to_string(),
             size_bytes,
@@ -696,6 +1774,9 @@ impl GriteStore {
             events_since_rebuild,
             days_since_rebuild,
             rebuild_recommended,
+            context_file_count: self.context_files.len(),
+            context_symbol_count: self.context_symbols.len(),
+            last_context_update_ts,
         })
     }
 
@@ -737,6 +1818,29 @@ impl GriteStore {
         Ok(deps)
     }
 
+    /// Get dependencies (or, with `reverse`, dependents) along with the
+    /// other issue's title
+    pub fn get_dependency_refs(
+        &self,
+        issue_id: &IssueId,
+        reverse: bool,
+    ) -> Result<Vec<DependencyRef>, GriteError> {
+        let deps = if reverse {
+            self.get_dependents(issue_id)?
+        } else {
+            self.get_dependencies(issue_id)?
+        };
+        deps.into_iter()
+            .map(|(other, dep_type)| {
+                Ok(DependencyRef {
+                    issue_id: other,
+                    dep_type,
+                    title: self.get_issue(&other)?.map(|p| p.title),
+                })
+            })
+            .collect()
+    }
+
     /// Check if adding a dependency would create a cycle.
     /// Only checks for Blocks/DependsOn (acyclic types).
     pub fn would_create_cycle(
@@ -770,6 +1874,131 @@ impl GriteStore {
         Ok(false)
     }
 
+    /// Tree of the `Blocks`/`DependsOn` edges reachable from `issue_id`.
+    ///
+    /// Follows outgoing edges depth-first for at most `max_depth` levels. An
+    /// issue already on the path from the root is marked as a cycle and not
+    /// expanded, so cycles are shown once rather than followed forever.
+    pub fn dependency_tree(
+        &self,
+        issue_id: &IssueId,
+        max_depth: usize,
+    ) -> Result<DependencyTreeNode, GriteError> {
+        let mut path = Vec::new();
+        self.dependency_subtree(issue_id, None, max_depth, &mut path)
+    }
+
+    fn dependency_subtree(
+        &self,
+        issue_id: &IssueId,
+        dep_type: Option<DependencyType>,
+        depth_left: usize,
+        path: &mut Vec<IssueId>,
+    ) -> Result<DependencyTreeNode, GriteError> {
+        let projection = self.get_issue(issue_id)?;
+        let mut node = DependencyTreeNode {
+            issue_id: *issue_id,
+            dep_type,
+            title: projection.as_ref().map(|p| p.title.clone()),
+            state: projection.as_ref().map(|p| p.state),
+            cycle: path.contains(issue_id),
+            truncated: false,
+            children: Vec::new(),
+        };
+        if node.cycle {
+            return Ok(node);
+        }
+
+        let deps: Vec<_> = self
+            .get_dependencies(issue_id)?
+            .into_iter()
+            .filter(|(_, dep_type)| dep_type.is_acyclic())
+            .collect();
+        if deps.is_empty() {
+            return Ok(node);
+        }
+        if depth_left == 0 {
+            node.truncated = true;
+            return Ok(node);
+        }
+
+        path.push(*issue_id);
+        for (target, dep_type) in deps {
+            node.children.push(self.dependency_subtree(
+                &target,
+                Some(dep_type),
+                depth_left - 1,
+                path,
+            )?);
+        }
+        path.pop();
+        Ok(node)
+    }
+
+    /// Open issues that must be closed before `issue_id` is ready: those
+    /// that block it and those it depends on
+    fn open_blockers(&self, issue_id: &IssueId) -> Result<Vec<IssueId>, GriteError> {
+        let blockers = self
+            .get_dependents(issue_id)?
+            .into_iter()
+            .filter(|(_, dep_type)| *dep_type == DependencyType::Blocks)
+            .chain(
+                self.get_dependencies(issue_id)?
+                    .into_iter()
+                    .filter(|(_, dep_type)| *dep_type == DependencyType::DependsOn),
+            )
+            .map(|(other, _)| other);
+
+        let mut open = Vec::new();
+        for blocker in blockers {
+            let is_open = self
+                .get_issue(&blocker)?
+                .is_some_and(|p| p.state == IssueState::Open);
+            if is_open && !open.contains(&blocker) {
+                open.push(blocker);
+            }
+        }
+        Ok(open)
+    }
+
+    /// Explain why an issue is not ready.
+    ///
+    /// Returns every chain of open `Blocks`/`DependsOn` prerequisites behind
+    /// `issue_id`. Each path starts at a direct blocker and follows its own
+    /// open blockers outward until one has none. An empty result means the
+    /// issue is ready.
+    pub fn blocking_chain(&self, issue_id: &IssueId) -> Result<Vec<Vec<IssueId>>, GriteError> {
+        let mut paths = Vec::new();
+        let mut stack: Vec<Vec<IssueId>> = self
+            .open_blockers(issue_id)?
+            .into_iter()
+            .rev()
+            .map(|blocker| vec![blocker])
+            .collect();
+
+        while let Some(path) = stack.pop() {
+            let last = path[path.len() - 1];
+            // Skip blockers already on the path, or the issue itself, so
+            // mixed blocks/depends_on cycles terminate
+            let next: Vec<IssueId> = self
+                .open_blockers(&last)?
+                .into_iter()
+                .filter(|b| b != issue_id && !path.contains(b))
+                .collect();
+            if next.is_empty() {
+                paths.push(path);
+                continue;
+            }
+            for blocker in next.into_iter().rev() {
+                let mut extended = path.clone();
+                extended.push(blocker);
+                stack.push(extended);
+            }
+        }
+
+        Ok(paths)
+    }
+
     /// Get issues in topological order based on dependency relationships.
     /// Issues with no dependencies come first.
     pub fn topological_order(&self, filter: &IssueFilter) -> Result<Vec<IssueSummary>, GriteError> {
@@ -847,11 +2076,22 @@ impl GriteStore {
     }
 
     /// Query symbols by name prefix
-    pub fn query_symbols(&self, query: &str) -> Result<Vec<(String, String)>, GriteError> {
+    ///
+    /// Matches come back in key order. With a `limit`, the scan stops as soon
+    /// as that many matches are found.
+    pub fn query_symbols(
+        &self,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<(String, String)>, GriteError> {
         let prefix = context_symbol_prefix(query);
+        let limit = limit.unwrap_or(usize::MAX);
         let mut results = Vec::new();
 
         for result in self.context_symbols.scan_prefix(&prefix) {
+            if results.len() >= limit {
+                break;
+            }
             let (key, _) = result?;
             if let Ok(key_str) = std::str::from_utf8(&key) {
                 // Key format: "ctx/sym/<name>/<path>"
@@ -882,6 +2122,30 @@ impl GriteStore {
         Ok(paths)
     }
 
+    /// Indexed files, most recently updated first
+    ///
+    /// Ordered by each file's LWW version, so a file is dated by the winning
+    /// `ContextUpdated` event; a file never re-indexed keeps its first index
+    /// time.
+    pub fn recent_context_files(
+        &self,
+        limit: Option<usize>,
+    ) -> Result<Vec<FileContext>, GriteError> {
+        let mut files = Vec::new();
+        for result in self.context_files.iter() {
+            let (_, value) = result?;
+            files.push(serde_json::from_slice::<FileContext>(&value)?);
+        }
+        files.sort_by(|a, b| {
+            let key = |f: &FileContext| (f.version.ts_unix_ms, f.version.actor, f.version.event_id);
+            key(b).cmp(&key(a))
+        });
+        if let Some(limit) = limit {
+            files.truncate(limit);
+        }
+        Ok(files)
+    }
+
     /// Get a project context entry by key
     pub fn get_project_context(
         &self,
@@ -918,6 +2182,44 @@ impl GriteStore {
 
 // Key construction helpers
 
+/// Serialize an event for the events tree.
+///
+/// Values are bare JSON, which every version reads, unless the JSON exceeds
+/// the threshold: then they are `EVENT_VALUE_DEFLATE` followed by
+/// deflate-compressed JSON.
+fn encode_event_value(event: &Event, threshold: Option<usize>) -> Result<Vec<u8>, GriteError> {
+    use flate2::write::DeflateEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let json = serde_json::to_vec(event)?;
+    match threshold {
+        Some(t) if json.len() > t => {
+            let mut encoder =
+                DeflateEncoder::new(vec![EVENT_VALUE_DEFLATE], Compression::default());
+            encoder.write_all(&json)?;
+            Ok(encoder.finish()?)
+        }
+        _ => Ok(json),
+    }
+}
+
+/// Deserialize an event from the events tree.
+///
+/// Bare JSON objects, and values behind an `EVENT_VALUE_RAW` header, are
+/// read as-is.
+fn decode_event_value(bytes: &[u8]) -> Result<Event, GriteError> {
+    use flate2::read::DeflateDecoder;
+
+    match bytes.first() {
+        Some(&EVENT_VALUE_RAW) => Ok(serde_json::from_slice(&bytes[1..])?),
+        Some(&EVENT_VALUE_DEFLATE) => {
+            Ok(serde_json::from_reader(DeflateDecoder::new(&bytes[1..]))?)
+        }
+        _ => Ok(serde_json::from_slice(bytes)?),
+    }
+}
+
 fn event_key(event_id: &EventId) -> Vec<u8> {
     let mut key = Vec::with_capacity(6 + 32);
     key.extend_from_slice(b"event/");
@@ -948,6 +2250,135 @@ fn issue_events_key(issue_id: &IssueId, ts: u64, event_id: &EventId) -> Vec<u8>
     key
 }
 
+/// Internal metadata keys that integrations may not use
+const RESERVED_META_KEYS: &[&str] = &["last_rebuild_ts", "events_since_rebuild"];
+
+fn integration_meta_prefix(namespace: &str) -> Result<Vec<u8>, GriteError> {
+    if namespace.is_empty() || namespace.contains('\0') {
+        return Err(GriteError::InvalidArgs(format!(
+            "invalid metadata namespace '{}'",
+            namespace.escape_default()
+        )));
+    }
+    let mut prefix = Vec::with_capacity(5 + namespace.len() + 1);
+    prefix.extend_from_slice(b"meta/");
+    prefix.extend_from_slice(namespace.as_bytes());
+    prefix.push(0);
+    Ok(prefix)
+}
+
+fn integration_meta_key(namespace: &str, key: &str) -> Result<Vec<u8>, GriteError> {
+    if key.is_empty() || key.contains('\0') {
+        return Err(GriteError::InvalidArgs(format!(
+            "invalid metadata key '{}'",
+            key.escape_default()
+        )));
+    }
+    if RESERVED_META_KEYS.contains(&key) {
+        return Err(GriteError::InvalidArgs(format!(
+            "metadata key '{}' is reserved",
+            key
+        )));
+    }
+    let mut meta_key = integration_meta_prefix(namespace)?;
+    meta_key.extend_from_slice(key.as_bytes());
+    Ok(meta_key)
+}
+
+/// Number of leading hex digits two issue state keys have in common
+fn shared_hex_prefix(a: &[u8], b: &[u8]) -> usize {
+    let a = a.strip_prefix(b"issue_state/").unwrap_or(a);
+    let b = b.strip_prefix(b"issue_state/").unwrap_or(b);
+    let mut digits = 0;
+    for (x, y) in a.iter().zip(b) {
+        if x == y {
+            digits += 2;
+        } else {
+            if x >> 4 == y >> 4 {
+                digits += 1;
+            }
+            break;
+        }
+    }
+    digits
+}
+
+fn field_index_prefix(key: &str, value: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(6 + key.len() + 1 + value.len() + 1);
+    prefix.extend_from_slice(b"field/");
+    prefix.extend_from_slice(key.as_bytes());
+    prefix.push(b'/');
+    prefix.extend_from_slice(value.as_bytes());
+    prefix.push(b'/');
+    prefix
+}
+
+fn field_index_key(key: &str, value: &str, issue_id: &IssueId) -> Vec<u8> {
+    let mut index_key = field_index_prefix(key, value);
+    index_key.extend_from_slice(issue_id);
+    index_key
+}
+
+fn extract_issue_id_from_field_index_key(key: &[u8]) -> Result<IssueId, GriteError> {
+    // Issue ID is the trailing 16 bytes; key and value may themselves contain '/'
+    if key.len() < 16 {
+        return Err(GriteError::Internal("Invalid field index key".to_string()));
+    }
+    key[key.len() - 16..]
+        .try_into()
+        .map_err(|_| GriteError::Internal("Invalid issue ID in field index key".to_string()))
+}
+
+fn extract_issue_id_from_issue_state_key(key: &[u8]) -> Result<IssueId, GriteError> {
+    key.strip_prefix(b"issue_state/".as_slice())
+        .and_then(|id| id.try_into().ok())
+        .ok_or_else(|| GriteError::Internal("Invalid issue_state key".to_string()))
+}
+
+/// Top-level keys whose values differ between two JSON objects, sorted
+fn differing_fields(stored: &serde_json::Value, expected: &serde_json::Value) -> Vec<String> {
+    let (Some(stored), Some(expected)) = (stored.as_object(), expected.as_object()) else {
+        return if stored == expected {
+            Vec::new()
+        } else {
+            vec!["projection".to_string()]
+        };
+    };
+    let keys: BTreeSet<&String> = stored.keys().chain(expected.keys()).collect();
+    keys.into_iter()
+        .filter(|k| stored.get(*k) != expected.get(*k))
+        .cloned()
+        .collect()
+}
+
+fn milestone_index_prefix(milestone: &str) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(10 + milestone.len() + 1);
+    prefix.extend_from_slice(b"milestone/");
+    prefix.extend_from_slice(milestone.as_bytes());
+    prefix.push(b'/');
+    prefix
+}
+
+/// Milestone index key; the issue ID is the trailing 16 bytes, as in the
+/// field index
+fn milestone_index_key(milestone: &str, issue_id: &IssueId) -> Vec<u8> {
+    let mut index_key = milestone_index_prefix(milestone);
+    index_key.extend_from_slice(issue_id);
+    index_key
+}
+
+fn severity_index_prefix(severity: Severity) -> Vec<u8> {
+    format!("severity/{}/", severity.as_str()).into_bytes()
+}
+
+/// Severity index key; the issue ID is the trailing 16 bytes, as in the
+/// field index
+fn severity_index_key(severity: Severity, issue_id: &IssueId) -> Vec<u8> {
+    let mut index_key = severity_index_prefix(severity);
+    index_key.extend_from_slice(issue_id);
+    index_key
+}
+
 fn label_index_key(label: &str, issue_id: &IssueId) -> Vec<u8> {
     let mut key = Vec::with_capacity(12 + label.len() + 1 + 16);
     key.extend_from_slice(b"label_index/");
@@ -1152,6 +2583,1930 @@ mod tests {
         assert_eq!(issues.len(), 2);
     }
 
+    #[test]
+    fn test_store_list_unassigned_issues() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let mut issue_ids = Vec::new();
+
+        for i in 0..3 {
+            let issue_id = generate_issue_id();
+            let event = make_event(
+                issue_id,
+                actor,
+                1000 + i,
+                EventKind::IssueCreated {
+                    title: format!("Issue {}", i),
+                    body: "Body".to_string(),
+                    labels: vec![],
+                },
+            );
+            store.insert_event(&event).unwrap();
+            issue_ids.push(issue_id);
+        }
+
+        // Assign the first issue; assign then unassign the second
+        store
+            .insert_event(&make_event(
+                issue_ids[0],
+                actor,
+                2000,
+                EventKind::AssigneeAdded {
+                    user: "alice".to_string(),
+                },
+            ))
+            .unwrap();
+        store
+            .insert_event(&make_event(
+                issue_ids[1],
+                actor,
+                2001,
+                EventKind::AssigneeAdded {
+                    user: "bob".to_string(),
+                },
+            ))
+            .unwrap();
+        store
+            .insert_event(&make_event(
+                issue_ids[1],
+                actor,
+                2002,
+                EventKind::AssigneeRemoved {
+                    user: "bob".to_string(),
+                },
+            ))
+            .unwrap();
+
+        let filter = IssueFilter {
+            unassigned: true,
+            ..Default::default()
+        };
+        let issues = store.list_issues(&filter).unwrap();
+        let ids: Vec<IssueId> = issues.iter().map(|s| s.issue_id).collect();
+        assert_eq!(ids, vec![issue_ids[1], issue_ids[2]]);
+
+        let filter = IssueFilter {
+            assignee: Some("alice".to_string()),
+            ..Default::default()
+        };
+        let issues = store.list_issues(&filter).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_id, issue_ids[0]);
+
+        // Contradictory filters are rejected
+        let filter = IssueFilter {
+            assignee: Some("alice".to_string()),
+            unassigned: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            store.list_issues(&filter),
+            Err(GriteError::InvalidArgs(_))
+        ));
+    }
+
+    #[test]
+    fn test_diff_against_snapshot() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+
+        let mut events = Vec::new();
+        let issue_ids: Vec<IssueId> = (0..3).map(|_| generate_issue_id()).collect();
+        for (i, issue_id) in issue_ids.iter().enumerate() {
+            let event = make_event(
+                *issue_id,
+                actor,
+                1000 + i as u64,
+                EventKind::IssueCreated {
+                    title: format!("Issue {}", i),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            );
+            store.insert_event(&event).unwrap();
+            events.push(event);
+        }
+        assert!(store.diff_against_snapshot(&events).unwrap().is_empty());
+
+        // Tamper with one projection directly
+        let mut proj = store.get_issue(&issue_ids[0]).unwrap().unwrap();
+        proj.title = "Tampered".to_string();
+        store
+            .issue_states
+            .insert(
+                issue_state_key(&issue_ids[0]),
+                serde_json::to_vec(&proj).unwrap(),
+            )
+            .unwrap();
+
+        // A later event is expected drift, not corruption
+        store
+            .insert_event(&make_event(
+                issue_ids[1],
+                actor,
+                5000,
+                EventKind::CommentAdded {
+                    body: "after the snapshot".to_string(),
+                    parent_event: None,
+                },
+            ))
+            .unwrap();
+
+        // An issue the store lost entirely
+        store
+            .issue_states
+            .remove(issue_state_key(&issue_ids[2]))
+            .unwrap();
+
+        let drifts = store.diff_against_snapshot(&events).unwrap();
+        let drift_of = |id: &IssueId| {
+            drifts
+                .iter()
+                .find(|d| d.issue_id == *id)
+                .map(|d| d.drift.clone())
+        };
+        assert_eq!(drifts.len(), 3);
+        assert_eq!(
+            drift_of(&issue_ids[0]),
+            Some(ProjectionDrift::Mismatch(vec!["title".to_string()]))
+        );
+        assert_eq!(
+            drift_of(&issue_ids[1]),
+            Some(ProjectionDrift::NewerThanSnapshot)
+        );
+        assert_eq!(drift_of(&issue_ids[2]), Some(ProjectionDrift::Missing));
+    }
+
+    #[test]
+    fn test_blocks_count_and_most_blocking_sort() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+
+        let mut ids = Vec::new();
+        for ts in 1000..1005 {
+            let issue_id = generate_issue_id();
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    ts,
+                    EventKind::IssueCreated {
+                        title: format!("Issue {}", ts),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+            ids.push(issue_id);
+        }
+        // The newest issue blocks the first two outright, and the third
+        // depends on it
+        let blocker = ids[4];
+        let edges = [
+            (blocker, ids[0], DependencyType::Blocks),
+            (blocker, ids[1], DependencyType::Blocks),
+            (ids[2], blocker, DependencyType::DependsOn),
+            (ids[0], ids[3], DependencyType::RelatedTo),
+        ];
+        for (i, (source, target, dep_type)) in edges.into_iter().enumerate() {
+            store
+                .insert_event(&make_event(
+                    source,
+                    actor,
+                    2000 + i as u64,
+                    EventKind::DependencyAdded { target, dep_type },
+                ))
+                .unwrap();
+        }
+        // A closed blocker still counts
+        store
+            .insert_event(&make_event(
+                blocker,
+                actor,
+                3000,
+                EventKind::StateChanged {
+                    state: IssueState::Closed,
+                },
+            ))
+            .unwrap();
+
+        assert_eq!(store.blocks_count(&blocker).unwrap(), 3);
+        assert_eq!(store.blocks_count(&ids[0]).unwrap(), 0);
+
+        let sorted = store
+            .list_issues_sorted(&IssueFilter::default(), IssueSort::MostBlocking)
+            .unwrap();
+        assert_eq!(sorted[0].issue_id, blocker);
+        assert_eq!(sorted[0].blocks_count, 3);
+        // Ties keep creation order
+        let rest: Vec<IssueId> = sorted[1..].iter().map(|s| s.issue_id).collect();
+        assert_eq!(rest, ids[..4]);
+
+        let by_created = store.list_issues(&IssueFilter::default()).unwrap();
+        assert_eq!(by_created[4].issue_id, blocker);
+    }
+
+    #[test]
+    fn test_for_each_issue_stops_on_break() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+
+        for i in 0..5u64 {
+            store
+                .insert_event(&make_event(
+                    generate_issue_id(),
+                    actor,
+                    1000 + i,
+                    EventKind::IssueCreated {
+                        title: format!("Issue {}", i),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+        }
+
+        let mut seen = Vec::new();
+        store
+            .for_each_issue(&IssueFilter::default(), |summary| {
+                seen.push(summary.issue_id);
+                if seen.len() == 2 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+            .unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_ne!(seen[0], seen[1]);
+
+        let mut count = 0;
+        store
+            .for_each_issue(&IssueFilter::default(), |_| {
+                count += 1;
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(count, 5);
+    }
+
+    #[test]
+    fn test_issue_exists() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+
+        assert!(!store.issue_exists(&issue_id).unwrap());
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Exists".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+        assert!(store.issue_exists(&issue_id).unwrap());
+
+        // Only key presence is checked, so an unreadable projection still counts
+        let other = generate_issue_id();
+        store
+            .issue_states
+            .insert(issue_state_key(&other), b"not json".as_slice())
+            .unwrap();
+        assert!(store.get_issue(&other).is_err());
+        assert!(store.issue_exists(&other).unwrap());
+    }
+
+    #[test]
+    fn test_soft_deleted_issues_hidden_from_list() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+
+        let issue_ids: Vec<IssueId> = (0..2).map(|_| generate_issue_id()).collect();
+        for (i, issue_id) in issue_ids.iter().enumerate() {
+            store
+                .insert_event(&make_event(
+                    *issue_id,
+                    actor,
+                    1000 + i as u64,
+                    EventKind::IssueCreated {
+                        title: format!("Issue {}", i),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+        }
+        store
+            .insert_event(&make_event(
+                issue_ids[0],
+                actor,
+                2000,
+                EventKind::IssueDeleted {},
+            ))
+            .unwrap();
+
+        let issues = store.list_issues(&IssueFilter::default()).unwrap();
+        let ids: Vec<IssueId> = issues.iter().map(|s| s.issue_id).collect();
+        assert_eq!(ids, vec![issue_ids[1]]);
+
+        let filter = IssueFilter {
+            include_deleted: true,
+            ..Default::default()
+        };
+        let issues = store.list_issues(&filter).unwrap();
+        let ids: Vec<IssueId> = issues.iter().map(|s| s.issue_id).collect();
+        assert_eq!(ids, issue_ids);
+        assert!(issues[0].deleted);
+        assert!(!issues[1].deleted);
+
+        // An older undelete loses to the delete; a newer one restores it
+        store
+            .insert_event(&make_event(
+                issue_ids[0],
+                actor,
+                1500,
+                EventKind::IssueUndeleted {},
+            ))
+            .unwrap();
+        assert!(store.get_issue(&issue_ids[0]).unwrap().unwrap().deleted);
+        store
+            .insert_event(&make_event(
+                issue_ids[0],
+                actor,
+                3000,
+                EventKind::IssueUndeleted {},
+            ))
+            .unwrap();
+        let issues = store.list_issues(&IssueFilter::default()).unwrap();
+        assert_eq!(issues.len(), 2);
+    }
+
+    #[test]
+    fn test_resolve_issue_id_ambiguous_prefix() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+
+        let mut first = [0u8; 16];
+        first[..3].copy_from_slice(&[0x1a, 0x2b, 0x01]);
+        let mut second = [0u8; 16];
+        second[..3].copy_from_slice(&[0x1a, 0x2b, 0x02]);
+        for issue_id in [first, second] {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    1000,
+                    EventKind::IssueCreated {
+                        title: "Twin".to_string(),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+        }
+
+        match store.resolve_issue_id("1a2b") {
+            Err(GriteError::Ambiguous(msg)) => {
+                assert!(msg.contains(&id_to_hex(&first)), "{}", msg);
+                assert!(msg.contains(&id_to_hex(&second)), "{}", msg);
+            }
+            other => panic!("expected Ambiguous, got {:?}", other),
+        }
+        assert_eq!(store.resolve_issue_id("1A2B01").unwrap(), first);
+        assert_eq!(store.resolve_issue_id("1a2b02").unwrap(), second);
+        assert!(matches!(
+            store.resolve_issue_id("1a2b03"),
+            Err(GriteError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_existing_issues_lists_missing() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let ids: Vec<IssueId> = (0..2).map(|_| generate_issue_id()).collect();
+        for issue_id in &ids {
+            store
+                .insert_event(&make_event(
+                    *issue_id,
+                    [1u8; 16],
+                    1000,
+                    EventKind::IssueCreated {
+                        title: "Batch".to_string(),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+        }
+        let first = id_to_hex(&ids[0]);
+        let second = id_to_hex(&ids[1]);
+
+        let resolved = store
+            .resolve_existing_issues(&[first[..8].to_string(), second.clone(), first.clone()])
+            .unwrap();
+        assert_eq!(resolved, ids);
+
+        let absent = id_to_hex(&generate_issue_id());
+        match store.resolve_existing_issues(&[first, absent.clone(), "ffffffff".to_string()]) {
+            Err(GriteError::NotFound(msg)) => {
+                assert!(msg.contains(&absent), "{}", msg);
+                assert!(msg.contains("ffffffff"), "{}", msg);
+            }
+            other => panic!("expected NotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_min_unique_id_prefix() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+        assert_eq!(store.min_unique_id_prefix().unwrap(), 1);
+
+        // Three IDs sharing the first 10 hex digits, two of them 11
+        let mut ids = [[0xab; 16]; 3];
+        ids[0][5] = 0x10;
+        ids[1][5] = 0x20;
+        ids[2][5] = 0x21;
+        for (i, issue_id) in ids.into_iter().enumerate() {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    1000 + i as u64,
+                    EventKind::IssueCreated {
+                        title: format!("Issue {}", i),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+        }
+
+        // 8 characters no longer tell these apart
+        assert_eq!(store.min_unique_id_prefix().unwrap(), 12);
+    }
+
+    #[test]
+    fn test_integration_meta_is_namespaced() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        store.put_meta("ci", "last_run", b"42").unwrap();
+        store.put_meta("ci", "branch", b"main").unwrap();
+        store.put_meta("cis", "other", b"x").unwrap();
+        store.rebuild().unwrap();
+
+        assert_eq!(
+            store.get_meta("ci", "last_run").unwrap(),
+            Some(b"42".to_vec())
+        );
+        assert_eq!(store.get_meta("cis", "last_run").unwrap(), None);
+
+        // Only this namespace's keys come back, never internal metadata
+        let entries = store.list_meta("ci").unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("branch".to_string(), b"main".to_vec()),
+                ("last_run".to_string(), b"42".to_vec()),
+            ]
+        );
+        assert!(store.metadata.get("last_rebuild_ts").unwrap().is_some());
+
+        // Reserved internal keys cannot be read or written through this API
+        for key in RESERVED_META_KEYS {
+            assert!(matches!(
+                store.put_meta("ci", key, b"0"),
+                Err(GriteError::InvalidArgs(_))
+            ));
+            assert!(store.get_meta("ci", key).is_err());
+        }
+        assert!(store.put_meta("", "k", b"v").is_err());
+    }
+
+    #[test]
+    fn test_recount_since_rebuild_repairs_drift() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Drift".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+
+        // Without a rebuild timestamp every event counts
+        store.metadata.remove("last_rebuild_ts").unwrap();
+        assert_eq!(store.recount_since_rebuild().unwrap(), 1);
+
+        store.rebuild().unwrap();
+        let rebuilt_at = store.stats(dir.path()).unwrap().last_rebuild_ts.unwrap();
+        for offset in [1000, 2000] {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    rebuilt_at + offset,
+                    EventKind::CommentAdded {
+                        body: format!("After rebuild {}", offset),
+                        parent_event: None,
+                    },
+                ))
+                .unwrap();
+        }
+
+        store
+            .metadata
+            .insert("events_since_rebuild", &99u64.to_le_bytes())
+            .unwrap();
+        assert_eq!(store.stats(dir.path()).unwrap().events_since_rebuild, 99);
+
+        assert_eq!(store.recount_since_rebuild().unwrap(), 2);
+        assert_eq!(store.stats(dir.path()).unwrap().events_since_rebuild, 2);
+    }
+
+    #[test]
+    fn test_rebuild_skipping_errors() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Fine".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+
+        // A comment on an issue that was never created cannot be projected,
+        // but is still stored
+        let orphan = make_event(
+            generate_issue_id(),
+            actor,
+            2000,
+            EventKind::CommentAdded {
+                body: "Lost".to_string(),
+                parent_event: None,
+            },
+        );
+        assert!(store.insert_event(&orphan).is_err());
+
+        assert!(store.rebuild().is_err());
+
+        let stats = store.rebuild_skipping_errors().unwrap();
+        assert_eq!(stats.event_count, 2);
+        assert_eq!(stats.issue_count, 1);
+        assert_eq!(stats.skipped.len(), 1);
+        assert_eq!(stats.skipped[0].0, orphan.event_id);
+        assert!(stats.skipped[0].1.contains("IssueCreated"));
+        assert!(store.issue_exists(&issue_id).unwrap());
+
+        let events = store.get_all_events().unwrap();
+        assert!(store.rebuild_from_events(&events).is_err());
+        let stats = store.rebuild_from_events_skipping_errors(&events).unwrap();
+        assert_eq!(stats.skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_rebuild_plan_leaves_metadata_unchanged() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        let created = make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Issue".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        );
+        store.insert_event(&created).unwrap();
+        store.rebuild().unwrap();
+        for ts in [2000, 3000] {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    ts,
+                    EventKind::CommentAdded {
+                        body: format!("Comment {}", ts),
+                        parent_event: None,
+                    },
+                ))
+                .unwrap();
+        }
+        store
+            .insert_event(&make_event(
+                crate::context::PROJECT_CONTEXT_ISSUE_ID,
+                actor,
+                4000,
+                EventKind::ProjectContextUpdated {
+                    key: "lang".to_string(),
+                    value: "rust".to_string(),
+                },
+            ))
+            .unwrap();
+
+        let before = store.stats(dir.path()).unwrap();
+        let plan = store.rebuild_plan().unwrap();
+        let after = store.stats(dir.path()).unwrap();
+
+        assert_eq!(plan.event_count, 4);
+        assert_eq!(plan.issue_count, 1);
+        assert_eq!(plan.kinds.get("IssueCreated"), Some(&1));
+        assert_eq!(plan.kinds.get("CommentAdded"), Some(&2));
+        assert_eq!(plan.kinds.get("ProjectContextUpdated"), Some(&1));
+
+        assert!(before.last_rebuild_ts.is_some());
+        assert_eq!(after.last_rebuild_ts, before.last_rebuild_ts);
+        assert_eq!(after.events_since_rebuild, 3);
+        assert_eq!(after.events_since_rebuild, before.events_since_rebuild);
+        assert_eq!(
+            store.get_issue(&issue_id).unwrap().unwrap().comments.len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_store_open_with_options() {
+        let dir = tempdir().unwrap();
+        let options = StoreOptions {
+            cache_mb: Some(8),
+            flush_every_ms: Some(50),
+            ..Default::default()
+        };
+        let issue_id = generate_issue_id();
+        let event = make_event(
+            issue_id,
+            [1u8; 16],
+            1000,
+            EventKind::IssueCreated {
+                title: "Tuned".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        );
+
+        {
+            let store = GriteStore::open_locked_with_options(dir.path(), &options).unwrap();
+            store.insert_event(&event).unwrap();
+            store.flush().unwrap();
+        }
+
+        // Data written with custom options is readable with the defaults. The
+        // background flusher holds sled's file lock until its next tick, so
+        // allow a few retries before giving up.
+        let mut attempts = 0;
+        let store = loop {
+            match GriteStore::open(dir.path()) {
+                Ok(store) => break store,
+                Err(_) if attempts < 20 => {
+                    attempts += 1;
+                    std::thread::sleep(std::time::Duration::from_millis(25));
+                }
+                Err(e) => panic!("reopen failed: {}", e),
+            }
+        };
+        assert_eq!(store.get_issue(&issue_id).unwrap().unwrap().title, "Tuned");
+        assert_eq!(store.get_event(&event.event_id).unwrap(), Some(event));
+    }
+
+    #[test]
+    fn test_events_of_kind() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_1 = generate_issue_id();
+        let issue_2 = generate_issue_id();
+        let created = |title: &str| EventKind::IssueCreated {
+            title: title.to_string(),
+            body: String::new(),
+            labels: vec![],
+        };
+        let closed = EventKind::StateChanged {
+            state: IssueState::Closed,
+        };
+        let events = vec![
+            make_event(issue_1, actor, 1000, created("One")),
+            make_event(issue_2, actor, 1100, created("Two")),
+            make_event(issue_2, actor, 1500, closed.clone()),
+            make_event(
+                issue_1,
+                actor,
+                2000,
+                EventKind::CommentAdded {
+                    body: "Hi".to_string(),
+                    parent_event: None,
+                },
+            ),
+            make_event(issue_1, actor, 3000, closed.clone()),
+        ];
+        for event in &events {
+            store.insert_event(event).unwrap();
+        }
+
+        let tag = EventKind::tag_for_name("state_changed").unwrap();
+        let state_changes = store.events_of_kind(tag).unwrap();
+        let mut expected = vec![events[2].clone(), events[4].clone()];
+        expected.sort_by_key(|e| e.issue_id);
+        assert_eq!(state_changes, expected);
+
+        let tag = EventKind::tag_for_name("CommentAdded").unwrap();
+        let comments = store.events_of_kind(tag).unwrap();
+        assert_eq!(comments, vec![events[3].clone()]);
+        assert!(store.events_of_kind(99).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_actor_last_activity() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let active = [1u8; 16];
+        let idle = [2u8; 16];
+        assert!(store.actor_last_activity().unwrap().is_empty());
+
+        let issue_id = generate_issue_id();
+        store
+            .insert_event(&make_event(
+                issue_id,
+                active,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Active".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+        store
+            .insert_event(&make_event(
+                issue_id,
+                active,
+                3000,
+                EventKind::CommentAdded {
+                    body: "Later".to_string(),
+                    parent_event: None,
+                },
+            ))
+            .unwrap();
+
+        let activity = store.actor_last_activity().unwrap();
+        assert_eq!(activity.get(&active), Some(&3000));
+        assert_eq!(activity.get(&idle), None);
+    }
+
+    #[test]
+    fn test_time_by_assignee() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let alice = [1u8; 16];
+        let unnamed = [0xab; 16];
+        let names = HashMap::from([(alice, "alice".to_string())]);
+        assert!(store.time_by_assignee(&names).unwrap().is_empty());
+
+        let issue_1 = generate_issue_id();
+        let issue_2 = generate_issue_id();
+        for issue_id in [issue_1, issue_2] {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    alice,
+                    500,
+                    EventKind::IssueCreated {
+                        title: "Tracked".to_string(),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+        }
+        let logs = [
+            (issue_1, alice, 1000, 30),
+            (issue_2, alice, 2000, 15),
+            (issue_1, unnamed, 3000, 60),
+        ];
+        for (issue_id, actor, ts, minutes) in logs {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    ts,
+                    EventKind::TimeLogged {
+                        minutes,
+                        note: None,
+                    },
+                ))
+                .unwrap();
+        }
+        // Estimates are not logged time
+        store
+            .insert_event(&make_event(
+                issue_1,
+                alice,
+                4000,
+                EventKind::TimeEstimated { minutes: 500 },
+            ))
+            .unwrap();
+
+        assert_eq!(
+            store.time_by_assignee(&names).unwrap(),
+            vec![("abababab".to_string(), 60), ("alice".to_string(), 45)]
+        );
+    }
+
+    #[test]
+    fn test_response_time_stats() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let creator = [1u8; 16];
+        let responder = [2u8; 16];
+
+        // Responses after 1s, 2s and 6s, plus one unanswered issue
+        for response_ms in [Some(1000), Some(2000), Some(6000), None] {
+            let issue_id = generate_issue_id();
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    creator,
+                    1000,
+                    EventKind::IssueCreated {
+                        title: "Waiting".to_string(),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ))
+                .unwrap();
+            if let Some(ms) = response_ms {
+                store
+                    .insert_event(&make_event(
+                        issue_id,
+                        responder,
+                        1000 + ms,
+                        EventKind::CommentAdded {
+                            body: "On it".to_string(),
+                            parent_event: None,
+                        },
+                    ))
+                    .unwrap();
+            }
+        }
+
+        let stats = store.response_time_stats().unwrap();
+        assert_eq!(
+            stats,
+            ResponseTimeStats {
+                responded: 3,
+                unanswered: 1,
+                mean_ms: Some(3000),
+                median_ms: Some(2000),
+            }
+        );
+    }
+
+    #[test]
+    fn test_event_preimage() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        let kind = EventKind::IssueCreated {
+            title: "Hashed".to_string(),
+            body: String::new(),
+            labels: vec![],
+        };
+        let event = make_event(issue_id, actor, 1000, kind.clone());
+        store.insert_event(&event).unwrap();
+
+        let preimage = store.event_preimage(&event.event_id).unwrap();
+        assert_eq!(preimage.event_id, event.event_id);
+        assert_eq!(
+            preimage.computed_id,
+            compute_event_id(&issue_id, &actor, 1000, None, &kind)
+        );
+        assert_eq!(
+            preimage.preimage,
+            crate::hash::build_canonical_cbor(&issue_id, &actor, 1000, None, &kind)
+        );
+
+        assert!(matches!(
+            store.event_preimage(&[0xaa; 32]),
+            Err(GriteError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_largest_events() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        assert!(store.largest_events(5).unwrap().is_empty());
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Bloated".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+        for i in 0..5 {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    2000 + i,
+                    EventKind::CommentAdded {
+                        body: format!("Small comment {}", i),
+                        parent_event: None,
+                    },
+                ))
+                .unwrap();
+        }
+
+        // Random hex barely compresses, so the stored value stays large
+        let noise: String = (0..2000)
+            .map(|_| crate::types::ids::id_to_hex(&generate_issue_id()))
+            .collect();
+        let large = make_event(
+            issue_id,
+            actor,
+            3000,
+            EventKind::CommentAdded {
+                body: noise,
+                parent_event: None,
+            },
+        );
+        store.insert_event(&large).unwrap();
+
+        let largest = store.largest_events(3).unwrap();
+        assert_eq!(largest.len(), 3);
+        assert_eq!(largest[0].event_id, large.event_id);
+        assert_eq!(largest[0].issue_id, issue_id);
+        assert_eq!(largest[0].kind, "CommentAdded");
+        assert!(largest[0].size_bytes > DEFAULT_LARGE_EVENT_WARN_BYTES / 4);
+        assert!(largest[1].size_bytes < 1024);
+        assert!(largest[1].size_bytes >= largest[2].size_bytes);
+    }
+
+    #[test]
+    fn test_stats_include_context_index() {
+        use crate::types::event::SymbolInfo;
+
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let stats = store.stats(dir.path()).unwrap();
+        assert_eq!(stats.context_file_count, 0);
+        assert_eq!(stats.context_symbol_count, 0);
+        assert_eq!(stats.last_context_update_ts, None);
+
+        let actor = [1u8; 16];
+        let symbol = |name: &str| SymbolInfo {
+            name: name.to_string(),
+            kind: "function".to_string(),
+            line_start: 1,
+            line_end: 5,
+        };
+        for (ts, path, symbols) in [
+            (1000, "src/a.rs", vec![symbol("alpha"), symbol("beta")]),
+            (2000, "src/b.rs", vec![symbol("gamma")]),
+        ] {
+            store
+                .insert_event(&make_event(
+                    [0u8; 16],
+                    actor,
+                    ts,
+                    EventKind::ContextUpdated {
+                        path: path.to_string(),
+                        language: "rust".to_string(),
+                        symbols,
+                        summary: String::new(),
+                        content_hash: [0; 32],
+                    },
+                ))
+                .unwrap();
+        }
+
+        let stats = store.stats(dir.path()).unwrap();
+        assert_eq!(stats.context_file_count, 2);
+        assert_eq!(stats.context_symbol_count, 3);
+        assert_eq!(stats.last_context_update_ts, Some(2000));
+    }
+
+    #[test]
+    fn test_reindex_issue_events_restores_history() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        let events: Vec<Event> = [
+            EventKind::IssueCreated {
+                title: "Issue".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+            EventKind::CommentAdded {
+                body: "first".to_string(),
+                parent_event: None,
+            },
+            EventKind::CommentAdded {
+                body: "second".to_string(),
+                parent_event: None,
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, kind)| make_event(issue_id, actor, 1000 + i as u64, kind))
+        .collect();
+        for event in &events {
+            store.insert_event(event).unwrap();
+        }
+        assert!(store.check_issue_events_index().unwrap().is_clean());
+
+        // Lose one entry and index another event twice, under a wrong timestamp
+        store
+            .issue_events
+            .remove(issue_events_key(&issue_id, 1001, &events[1].event_id))
+            .unwrap();
+        store
+            .issue_events
+            .insert(issue_events_key(&issue_id, 9999, &events[2].event_id), &[])
+            .unwrap();
+        assert_eq!(store.get_issue_events(&issue_id).unwrap().len(), 3);
+        assert!(!store
+            .get_issue_events(&issue_id)
+            .unwrap()
+            .iter()
+            .any(|e| e.event_id == events[1].event_id));
+
+        let expected = IssueEventsReindex {
+            missing: 1,
+            stale: 1,
+        };
+        assert_eq!(store.check_issue_events_index().unwrap(), expected);
+        assert_eq!(store.reindex_issue_events().unwrap(), expected);
+
+        let ids: Vec<EventId> = store
+            .get_issue_events(&issue_id)
+            .unwrap()
+            .iter()
+            .map(|e| e.event_id)
+            .collect();
+        let want: Vec<EventId> = events.iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, want);
+        assert!(store.reindex_issue_events().unwrap().is_clean());
+    }
+
+    #[test]
+    fn test_recent_context_files_orders_by_latest_update() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let index = |ts: u64, path: &str| {
+            store
+                .insert_event(&make_event(
+                    [0u8; 16],
+                    [1u8; 16],
+                    ts,
+                    EventKind::ContextUpdated {
+                        path: path.to_string(),
+                        language: "rust".to_string(),
+                        symbols: vec![],
+                        summary: String::new(),
+                        content_hash: [0; 32],
+                    },
+                ))
+                .unwrap();
+        };
+        let recent = |limit: Option<usize>| {
+            store
+                .recent_context_files(limit)
+                .unwrap()
+                .into_iter()
+                .map(|f| (f.path, f.version.ts_unix_ms))
+                .collect::<Vec<_>>()
+        };
+
+        index(1000, "src/a.rs");
+        index(2000, "src/b.rs");
+        assert_eq!(
+            recent(None),
+            vec![
+                ("src/b.rs".to_string(), 2000),
+                ("src/a.rs".to_string(), 1000)
+            ]
+        );
+
+        // Re-indexing moves a file to the front; a stale update does not
+        index(3000, "src/a.rs");
+        index(1500, "src/b.rs");
+        assert_eq!(
+            recent(None),
+            vec![
+                ("src/a.rs".to_string(), 3000),
+                ("src/b.rs".to_string(), 2000)
+            ]
+        );
+        assert_eq!(recent(Some(1)), vec![("src/a.rs".to_string(), 3000)]);
+    }
+
+    #[test]
+    fn test_query_symbols_limit() {
+        use crate::types::event::SymbolInfo;
+
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let symbols: Vec<SymbolInfo> = (0..50)
+            .map(|i| SymbolInfo {
+                name: format!("handle_{:02}", i),
+                kind: "function".to_string(),
+                line_start: i,
+                line_end: i,
+            })
+            .collect();
+        store
+            .insert_event(&make_event(
+                [0u8; 16],
+                [1u8; 16],
+                1000,
+                EventKind::ContextUpdated {
+                    path: "src/handlers.rs".to_string(),
+                    language: "rust".to_string(),
+                    symbols,
+                    summary: String::new(),
+                    content_hash: [0; 32],
+                },
+            ))
+            .unwrap();
+
+        assert_eq!(store.query_symbols("handle_", None).unwrap().len(), 50);
+
+        // The scan stops at the limit, so the first keys in order are returned
+        let limited = store.query_symbols("handle_", Some(5)).unwrap();
+        let names: Vec<&str> = limited.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "handle_00",
+                "handle_01",
+                "handle_02",
+                "handle_03",
+                "handle_04"
+            ]
+        );
+        assert!(store.query_symbols("handle_", Some(0)).unwrap().is_empty());
+        assert_eq!(
+            store.query_symbols("handle_4", Some(100)).unwrap().len(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_issue_digest_changes_with_history() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        assert!(matches!(
+            store.issue_digest(&issue_id),
+            Err(GriteError::NotFound(_))
+        ));
+
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Digest".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+        let before = store.issue_digest(&issue_id).unwrap();
+        assert_eq!(store.issue_digest(&issue_id).unwrap(), before);
+
+        let comment = make_event(
+            issue_id,
+            actor,
+            2000,
+            EventKind::CommentAdded {
+                body: "Changed".to_string(),
+                parent_event: None,
+            },
+        );
+        store.insert_event(&comment).unwrap();
+        let after = store.issue_digest(&issue_id).unwrap();
+        assert_ne!(after, before);
+        let event_ids = store
+            .get_issue_events(&issue_id)
+            .unwrap()
+            .iter()
+            .rev()
+            .map(|e| e.event_id)
+            .collect::<Vec<_>>();
+        assert_eq!(crate::hash::compute_issue_digest(event_ids), after);
+    }
+
+    #[test]
+    fn test_projection_keeps_only_latest_body() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Bodies".to_string(),
+                    body: "Draft 0".to_string(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+        for i in 1..=3 {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    1000 + i,
+                    EventKind::IssueUpdated {
+                        title: None,
+                        body: Some(format!("Draft {}", i)),
+                    },
+                ))
+                .unwrap();
+        }
+        // A title-only edit is not a body revision
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                2000,
+                EventKind::IssueUpdated {
+                    title: Some("Renamed".to_string()),
+                    body: None,
+                },
+            ))
+            .unwrap();
+
+        let proj = store.get_issue(&issue_id).unwrap().unwrap();
+        assert_eq!(proj.body, "Draft 3");
+        assert_eq!(proj.body_revisions, 3);
+
+        // Every earlier body is still in the events
+        let mut bodies: Vec<String> = store
+            .get_issue_events(&issue_id)
+            .unwrap()
+            .into_iter()
+            .filter_map(|e| match e.kind {
+                EventKind::IssueCreated { body, .. } => Some(body),
+                EventKind::IssueUpdated { body, .. } => body,
+                _ => None,
+            })
+            .collect();
+        bodies.sort();
+        assert_eq!(bodies, ["Draft 0", "Draft 1", "Draft 2", "Draft 3"]);
+        assert!(proj.body_history.is_empty());
+
+        // With `body_history` set, the newest superseded bodies stay as well
+        let kept_dir = tempdir().unwrap();
+        let options = StoreOptions {
+            body_history: Some(2),
+            ..Default::default()
+        };
+        let kept = GriteStore::open_with_options(kept_dir.path(), &options).unwrap();
+        for event in store.get_issue_events(&issue_id).unwrap() {
+            kept.insert_event(&event).unwrap();
+        }
+        let proj = kept.get_issue(&issue_id).unwrap().unwrap();
+        assert_eq!(proj.body, "Draft 3");
+        assert_eq!(proj.body_revisions, 3);
+        let history: Vec<&str> = proj.body_history.iter().map(|r| r.body.as_str()).collect();
+        assert_eq!(history, ["Draft 2", "Draft 1"]);
+    }
+
+    #[test]
+    fn test_projections_from_events_match_store() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor_a = [1u8; 16];
+        let actor_b = [2u8; 16];
+        let issue_1 = generate_issue_id();
+        let issue_2 = generate_issue_id();
+        let created = |title: &str| EventKind::IssueCreated {
+            title: title.to_string(),
+            body: "Body".to_string(),
+            labels: vec!["bug".to_string()],
+        };
+        let events = vec![
+            make_event(issue_1, actor_a, 1000, created("One")),
+            make_event(issue_2, actor_b, 1100, created("Two")),
+            make_event(
+                issue_1,
+                actor_b,
+                2000,
+                EventKind::CommentAdded {
+                    body: "First".to_string(),
+                    parent_event: None,
+                },
+            ),
+            make_event(
+                crate::context::PROJECT_CONTEXT_ISSUE_ID,
+                actor_a,
+                2100,
+                EventKind::ProjectContextUpdated {
+                    key: "lang".to_string(),
+                    value: "rust".to_string(),
+                },
+            ),
+            make_event(
+                issue_1,
+                actor_a,
+                3000,
+                EventKind::IssueUpdated {
+                    title: Some("One, renamed".to_string()),
+                    body: None,
+                },
+            ),
+            make_event(
+                issue_2,
+                actor_a,
+                3000,
+                EventKind::FieldSet {
+                    key: "severity".to_string(),
+                    value: Some("high".to_string()),
+                },
+            ),
+            make_event(
+                issue_2,
+                actor_b,
+                4000,
+                EventKind::LabelRemoved {
+                    label: "bug".to_string(),
+                },
+            ),
+        ];
+        for event in &events {
+            store.insert_event(event).unwrap();
+        }
+
+        let projections = GriteStore::projections_from_events(&events, 0).unwrap();
+        assert_eq!(projections.len(), 2);
+        for (issue_id, projection) in &projections {
+            assert_eq!(
+                store.get_issue(issue_id).unwrap().as_ref(),
+                Some(projection)
+            );
+        }
+
+        // Same result as a real rebuild
+        let stats = store.rebuild_from_events(&events).unwrap();
+        assert_eq!(stats.issue_count, projections.len());
+        for (issue_id, projection) in &projections {
+            assert_eq!(
+                store.get_issue(issue_id).unwrap().as_ref(),
+                Some(projection)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rebuild_from_events_keeps_signed_copy() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        let created = make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Signed later".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        );
+        let comment = make_event(
+            issue_id,
+            actor,
+            2000,
+            EventKind::CommentAdded {
+                body: "Once".to_string(),
+                parent_event: None,
+            },
+        );
+        let mut signed = comment.clone();
+        signed.sig = Some(vec![9; 64]);
+
+        let stats = store
+            .rebuild_from_events(&[created, comment.clone(), signed])
+            .unwrap();
+        assert_eq!(stats.event_count, 2);
+        assert_eq!(
+            store.get_issue(&issue_id).unwrap().unwrap().comments.len(),
+            1
+        );
+        let stored = store.get_event(&comment.event_id).unwrap().unwrap();
+        assert_eq!(stored.sig, Some(vec![9; 64]));
+    }
+
+    #[test]
+    fn test_store_list_by_field() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        store
+            .insert_event(&make_event(
+                issue_id,
+                actor,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Issue".to_string(),
+                    body: "Body".to_string(),
+                    labels: vec![],
+                },
+            ))
+            .unwrap();
+
+        let set_severity = |ts: u64, value: &str| {
+            store
+                .insert_event(&make_event(
+                    issue_id,
+                    actor,
+                    ts,
+                    EventKind::FieldSet {
+                        key: "severity".to_string(),
+                        value: Some(value.to_string()),
+                    },
+                ))
+                .unwrap();
+        };
+        let by_severity = |value: &str| {
+            let filter = IssueFilter {
+                field: Some(("severity".to_string(), value.to_string())),
+                ..Default::default()
+            };
+            store.list_issues(&filter).unwrap()
+        };
+
+        set_severity(2000, "high");
+        assert_eq!(by_severity("high").len(), 1);
+
+        set_severity(3000, "low");
+        assert!(by_severity("high").is_empty());
+        let issues = by_severity("low");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].issue_id, issue_id);
+
+        // The index survives a rebuild
+        store.rebuild().unwrap();
+        assert!(by_severity("high").is_empty());
+        assert_eq!(by_severity("low").len(), 1);
+
+        assert!(IssueFilter::parse_field("severity").is_err());
+        assert_eq!(
+            IssueFilter::parse_field("severity=high").unwrap(),
+            ("severity".to_string(), "high".to_string())
+        );
+    }
+
+    #[test]
+    fn test_store_milestone_rollups() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let insert = |issue_id: IssueId, ts: u64, kind: EventKind| {
+            store
+                .insert_event(&make_event(issue_id, actor, ts, kind))
+                .unwrap();
+        };
+        let set_milestone = |issue_id: IssueId, ts: u64, milestone: Option<&str>| {
+            insert(
+                issue_id,
+                ts,
+                EventKind::MilestoneSet {
+                    milestone: milestone.map(str::to_string),
+                },
+            );
+        };
+        let in_milestone = |name: &str| {
+            let filter = IssueFilter {
+                milestone: Some(name.to_string()),
+                ..Default::default()
+            };
+            store.list_issues(&filter).unwrap()
+        };
+
+        let issues: Vec<IssueId> = (0..3).map(|_| generate_issue_id()).collect();
+        for issue_id in &issues {
+            insert(
+                *issue_id,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Issue".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            );
+        }
+        set_milestone(issues[0], 2000, Some("v1"));
+        set_milestone(issues[1], 2000, Some("v1"));
+        set_milestone(issues[2], 2000, Some("v2"));
+        insert(
+            issues[1],
+            3000,
+            EventKind::StateChanged {
+                state: IssueState::Closed,
+            },
+        );
+
+        let expected = vec![
+            MilestoneRollup {
+                milestone: "v1".to_string(),
+                open: 1,
+                closed: 1,
+            },
+            MilestoneRollup {
+                milestone: "v2".to_string(),
+                open: 1,
+                closed: 0,
+            },
+        ];
+        assert_eq!(store.milestone_rollups().unwrap(), expected);
+        assert_eq!(in_milestone("v1").len(), 2);
+
+        // Clearing drops the index entry, so the milestone disappears
+        set_milestone(issues[2], 4000, None);
+        assert!(in_milestone("v2").is_empty());
+        assert_eq!(store.milestone_rollups().unwrap(), expected[..1].to_vec());
+        assert_eq!(
+            store.get_issue(&issues[2]).unwrap().unwrap().milestone,
+            None
+        );
+
+        // An older set cannot bring the cleared milestone back
+        set_milestone(issues[2], 3500, Some("v2"));
+        assert!(in_milestone("v2").is_empty());
+
+        // The index survives a rebuild
+        store.rebuild().unwrap();
+        assert_eq!(store.milestone_rollups().unwrap(), expected[..1].to_vec());
+    }
+
+    #[test]
+    fn test_store_severity_tracks_latest() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let insert = |issue_id: IssueId, ts: u64, kind: EventKind| {
+            store
+                .insert_event(&make_event(issue_id, actor, ts, kind))
+                .unwrap();
+        };
+        let with_severity = |severity: Severity| {
+            let filter = IssueFilter {
+                severity: Some(severity),
+                ..Default::default()
+            };
+            store
+                .list_issues(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|s| s.issue_id)
+                .collect::<Vec<_>>()
+        };
+
+        let issues: Vec<IssueId> = (0..2).map(|_| generate_issue_id()).collect();
+        for issue_id in &issues {
+            insert(
+                *issue_id,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Issue".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            );
+        }
+        let issue_id = issues[0];
+        insert(
+            issue_id,
+            2000,
+            EventKind::SeveritySet {
+                severity: Severity::Sev1,
+            },
+        );
+        assert_eq!(with_severity(Severity::Sev1), vec![issue_id]);
+        assert_eq!(with_severity(Severity::None), vec![issues[1]]);
+
+        // Lowering the severity moves the issue between filters
+        insert(
+            issue_id,
+            3000,
+            EventKind::SeveritySet {
+                severity: Severity::Sev3,
+            },
+        );
+        assert!(with_severity(Severity::Sev1).is_empty());
+        assert_eq!(with_severity(Severity::Sev3), vec![issue_id]);
+        let proj = store.get_issue(&issue_id).unwrap().unwrap();
+        assert_eq!(proj.severity, Some(Severity::Sev3));
+
+        // Clearing drops the index entry
+        insert(
+            issue_id,
+            4000,
+            EventKind::SeveritySet {
+                severity: Severity::None,
+            },
+        );
+        assert!(with_severity(Severity::Sev3).is_empty());
+        assert_eq!(store.severity_index.len(), 0);
+        assert_eq!(store.get_issue(&issue_id).unwrap().unwrap().severity, None);
+
+        // An older set cannot bring the cleared severity back
+        insert(
+            issue_id,
+            3500,
+            EventKind::SeveritySet {
+                severity: Severity::Sev2,
+            },
+        );
+        assert!(with_severity(Severity::Sev2).is_empty());
+
+        // The index survives a rebuild
+        insert(
+            issue_id,
+            5000,
+            EventKind::SeveritySet {
+                severity: Severity::Sev4,
+            },
+        );
+        store.rebuild().unwrap();
+        assert_eq!(with_severity(Severity::Sev4), vec![issue_id]);
+    }
+
+    #[test]
+    fn test_store_blocking_chain() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let insert = |issue_id: IssueId, ts: u64, kind: EventKind| {
+            store
+                .insert_event(&make_event(issue_id, actor, ts, kind))
+                .unwrap();
+        };
+        let [a, b, c, d] = [(); 4].map(|_| generate_issue_id());
+        for issue_id in [a, b, c, d] {
+            insert(
+                issue_id,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Issue".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            );
+        }
+        assert!(store.blocking_chain(&c).unwrap().is_empty());
+
+        // A blocks B, B blocks C
+        for (source, target) in [(a, b), (b, c)] {
+            insert(
+                source,
+                2000,
+                EventKind::DependencyAdded {
+                    target,
+                    dep_type: DependencyType::Blocks,
+                },
+            );
+        }
+        assert_eq!(store.blocking_chain(&c).unwrap(), vec![vec![b, a]]);
+        assert_eq!(store.blocking_chain(&b).unwrap(), vec![vec![a]]);
+        assert!(store.blocking_chain(&a).unwrap().is_empty());
+
+        // C also depends on D, giving a second path
+        insert(
+            c,
+            3000,
+            EventKind::DependencyAdded {
+                target: d,
+                dep_type: DependencyType::DependsOn,
+            },
+        );
+        let paths = store.blocking_chain(&c).unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains(&vec![b, a]));
+        assert!(paths.contains(&vec![d]));
+
+        // Closed blockers no longer count, so closing A shortens the chain
+        insert(
+            a,
+            4000,
+            EventKind::StateChanged {
+                state: IssueState::Closed,
+            },
+        );
+        let paths = store.blocking_chain(&c).unwrap();
+        assert!(paths.contains(&vec![b]));
+        assert!(paths.contains(&vec![d]));
+    }
+
+    #[test]
+    fn test_store_dependency_tree() {
+        let dir = tempdir().unwrap();
+        let store = GriteStore::open(dir.path()).unwrap();
+
+        let actor = [1u8; 16];
+        let insert = |issue_id: IssueId, ts: u64, kind: EventKind| {
+            store
+                .insert_event(&make_event(issue_id, actor, ts, kind))
+                .unwrap();
+        };
+        let [a, b, c, d] = [(); 4].map(|_| generate_issue_id());
+        for issue_id in [a, b, c, d] {
+            insert(
+                issue_id,
+                1000,
+                EventKind::IssueCreated {
+                    title: "Issue".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            );
+        }
+        // A depends on B and C, B depends on A (a cycle), D is only related
+        for (source, target, dep_type) in [
+            (a, b, DependencyType::DependsOn),
+            (a, c, DependencyType::DependsOn),
+            (b, a, DependencyType::DependsOn),
+            (c, d, DependencyType::RelatedTo),
+        ] {
+            insert(
+                source,
+                2000,
+                EventKind::DependencyAdded { target, dep_type },
+            );
+        }
+
+        let tree = store.dependency_tree(&a, 10).unwrap();
+        assert_eq!(tree.issue_id, a);
+        assert_eq!(tree.dep_type, None);
+        let children: Vec<IssueId> = tree.children.iter().map(|n| n.issue_id).collect();
+        assert_eq!(children.len(), 2);
+        assert!(children.contains(&b) && children.contains(&c));
+
+        // The cycle back to A is shown once, not expanded
+        let b_node = tree.children.iter().find(|n| n.issue_id == b).unwrap();
+        assert_eq!(b_node.children.len(), 1);
+        assert_eq!(b_node.children[0].issue_id, a);
+        assert!(b_node.children[0].cycle);
+        assert!(b_node.children[0].children.is_empty());
+
+        // related_to edges are not followed
+        let c_node = tree.children.iter().find(|n| n.issue_id == c).unwrap();
+        assert!(c_node.children.is_empty());
+        assert!(!c_node.truncated);
+
+        // The depth limit cuts off B's dependencies
+        let shallow = store.dependency_tree(&a, 1).unwrap();
+        let b_node = shallow.children.iter().find(|n| n.issue_id == b).unwrap();
+        assert!(b_node.truncated);
+        assert!(b_node.children.is_empty());
+    }
+
+    #[test]
+    fn test_store_compresses_large_events() {
+        let dir = tempdir().unwrap();
+        let options = StoreOptions {
+            compress_events: true,
+            ..Default::default()
+        };
+        let store = GriteStore::open_with_options(dir.path(), &options).unwrap();
+
+        let issue_id = generate_issue_id();
+        let actor = [1u8; 16];
+        let body = "lorem ipsum dolor sit amet ".repeat(200);
+
+        let large = make_event(
+            issue_id,
+            actor,
+            1000,
+            EventKind::IssueCreated {
+                title: "Large".to_string(),
+                body: body.clone(),
+                labels: vec![],
+            },
+        );
+        let small = make_event(
+            issue_id,
+            actor,
+            1001,
+            EventKind::CommentAdded {
+                body: "short".to_string(),
+                parent_event: None,
+            },
+        );
+        store.insert_event(&large).unwrap();
+        store.insert_event(&small).unwrap();
+
+        let json = serde_json::to_vec(&large).unwrap();
+        let stored = store
+            .events
+            .get(event_key(&large.event_id))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored[0], EVENT_VALUE_DEFLATE);
+        assert!(stored.len() < json.len());
+
+        let retrieved = store.get_event(&large.event_id).unwrap().unwrap();
+        assert_eq!(serde_json::to_vec(&retrieved).unwrap(), json);
+
+        // Small values stay plain JSON
+        let stored = store
+            .events
+            .get(event_key(&small.event_id))
+            .unwrap()
+            .unwrap();
+        assert_eq!(&stored[..], serde_json::to_vec(&small).unwrap().as_slice());
+
+        // Values behind a raw header remain readable
+        let mut headed = vec![EVENT_VALUE_RAW];
+        headed.extend_from_slice(&serde_json::to_vec(&small).unwrap());
+        store
+            .events
+            .insert(event_key(&small.event_id), headed)
+            .unwrap();
+        assert_eq!(store.get_event(&small.event_id).unwrap().unwrap(), small);
+        assert_eq!(store.get_all_events().unwrap().len(), 2);
+        drop(store);
+
+        // Without the option, even large events are written as plain JSON
+        let plain_dir = tempdir().unwrap();
+        let plain = GriteStore::open(plain_dir.path()).unwrap();
+        plain.insert_event(&large).unwrap();
+        let stored = plain
+            .events
+            .get(event_key(&large.event_id))
+            .unwrap()
+            .unwrap();
+        assert_eq!(&stored[..], json.as_slice());
+        // A store written with compression stays readable without it
+        let reopened = GriteStore::open(dir.path()).unwrap();
+        assert_eq!(reopened.get_event(&large.event_id).unwrap().unwrap(), large);
+    }
+
     #[test]
     fn test_store_rebuild() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,76 @@
+//! Deterministic test mode progress, persisted between runs
+//!
+//! `GRITE_TEST_CLOCK` and `GRITE_TEST_SEED` are read once per process. Once
+//! a directory is chosen with [`persist_in`], the clock and the seeded ID
+//! streams record how far they have advanced there, and the next process
+//! using the same directory resumes from that point. Separate CLI runs
+//! against one repository therefore never reuse an ID or a timestamp, while
+//! fresh repositories still see the same sequence.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clock::TEST_CLOCK_ENV;
+use crate::types::ids::TEST_SEED_ENV;
+
+/// File, in the directory given to [`persist_in`], holding the progress
+pub const TEST_STATE_FILE: &str = "test_state.json";
+
+/// How far the test clock and ID streams have advanced
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct TestState {
+    /// Next test clock reading (Unix ms), 0 if the clock was never read
+    #[serde(default)]
+    pub clock_next: u64,
+    /// Actor IDs drawn from the seeded stream so far
+    #[serde(default)]
+    pub actors_drawn: u64,
+    /// Issue IDs drawn from the seeded stream so far
+    #[serde(default)]
+    pub issues_drawn: u64,
+}
+
+/// Whether either test mode variable is set
+pub fn is_enabled() -> bool {
+    std::env::var_os(TEST_CLOCK_ENV).is_some() || std::env::var_os(TEST_SEED_ENV).is_some()
+}
+
+/// Persist test mode progress in `dir` (usually `.git/grite`)
+///
+/// Must be called before the first timestamp or ID is generated; later
+/// calls are ignored.
+pub fn persist_in(dir: &Path) {
+    let _ = state_dir().set(dir.to_path_buf());
+}
+
+fn state_dir() -> &'static OnceLock<PathBuf> {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    &DIR
+}
+
+/// Progress recorded by earlier runs, or the start if there is none
+pub(crate) fn load() -> TestState {
+    state_dir()
+        .get()
+        .and_then(|dir| std::fs::read(dir.join(TEST_STATE_FILE)).ok())
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Record progress, if a directory was chosen
+///
+/// Test mode is best-effort: a state file that cannot be written only means
+/// the next run starts over.
+pub(crate) fn update(apply: impl FnOnce(&mut TestState)) {
+    let Some(dir) = state_dir().get() else {
+        return;
+    };
+    let mut state = load();
+    apply(&mut state);
+    if let Ok(raw) = serde_json::to_vec(&state) {
+        let _ = std::fs::create_dir_all(dir);
+        let _ = std::fs::write(dir.join(TEST_STATE_FILE), raw);
+    }
+}
@@ -23,10 +23,7 @@ pub struct ActorConfig {
 impl ActorConfig {
     /// Create a new actor config with the given ID
     pub fn new(actor_id: ActorId, label: Option<String>) -> Self {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
+        let now = crate::clock::now_ms();
 
         Self {
             actor_id: hex::encode(actor_id),
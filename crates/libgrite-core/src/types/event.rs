@@ -19,6 +19,43 @@ impl IssueState {
     }
 }
 
+/// Customer-facing severity, independent of internal priority
+///
+/// `Sev1` is the most severe. `None` clears the severity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Sev1,
+    Sev2,
+    Sev3,
+    Sev4,
+    None,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Sev1 => "sev1",
+            Severity::Sev2 => "sev2",
+            Severity::Sev3 => "sev3",
+            Severity::Sev4 => "sev4",
+            Severity::None => "none",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "sev1" => Some(Severity::Sev1),
+            "sev2" => Some(Severity::Sev2),
+            "sev3" => Some(Severity::Sev3),
+            "sev4" => Some(Severity::Sev4),
+            "none" => Some(Severity::None),
+            _ => None,
+        }
+    }
+}
+
 /// Dependency relationship type between issues
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -79,6 +116,9 @@ pub enum EventKind {
     },
     CommentAdded {
         body: String,
+        /// Comment this one replies to, `None` for a top-level comment
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        parent_event: Option<EventId>,
     },
     LabelAdded {
         label: String,
@@ -123,6 +163,50 @@ pub enum EventKind {
         key: String,
         value: String,
     },
+    FieldSet {
+        key: String,
+        /// New value, or `None` to clear the field
+        value: Option<String>,
+    },
+    IssueLinked {
+        target: IssueId,
+        /// Free-form relation, e.g. "duplicate-of", "relates", "parent"
+        relation: String,
+    },
+    IssueUnlinked {
+        target: IssueId,
+        relation: String,
+    },
+    Subscribed {
+        user: String,
+    },
+    Unsubscribed {
+        user: String,
+    },
+    /// Estimated effort; the latest estimate wins
+    TimeEstimated {
+        minutes: u32,
+    },
+    /// Effort spent; logged minutes add up across events
+    TimeLogged {
+        minutes: u32,
+        note: Option<String>,
+    },
+    /// Milestone the issue belongs to; the latest wins
+    MilestoneSet {
+        /// Milestone name, or `None` to clear it
+        milestone: Option<String>,
+    },
+    /// Soft delete: hides the issue from lists; the latest of delete and
+    /// undelete wins
+    IssueDeleted {},
+    /// Reverses an earlier `IssueDeleted`
+    IssueUndeleted {},
+    /// Customer-facing severity; the latest wins
+    SeveritySet {
+        /// New severity, or `Severity::None` to clear it
+        severity: Severity,
+    },
 }
 
 impl EventKind {
@@ -143,10 +227,76 @@ impl EventKind {
             EventKind::DependencyRemoved { .. } => 12,
             EventKind::ContextUpdated { .. } => 13,
             EventKind::ProjectContextUpdated { .. } => 14,
+            EventKind::FieldSet { .. } => 15,
+            EventKind::IssueLinked { .. } => 16,
+            EventKind::IssueUnlinked { .. } => 17,
+            EventKind::Subscribed { .. } => 18,
+            EventKind::Unsubscribed { .. } => 19,
+            EventKind::TimeEstimated { .. } => 20,
+            EventKind::TimeLogged { .. } => 21,
+            EventKind::MilestoneSet { .. } => 22,
+            EventKind::IssueDeleted {} => 23,
+            EventKind::IssueUndeleted {} => 24,
+            EventKind::SeveritySet { .. } => 25,
         }
     }
+
+    /// Get the variant name, e.g. "IssueCreated"
+    pub fn kind_name(&self) -> &'static str {
+        KIND_NAMES[self.kind_tag() as usize - 1]
+    }
+
+    /// Look up a kind tag by name.
+    ///
+    /// Accepts the variant name (`StateChanged`) or its snake_case form
+    /// (`state_changed`), case-insensitively.
+    pub fn tag_for_name(name: &str) -> Option<u32> {
+        let wanted: String = name
+            .chars()
+            .filter(|c| *c != '_')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        KIND_NAMES
+            .iter()
+            .position(|n| n.to_ascii_lowercase() == wanted)
+            .map(|i| i as u32 + 1)
+    }
+
+    /// All variant names, in kind tag order
+    pub fn names() -> &'static [&'static str] {
+        &KIND_NAMES
+    }
 }
 
+/// Variant names indexed by `kind_tag() - 1`
+const KIND_NAMES: [&str; 25] = [
+    "IssueCreated",
+    "IssueUpdated",
+    "CommentAdded",
+    "LabelAdded",
+    "LabelRemoved",
+    "StateChanged",
+    "LinkAdded",
+    "AssigneeAdded",
+    "AssigneeRemoved",
+    "AttachmentAdded",
+    "DependencyAdded",
+    "DependencyRemoved",
+    "ContextUpdated",
+    "ProjectContextUpdated",
+    "FieldSet",
+    "IssueLinked",
+    "IssueUnlinked",
+    "Subscribed",
+    "Unsubscribed",
+    "TimeEstimated",
+    "TimeLogged",
+    "MilestoneSet",
+    "IssueDeleted",
+    "IssueUndeleted",
+    "SeveritySet",
+];
+
 /// An event in the issue tracking system
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Event {
@@ -194,9 +344,53 @@ impl Event {
     }
 }
 
+/// Sort events into canonical order: by `(issue_id, ts, actor, event_id)`.
+///
+/// Events are grouped by issue, and within an issue ordered as by
+/// [`Event::version`]. Rebuilds, exports and sync all apply events in this
+/// order so every replica projects them identically. The key is unique per
+/// event, so the result does not depend on the input order.
+pub fn canonical_order(events: &mut [Event]) {
+    events.sort_by(|a, b| (&a.issue_id, a.version()).cmp(&(&b.issue_id, b.version())));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    fn test_canonical_order_is_independent_of_input_order() {
+        let event = |issue: u8, ts: u64, actor: u8, id: u8| {
+            Event::new(
+                [id; 32],
+                [issue; 16],
+                [actor; 16],
+                ts,
+                None,
+                EventKind::StateChanged {
+                    state: IssueState::Closed,
+                },
+            )
+        };
+        // Already in canonical order: issue first, then ts, actor, event ID
+        let expected = vec![
+            event(1, 500, 9, 9),
+            event(1, 1000, 1, 5),
+            event(1, 1000, 2, 1),
+            event(1, 1000, 2, 3),
+            event(2, 100, 1, 1),
+            event(2, 2000, 1, 1),
+        ];
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..20 {
+            let mut events = expected.clone();
+            events.shuffle(&mut rng);
+            canonical_order(&mut events);
+            assert_eq!(events, expected);
+        }
+    }
 
     #[test]
     fn test_issue_state_as_str() {
@@ -225,7 +419,8 @@ mod tests {
         );
         assert_eq!(
             EventKind::CommentAdded {
-                body: String::new()
+                body: String::new(),
+                parent_event: None
             }
             .kind_tag(),
             3
@@ -317,6 +512,83 @@ mod tests {
             .kind_tag(),
             14
         );
+        assert_eq!(
+            EventKind::FieldSet {
+                key: String::new(),
+                value: None
+            }
+            .kind_tag(),
+            15
+        );
+        assert_eq!(
+            EventKind::IssueLinked {
+                target: [0; 16],
+                relation: String::new()
+            }
+            .kind_tag(),
+            16
+        );
+        assert_eq!(
+            EventKind::IssueUnlinked {
+                target: [0; 16],
+                relation: String::new()
+            }
+            .kind_tag(),
+            17
+        );
+        assert_eq!(
+            EventKind::Subscribed {
+                user: String::new()
+            }
+            .kind_tag(),
+            18
+        );
+        assert_eq!(
+            EventKind::Unsubscribed {
+                user: String::new()
+            }
+            .kind_tag(),
+            19
+        );
+        assert_eq!(EventKind::TimeEstimated { minutes: 0 }.kind_tag(), 20);
+        assert_eq!(
+            EventKind::TimeLogged {
+                minutes: 0,
+                note: None
+            }
+            .kind_tag(),
+            21
+        );
+        assert_eq!(EventKind::MilestoneSet { milestone: None }.kind_tag(), 22);
+        assert_eq!(EventKind::IssueDeleted {}.kind_tag(), 23);
+        assert_eq!(EventKind::IssueUndeleted {}.kind_tag(), 24);
+        assert_eq!(
+            EventKind::SeveritySet {
+                severity: Severity::Sev2
+            }
+            .kind_tag(),
+            25
+        );
+    }
+
+    #[test]
+    fn test_tag_for_name() {
+        assert_eq!(EventKind::tag_for_name("StateChanged"), Some(6));
+        assert_eq!(EventKind::tag_for_name("state_changed"), Some(6));
+        assert_eq!(EventKind::tag_for_name("project_context_updated"), Some(14));
+        assert_eq!(EventKind::tag_for_name("Unsubscribed"), Some(19));
+        assert_eq!(EventKind::tag_for_name("state_change"), None);
+        assert_eq!(EventKind::tag_for_name(""), None);
+
+        let kind = EventKind::CommentAdded {
+            body: String::new(),
+            parent_event: None,
+        };
+        assert_eq!(kind.kind_name(), "CommentAdded");
+        assert_eq!(
+            EventKind::tag_for_name(kind.kind_name()),
+            Some(kind.kind_tag())
+        );
     }
 
     #[test]
@@ -1,6 +1,19 @@
-use rand::Rng;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
+/// Environment variable holding a numeric seed for generated IDs
+///
+/// When set, actor and issue IDs are drawn from seeded streams instead of the
+/// thread RNG, so end-to-end tests see the same IDs on every run. Each
+/// process starts the streams over, unless [`crate::test_state`] resumes
+/// them where the previous process stopped.
+pub const TEST_SEED_ENV: &str = "GRITE_TEST_SEED";
+
 /// 128-bit actor identifier (random)
 pub type ActorId = [u8; 16];
 
@@ -20,12 +33,103 @@ pub enum IdParseError {
 
 /// Generate a random 128-bit actor ID
 pub fn generate_actor_id() -> ActorId {
-    rand::thread_rng().gen()
+    match seeded_ids() {
+        Some(ids) => lock_ids(ids).next_actor_id(),
+        None => rand::thread_rng().gen(),
+    }
 }
 
 /// Generate a random 128-bit issue ID
 pub fn generate_issue_id() -> IssueId {
-    rand::thread_rng().gen()
+    match seeded_ids() {
+        Some(ids) => lock_ids(ids).next_issue_id(),
+        None => rand::thread_rng().gen(),
+    }
+}
+
+/// Seeded ID streams that never repeat an ID
+///
+/// Actor and issue IDs come from separate streams, so a new process does not
+/// hand out its first actor ID again as its first issue ID.
+struct SeededIds {
+    actors: StdRng,
+    issues: StdRng,
+    issued: HashSet<[u8; 16]>,
+    actors_drawn: u64,
+    issues_drawn: u64,
+}
+
+impl SeededIds {
+    fn new(seed: u64) -> Self {
+        Self {
+            actors: stream(seed, b"actor"),
+            issues: stream(seed, b"issue"),
+            issued: HashSet::new(),
+            actors_drawn: 0,
+            issues_drawn: 0,
+        }
+    }
+
+    /// Streams for `seed`, advanced past the IDs earlier runs drew
+    fn resume(seed: u64) -> Self {
+        let mut ids = Self::new(seed);
+        let state = crate::test_state::load();
+        for _ in 0..state.actors_drawn {
+            next_unique(&mut ids.actors, &mut ids.issued);
+        }
+        for _ in 0..state.issues_drawn {
+            next_unique(&mut ids.issues, &mut ids.issued);
+        }
+        ids.actors_drawn = state.actors_drawn;
+        ids.issues_drawn = state.issues_drawn;
+        ids
+    }
+
+    fn next_actor_id(&mut self) -> ActorId {
+        self.actors_drawn += 1;
+        let drawn = self.actors_drawn;
+        crate::test_state::update(|state| state.actors_drawn = drawn);
+        next_unique(&mut self.actors, &mut self.issued)
+    }
+
+    fn next_issue_id(&mut self) -> IssueId {
+        self.issues_drawn += 1;
+        let drawn = self.issues_drawn;
+        crate::test_state::update(|state| state.issues_drawn = drawn);
+        next_unique(&mut self.issues, &mut self.issued)
+    }
+}
+
+fn stream(seed: u64, domain: &[u8]) -> StdRng {
+    let mut hasher = Sha256::new();
+    hasher.update(domain);
+    hasher.update(seed.to_le_bytes());
+    StdRng::from_seed(hasher.finalize().into())
+}
+
+fn next_unique(rng: &mut StdRng, issued: &mut HashSet<[u8; 16]>) -> [u8; 16] {
+    loop {
+        let id = rng.gen();
+        if issued.insert(id) {
+            return id;
+        }
+    }
+}
+
+fn seeded_ids() -> Option<&'static Mutex<SeededIds>> {
+    static SEEDED: OnceLock<Option<Mutex<SeededIds>>> = OnceLock::new();
+    SEEDED
+        .get_or_init(|| {
+            std::env::var(TEST_SEED_ENV)
+                .ok()
+                .and_then(|raw| raw.trim().parse().ok())
+                .map(|seed| Mutex::new(SeededIds::resume(seed)))
+        })
+        .as_ref()
+}
+
+fn lock_ids(ids: &Mutex<SeededIds>) -> std::sync::MutexGuard<'_, SeededIds> {
+    ids.lock().unwrap_or_else(|e| e.into_inner())
 }
 
 /// Convert a fixed-size byte array to lowercase hex string
@@ -80,6 +184,20 @@ mod tests {
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_seeded_ids_repeat_across_runs() {
+        let mut first = SeededIds::new(42);
+        let mut second = SeededIds::new(42);
+        let run1: Vec<_> = (0..100).map(|_| first.next_issue_id()).collect();
+        let run2: Vec<_> = (0..100).map(|_| second.next_issue_id()).collect();
+        assert_eq!(run1, run2);
+
+        let unique: HashSet<_> = run1.iter().collect();
+        assert_eq!(unique.len(), run1.len());
+        assert!(!run1.contains(&first.next_actor_id()));
+        assert_ne!(SeededIds::new(43).next_issue_id(), run1[0]);
+    }
+
     #[test]
     fn test_id_to_hex() {
         let id: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
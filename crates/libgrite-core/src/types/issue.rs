@@ -1,7 +1,15 @@
-use super::event::{DependencyType, IssueState};
+use super::event::{DependencyType, IssueState, Severity};
 use super::ids::{ActorId, EventId, IssueId};
-use serde::{Deserialize, Serialize};
-use std::collections::BTreeSet;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A body an issue no longer shows, kept in its projection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BodyRevision {
+    pub body: String,
+    /// Version of the edit that set this body
+    pub version: Version,
+}
 
 /// A comment on an issue
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -10,6 +18,17 @@ pub struct Comment {
     pub actor: ActorId,
     pub ts_unix_ms: u64,
     pub body: String,
+    /// Comment this one replies to, `None` for a top-level comment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_event: Option<EventId>,
+}
+
+/// A comment with its replies, as built by [`IssueProjection::comment_threads`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentThread {
+    pub comment: Comment,
+    /// Direct replies in event order
+    pub replies: Vec<CommentThread>,
 }
 
 /// A link attached to an issue
@@ -36,6 +55,26 @@ pub struct Dependency {
     pub dep_type: DependencyType,
 }
 
+/// A typed reference from this issue to another, distinct from dependencies
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct IssueLink {
+    pub target: IssueId,
+    pub relation: String,
+}
+
+impl IssueLink {
+    /// Normalize a relation name: trimmed and lowercased, with no inner
+    /// whitespace. Returns `None` if the result is empty or contains spaces.
+    pub fn normalize_relation(relation: &str) -> Option<String> {
+        let relation = relation.trim().to_lowercase();
+        if relation.is_empty() || relation.contains(char::is_whitespace) {
+            None
+        } else {
+            Some(relation)
+        }
+    }
+}
+
 /// Version tuple for LWW comparison: (timestamp, actor, event_id)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Version {
@@ -61,7 +100,7 @@ impl Version {
 }
 
 /// Full projection of an issue from its event stream
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IssueProjection {
     pub issue_id: IssueId,
     pub title: String,
@@ -79,16 +118,78 @@ pub struct IssueProjection {
     pub attachments: Vec<Attachment>,
     /// Dependencies on other issues (add/remove set CRDT)
     pub dependencies: BTreeSet<Dependency>,
+    /// Typed links to other issues (add/remove set CRDT)
+    #[serde(default)]
+    pub issue_links: BTreeSet<IssueLink>,
+    /// Users watching this issue, sorted lexicographically (add/remove set)
+    #[serde(default)]
+    pub subscribers: BTreeSet<String>,
     /// Timestamp when issue was created
     pub created_ts: u64,
+    /// Actor who created the issue; `None` for projections stored before
+    /// this was recorded
+    #[serde(
+        default,
+        deserialize_with = "deserialize_created_by",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub created_by: Option<ActorId>,
+    /// Milliseconds from creation to the first comment or assignment by an
+    /// actor other than the creator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_response_ms: Option<u64>,
     /// Timestamp of last update
     pub updated_ts: u64,
     /// Version tracking for LWW on title
     pub title_version: Version,
+    /// Competing titles written by different actors at the same timestamp,
+    /// sorted; cleared by a later title edit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_conflict: Option<Vec<String>>,
     /// Version tracking for LWW on body
     pub body_version: Version,
+    /// Number of body edits after creation that are in the store's events
+    #[serde(default)]
+    pub body_revisions: u32,
+    /// The newest bodies that lost to `body`, newest first, up to the
+    /// store's `body_history`; every earlier body is in the issue's events
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub body_history: Vec<BodyRevision>,
     /// Version tracking for LWW on state
     pub state_version: Version,
+    /// Custom metadata fields sorted by key (LWW per key)
+    #[serde(default)]
+    pub custom_fields: BTreeMap<String, String>,
+    /// Version tracking for LWW on each custom field, including cleared ones
+    #[serde(default)]
+    pub field_versions: BTreeMap<String, Version>,
+    /// Estimated effort in minutes (LWW)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate_minutes: Option<u32>,
+    /// Version tracking for LWW on the estimate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate_version: Option<Version>,
+    /// Total logged effort in minutes (sum of all TimeLogged events)
+    #[serde(default)]
+    pub logged_minutes: u64,
+    /// Milestone the issue belongs to (LWW)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone: Option<String>,
+    /// Version tracking for LWW on the milestone, including clears
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub milestone_version: Option<Version>,
+    /// Customer-facing severity (LWW); `None` when unset or cleared
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Version tracking for LWW on the severity, including clears
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity_version: Option<Version>,
+    /// Soft-deleted issues are hidden from lists but keep their history
+    #[serde(default)]
+    pub deleted: bool,
+    /// Version tracking for LWW on `deleted`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_version: Option<Version>,
 }
 
 impl IssueProjection {
@@ -114,11 +215,29 @@ impl IssueProjection {
             links: Vec::new(),
             attachments: Vec::new(),
             dependencies: BTreeSet::new(),
+            issue_links: BTreeSet::new(),
+            subscribers: BTreeSet::new(),
             created_ts: ts_unix_ms,
+            created_by: Some(actor),
+            first_response_ms: None,
             updated_ts: ts_unix_ms,
             title_version: version.clone(),
+            title_conflict: None,
             body_version: version.clone(),
+            body_revisions: 0,
+            body_history: Vec::new(),
             state_version: version,
+            custom_fields: BTreeMap::new(),
+            field_versions: BTreeMap::new(),
+            estimate_minutes: None,
+            estimate_version: None,
+            logged_minutes: 0,
+            milestone: None,
+            milestone_version: None,
+            severity: None,
+            severity_version: None,
+            deleted: false,
+            deleted_version: None,
         }
     }
 }
@@ -132,8 +251,21 @@ pub struct IssueSummary {
     pub labels: Vec<String>,
     pub assignees: Vec<String>,
     pub created_ts: u64,
+    /// Actor who created the issue, if known
+    #[serde(
+        default,
+        deserialize_with = "deserialize_created_by",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub created_by: Option<ActorId>,
     pub updated_ts: u64,
     pub comment_count: usize,
+    #[serde(default)]
+    pub deleted: bool,
+    /// Number of issues this one blocks; only filled in by the store (see
+    /// `GriteStore::issue_summary`), zero when converted from a projection
+    #[serde(default)]
+    pub blocks_count: usize,
 }
 
 impl From<&IssueProjection> for IssueSummary {
@@ -145,12 +277,27 @@ impl From<&IssueProjection> for IssueSummary {
             labels: proj.labels.iter().cloned().collect(),
             assignees: proj.assignees.iter().cloned().collect(),
             created_ts: proj.created_ts,
+            created_by: proj.created_by,
             updated_ts: proj.updated_ts,
             comment_count: proj.comments.len(),
+            deleted: proj.deleted,
+            blocks_count: 0,
         }
     }
 }
 
+/// Read `created_by`, treating the all-zero actor as unknown
+///
+/// Projections stored before the field existed may have been saved again
+/// with an all-zero default in its place.
+fn deserialize_created_by<'de, D>(deserializer: D) -> Result<Option<ActorId>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let actor = Option::<ActorId>::deserialize(deserializer)?;
+    Ok(actor.filter(|actor| *actor != [0u8; 16]))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,6 +341,32 @@ mod tests {
         assert!(proj.labels.contains("p0"));
     }
 
+    #[test]
+    fn test_created_by_unknown_for_legacy_projections() {
+        let proj = IssueProjection::new(
+            [0u8; 16],
+            "Test".to_string(),
+            "Body".to_string(),
+            vec![],
+            1700000000000,
+            [1u8; 16],
+            [2u8; 32],
+        );
+        let mut json = serde_json::to_value(&proj).unwrap();
+        let loaded: IssueProjection = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(loaded.created_by, Some([1u8; 16]));
+
+        // Missing, as written before the field existed
+        json.as_object_mut().unwrap().remove("created_by");
+        let loaded: IssueProjection = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(loaded.created_by, None);
+
+        // All zero, as saved back with the old default
+        json["created_by"] = serde_json::to_value([0u8; 16]).unwrap();
+        let loaded: IssueProjection = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.created_by, None);
+    }
+
     #[test]
     fn test_issue_summary_from_projection() {
         let proj = IssueProjection::new(
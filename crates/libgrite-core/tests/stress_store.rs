@@ -25,6 +25,7 @@ fn create_comment_event(actor: &[u8; 16], issue_id: &[u8; 16], ts_offset: u64) -
     let ts = 1700000000000 + ts_offset;
     let kind = EventKind::CommentAdded {
         body: format!("Comment at ts {}", ts),
+        parent_event: None,
     };
     let event_id = compute_event_id(issue_id, actor, ts, None, &kind);
     Event::new(event_id, *issue_id, *actor, ts, None, kind)
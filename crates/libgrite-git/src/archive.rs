@@ -0,0 +1,341 @@
+//! Portable single-file store archives for backup and machine moves
+//!
+//! Archive format:
+//! - Magic: `GRITEARC` (8 bytes)
+//! - Version: u16 (little-endian)
+//! - Entries until end of file, each:
+//!   - Name length: u16 (little-endian), then the UTF-8 name
+//!   - Data length: u64 (little-endian), then the data
+//!
+//! Entries written by this version:
+//! - `manifest.json`: creation time and event count
+//! - `events.chunk`: every event, as a CBOR chunk (see [`encode_chunk`])
+//! - `config.toml`: the repo config, if one exists
+//! - `attachments/<sha256 hex>`: the bytes of each stored attachment
+//!
+//! `AttachmentAdded` events only reference attachments by hash, so the bytes
+//! from the store's attachment tree get an entry each. Unknown entries are
+//! skipped on read.
+
+use std::path::Path;
+
+use libgrite_core::types::event::Event;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::chunk::{decode_chunk, encode_chunk};
+use crate::GitError;
+
+/// Magic bytes at start of an archive
+pub const ARCHIVE_MAGIC: &[u8; 8] = b"GRITEARC";
+
+/// Current archive format version
+pub const ARCHIVE_VERSION: u16 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const EVENTS_ENTRY: &str = "events.chunk";
+const CONFIG_ENTRY: &str = "config.toml";
+const ATTACHMENT_PREFIX: &str = "attachments/";
+
+/// Archive metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    /// When the archive was written (Unix timestamp in ms)
+    pub created_ts: u64,
+    /// Number of events in `events.chunk`
+    pub event_count: usize,
+    /// Number of `attachments/` entries (0 in archives written before
+    /// attachments were included)
+    #[serde(default)]
+    pub attachment_count: usize,
+}
+
+/// A full copy of a store's events and attachments plus the repo config
+#[derive(Debug, Clone)]
+pub struct StoreArchive {
+    pub manifest: ArchiveManifest,
+    pub events: Vec<Event>,
+    /// Attachment bytes; each entry is named by its SHA-256
+    pub attachments: Vec<Vec<u8>>,
+    /// Raw contents of `.git/grite/config.toml`, if present
+    pub repo_config: Option<String>,
+}
+
+impl StoreArchive {
+    /// Build an archive from events, attachments and the raw repo config
+    pub fn new(events: Vec<Event>, attachments: Vec<Vec<u8>>, repo_config: Option<String>) -> Self {
+        let created_ts = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        Self {
+            manifest: ArchiveManifest {
+                created_ts,
+                event_count: events.len(),
+                attachment_count: attachments.len(),
+            },
+            events,
+            attachments,
+            repo_config,
+        }
+    }
+
+    /// Encode the archive to bytes
+    pub fn encode(&self) -> Result<Vec<u8>, GitError> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(ARCHIVE_MAGIC);
+        buf.extend_from_slice(&ARCHIVE_VERSION.to_le_bytes());
+
+        write_entry(
+            &mut buf,
+            MANIFEST_ENTRY,
+            &serde_json::to_vec(&self.manifest)?,
+        );
+        write_entry(&mut buf, EVENTS_ENTRY, &encode_chunk(&self.events)?);
+        if let Some(ref config) = self.repo_config {
+            write_entry(&mut buf, CONFIG_ENTRY, config.as_bytes());
+        }
+        for data in &self.attachments {
+            let name = format!("{}{}", ATTACHMENT_PREFIX, hex::encode(Sha256::digest(data)));
+            write_entry(&mut buf, &name, data);
+        }
+
+        Ok(buf)
+    }
+
+    /// Decode an archive from bytes
+    pub fn decode(data: &[u8]) -> Result<Self, GitError> {
+        if data.len() < 8 + 2 {
+            return Err(GitError::InvalidArchive("Archive too small".to_string()));
+        }
+        if &data[0..8] != ARCHIVE_MAGIC {
+            return Err(GitError::InvalidArchive("Invalid magic bytes".to_string()));
+        }
+        let version = u16::from_le_bytes([data[8], data[9]]);
+        if version != ARCHIVE_VERSION {
+            return Err(GitError::InvalidArchive(format!(
+                "Unsupported archive version: {}",
+                version
+            )));
+        }
+
+        let mut manifest = None;
+        let mut events = None;
+        let mut repo_config = None;
+        let mut attachments = Vec::new();
+        let mut rest = &data[10..];
+        while !rest.is_empty() {
+            let (name, entry, remaining) = read_entry(rest)?;
+            rest = remaining;
+            match name {
+                MANIFEST_ENTRY => manifest = Some(serde_json::from_slice(entry)?),
+                EVENTS_ENTRY => events = Some(decode_chunk(entry)?),
+                CONFIG_ENTRY => {
+                    let config = std::str::from_utf8(entry).map_err(|_| {
+                        GitError::InvalidArchive("config.toml is not UTF-8".to_string())
+                    })?;
+                    repo_config = Some(config.to_string());
+                }
+                _ if name.starts_with(ATTACHMENT_PREFIX) => {
+                    if hex::encode(Sha256::digest(entry)) != name[ATTACHMENT_PREFIX.len()..] {
+                        return Err(GitError::InvalidArchive(format!(
+                            "{} does not match its content",
+                            name
+                        )));
+                    }
+                    attachments.push(entry.to_vec());
+                }
+                _ => {}
+            }
+        }
+
+        let manifest: ArchiveManifest = manifest
+            .ok_or_else(|| GitError::InvalidArchive("Missing manifest.json".to_string()))?;
+        let events: Vec<Event> =
+            events.ok_or_else(|| GitError::InvalidArchive("Missing events.chunk".to_string()))?;
+        if events.len() != manifest.event_count {
+            return Err(GitError::InvalidArchive(format!(
+                "Manifest lists {} events but archive has {}",
+                manifest.event_count,
+                events.len()
+            )));
+        }
+        if attachments.len() != manifest.attachment_count {
+            return Err(GitError::InvalidArchive(format!(
+                "Manifest lists {} attachments but archive has {}",
+                manifest.attachment_count,
+                attachments.len()
+            )));
+        }
+
+        Ok(Self {
+            manifest,
+            events,
+            attachments,
+            repo_config,
+        })
+    }
+
+    /// Write the archive to a file
+    pub fn write_to(&self, path: &Path) -> Result<(), GitError> {
+        std::fs::write(path, self.encode()?)?;
+        Ok(())
+    }
+
+    /// Read an archive from a file
+    pub fn read_from(path: &Path) -> Result<Self, GitError> {
+        Self::decode(&std::fs::read(path)?)
+    }
+}
+
+fn write_entry(buf: &mut Vec<u8>, name: &str, data: &[u8]) {
+    buf.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    buf.extend_from_slice(name.as_bytes());
+    buf.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    buf.extend_from_slice(data);
+}
+
+/// Split `n` bytes off the front of `data`
+fn take(data: &[u8], n: usize) -> Option<(&[u8], &[u8])> {
+    (data.len() >= n).then(|| data.split_at(n))
+}
+
+/// Split one entry off the front of `data`, returning (name, data, rest)
+fn read_entry(data: &[u8]) -> Result<(&str, &[u8], &[u8]), GitError> {
+    let truncated = || GitError::InvalidArchive("Archive truncated".to_string());
+
+    let (len_bytes, rest) = take(data, 2).ok_or_else(truncated)?;
+    let name_len = u16::from_le_bytes([len_bytes[0], len_bytes[1]]) as usize;
+    let (name, rest) = take(rest, name_len).ok_or_else(truncated)?;
+    let name = std::str::from_utf8(name)
+        .map_err(|_| GitError::InvalidArchive("Invalid entry name".to_string()))?;
+
+    let (len_bytes, rest) = take(rest, 8).ok_or_else(truncated)?;
+    let data_len = u64::from_le_bytes(len_bytes.try_into().expect("8 bytes"));
+    let data_len = usize::try_from(data_len).map_err(|_| truncated())?;
+    let (entry, rest) = take(rest, data_len).ok_or_else(truncated)?;
+
+    Ok((name, entry, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libgrite_core::hash::compute_event_id;
+    use libgrite_core::types::event::{EventKind, IssueState};
+    use libgrite_core::types::ids::generate_issue_id;
+    use libgrite_core::GriteStore;
+    use tempfile::TempDir;
+
+    fn make_event(issue_id: [u8; 16], ts: u64, kind: EventKind) -> Event {
+        let actor = [1u8; 16];
+        let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+        Event::new(event_id, issue_id, actor, ts, None, kind)
+    }
+
+    #[test]
+    fn test_archive_restore_matches_projections() {
+        let temp = TempDir::new().unwrap();
+        let source = GriteStore::open(&temp.path().join("src-sled")).unwrap();
+        let issue_1 = generate_issue_id();
+        let issue_2 = generate_issue_id();
+        let events = [
+            make_event(
+                issue_1,
+                1000,
+                EventKind::IssueCreated {
+                    title: "One".to_string(),
+                    body: "Body".to_string(),
+                    labels: vec!["bug".to_string()],
+                },
+            ),
+            make_event(
+                issue_2,
+                1100,
+                EventKind::IssueCreated {
+                    title: "Two".to_string(),
+                    body: String::new(),
+                    labels: vec![],
+                },
+            ),
+            make_event(
+                issue_1,
+                2000,
+                EventKind::AttachmentAdded {
+                    name: "log.txt".to_string(),
+                    sha256: [7; 32],
+                    mime: "text/plain".to_string(),
+                },
+            ),
+            make_event(
+                issue_2,
+                3000,
+                EventKind::StateChanged {
+                    state: IssueState::Closed,
+                },
+            ),
+        ];
+        for event in &events {
+            source.insert_event(event).unwrap();
+        }
+        let log_sha = source.put_attachment(b"log contents").unwrap();
+
+        let archive_path = temp.path().join("backup.grite");
+        let config = Some("lock_policy = \"warn\"\n".to_string());
+        StoreArchive::new(
+            source.get_all_events().unwrap(),
+            source.get_all_attachments().unwrap(),
+            config,
+        )
+        .write_to(&archive_path)
+        .unwrap();
+
+        let archive = StoreArchive::read_from(&archive_path).unwrap();
+        assert_eq!(archive.manifest.event_count, 4);
+        assert_eq!(archive.manifest.attachment_count, 1);
+        assert_eq!(
+            archive.repo_config.as_deref(),
+            Some("lock_policy = \"warn\"\n")
+        );
+
+        let restored = GriteStore::open(&temp.path().join("dst-sled")).unwrap();
+        let stats = restored.rebuild_from_events(&archive.events).unwrap();
+        assert_eq!(stats.event_count, 4);
+        assert_eq!(stats.issue_count, 2);
+        for data in &archive.attachments {
+            restored.put_attachment(data).unwrap();
+        }
+        assert_eq!(
+            restored.get_attachment(&log_sha).unwrap().as_deref(),
+            Some(&b"log contents"[..])
+        );
+        for issue_id in [issue_1, issue_2] {
+            assert_eq!(
+                restored.get_issue(&issue_id).unwrap(),
+                source.get_issue(&issue_id).unwrap()
+            );
+        }
+        assert_eq!(
+            restored.get_all_events().unwrap(),
+            source.get_all_events().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_archive_rejects_corrupt_data() {
+        assert!(StoreArchive::decode(b"NOTANARC\x01\x00").is_err());
+
+        let archive = StoreArchive::new(vec![], vec![], None);
+        let bytes = archive.encode().unwrap();
+        assert!(StoreArchive::decode(&bytes).unwrap().events.is_empty());
+        assert!(StoreArchive::decode(&bytes[..bytes.len() - 1]).is_err());
+
+        // Attachment bytes must match the hash in their entry name
+        let archive = StoreArchive::new(vec![], vec![b"data".to_vec()], None);
+        let mut bytes = archive.encode().unwrap();
+        assert_eq!(StoreArchive::decode(&bytes).unwrap().attachments.len(), 1);
+        *bytes.last_mut().unwrap() = b'x';
+        assert!(StoreArchive::decode(&bytes).is_err());
+    }
+}
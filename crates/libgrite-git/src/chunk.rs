@@ -4,51 +4,185 @@
 //! - Magic: `GRITECNK` (8 bytes)
 //! - Version: u16 (little-endian)
 //! - Codec length: u8
-//! - Codec: "cbor-v1"
+//! - Codec: "cbor-v1", or "cbor-zstd-v1" for a zstd-compressed payload
+//! - Hash algorithm: u8 id of the [`HashAlgo`] that derived the event IDs
+//!   (version 2 only; version 1 chunks always use BLAKE2b-256 and are what
+//!   the default algorithm is written as)
 //! - Payload: CBOR array of events
+//!
+//! Decoding rejects chunks over [`ChunkLimits`] before allocating for them;
+//! a compressed payload is held to the same size limit once decompressed.
 
 use blake2::digest::consts::U32;
 use blake2::{Blake2b, Digest};
 use ciborium::Value;
-use libgrite_core::types::event::{DependencyType, Event, EventKind, IssueState, SymbolInfo};
+use libgrite_core::hash::HashAlgo;
+use libgrite_core::types::event::{
+    DependencyType, Event, EventKind, IssueState, Severity, SymbolInfo,
+};
 use libgrite_core::types::ids::{ActorId, EventId, IssueId};
+use std::io::Read;
 
 use crate::GitError;
 
 /// Magic bytes at start of chunk
 pub const CHUNK_MAGIC: &[u8; 8] = b"GRITECNK";
 
-/// Current chunk format version
-pub const CHUNK_VERSION: u16 = 1;
+/// Newest chunk format version
+///
+/// Version 2 adds the hash algorithm byte. It is only written for event IDs
+/// derived with a non-default [`HashAlgo`]; everything else is written as
+/// version 1 so older readers keep decoding it.
+pub const CHUNK_VERSION: u16 = 2;
+
+/// Oldest chunk format version that can still be decoded
+pub const MIN_CHUNK_VERSION: u16 = 1;
 
 /// Codec identifier
 pub const CHUNK_CODEC: &str = "cbor-v1";
 
+/// Codec identifier for a zstd-compressed CBOR payload
+pub const CHUNK_CODEC_ZSTD: &str = "cbor-zstd-v1";
+
+/// zstd level for compressed chunks
+const ZSTD_LEVEL: i32 = 3;
+
+/// How a chunk's CBOR payload is stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChunkCodec {
+    /// Raw CBOR, readable by every version
+    #[default]
+    Cbor,
+    /// zstd-compressed CBOR
+    CborZstd,
+}
+
+impl ChunkCodec {
+    /// Codec string written into the chunk header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChunkCodec::Cbor => CHUNK_CODEC,
+            ChunkCodec::CborZstd => CHUNK_CODEC_ZSTD,
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            CHUNK_CODEC => Some(ChunkCodec::Cbor),
+            CHUNK_CODEC_ZSTD => Some(ChunkCodec::CborZstd),
+            _ => None,
+        }
+    }
+}
+
+/// Default cap on the size of a chunk accepted by [`decode_chunk`]
+pub const DEFAULT_MAX_CHUNK_BYTES: usize = 256 * 1024 * 1024;
+
+/// Default cap on the number of events a chunk may declare
+pub const DEFAULT_MAX_EVENTS_PER_CHUNK: u64 = 1_000_000;
+
+/// Limits checked before a chunk's payload is decoded, so a corrupt or
+/// hostile chunk is rejected instead of exhausting memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLimits {
+    pub max_chunk_bytes: usize,
+    pub max_events_per_chunk: u64,
+}
+
+impl Default for ChunkLimits {
+    fn default() -> Self {
+        Self {
+            max_chunk_bytes: DEFAULT_MAX_CHUNK_BYTES,
+            max_events_per_chunk: DEFAULT_MAX_EVENTS_PER_CHUNK,
+        }
+    }
+}
+
 /// Encode a list of events into a chunk
 pub fn encode_chunk(events: &[Event]) -> Result<Vec<u8>, GitError> {
+    encode_chunk_with_algo(events, HashAlgo::default())
+}
+
+/// Encode events whose IDs were derived with `algo`
+pub fn encode_chunk_with_algo(events: &[Event], algo: HashAlgo) -> Result<Vec<u8>, GitError> {
+    encode_chunk_with_codec(events, algo, ChunkCodec::Cbor)
+}
+
+/// Encode events with the payload stored as `codec`.
+///
+/// Readers that predate a codec reject its chunks as unsupported, so only
+/// write [`ChunkCodec::CborZstd`] once every reader of the WAL can decode it.
+pub fn encode_chunk_with_codec(
+    events: &[Event],
+    algo: HashAlgo,
+    codec: ChunkCodec,
+) -> Result<Vec<u8>, GitError> {
     let mut buf = Vec::new();
 
     // Magic
     buf.extend_from_slice(CHUNK_MAGIC);
 
     // Version (little-endian u16)
-    buf.extend_from_slice(&CHUNK_VERSION.to_le_bytes());
+    let version = if algo == HashAlgo::default() {
+        MIN_CHUNK_VERSION
+    } else {
+        CHUNK_VERSION
+    };
+    buf.extend_from_slice(&version.to_le_bytes());
 
     // Codec length and codec string
-    let codec_bytes = CHUNK_CODEC.as_bytes();
+    let codec_bytes = codec.as_str().as_bytes();
     buf.push(codec_bytes.len() as u8);
     buf.extend_from_slice(codec_bytes);
 
+    // Hash algorithm (version 2 only)
+    if version >= 2 {
+        buf.push(algo.id());
+    }
+
     // Encode events as CBOR array
     let events_value = events_to_cbor(events);
-    ciborium::into_writer(&events_value, &mut buf)
+    let mut payload = Vec::new();
+    ciborium::into_writer(&events_value, &mut payload)
         .map_err(|e| GitError::CborDecode(format!("Failed to encode events: {}", e)))?;
 
+    match codec {
+        ChunkCodec::Cbor => buf.extend_from_slice(&payload),
+        ChunkCodec::CborZstd => {
+            let compressed = zstd::stream::encode_all(payload.as_slice(), ZSTD_LEVEL)
+                .map_err(|e| GitError::InvalidChunk(format!("Failed to compress chunk: {}", e)))?;
+            buf.extend_from_slice(&compressed);
+        }
+    }
+
     Ok(buf)
 }
 
 /// Decode a chunk into a list of events
 pub fn decode_chunk(data: &[u8]) -> Result<Vec<Event>, GitError> {
+    decode_chunk_with_algo(data).map(|(_, events)| events)
+}
+
+/// Header fields of an encoded chunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkHeader {
+    pub version: u16,
+    pub codec: String,
+    /// Raw hash algorithm id (always BLAKE2b-256 for version 1)
+    pub hash_algo: u8,
+}
+
+/// Read a chunk's header without decoding its payload.
+///
+/// Unlike [`decode_chunk`], this accepts versions, codecs and hash algorithms
+/// this build cannot decode, so callers can report what a chunk requires.
+pub fn read_chunk_header(data: &[u8]) -> Result<ChunkHeader, GitError> {
+    read_header(data).map(|(header, _)| header)
+}
+
+/// Parse the header, returning it with the offset of the payload
+fn read_header(data: &[u8]) -> Result<(ChunkHeader, usize), GitError> {
     // Check minimum size
     if data.len() < 8 + 2 + 1 {
         return Err(GitError::InvalidChunk("Chunk too small".to_string()));
@@ -61,12 +195,6 @@ pub fn decode_chunk(data: &[u8]) -> Result<Vec<Event>, GitError> {
 
     // Read version
     let version = u16::from_le_bytes([data[8], data[9]]);
-    if version != CHUNK_VERSION {
-        return Err(GitError::InvalidChunk(format!(
-            "Unsupported chunk version: {}",
-            version
-        )));
-    }
 
     // Read codec
     let codec_len = data[10] as usize;
@@ -76,20 +204,131 @@ pub fn decode_chunk(data: &[u8]) -> Result<Vec<Event>, GitError> {
         ));
     }
     let codec = std::str::from_utf8(&data[11..11 + codec_len])
-        .map_err(|_| GitError::InvalidChunk("Invalid codec string".to_string()))?;
-    if codec != CHUNK_CODEC {
+        .map_err(|_| GitError::InvalidChunk("Invalid codec string".to_string()))?
+        .to_string();
+
+    // Read hash algorithm (version 1 predates the field)
+    let mut payload_start = 11 + codec_len;
+    let hash_algo = if version >= 2 {
+        let id = *data.get(payload_start).ok_or_else(|| {
+            GitError::InvalidChunk("Chunk truncated at hash algorithm".to_string())
+        })?;
+        payload_start += 1;
+        id
+    } else {
+        HashAlgo::Blake2b256.id()
+    };
+
+    Ok((
+        ChunkHeader {
+            version,
+            codec,
+            hash_algo,
+        },
+        payload_start,
+    ))
+}
+
+/// Decode a chunk, also returning the hash algorithm of its event IDs
+pub fn decode_chunk_with_algo(data: &[u8]) -> Result<(HashAlgo, Vec<Event>), GitError> {
+    decode_chunk_with_limits(data, &ChunkLimits::default())
+}
+
+/// Decode a chunk under explicit `limits`, for callers that need to accept
+/// larger chunks than the defaults allow
+pub fn decode_chunk_with_limits(
+    data: &[u8],
+    limits: &ChunkLimits,
+) -> Result<(HashAlgo, Vec<Event>), GitError> {
+    if data.len() > limits.max_chunk_bytes {
+        return Err(GitError::ChunkTooLarge {
+            what: "size in bytes",
+            actual: data.len() as u64,
+            limit: limits.max_chunk_bytes as u64,
+        });
+    }
+
+    let (header, payload_start) = read_header(data)?;
+    if !(MIN_CHUNK_VERSION..=CHUNK_VERSION).contains(&header.version) {
         return Err(GitError::InvalidChunk(format!(
-            "Unsupported codec: {}",
-            codec
+            "Unsupported chunk version: {}",
+            header.version
         )));
     }
+    let codec = ChunkCodec::from_str(&header.codec)
+        .ok_or_else(|| GitError::InvalidChunk(format!("Unsupported codec: {}", header.codec)))?;
+    let algo = HashAlgo::from_id(header.hash_algo).ok_or_else(|| {
+        GitError::InvalidChunk(format!(
+            "Unsupported hash algorithm id: {}",
+            header.hash_algo
+        ))
+    })?;
+
+    let decompressed;
+    let payload = match codec {
+        ChunkCodec::Cbor => &data[payload_start..],
+        ChunkCodec::CborZstd => {
+            decompressed = decompress_payload(&data[payload_start..], limits.max_chunk_bytes)?;
+            decompressed.as_slice()
+        }
+    };
+
+    // Check the declared event count before anything is allocated for it
+    if let Some(count) = declared_array_len(payload) {
+        if count > limits.max_events_per_chunk {
+            return Err(GitError::ChunkTooLarge {
+                what: "event count",
+                actual: count,
+                limit: limits.max_events_per_chunk,
+            });
+        }
+    }
 
     // Parse CBOR payload
-    let payload_start = 11 + codec_len;
-    let value: Value = ciborium::from_reader(&data[payload_start..])
+    let value: Value = ciborium::from_reader(payload)
         .map_err(|e| GitError::CborDecode(format!("Failed to decode CBOR: {}", e)))?;
 
-    cbor_to_events(value)
+    Ok((algo, cbor_to_events(value)?))
+}
+
+/// Decompress a zstd payload, refusing to inflate it past `max_bytes`
+fn decompress_payload(compressed: &[u8], max_bytes: usize) -> Result<Vec<u8>, GitError> {
+    let decoder = zstd::stream::read::Decoder::new(compressed)
+        .map_err(|e| GitError::InvalidChunk(format!("Failed to decompress chunk: {}", e)))?;
+    let mut payload = Vec::new();
+    decoder
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut payload)
+        .map_err(|e| GitError::InvalidChunk(format!("Failed to decompress chunk: {}", e)))?;
+    if payload.len() > max_bytes {
+        return Err(GitError::ChunkTooLarge {
+            what: "decompressed size in bytes",
+            actual: payload.len() as u64,
+            limit: max_bytes as u64,
+        });
+    }
+    Ok(payload)
+}
+
+/// Length declared by a definite-length CBOR array header, if `payload`
+/// starts with one
+fn declared_array_len(payload: &[u8]) -> Option<u64> {
+    let (&initial, rest) = payload.split_first()?;
+    // Major type 4 is an array
+    if initial >> 5 != 4 {
+        return None;
+    }
+    let width = match initial & 0x1f {
+        n @ 0..=23 => return Some(n as u64),
+        24 => 1,
+        25 => 2,
+        26 => 4,
+        27 => 8,
+        // Indefinite length: nothing to check up front
+        _ => return None,
+    };
+    let bytes = rest.get(..width)?;
+    Some(bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64))
 }
 
 /// Compute BLAKE2b-256 hash of chunk data
@@ -263,15 +502,25 @@ fn parse_event_kind(tag: u32, payload: Value) -> Result<EventKind, GitError> {
             Ok(EventKind::IssueUpdated { title, body })
         }
         3 => {
-            // CommentAdded { body }
-            if array.len() != 1 {
+            // CommentAdded { body, parent_event: None } or, for replies, { body, parent_event }
+            if array.len() != 1 && array.len() != 2 {
                 return Err(GitError::InvalidEvent(
-                    "CommentAdded expects 1 field".to_string(),
+                    "CommentAdded expects 1 or 2 fields".to_string(),
                 ));
             }
             let mut iter = array.into_iter();
             let body = extract_string(&next_item(&mut iter, "body")?, "body")?;
-            Ok(EventKind::CommentAdded { body })
+            let parent_event = match iter.next() {
+                Some(value) => Some(
+                    extract_bytes(&value, "parent_event", 32)?
+                        .try_into()
+                        .map_err(|_| {
+                            GitError::InvalidEvent("Invalid parent_event length".to_string())
+                        })?,
+                ),
+                None => None,
+            };
+            Ok(EventKind::CommentAdded { body, parent_event })
         }
         4 => {
             // LabelAdded { label }
@@ -438,6 +687,126 @@ fn parse_event_kind(tag: u32, payload: Value) -> Result<EventKind, GitError> {
             let value = extract_string(&next_item(&mut iter, "value")?, "value")?;
             Ok(EventKind::ProjectContextUpdated { key, value })
         }
+        15 => {
+            // FieldSet { key, value }
+            if array.len() != 2 {
+                return Err(GitError::InvalidEvent(
+                    "FieldSet expects 2 fields".to_string(),
+                ));
+            }
+            let mut iter = array.into_iter();
+            let key = extract_string(&next_item(&mut iter, "key")?, "key")?;
+            let value = extract_optional_string(&next_item(&mut iter, "value")?, "value")?;
+            Ok(EventKind::FieldSet { key, value })
+        }
+        16 | 17 => {
+            // IssueLinked / IssueUnlinked { target, relation }
+            if array.len() != 2 {
+                return Err(GitError::InvalidEvent(format!(
+                    "{} expects 2 fields",
+                    if tag == 16 {
+                        "IssueLinked"
+                    } else {
+                        "IssueUnlinked"
+                    }
+                )));
+            }
+            let mut iter = array.into_iter();
+            let target: IssueId = extract_bytes(&next_item(&mut iter, "target")?, "target", 16)?
+                .try_into()
+                .map_err(|_| GitError::InvalidEvent("Invalid target length".to_string()))?;
+            let relation = extract_string(&next_item(&mut iter, "relation")?, "relation")?;
+            if tag == 16 {
+                Ok(EventKind::IssueLinked { target, relation })
+            } else {
+                Ok(EventKind::IssueUnlinked { target, relation })
+            }
+        }
+        18 => {
+            // Subscribed { user }
+            if array.len() != 1 {
+                return Err(GitError::InvalidEvent(
+                    "Subscribed expects 1 field".to_string(),
+                ));
+            }
+            let mut iter = array.into_iter();
+            let user = extract_string(&next_item(&mut iter, "user")?, "user")?;
+            Ok(EventKind::Subscribed { user })
+        }
+        19 => {
+            // Unsubscribed { user }
+            if array.len() != 1 {
+                return Err(GitError::InvalidEvent(
+                    "Unsubscribed expects 1 field".to_string(),
+                ));
+            }
+            let mut iter = array.into_iter();
+            let user = extract_string(&next_item(&mut iter, "user")?, "user")?;
+            Ok(EventKind::Unsubscribed { user })
+        }
+        20 => {
+            // TimeEstimated { minutes }
+            if array.len() != 1 {
+                return Err(GitError::InvalidEvent(
+                    "TimeEstimated expects 1 field".to_string(),
+                ));
+            }
+            let mut iter = array.into_iter();
+            let minutes = extract_u32(&next_item(&mut iter, "minutes")?, "minutes")?;
+            Ok(EventKind::TimeEstimated { minutes })
+        }
+        21 => {
+            // TimeLogged { minutes, note }
+            if array.len() != 2 {
+                return Err(GitError::InvalidEvent(
+                    "TimeLogged expects 2 fields".to_string(),
+                ));
+            }
+            let mut iter = array.into_iter();
+            let minutes = extract_u32(&next_item(&mut iter, "minutes")?, "minutes")?;
+            let note = extract_optional_string(&next_item(&mut iter, "note")?, "note")?;
+            Ok(EventKind::TimeLogged { minutes, note })
+        }
+        22 => {
+            // MilestoneSet { milestone }
+            if array.len() != 1 {
+                return Err(GitError::InvalidEvent(
+                    "MilestoneSet expects 1 field".to_string(),
+                ));
+            }
+            let mut iter = array.into_iter();
+            let milestone =
+                extract_optional_string(&next_item(&mut iter, "milestone")?, "milestone")?;
+            Ok(EventKind::MilestoneSet { milestone })
+        }
+        23 | 24 => {
+            // IssueDeleted {} / IssueUndeleted {}
+            if !array.is_empty() {
+                return Err(GitError::InvalidEvent(format!(
+                    "kind {} expects no fields",
+                    tag
+                )));
+            }
+            Ok(if tag == 23 {
+                EventKind::IssueDeleted {}
+            } else {
+                EventKind::IssueUndeleted {}
+            })
+        }
+        25 => {
+            // SeveritySet { severity }
+            if array.len() != 1 {
+                return Err(GitError::InvalidEvent(
+                    "SeveritySet expects 1 field".to_string(),
+                ));
+            }
+            let mut iter = array.into_iter();
+            let severity_str = extract_string(&next_item(&mut iter, "severity")?, "severity")?;
+            let severity = Severity::from_str(&severity_str).ok_or_else(|| {
+                GitError::InvalidEvent(format!("Invalid severity: {}", severity_str))
+            })?;
+            Ok(EventKind::SeveritySet { severity })
+        }
         _ => Err(GitError::InvalidEvent(format!("Unknown kind tag: {}", tag))),
     }
 }
@@ -618,6 +987,11 @@ mod tests {
             }),
             make_test_event(EventKind::CommentAdded {
                 body: "A comment".to_string(),
+                parent_event: None,
+            }),
+            make_test_event(EventKind::CommentAdded {
+                body: "A reply".to_string(),
+                parent_event: Some([9u8; 32]),
             }),
             make_test_event(EventKind::LabelAdded {
                 label: "bug".to_string(),
@@ -667,6 +1041,49 @@ mod tests {
                 key: "framework".to_string(),
                 value: "actix-web".to_string(),
             }),
+            make_test_event(EventKind::FieldSet {
+                key: "severity".to_string(),
+                value: Some("high".to_string()),
+            }),
+            make_test_event(EventKind::FieldSet {
+                key: "severity".to_string(),
+                value: None,
+            }),
+            make_test_event(EventKind::IssueLinked {
+                target: [0xDD; 16],
+                relation: "duplicate-of".to_string(),
+            }),
+            make_test_event(EventKind::IssueUnlinked {
+                target: [0xDD; 16],
+                relation: "duplicate-of".to_string(),
+            }),
+            make_test_event(EventKind::Subscribed {
+                user: "alice".to_string(),
+            }),
+            make_test_event(EventKind::Unsubscribed {
+                user: "alice".to_string(),
+            }),
+            make_test_event(EventKind::TimeEstimated { minutes: 120 }),
+            make_test_event(EventKind::TimeLogged {
+                minutes: 45,
+                note: Some("pairing".to_string()),
+            }),
+            make_test_event(EventKind::TimeLogged {
+                minutes: 15,
+                note: None,
+            }),
+            make_test_event(EventKind::MilestoneSet {
+                milestone: Some("v1".to_string()),
+            }),
+            make_test_event(EventKind::MilestoneSet { milestone: None }),
+            make_test_event(EventKind::IssueDeleted {}),
+            make_test_event(EventKind::IssueUndeleted {}),
+            make_test_event(EventKind::SeveritySet {
+                severity: Severity::Sev1,
+            }),
+            make_test_event(EventKind::SeveritySet {
+                severity: Severity::None,
+            }),
         ];
 
         let chunk = encode_chunk(&events).unwrap();
@@ -714,4 +1131,197 @@ mod tests {
         let result = decode_chunk(&data);
         assert!(matches!(result, Err(GitError::InvalidChunk(_))));
     }
+
+    #[test]
+    fn test_chunk_records_hash_algo() {
+        use libgrite_core::hash::compute_event_id_with;
+
+        let event = make_test_event(EventKind::CommentAdded {
+            body: "Stable".to_string(),
+            parent_event: None,
+        });
+        // The default algorithm is written as version 1, with no algorithm byte
+        let chunk = encode_chunk(std::slice::from_ref(&event)).unwrap();
+        assert_eq!(read_chunk_header(&chunk).unwrap().version, 1);
+        let (algo, decoded) = decode_chunk_with_algo(&chunk).unwrap();
+        assert_eq!(algo, HashAlgo::Blake2b256);
+        let e = &decoded[0];
+        let recomputed =
+            compute_event_id_with(algo, &e.issue_id, &e.actor, e.ts_unix_ms, None, &e.kind);
+        assert_eq!(recomputed, event.event_id);
+
+        // Version 2 records the algorithm after the codec
+        let algo_offset = 8 + 2 + 1 + CHUNK_CODEC.len();
+        let mut v2 = chunk[..algo_offset].to_vec();
+        v2[8..10].copy_from_slice(&2u16.to_le_bytes());
+        v2.push(HashAlgo::Blake2b256.id());
+        v2.extend_from_slice(&chunk[algo_offset..]);
+        let (algo, decoded) = decode_chunk_with_algo(&v2).unwrap();
+        assert_eq!(algo, HashAlgo::Blake2b256);
+        assert_eq!(decoded[0].event_id, event.event_id);
+
+        // An unknown algorithm is rejected rather than misread
+        let mut unknown = v2.clone();
+        unknown[algo_offset] = 0xff;
+        match decode_chunk(&unknown) {
+            Err(GitError::InvalidChunk(msg)) => {
+                assert!(msg.contains("hash algorithm"), "{}", msg)
+            }
+            other => panic!("expected InvalidChunk, got {:?}", other.map(|e| e.len())),
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_huge_declared_array() {
+        let event = make_test_event(EventKind::CommentAdded {
+            body: "Real".to_string(),
+            parent_event: None,
+        });
+        let chunk = encode_chunk(&[event]).unwrap();
+        let payload_start = 8 + 2 + 1 + CHUNK_CODEC.len();
+
+        // Same header, but the payload claims u64::MAX events
+        let mut data = chunk[..payload_start].to_vec();
+        data.push(0x9b);
+        data.extend_from_slice(&u64::MAX.to_be_bytes());
+        match decode_chunk(&data) {
+            Err(GitError::ChunkTooLarge { what, actual, .. }) => {
+                assert_eq!(what, "event count");
+                assert_eq!(actual, u64::MAX);
+            }
+            other => panic!("expected ChunkTooLarge, got {:?}", other.map(|e| e.len())),
+        }
+
+        // Tight limits reject a real chunk, and raising them accepts it
+        let tight = ChunkLimits {
+            max_chunk_bytes: 16,
+            max_events_per_chunk: 1,
+        };
+        assert!(matches!(
+            decode_chunk_with_limits(&chunk, &tight),
+            Err(GitError::ChunkTooLarge {
+                what: "size in bytes",
+                ..
+            })
+        ));
+        let raised = ChunkLimits {
+            max_chunk_bytes: chunk.len(),
+            ..tight
+        };
+        assert_eq!(
+            decode_chunk_with_limits(&chunk, &raised).unwrap().1.len(),
+            1
+        );
+        let no_events = ChunkLimits {
+            max_events_per_chunk: 0,
+            ..raised
+        };
+        assert!(matches!(
+            decode_chunk_with_limits(&chunk, &no_events),
+            Err(GitError::ChunkTooLarge {
+                what: "event count",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_zstd_chunk_roundtrip_is_smaller() {
+        let symbols: Vec<SymbolInfo> = (0..20)
+            .map(|i| SymbolInfo {
+                name: format!("handle_request_{}", i),
+                kind: "function".to_string(),
+                line_start: i * 10,
+                line_end: i * 10 + 8,
+            })
+            .collect();
+        let events: Vec<Event> = (0..300)
+            .map(|i| {
+                make_test_event(EventKind::ContextUpdated {
+                    path: format!("src/handlers/handler_{}.rs", i),
+                    language: "rust".to_string(),
+                    symbols: symbols.clone(),
+                    summary: "rust file with 20 functions".to_string(),
+                    content_hash: [i as u8; 32],
+                })
+            })
+            .collect();
+
+        let raw = encode_chunk(&events).unwrap();
+        let compressed =
+            encode_chunk_with_codec(&events, HashAlgo::default(), ChunkCodec::CborZstd).unwrap();
+        assert_eq!(
+            read_chunk_header(&compressed).unwrap().codec,
+            CHUNK_CODEC_ZSTD
+        );
+        assert!(
+            compressed.len() * 5 < raw.len(),
+            "compressed {} bytes, raw {} bytes",
+            compressed.len(),
+            raw.len()
+        );
+
+        // Both codecs decode to the same events
+        let decoded = decode_chunk(&compressed).unwrap();
+        assert_eq!(decoded, decode_chunk(&raw).unwrap());
+        let ids: Vec<EventId> = decoded.iter().map(|e| e.event_id).collect();
+        let want: Vec<EventId> = events.iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, want);
+
+        // The size limit applies to the decompressed payload
+        let limits = ChunkLimits {
+            max_chunk_bytes: compressed.len(),
+            ..ChunkLimits::default()
+        };
+        assert!(matches!(
+            decode_chunk_with_limits(&compressed, &limits),
+            Err(GitError::ChunkTooLarge {
+                what: "decompressed size in bytes",
+                ..
+            })
+        ));
+
+        // A corrupt compressed payload is an invalid chunk
+        let mut corrupt = compressed.clone();
+        let payload_start = 8 + 2 + 1 + CHUNK_CODEC_ZSTD.len();
+        corrupt.truncate(payload_start + 8);
+        assert!(matches!(
+            decode_chunk(&corrupt),
+            Err(GitError::InvalidChunk(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_chunk_header_accepts_unknown_fields() {
+        let event = make_test_event(EventKind::CommentAdded {
+            body: "Header".to_string(),
+            parent_event: None,
+        });
+        let chunk = encode_chunk(&[event]).unwrap();
+        let header = read_chunk_header(&chunk).unwrap();
+        assert_eq!(
+            header,
+            ChunkHeader {
+                version: 1,
+                codec: CHUNK_CODEC.to_string(),
+                hash_algo: HashAlgo::Blake2b256.id(),
+            }
+        );
+
+        // A codec this build cannot decode still has a readable header
+        let mut data = Vec::new();
+        data.extend_from_slice(CHUNK_MAGIC);
+        data.extend_from_slice(&3u16.to_le_bytes());
+        data.push(9);
+        data.extend_from_slice(b"cbor-zstd");
+        data.push(7);
+        let header = read_chunk_header(&data).unwrap();
+        assert_eq!(header.version, 3);
+        assert_eq!(header.codec, "cbor-zstd");
+        assert_eq!(header.hash_algo, 7);
+        assert!(matches!(
+            decode_chunk(&data),
+            Err(GitError::InvalidChunk(_))
+        ));
+    }
 }
@@ -0,0 +1,82 @@
+//! Chunk compatibility with a remote's declared capabilities
+//!
+//! A remote declares which chunk formats its readers understand in
+//! `capabilities.json` on `refs/grite/capabilities`. A remote without that ref
+//! is assumed to read only the baseline `cbor-v1` format.
+
+use libgrite_core::hash::HashAlgo;
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::{ChunkHeader, CHUNK_CODEC};
+
+/// Ref holding the repository's declared capabilities
+pub const CAPABILITIES_REF: &str = "refs/grite/capabilities";
+
+/// File in the capabilities commit tree
+pub const CAPABILITIES_FILE: &str = "capabilities.json";
+
+/// Chunk formats a repository's readers can decode
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub chunk_versions: Vec<u16>,
+    pub codecs: Vec<String>,
+    /// Hash algorithm names, as reported by [`HashAlgo::as_str`]
+    pub hash_algos: Vec<String>,
+}
+
+impl Capabilities {
+    /// Capabilities assumed for a remote that declares none
+    ///
+    /// Readers from before capabilities existed only know chunk version 1.
+    pub fn baseline() -> Self {
+        Self {
+            chunk_versions: vec![1],
+            codecs: vec![CHUNK_CODEC.to_string()],
+            hash_algos: vec![HashAlgo::Blake2b256.as_str().to_string()],
+        }
+    }
+
+    /// Reasons a chunk with `header` cannot be read, empty if compatible
+    pub fn check(&self, header: &ChunkHeader) -> Vec<String> {
+        let mut reasons = Vec::new();
+        if !self.chunk_versions.contains(&header.version) {
+            reasons.push(format!("chunk version {} not supported", header.version));
+        }
+        if !self.codecs.contains(&header.codec) {
+            reasons.push(format!("codec {} not supported", header.codec));
+        }
+        let algo = HashAlgo::from_id(header.hash_algo)
+            .map(|a| a.as_str().to_string())
+            .unwrap_or_else(|| format!("id {}", header.hash_algo));
+        if !self.hash_algos.contains(&algo) {
+            reasons.push(format!("hash algorithm {} not supported", algo));
+        }
+        reasons
+    }
+}
+
+/// A local chunk the remote cannot read
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IncompatibleChunk {
+    pub commit: String,
+    pub path: String,
+    pub reasons: Vec<String>,
+}
+
+/// Outcome of `grite sync --check-compat`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatReport {
+    pub remote: String,
+    /// Whether the remote declared capabilities (otherwise baseline is assumed)
+    pub declared: bool,
+    pub capabilities: Capabilities,
+    pub chunks_checked: usize,
+    pub incompatible: Vec<IncompatibleChunk>,
+}
+
+impl CompatReport {
+    /// Whether every local chunk is readable by the remote
+    pub fn is_compatible(&self) -> bool {
+        self.incompatible.is_empty()
+    }
+}
@@ -18,6 +18,16 @@ pub enum GitError {
     #[error("Invalid chunk format: {0}")]
     InvalidChunk(String),
 
+    #[error("Chunk {what} {actual} exceeds the limit of {limit}")]
+    ChunkTooLarge {
+        what: &'static str,
+        actual: u64,
+        limit: u64,
+    },
+
+    #[error("Invalid archive: {0}")]
+    InvalidArchive(String),
+
     #[error("WAL error: {0}")]
     Wal(String),
 
@@ -45,6 +55,9 @@ pub enum GitError {
 
     #[error("Lock not owned: {resource} is owned by {owner}")]
     LockNotOwned { resource: String, owner: String },
+
+    #[error("Push to {remote} rejected: {reason}")]
+    PushRejected { remote: String, reason: String },
 }
 
 /// Bridge GitError into GriteError, preserving semantic variants.
@@ -64,6 +77,12 @@ impl From<GitError> for libgrite_core::GriteError {
             GitError::LockNotOwned { resource, owner } => libgrite_core::GriteError::Conflict(
                 format!("Cannot release lock on '{}': owned by {}", resource, owner),
             ),
+            GitError::PushRejected { remote, reason } => {
+                libgrite_core::GriteError::Conflict(format!(
+                    "Push to '{}' was rejected ({}); run 'grite sync --pull' and push again",
+                    remote, reason
+                ))
+            }
             GitError::NotARepo => {
                 libgrite_core::GriteError::NotFound("Not a git repository".to_string())
             }
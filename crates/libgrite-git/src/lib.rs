@@ -2,20 +2,33 @@
 //!
 //! This crate provides Git integration for Grite's event system:
 //! - CBOR chunk encoding/decoding for portable event storage
+//! - Single-file store archives for backup and restore
 //! - WAL (Write-Ahead Log) operations via `refs/grite/wal`
 //! - Snapshot management via `refs/grite/snapshots/<ts>`
 //! - Push/pull sync operations with conflict handling
+//! - Chunk compatibility checks against a remote's declared capabilities
 
+mod archive;
 mod chunk;
+mod compat;
 mod error;
 mod lock_manager;
 mod snapshot;
 mod sync;
 mod wal;
 
-pub use chunk::{chunk_hash, decode_chunk, encode_chunk, CHUNK_CODEC, CHUNK_MAGIC, CHUNK_VERSION};
+pub use archive::{ArchiveManifest, StoreArchive, ARCHIVE_MAGIC, ARCHIVE_VERSION};
+pub use chunk::{
+    chunk_hash, decode_chunk, decode_chunk_with_algo, decode_chunk_with_limits, encode_chunk,
+    encode_chunk_with_algo, encode_chunk_with_codec, read_chunk_header, ChunkCodec, ChunkHeader,
+    ChunkLimits, CHUNK_CODEC, CHUNK_CODEC_ZSTD, CHUNK_MAGIC, CHUNK_VERSION,
+    DEFAULT_MAX_CHUNK_BYTES, DEFAULT_MAX_EVENTS_PER_CHUNK, MIN_CHUNK_VERSION,
+};
+pub use compat::{Capabilities, CompatReport, IncompatibleChunk, CAPABILITIES_REF};
 pub use error::GitError;
 pub use lock_manager::{LockGcStats, LockManager};
-pub use snapshot::{SnapshotManager, SnapshotMeta, SnapshotRef};
-pub use sync::{PullResult, PushResult, SyncManager};
-pub use wal::{WalCommit, WalManager};
+pub use snapshot::{
+    SignatureStatus, SnapshotManager, SnapshotMeta, SnapshotRef, SnapshotSignature,
+};
+pub use sync::{IssuePreview, PullPreview, PullResult, PushResult, SyncManager, SyncReport};
+pub use wal::{ChunkVerification, PruneStats, WalChunk, WalCommit, WalManager, WalTail};
@@ -434,6 +434,24 @@ mod tests {
         assert!(lock2.expires_unix_ms > expires1);
     }
 
+    #[test]
+    fn test_renew_lock_owned_by_other_fails() {
+        let dir = setup_repo();
+        let manager = LockManager::open(dir.path()).unwrap();
+
+        let lock = manager
+            .acquire("issue:abc123", "actor1", Some(60000))
+            .unwrap();
+
+        match manager.renew("issue:abc123", "actor2", Some(600000)) {
+            Err(GitError::LockNotOwned { owner, .. }) => assert_eq!(owner, "actor1"),
+            other => panic!("expected LockNotOwned, got {:?}", other),
+        }
+        let unchanged = manager.read_lock("issue:abc123").unwrap().unwrap();
+        assert_eq!(unchanged.owner, "actor1");
+        assert_eq!(unchanged.expires_unix_ms, lock.expires_unix_ms);
+    }
+
     #[test]
     fn test_list_locks() {
         let dir = setup_repo();
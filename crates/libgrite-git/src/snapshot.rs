@@ -4,8 +4,10 @@
 //! replaying the entire WAL history.
 
 use git2::{Oid, Repository, Signature};
+use libgrite_core::signing::{verify_raw, SigningKeyPair};
 use libgrite_core::types::event::Event;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 
 use crate::chunk::{chunk_hash, decode_chunk, encode_chunk};
@@ -23,8 +25,75 @@ pub struct SnapshotMeta {
     pub schema_version: u32,
     pub created_ts: u64,
     pub wal_head: String,
+    /// Events stored in this snapshot's own chunks (for a delta, only the
+    /// events added since its base)
     pub event_count: usize,
+    /// Timestamp of the snapshot this delta builds on (None for a full snapshot)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_ts: Option<u64>,
+    /// Total size of the encoded event chunks (absent in older snapshots)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    /// Pinned snapshots are never deleted by [`SnapshotManager::gc`]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
     pub chunks: Vec<ChunkInfo>,
+    /// Signature over [`SnapshotMeta::content_id`], for signed snapshots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<SnapshotSignature>,
+}
+
+impl SnapshotMeta {
+    /// Digest identifying the snapshot's content.
+    ///
+    /// Covers the creation time, WAL head, event count, delta base and
+    /// every chunk's path, hash and event count, but not `pinned` or
+    /// `signature`, which may change after the snapshot is created.
+    pub fn content_id(&self) -> [u8; 32] {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(b"grite-snapshot-v1");
+        buf.extend_from_slice(&self.schema_version.to_le_bytes());
+        buf.extend_from_slice(&self.created_ts.to_le_bytes());
+        push_str(&mut buf, &self.wal_head);
+        buf.extend_from_slice(&(self.event_count as u64).to_le_bytes());
+        // Absent for full snapshots, so their IDs are unchanged
+        if let Some(base_ts) = self.base_ts {
+            buf.extend_from_slice(b"base");
+            buf.extend_from_slice(&base_ts.to_le_bytes());
+        }
+        for chunk in &self.chunks {
+            push_str(&mut buf, &chunk.path);
+            push_str(&mut buf, &chunk.chunk_hash);
+            buf.extend_from_slice(&(chunk.event_count as u64).to_le_bytes());
+        }
+        chunk_hash(&buf)
+    }
+}
+
+/// Append a length-prefixed string, so adjacent fields cannot run together
+fn push_str(buf: &mut Vec<u8>, s: &str) {
+    buf.extend_from_slice(&(s.len() as u64).to_le_bytes());
+    buf.extend_from_slice(s.as_bytes());
+}
+
+/// Ed25519 signature of a snapshot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotSignature {
+    /// Hex public key of the signer
+    pub public_key: String,
+    /// Hex signature over the snapshot's content ID
+    pub signature: String,
+}
+
+/// Outcome of [`SnapshotManager::verify`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The snapshot carries no signature
+    Unsigned,
+    /// The signature matches the key and the chunks match their hashes
+    Valid,
+    /// The signature or the content does not check out, with the reason
+    Invalid(String),
 }
 
 /// Information about a chunk in a snapshot
@@ -58,6 +127,63 @@ impl SnapshotManager {
 
     /// Create a new snapshot from events
     pub fn create(&self, wal_head: Oid, events: &[Event]) -> Result<Oid, GitError> {
+        self.write(wal_head, events, None, None)
+    }
+
+    /// Create a new snapshot signed with `keypair`
+    pub fn create_signed(
+        &self,
+        wal_head: Oid,
+        events: &[Event],
+        keypair: &SigningKeyPair,
+    ) -> Result<Oid, GitError> {
+        self.write(wal_head, events, Some(keypair), None)
+    }
+
+    /// Create a delta snapshot on top of the snapshot at `base_ts`
+    ///
+    /// `events` is the full current event set; only events the base chain
+    /// does not already hold are stored. [`SnapshotManager::read`] walks the
+    /// chain to reconstruct the full set.
+    pub fn create_delta(
+        &self,
+        base_ts: u64,
+        wal_head: Oid,
+        events: &[Event],
+    ) -> Result<Oid, GitError> {
+        let base = self.find(base_ts)?;
+        let known: HashSet<_> = self
+            .read(base.id())?
+            .into_iter()
+            .map(|event| event.event_id)
+            .collect();
+        let added: Vec<Event> = events
+            .iter()
+            .filter(|event| !known.contains(&event.event_id))
+            .cloned()
+            .collect();
+        if added.is_empty() {
+            return Err(GitError::Snapshot(format!(
+                "No events added since snapshot {}",
+                base_ts
+            )));
+        }
+        self.write(wal_head, &added, None, Some(base_ts))
+    }
+
+    /// The commit of the snapshot created at `timestamp`
+    fn find(&self, timestamp: u64) -> Result<git2::Commit<'_>, GitError> {
+        let ref_name = format!("{}{}", SNAPSHOT_REF_PREFIX, timestamp);
+        Ok(self.repo.find_reference(&ref_name)?.peel_to_commit()?)
+    }
+
+    fn write(
+        &self,
+        wal_head: Oid,
+        events: &[Event],
+        keypair: Option<&SigningKeyPair>,
+        base_ts: Option<u64>,
+    ) -> Result<Oid, GitError> {
         if events.is_empty() {
             return Err(GitError::Snapshot(
                 "Cannot create empty snapshot".to_string(),
@@ -71,6 +197,7 @@ impl SnapshotManager {
 
         // Split events into chunks
         let mut chunks_info = Vec::new();
+        let mut size_bytes = 0u64;
         let mut tree_builder = self.repo.treebuilder(None)?;
 
         // Create events directory
@@ -78,6 +205,7 @@ impl SnapshotManager {
 
         for (i, chunk_events) in events.chunks(SNAPSHOT_CHUNK_SIZE).enumerate() {
             let chunk_data = encode_chunk(chunk_events)?;
+            size_bytes += chunk_data.len() as u64;
             let hash = chunk_hash(&chunk_data);
             let hash_hex = hex::encode(hash);
 
@@ -96,13 +224,21 @@ impl SnapshotManager {
         tree_builder.insert("events", events_tree_oid, 0o040000)?;
 
         // Create snapshot.json
-        let meta = SnapshotMeta {
+        let mut meta = SnapshotMeta {
             schema_version: 1,
             created_ts: now_ms,
             wal_head: wal_head.to_string(),
             event_count: events.len(),
+            base_ts,
+            size_bytes: Some(size_bytes),
+            pinned: false,
             chunks: chunks_info,
+            signature: None,
         };
+        meta.signature = keypair.map(|keypair| SnapshotSignature {
+            public_key: keypair.public_key_hex(),
+            signature: hex::encode(keypair.sign(&meta.content_id())),
+        });
         let meta_json = serde_json::to_string_pretty(&meta)?;
         let meta_blob = self.repo.blob(meta_json.as_bytes())?;
         tree_builder.insert("snapshot.json", meta_blob, 0o100644)?;
@@ -157,25 +293,141 @@ impl SnapshotManager {
         Ok(self.list()?.into_iter().next())
     }
 
-    /// Read all events from a snapshot
-    pub fn read(&self, oid: Oid) -> Result<Vec<Event>, GitError> {
+    /// Read the metadata of a snapshot
+    pub fn meta(&self, oid: Oid) -> Result<SnapshotMeta, GitError> {
         let commit = self.repo.find_commit(oid)?;
         let tree = commit.tree()?;
-
-        // Read snapshot.json for chunk order
         let meta_entry = tree
             .get_name("snapshot.json")
             .ok_or_else(|| GitError::Snapshot("Missing snapshot.json".to_string()))?;
         let meta_blob = self.repo.find_blob(meta_entry.id())?;
-        let meta: SnapshotMeta = serde_json::from_slice(meta_blob.content())?;
+        Ok(serde_json::from_slice(meta_blob.content())?)
+    }
 
-        // Read chunks in order
-        let mut all_events = Vec::with_capacity(meta.event_count);
+    /// Pin or unpin the snapshot created at `timestamp`
+    ///
+    /// Snapshot commits are immutable, so this writes a new commit with the
+    /// updated `snapshot.json` and moves the snapshot's ref to it. Fails with
+    /// a not-found error if there is no snapshot at `timestamp`.
+    pub fn set_pinned(&self, timestamp: u64, pinned: bool) -> Result<Oid, GitError> {
+        let ref_name = format!("{}{}", SNAPSHOT_REF_PREFIX, timestamp);
+        let reference = self.repo.find_reference(&ref_name)?;
+        let commit = reference.peel_to_commit()?;
+
+        let mut meta = self.meta(commit.id())?;
+        if meta.pinned == pinned {
+            return Ok(commit.id());
+        }
+        meta.pinned = pinned;
 
+        let meta_json = serde_json::to_string_pretty(&meta)?;
+        let meta_blob = self.repo.blob(meta_json.as_bytes())?;
+        let mut tree_builder = self.repo.treebuilder(Some(&commit.tree()?))?;
+        tree_builder.insert("snapshot.json", meta_blob, 0o100644)?;
+        let tree = self.repo.find_tree(tree_builder.write()?)?;
+
+        let sig = Signature::now("grite", "grit@local")?;
+        let message = format!(
+            "{} snapshot {}",
+            if pinned { "Pin" } else { "Unpin" },
+            timestamp
+        );
+        let new_oid = self.repo.commit(None, &sig, &sig, &message, &tree, &[])?;
+        self.repo
+            .reference(&ref_name, new_oid, true, "snapshot pin")?;
+
+        Ok(new_oid)
+    }
+
+    /// Check a snapshot's signature against `public_key_hex`.
+    ///
+    /// Every chunk is also hashed and compared with `snapshot.json`, so a
+    /// valid result covers the stored events and not just the metadata.
+    pub fn verify(&self, oid: Oid, public_key_hex: &str) -> Result<SignatureStatus, GitError> {
+        let meta = self.meta(oid)?;
+        let Some(signature) = &meta.signature else {
+            return Ok(SignatureStatus::Unsigned);
+        };
+
+        let events_tree = self.events_tree(oid)?;
+        for chunk_info in &meta.chunks {
+            let chunk_name = chunk_info
+                .path
+                .strip_prefix("events/")
+                .unwrap_or(&chunk_info.path);
+            let Some(entry) = events_tree.get_name(chunk_name) else {
+                return Ok(SignatureStatus::Invalid(format!(
+                    "missing chunk {}",
+                    chunk_info.path
+                )));
+            };
+            let blob = self.repo.find_blob(entry.id())?;
+            if hex::encode(chunk_hash(blob.content())) != chunk_info.chunk_hash {
+                return Ok(SignatureStatus::Invalid(format!(
+                    "chunk {} does not match its recorded hash",
+                    chunk_info.path
+                )));
+            }
+        }
+
+        let sig_bytes = match hex::decode(&signature.signature) {
+            Ok(bytes) => bytes,
+            Err(e) => return Ok(SignatureStatus::Invalid(format!("bad signature: {}", e))),
+        };
+        Ok(
+            match verify_raw(&meta.content_id(), &sig_bytes, public_key_hex) {
+                Ok(()) => SignatureStatus::Valid,
+                Err(e) => SignatureStatus::Invalid(e.to_string()),
+            },
+        )
+    }
+
+    /// The `events` tree of a snapshot commit
+    fn events_tree(&self, oid: Oid) -> Result<git2::Tree<'_>, GitError> {
+        let tree = self.repo.find_commit(oid)?.tree()?;
         let events_entry = tree
             .get_name("events")
             .ok_or_else(|| GitError::Snapshot("Missing events directory".to_string()))?;
-        let events_tree = self.repo.find_tree(events_entry.id())?;
+        Ok(self.repo.find_tree(events_entry.id())?)
+    }
+
+    /// Read all events from a snapshot
+    ///
+    /// For a delta snapshot, the events of every base in its chain come
+    /// first. A delta whose base no longer exists is incomplete and fails to
+    /// read.
+    pub fn read(&self, oid: Oid) -> Result<Vec<Event>, GitError> {
+        // Collect the chain, newest first
+        let mut chain = vec![(oid, self.meta(oid)?)];
+        while let Some(base_ts) = chain[chain.len() - 1].1.base_ts {
+            let created_ts = chain[chain.len() - 1].1.created_ts;
+            if base_ts >= created_ts {
+                return Err(GitError::Snapshot(format!(
+                    "Delta snapshot {} has base {} that is not older",
+                    created_ts, base_ts
+                )));
+            }
+            let base = self.find(base_ts).map_err(|_| {
+                GitError::Snapshot(format!(
+                    "Incomplete delta snapshot {}: base snapshot {} is missing",
+                    created_ts, base_ts
+                ))
+            })?;
+            chain.push((base.id(), self.meta(base.id())?));
+        }
+
+        let mut all_events = Vec::with_capacity(chain.iter().map(|(_, m)| m.event_count).sum());
+        for (oid, meta) in chain.iter().rev() {
+            all_events.extend(self.read_own(*oid, meta)?);
+        }
+        Ok(all_events)
+    }
+
+    /// Read the events stored in a snapshot's own chunks
+    fn read_own(&self, oid: Oid, meta: &SnapshotMeta) -> Result<Vec<Event>, GitError> {
+        // Read chunks in order
+        let mut all_events = Vec::with_capacity(meta.event_count);
+        let events_tree = self.events_tree(oid)?;
 
         for chunk_info in &meta.chunks {
             let chunk_name = chunk_info
@@ -199,11 +451,44 @@ impl SnapshotManager {
     }
 
     /// Garbage collect old snapshots, keeping the N most recent
+    ///
+    /// Pinned snapshots are kept whatever their age, as is every base that a
+    /// kept delta snapshot depends on.
     pub fn gc(&self, keep: usize) -> Result<GcStats, GitError> {
         let snapshots = self.list()?;
         let mut deleted = 0;
+        let mut pinned = 0;
+        let mut bases = 0;
+        let mut reclaimable_bytes = 0;
+        // Bases of kept snapshots; a base is always older than its delta,
+        // so it is reached after every snapshot that needs it
+        let mut needed = HashSet::new();
+
+        for (i, snapshot) in snapshots.into_iter().enumerate() {
+            let meta = self.meta(snapshot.oid).ok();
+            let kept = if i < keep {
+                true
+            } else if meta.as_ref().is_some_and(|m| m.pinned) {
+                pinned += 1;
+                true
+            } else if needed.contains(&snapshot.timestamp) {
+                bases += 1;
+                true
+            } else {
+                false
+            };
+            if kept {
+                if let Some(base_ts) = meta.and_then(|m| m.base_ts) {
+                    needed.insert(base_ts);
+                }
+                continue;
+            }
+
+            // Older snapshots do not record a size and add nothing here
+            if let Some(size) = meta.and_then(|m| m.size_bytes) {
+                reclaimable_bytes += size;
+            }
 
-        for snapshot in snapshots.into_iter().skip(keep) {
             // Delete the reference
             let mut reference = self.repo.find_reference(&snapshot.ref_name)?;
             reference.delete()?;
@@ -213,6 +498,9 @@ impl SnapshotManager {
         Ok(GcStats {
             deleted,
             kept: keep,
+            pinned,
+            bases,
+            reclaimable_bytes,
         })
     }
 }
@@ -222,6 +510,12 @@ impl SnapshotManager {
 pub struct GcStats {
     pub deleted: usize,
     pub kept: usize,
+    /// Snapshots past `kept` that survived because they are pinned
+    pub pinned: usize,
+    /// Snapshots past `kept` that survived because a kept delta builds on them
+    pub bases: usize,
+    /// Chunk bytes no longer referenced once `git gc` prunes the deleted snapshots
+    pub reclaimable_bytes: u64,
 }
 
 #[cfg(test)]
@@ -281,6 +575,51 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_delta_snapshot_reconstructs_all_events() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let mgr = SnapshotManager::open(&git_dir).unwrap();
+        let fake_wal = Oid::from_str("0000000000000000000000000000000000000000").unwrap();
+        let mut events = make_test_events(3);
+        mgr.create(fake_wal, &events).unwrap();
+        let base_ts = mgr.latest().unwrap().unwrap().timestamp;
+
+        // Add events, then snapshot only what is new
+        events.extend(make_test_events(2));
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let delta = mgr.create_delta(base_ts, fake_wal, &events).unwrap();
+        let meta = mgr.meta(delta).unwrap();
+        assert_eq!(meta.base_ts, Some(base_ts));
+        assert_eq!(meta.event_count, 2);
+
+        let restored = mgr.read(delta).unwrap();
+        let ids: Vec<_> = restored.iter().map(|e| e.event_id).collect();
+        let expected: Vec<_> = events.iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, expected);
+
+        // Nothing new since the delta
+        assert!(mgr
+            .create_delta(meta.created_ts, fake_wal, &events)
+            .is_err());
+
+        // GC keeps the base the delta depends on
+        let stats = mgr.gc(1).unwrap();
+        assert_eq!(stats.deleted, 0);
+        assert_eq!(stats.bases, 1);
+        assert_eq!(mgr.read(delta).unwrap().len(), 5);
+
+        // A delta whose base is gone is incomplete and cannot be restored
+        mgr.repo
+            .find_reference(&format!("{}{}", SNAPSHOT_REF_PREFIX, base_ts))
+            .unwrap()
+            .delete()
+            .unwrap();
+        let err = mgr.read(delta).unwrap_err().to_string();
+        assert!(err.contains("Incomplete delta snapshot"), "{}", err);
+    }
+
     #[test]
     fn test_snapshot_list_and_latest() {
         let (temp, _repo) = setup_test_repo();
@@ -306,6 +645,35 @@ mod tests {
         assert_eq!(latest.oid, snapshots[0].oid);
     }
 
+    #[test]
+    fn test_snapshot_meta_records_size_and_count() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let mgr = SnapshotManager::open(&git_dir).unwrap();
+        let fake_wal = Oid::from_str("0000000000000000000000000000000000000000").unwrap();
+        let small = mgr.create(fake_wal, &make_test_events(2)).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let large = mgr.create(fake_wal, &make_test_events(40)).unwrap();
+
+        let small_meta = mgr.meta(small).unwrap();
+        let large_meta = mgr.meta(large).unwrap();
+        assert_eq!(small_meta.event_count, 2);
+        assert_eq!(large_meta.event_count, 40);
+        assert!(large_meta.size_bytes.unwrap() > small_meta.size_bytes.unwrap());
+
+        // Older snapshot.json files have no size
+        let old: SnapshotMeta = serde_json::from_str(
+            r#"{"schema_version":1,"created_ts":1,"wal_head":"00","event_count":3,"chunks":[]}"#,
+        )
+        .unwrap();
+        assert_eq!(old.size_bytes, None);
+
+        let stats = mgr.gc(1).unwrap();
+        assert_eq!(stats.deleted, 1);
+        assert_eq!(stats.reclaimable_bytes, small_meta.size_bytes.unwrap());
+    }
+
     #[test]
     fn test_snapshot_gc() {
         let (temp, _repo) = setup_test_repo();
@@ -329,4 +697,94 @@ mod tests {
 
         assert_eq!(mgr.list().unwrap().len(), 2);
     }
+
+    #[test]
+    fn test_snapshot_gc_keeps_pinned() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let mgr = SnapshotManager::open(&git_dir).unwrap();
+        let fake_wal = Oid::from_str("0000000000000000000000000000000000000000").unwrap();
+
+        for _ in 0..4 {
+            mgr.create(fake_wal, &make_test_events(1)).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let snapshots = mgr.list().unwrap();
+        let newest = snapshots[0].timestamp;
+        let oldest = snapshots[3].timestamp;
+
+        let pinned_oid = mgr.set_pinned(oldest, true).unwrap();
+        assert!(mgr.meta(pinned_oid).unwrap().pinned);
+        // The pinned snapshot still reads back its events
+        assert_eq!(mgr.read(pinned_oid).unwrap().len(), 1);
+
+        let stats = mgr.gc(1).unwrap();
+        assert_eq!(stats.deleted, 2);
+        assert_eq!(stats.pinned, 1);
+
+        let remaining: Vec<u64> = mgr.list().unwrap().iter().map(|s| s.timestamp).collect();
+        assert_eq!(remaining, vec![newest, oldest]);
+
+        // Once unpinned it is collected like any other
+        mgr.set_pinned(oldest, false).unwrap();
+        assert_eq!(mgr.gc(1).unwrap().deleted, 1);
+        assert_eq!(mgr.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_pin_missing_snapshot_fails() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let mgr = SnapshotManager::open(&git_dir).unwrap();
+        let err = mgr.set_pinned(1234, true).unwrap_err();
+        assert!(matches!(
+            libgrite_core::GriteError::from(err),
+            libgrite_core::GriteError::NotFound(_)
+        ));
+    }
+
+    #[test]
+    fn test_signed_snapshot_verifies_with_matching_key() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let mgr = SnapshotManager::open(&git_dir).unwrap();
+        let fake_wal = Oid::from_str("0000000000000000000000000000000000000000").unwrap();
+        let keypair = SigningKeyPair::generate();
+        let other = SigningKeyPair::generate();
+
+        let oid = mgr
+            .create_signed(fake_wal, &make_test_events(3), &keypair)
+            .unwrap();
+        let meta = mgr.meta(oid).unwrap();
+        assert_eq!(
+            meta.signature.as_ref().unwrap().public_key,
+            keypair.public_key_hex()
+        );
+        assert_eq!(
+            mgr.verify(oid, &keypair.public_key_hex()).unwrap(),
+            SignatureStatus::Valid
+        );
+        assert!(matches!(
+            mgr.verify(oid, &other.public_key_hex()).unwrap(),
+            SignatureStatus::Invalid(_)
+        ));
+
+        // Pinning rewrites snapshot.json but keeps the signature valid
+        let pinned_oid = mgr.set_pinned(meta.created_ts, true).unwrap();
+        assert_eq!(
+            mgr.verify(pinned_oid, &keypair.public_key_hex()).unwrap(),
+            SignatureStatus::Valid
+        );
+
+        // An unsigned snapshot is reported as such, not as invalid
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let unsigned = mgr.create(fake_wal, &make_test_events(1)).unwrap();
+        assert_eq!(
+            mgr.verify(unsigned, &keypair.public_key_hex()).unwrap(),
+            SignatureStatus::Unsigned
+        );
+    }
 }
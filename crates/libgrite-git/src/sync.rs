@@ -4,18 +4,31 @@
 //! conflict resolution for non-fast-forward pushes.
 
 use git2::{FetchOptions, Oid, PushOptions, RemoteCallbacks, Repository};
-use libgrite_core::types::event::Event;
-use libgrite_core::types::ids::ActorId;
+use libgrite_core::types::event::{canonical_order, Event};
+use libgrite_core::types::ids::{id_to_hex, ActorId, IssueId};
+use libgrite_core::GriteStore;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 use std::rc::Rc;
 
-use crate::wal::WalManager;
+use crate::compat::{
+    Capabilities, CompatReport, IncompatibleChunk, CAPABILITIES_FILE, CAPABILITIES_REF,
+};
+use crate::wal::{WalManager, WAL_REF};
 use crate::GitError;
 
 /// Refspec for grite refs
 pub const GRITE_REFSPEC: &str = "refs/grite/*:refs/grite/*";
 
+/// Where [`SyncManager::pull_issues`] keeps a remote's fetched WAL, outside
+/// `refs/grite/` so it is never pushed or mistaken for the local WAL
+const PARTIAL_FETCH_REF_PREFIX: &str = "refs/grite-partial";
+
+/// Where [`SyncManager::check_compat`] keeps a remote's fetched capabilities
+const COMPAT_FETCH_REF_PREFIX: &str = "refs/grite-compat";
+
 /// Result of a pull operation
 #[derive(Debug)]
 pub struct PullResult {
@@ -42,6 +55,99 @@ pub struct PushResult {
     pub message: String,
 }
 
+/// Outcome of `grite sync`
+///
+/// Built by both the CLI and the daemon so the two emit the same JSON. Fields
+/// for a step that did not run keep their zero values.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub remote: String,
+    pub pulled: bool,
+    pub pushed: bool,
+    pub pull_events: usize,
+    pub pull_wal_head: Option<String>,
+    pub push_success: bool,
+    pub push_rebased: bool,
+    pub push_events_rebased: usize,
+    /// Events copied from the local store into an empty WAL before pushing
+    pub backfilled: usize,
+    pub message: String,
+}
+
+impl SyncReport {
+    /// Combine the results of whichever steps ran
+    pub fn new(
+        remote: &str,
+        backfilled: usize,
+        pull: Option<&PullResult>,
+        push: Option<&PushResult>,
+    ) -> Self {
+        let message = [
+            pull.map(|p| p.message.as_str()),
+            push.map(|p| p.message.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" / ");
+
+        Self {
+            remote: remote.to_string(),
+            pulled: pull.is_some(),
+            pushed: push.is_some(),
+            pull_events: pull.map_or(0, |p| p.events_pulled),
+            pull_wal_head: pull.and_then(|p| p.new_wal_head.map(|oid| oid.to_string())),
+            push_success: push.is_some_and(|p| p.success),
+            push_rebased: push.is_some_and(|p| p.rebased),
+            push_events_rebased: push.map_or(0, |p| p.events_rebased),
+            backfilled,
+            message,
+        }
+    }
+}
+
+/// What a pull would change for one issue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssuePreview {
+    pub issue_id: String,
+    /// Whether the local WAL has no events for this issue yet
+    pub new_issue: bool,
+    /// Title after the pull, if the issue can be projected
+    pub title: Option<String>,
+    /// State after the pull, if the issue can be projected
+    pub state: Option<String>,
+    /// Number of incoming events
+    pub events: usize,
+    /// Incoming events counted by kind name
+    pub kinds: BTreeMap<String, usize>,
+}
+
+/// Outcome of [`SyncManager::preview`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PullPreview {
+    pub remote: String,
+    pub remote_wal_head: Option<String>,
+    pub event_count: usize,
+    /// Affected issues, ordered by ID
+    pub issues: Vec<IssuePreview>,
+    /// Incoming events in canonical order
+    #[serde(skip)]
+    pub events: Vec<Event>,
+}
+
+/// Note WAL commits pulled without a recorded chunk hash, which could not be
+/// checked for corruption
+fn with_unrecorded_warning(message: String, unrecorded: usize) -> String {
+    if unrecorded == 0 {
+        message
+    } else {
+        format!(
+            "{} (warning: {} WAL commits have no recorded chunk hash)",
+            message, unrecorded
+        )
+    }
+}
+
 /// Manager for sync operations
 pub struct SyncManager {
     repo: Repository,
@@ -65,8 +171,282 @@ impl SyncManager {
         let old_head = wal.head()?;
 
         // Fetch refs/grite/* from remote
+        self.fetch(remote_name, &[GRITE_REFSPEC])?;
+
+        // Check if WAL head changed
+        let new_head = wal.head()?;
+        let mut unrecorded = 0;
+        if let Some(new_oid) = new_head.filter(|oid| Some(*oid) != old_head) {
+            match wal.verify_chunks(new_oid, old_head) {
+                Ok(verification) => unrecorded = verification.unrecorded.len(),
+                Err(e) => {
+                    // Put the WAL back so the corrupted chunks are never applied
+                    match old_head {
+                        Some(oid) => {
+                            self.repo.reference(
+                                WAL_REF,
+                                oid,
+                                true,
+                                "grite: reject corrupted pull",
+                            )?;
+                        }
+                        None => self.repo.find_reference(WAL_REF)?.delete()?,
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        let events_pulled = if new_head != old_head {
+            if let Some(_new_oid) = new_head {
+                if let Some(old_oid) = old_head {
+                    wal.read_since(old_oid)?.len()
+                } else {
+                    wal.read_all()?.len()
+                }
+            } else {
+                0
+            }
+        } else {
+            0
+        };
+
+        Ok(PullResult {
+            success: true,
+            new_wal_head: new_head,
+            events_pulled,
+            message: with_unrecorded_warning(
+                if events_pulled > 0 {
+                    format!("Pulled {} new events", events_pulled)
+                } else {
+                    "Already up to date".to_string()
+                },
+                unrecorded,
+            ),
+        })
+    }
+
+    /// Apply a remote's events for some issues only
+    ///
+    /// Fetches the remote WAL into a side ref, leaving the local WAL ref
+    /// untouched, and inserts into `store` the events of `issue_ids` that it
+    /// does not already have. Events of other issues wait for a later full
+    /// pull. `events_pulled` counts the events applied.
+    pub fn pull_issues(
+        &self,
+        remote_name: &str,
+        issue_ids: &[IssueId],
+        store: &GriteStore,
+    ) -> Result<PullResult, GitError> {
+        let remote_head = self.fetch_remote_wal(remote_name)?;
+        let wal = WalManager::open(&self.git_dir)?;
+        let mut unrecorded = 0;
+        let mut events = match remote_head {
+            Some(oid) => {
+                unrecorded = wal.verify_chunks(oid, None)?.unrecorded.len();
+                wal.read_from_oid(oid)?
+            }
+            None => Vec::new(),
+        };
+        events.retain(|e| issue_ids.contains(&e.issue_id));
+        canonical_order(&mut events);
+
+        let store_err = |e: libgrite_core::GriteError| GitError::Sync(e.to_string());
+        let mut applied = 0;
+        for event in &events {
+            if store
+                .get_event(&event.event_id)
+                .map_err(store_err)?
+                .is_none()
+            {
+                store.insert_event(event).map_err(store_err)?;
+                applied += 1;
+            }
+        }
+
+        Ok(PullResult {
+            success: true,
+            new_wal_head: None,
+            events_pulled: applied,
+            message: with_unrecorded_warning(
+                if applied > 0 {
+                    format!("Applied {} events for {} issues", applied, issue_ids.len())
+                } else {
+                    "Requested issues already up to date".to_string()
+                },
+                unrecorded,
+            ),
+        })
+    }
+
+    /// Show what a pull would bring in, without changing the local WAL or
+    /// store.
+    ///
+    /// The remote WAL is fetched into a side ref and compared with the local
+    /// WAL by event ID, so a remote that holds the same events in another
+    /// order previews as empty.
+    pub fn preview(&self, remote_name: &str) -> Result<PullPreview, GitError> {
+        let remote_head = self.fetch_remote_wal(remote_name)?;
+        let wal = WalManager::open(&self.git_dir)?;
+        let local_events = wal.read_all()?;
+        let remote_events = match remote_head {
+            Some(oid) => wal.read_from_oid(oid)?,
+            None => Vec::new(),
+        };
+
+        let local_ids: HashSet<_> = local_events.iter().map(|e| e.event_id).collect();
+        let mut events: Vec<Event> = remote_events
+            .into_iter()
+            .filter(|e| !local_ids.contains(&e.event_id))
+            .collect();
+        canonical_order(&mut events);
+
+        let mut by_issue: BTreeMap<IssueId, Vec<&Event>> = BTreeMap::new();
+        for event in &events {
+            by_issue.entry(event.issue_id).or_default().push(event);
+        }
+        let issues = by_issue
+            .into_iter()
+            .map(|(issue_id, new_events)| {
+                let mut kinds = BTreeMap::new();
+                for event in &new_events {
+                    *kinds.entry(event.kind.kind_name().to_string()).or_insert(0) += 1;
+                }
+
+                // Fold the issue's local and incoming events as a pull would.
+                // A local WAL pruned past the issue's creation cannot be
+                // projected, which leaves title and state unknown.
+                let mut issue_events: Vec<Event> = local_events
+                    .iter()
+                    .filter(|e| e.issue_id == issue_id)
+                    .cloned()
+                    .collect();
+                let new_issue = issue_events.is_empty();
+                issue_events.extend(new_events.iter().map(|e| (*e).clone()));
+                let projection = GriteStore::projections_from_events(&issue_events, 0)
+                    .ok()
+                    .and_then(|mut p| p.remove(&issue_id));
+
+                IssuePreview {
+                    issue_id: id_to_hex(&issue_id),
+                    new_issue,
+                    title: projection.as_ref().map(|p| p.title.clone()),
+                    state: projection
+                        .as_ref()
+                        .map(|p| format!("{:?}", p.state).to_lowercase()),
+                    events: new_events.len(),
+                    kinds,
+                }
+            })
+            .collect();
+
+        Ok(PullPreview {
+            remote: remote_name.to_string(),
+            remote_wal_head: remote_head.map(|oid| oid.to_string()),
+            event_count: events.len(),
+            issues,
+            events,
+        })
+    }
+
+    /// Fetch a remote's WAL into its side ref, returning the fetched head
+    fn fetch_remote_wal(&self, remote_name: &str) -> Result<Option<Oid>, GitError> {
+        let fetch_ref = format!("{}/{}/wal", PARTIAL_FETCH_REF_PREFIX, remote_name);
+        let refspec = format!("+refs/grite/wal:{}", fetch_ref);
+        self.fetch(remote_name, &[refspec.as_str()])?;
+
+        match self.repo.find_reference(&fetch_ref) {
+            Ok(reference) => Ok(reference.target()),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Check that every local WAL chunk uses a format the remote declares it
+    /// can read.
+    ///
+    /// The remote's `refs/grite/capabilities` is fetched into a side ref; a
+    /// remote without one is assumed to read only the baseline format.
+    pub fn check_compat(&self, remote_name: &str) -> Result<CompatReport, GitError> {
+        let fetch_ref = format!("{}/{}/capabilities", COMPAT_FETCH_REF_PREFIX, remote_name);
+        // Drop a copy left by an earlier check so a since-deleted remote ref
+        // is not mistaken for a declaration
+        match self.repo.find_reference(&fetch_ref) {
+            Ok(mut reference) => reference.delete()?,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        let refspec = format!("+{}:{}", CAPABILITIES_REF, fetch_ref);
+        self.fetch(remote_name, &[refspec.as_str()])?;
+
+        let declared = self.read_capabilities(&fetch_ref)?;
+        let capabilities = declared.clone().unwrap_or_else(Capabilities::baseline);
+
+        let chunks = WalManager::open(&self.git_dir)?.chunk_headers()?;
+        let incompatible = chunks
+            .iter()
+            .filter_map(|chunk| {
+                let reasons = capabilities.check(&chunk.header);
+                (!reasons.is_empty()).then(|| IncompatibleChunk {
+                    commit: chunk.commit.to_string(),
+                    path: chunk.path.clone(),
+                    reasons,
+                })
+            })
+            .collect();
+
+        Ok(CompatReport {
+            remote: remote_name.to_string(),
+            declared: declared.is_some(),
+            capabilities,
+            chunks_checked: chunks.len(),
+            incompatible,
+        })
+    }
+
+    /// Record this repository's capabilities on `refs/grite/capabilities`,
+    /// which the next push publishes
+    pub fn declare_capabilities(&self, capabilities: &Capabilities) -> Result<Oid, GitError> {
+        let json = serde_json::to_string_pretty(capabilities)?;
+        let blob = self.repo.blob(json.as_bytes())?;
+        let mut tree_builder = self.repo.treebuilder(None)?;
+        tree_builder.insert(CAPABILITIES_FILE, blob, 0o100644)?;
+        let tree = self.repo.find_tree(tree_builder.write()?)?;
+
+        let parent = match self.repo.find_reference(CAPABILITIES_REF) {
+            Ok(reference) => Some(reference.peel_to_commit()?),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        let sig = git2::Signature::now("grite", "grit@local")?;
+        Ok(self.repo.commit(
+            Some(CAPABILITIES_REF),
+            &sig,
+            &sig,
+            "Declare capabilities",
+            &tree,
+            &parents,
+        )?)
+    }
+
+    /// Read the capabilities stored on `ref_name`, if the ref exists
+    fn read_capabilities(&self, ref_name: &str) -> Result<Option<Capabilities>, GitError> {
+        let commit = match self.repo.find_reference(ref_name) {
+            Ok(reference) => reference.peel_to_commit()?,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let tree = commit.tree()?;
+        let entry = tree.get_name(CAPABILITIES_FILE).ok_or_else(|| {
+            GitError::Sync(format!("{} has no {}", CAPABILITIES_REF, CAPABILITIES_FILE))
+        })?;
+        let blob = self.repo.find_blob(entry.id())?;
+        Ok(Some(serde_json::from_slice(blob.content())?))
+    }
+
+    /// Fetch `refspecs` from a remote
+    fn fetch(&self, remote_name: &str, refspecs: &[&str]) -> Result<(), GitError> {
         let mut remote = self.repo.find_remote(remote_name)?;
-        let refspecs = [GRITE_REFSPEC];
 
         let config = self.repo.config()?;
         let mut callbacks = RemoteCallbacks::new();
@@ -89,48 +469,13 @@ impl SyncManager {
         let mut fetch_options = FetchOptions::new();
         fetch_options.remote_callbacks(callbacks);
 
-        remote.fetch(&refspecs, Some(&mut fetch_options), None)?;
-
-        // Check if WAL head changed
-        let new_head = wal.head()?;
-        let events_pulled = if new_head != old_head {
-            if let Some(_new_oid) = new_head {
-                if let Some(old_oid) = old_head {
-                    wal.read_since(old_oid)?.len()
-                } else {
-                    wal.read_all()?.len()
-                }
-            } else {
-                0
-            }
-        } else {
-            0
-        };
-
-        Ok(PullResult {
-            success: true,
-            new_wal_head: new_head,
-            events_pulled,
-            message: if events_pulled > 0 {
-                format!("Pulled {} new events", events_pulled)
-            } else {
-                "Already up to date".to_string()
-            },
-        })
+        remote.fetch(refspecs, Some(&mut fetch_options), None)?;
+        Ok(())
     }
 
     /// Push grite refs to a remote
     pub fn push(&self, remote_name: &str) -> Result<PushResult, GitError> {
-        // Enumerate concrete grite refs (libgit2 push doesn't expand globs)
-        let refspecs: Vec<String> = self
-            .repo
-            .references()?
-            .filter_map(Result::ok)
-            .filter_map(|r| r.name().map(|n| n.to_string()))
-            .filter(|n| n.starts_with("refs/grite/"))
-            .map(|n| format!("{}:{}", n, n))
-            .collect();
-
+        let refspecs = self.grite_refspecs()?;
         if refspecs.is_empty() {
             return Ok(PushResult {
                 success: true,
@@ -140,6 +485,43 @@ impl SyncManager {
             });
         }
 
+        if let Some(error_msg) = self.push_refspecs(remote_name, &refspecs)? {
+            // Push was rejected - likely non-fast-forward
+            return Ok(PushResult {
+                success: false,
+                rebased: false,
+                events_rebased: 0,
+                message: format!("Push rejected: {}", error_msg),
+            });
+        }
+
+        Ok(PushResult {
+            success: true,
+            rebased: false,
+            events_rebased: 0,
+            message: "Push successful".to_string(),
+        })
+    }
+
+    /// Enumerate concrete grite refs (libgit2 push doesn't expand globs)
+    fn grite_refspecs(&self) -> Result<Vec<String>, GitError> {
+        Ok(self
+            .repo
+            .references()?
+            .filter_map(Result::ok)
+            .filter_map(|r| r.name().map(|n| n.to_string()))
+            .filter(|n| n.starts_with("refs/grite/"))
+            .map(|n| format!("{}:{}", n, n))
+            .collect())
+    }
+
+    /// Push the given refspecs, returning the rejection reason if any ref
+    /// was refused by the remote
+    fn push_refspecs(
+        &self,
+        remote_name: &str,
+        refspecs: &[String],
+    ) -> Result<Option<String>, GitError> {
         let mut remote = self.repo.find_remote(remote_name)?;
         let refspec_strs: Vec<&str> = refspecs.iter().map(|s| s.as_str()).collect();
 
@@ -174,24 +556,8 @@ impl SyncManager {
 
         remote.push(&refspec_strs, Some(&mut push_options))?;
 
-        // Now check if there was an error
         let error = push_error.borrow().clone();
-        if let Some(error_msg) = error {
-            // Push was rejected - likely non-fast-forward
-            return Ok(PushResult {
-                success: false,
-                rebased: false,
-                events_rebased: 0,
-                message: format!("Push rejected: {}", error_msg),
-            });
-        }
-
-        Ok(PushResult {
-            success: true,
-            rebased: false,
-            events_rebased: 0,
-            message: "Push successful".to_string(),
-        })
+        Ok(error)
     }
 
     /// Push with automatic rebase on conflict
@@ -202,6 +568,9 @@ impl SyncManager {
     /// 3. Find events that were local-only
     /// 4. Re-append those events on top of remote head
     /// 5. Push again
+    ///
+    /// Returns [`GitError::PushRejected`] if the remote still refuses the
+    /// push after rebasing (for example, because it moved again).
     pub fn push_with_rebase(
         &self,
         remote_name: &str,
@@ -252,20 +621,22 @@ impl SyncManager {
         }
 
         // 6. Try push again
-        let retry_result = self.push(remote_name)?;
+        let refspecs = self.grite_refspecs()?;
+        if let Some(reason) = self.push_refspecs(remote_name, &refspecs)? {
+            return Err(GitError::PushRejected {
+                remote: remote_name.to_string(),
+                reason,
+            });
+        }
 
         Ok(PushResult {
-            success: retry_result.success,
+            success: true,
             rebased: true,
             events_rebased,
-            message: if retry_result.success {
-                format!(
-                    "Push successful after rebase ({} events rebased)",
-                    events_rebased
-                )
-            } else {
-                retry_result.message
-            },
+            message: format!(
+                "Push successful after rebase ({} events rebased)",
+                events_rebased
+            ),
         })
     }
 
@@ -309,4 +680,346 @@ mod tests {
         let mgr = super::SyncManager::open(&git_dir);
         assert!(mgr.is_ok());
     }
+
+    #[test]
+    fn test_push_rejected_after_rebase() {
+        use crate::{GitError, WalManager};
+        use libgrite_core::hash::compute_event_id;
+        use libgrite_core::types::event::{Event, EventKind};
+        use libgrite_core::types::ids::generate_issue_id;
+        use std::process::Command;
+        use tempfile::TempDir;
+
+        let remote = TempDir::new().unwrap();
+        Command::new("git")
+            .args(["init", "--bare"])
+            .current_dir(remote.path())
+            .output()
+            .unwrap();
+
+        let local = TempDir::new().unwrap();
+        Command::new("git")
+            .args(["init"])
+            .current_dir(local.path())
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["remote", "add", "origin", remote.path().to_str().unwrap()])
+            .current_dir(local.path())
+            .output()
+            .unwrap();
+
+        let git_dir = local.path().join(".git");
+        let wal = WalManager::open(&git_dir).unwrap();
+        let actor = [1u8; 16];
+        let make_event = |title: &str| {
+            let issue_id = generate_issue_id();
+            let kind = EventKind::IssueCreated {
+                title: title.to_string(),
+                body: String::new(),
+                labels: vec![],
+            };
+            let event_id = compute_event_id(&issue_id, &actor, 1700000000000, None, &kind);
+            Event::new(event_id, issue_id, actor, 1700000000000, None, kind)
+        };
+
+        let mgr = super::SyncManager::open(&git_dir).unwrap();
+        wal.append(&actor, &[make_event("First")]).unwrap();
+        assert!(mgr.push("origin").unwrap().success);
+
+        // Hold the remote ref lock so every later update of it is refused
+        std::fs::write(remote.path().join("refs/grite/wal.lock"), b"").unwrap();
+        wal.append(&actor, &[make_event("Second")]).unwrap();
+
+        match mgr.push_with_rebase("origin", &actor) {
+            Err(GitError::PushRejected { remote, reason }) => {
+                assert_eq!(remote, "origin");
+                assert!(reason.contains("refs/grite/wal"), "{}", reason);
+            }
+            other => panic!("expected PushRejected, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pull_issues_applies_only_requested() {
+        use crate::WalManager;
+        use libgrite_core::hash::compute_event_id;
+        use libgrite_core::types::event::{Event, EventKind};
+        use libgrite_core::types::ids::generate_issue_id;
+        use libgrite_core::GriteStore;
+        use std::process::Command;
+        use tempfile::TempDir;
+
+        let git = |dir: &std::path::Path, args: &[&str]| {
+            assert!(Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap()
+                .status
+                .success());
+        };
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        let url = remote.path().to_str().unwrap();
+
+        // The writer pushes two events for each of two issues
+        let writer = TempDir::new().unwrap();
+        git(writer.path(), &["init"]);
+        git(writer.path(), &["remote", "add", "origin", url]);
+        let actor = [1u8; 16];
+        let wanted = generate_issue_id();
+        let other = generate_issue_id();
+        let mut events = Vec::new();
+        for issue_id in [wanted, other] {
+            for (ts, kind) in [
+                (
+                    1000,
+                    EventKind::IssueCreated {
+                        title: "Issue".to_string(),
+                        body: String::new(),
+                        labels: vec![],
+                    },
+                ),
+                (
+                    2000,
+                    EventKind::CommentAdded {
+                        body: "Update".to_string(),
+                        parent_event: None,
+                    },
+                ),
+            ] {
+                let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+                events.push(Event::new(event_id, issue_id, actor, ts, None, kind));
+            }
+        }
+        let writer_git = writer.path().join(".git");
+        WalManager::open(&writer_git)
+            .unwrap()
+            .append(&actor, &events)
+            .unwrap();
+        assert!(
+            super::SyncManager::open(&writer_git)
+                .unwrap()
+                .push("origin")
+                .unwrap()
+                .success
+        );
+
+        let reader = TempDir::new().unwrap();
+        git(reader.path(), &["init"]);
+        git(reader.path(), &["remote", "add", "origin", url]);
+        let reader_git = reader.path().join(".git");
+        let store_dir = TempDir::new().unwrap();
+        let store = GriteStore::open(store_dir.path()).unwrap();
+        let mgr = super::SyncManager::open(&reader_git).unwrap();
+
+        let result = mgr.pull_issues("origin", &[wanted], &store).unwrap();
+        assert_eq!(result.events_pulled, 2);
+        assert!(store.get_issue(&wanted).unwrap().is_some());
+        assert!(store.get_issue(&other).unwrap().is_none());
+        assert_eq!(store.get_all_events().unwrap().len(), 2);
+
+        // The local WAL is left for a later full pull
+        assert!(WalManager::open(&reader_git)
+            .unwrap()
+            .head()
+            .unwrap()
+            .is_none());
+
+        // Nothing new for an issue already applied or without remote events
+        let again = mgr.pull_issues("origin", &[wanted], &store).unwrap();
+        assert_eq!(again.events_pulled, 0);
+        let unknown = mgr
+            .pull_issues("origin", &[generate_issue_id()], &store)
+            .unwrap();
+        assert_eq!(unknown.events_pulled, 0);
+    }
+
+    #[test]
+    fn test_check_compat_flags_zstd_chunk_against_baseline() {
+        use crate::{
+            encode_chunk_with_codec, Capabilities, ChunkCodec, WalManager, CHUNK_CODEC_ZSTD,
+        };
+        use libgrite_core::hash::compute_event_id;
+        use libgrite_core::hash::HashAlgo;
+        use libgrite_core::types::event::{Event, EventKind};
+        use libgrite_core::types::ids::generate_issue_id;
+        use std::process::Command;
+        use tempfile::TempDir;
+
+        let git = |dir: &std::path::Path, args: &[&str]| {
+            assert!(Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap()
+                .status
+                .success());
+        };
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        let local = TempDir::new().unwrap();
+        git(local.path(), &["init"]);
+        git(
+            local.path(),
+            &["remote", "add", "origin", remote.path().to_str().unwrap()],
+        );
+
+        let git_dir = local.path().join(".git");
+        let wal = WalManager::open(&git_dir).unwrap();
+        let mgr = super::SyncManager::open(&git_dir).unwrap();
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        let kind = EventKind::IssueCreated {
+            title: "Plain".to_string(),
+            body: String::new(),
+            labels: vec![],
+        };
+        let event_id = compute_event_id(&issue_id, &actor, 1700000000000, None, &kind);
+        let event = Event::new(event_id, issue_id, actor, 1700000000000, None, kind);
+        wal.append(&actor, std::slice::from_ref(&event)).unwrap();
+
+        // A baseline chunk is compatible with a remote that declares nothing
+        let report = mgr.check_compat("origin").unwrap();
+        assert!(!report.declared);
+        assert_eq!(report.capabilities, Capabilities::baseline());
+        assert_eq!(report.chunks_checked, 1);
+        assert!(report.is_compatible());
+
+        // A zstd chunk needs a codec the baseline lacks
+        let zstd =
+            encode_chunk_with_codec(&[event], HashAlgo::default(), ChunkCodec::CborZstd).unwrap();
+        let zstd_commit = wal.append_chunk(&actor, &zstd, 1700000001000, 1).unwrap();
+
+        let report = mgr.check_compat("origin").unwrap();
+        assert_eq!(report.chunks_checked, 2);
+        assert_eq!(report.incompatible.len(), 1);
+        let flagged = &report.incompatible[0];
+        assert_eq!(flagged.commit, zstd_commit.to_string());
+        assert!(flagged.path.ends_with(".bin"), "{}", flagged.path);
+        assert_eq!(
+            flagged.reasons,
+            vec![format!("codec {} not supported", CHUNK_CODEC_ZSTD)]
+        );
+
+        // Once the remote declares zstd support the chunk passes
+        let mut caps = Capabilities::baseline();
+        caps.codecs.push(CHUNK_CODEC_ZSTD.to_string());
+        mgr.declare_capabilities(&caps).unwrap();
+        assert!(mgr.push("origin").unwrap().success);
+        let report = mgr.check_compat("origin").unwrap();
+        assert!(report.declared);
+        assert_eq!(report.capabilities, caps);
+        assert!(report.is_compatible());
+    }
+
+    #[test]
+    fn test_preview_lists_new_remote_events() {
+        use crate::WalManager;
+        use libgrite_core::hash::compute_event_id;
+        use libgrite_core::types::event::{Event, EventKind, IssueState};
+        use libgrite_core::types::ids::generate_issue_id;
+        use std::process::Command;
+        use tempfile::TempDir;
+
+        let git = |dir: &std::path::Path, args: &[&str]| {
+            assert!(Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap()
+                .status
+                .success());
+        };
+        let remote = TempDir::new().unwrap();
+        git(remote.path(), &["init", "--bare"]);
+        let url = remote.path().to_str().unwrap();
+        let clone = || {
+            let dir = TempDir::new().unwrap();
+            git(dir.path(), &["init"]);
+            git(dir.path(), &["remote", "add", "origin", url]);
+            dir
+        };
+
+        let actor = [1u8; 16];
+        let issue_id = generate_issue_id();
+        let make_event = |ts: u64, kind: EventKind| {
+            let event_id = compute_event_id(&issue_id, &actor, ts, None, &kind);
+            Event::new(event_id, issue_id, actor, ts, None, kind)
+        };
+        let created = make_event(
+            1000,
+            EventKind::IssueCreated {
+                title: "Issue".to_string(),
+                body: String::new(),
+                labels: vec![],
+            },
+        );
+        let comment = make_event(
+            2000,
+            EventKind::CommentAdded {
+                body: "Update".to_string(),
+                parent_event: None,
+            },
+        );
+        let closed = make_event(
+            3000,
+            EventKind::StateChanged {
+                state: IssueState::Closed,
+            },
+        );
+
+        // The reader has the issue; the remote then gains two more events
+        let writer = clone();
+        let writer_git = writer.path().join(".git");
+        let writer_wal = WalManager::open(&writer_git).unwrap();
+        let writer_mgr = super::SyncManager::open(&writer_git).unwrap();
+        writer_wal
+            .append(&actor, std::slice::from_ref(&created))
+            .unwrap();
+        assert!(writer_mgr.push("origin").unwrap().success);
+
+        let reader = clone();
+        let reader_git = reader.path().join(".git");
+        let reader_mgr = super::SyncManager::open(&reader_git).unwrap();
+        reader_mgr.pull("origin").unwrap();
+        let reader_head = WalManager::open(&reader_git).unwrap().head().unwrap();
+
+        writer_wal
+            .append(&actor, &[comment.clone(), closed.clone()])
+            .unwrap();
+        assert!(writer_mgr.push("origin").unwrap().success);
+
+        let preview = reader_mgr.preview("origin").unwrap();
+        assert_eq!(preview.event_count, 2);
+        assert_eq!(preview.events, vec![comment.clone(), closed.clone()]);
+        assert_eq!(preview.issues.len(), 1);
+        let issue = &preview.issues[0];
+        assert!(!issue.new_issue);
+        assert_eq!(issue.title.as_deref(), Some("Issue"));
+        assert_eq!(issue.state.as_deref(), Some("closed"));
+        assert_eq!(issue.kinds.get("CommentAdded"), Some(&1));
+        assert_eq!(issue.kinds.get("StateChanged"), Some(&1));
+
+        // Previewing leaves the local WAL alone
+        assert_eq!(
+            WalManager::open(&reader_git).unwrap().head().unwrap(),
+            reader_head
+        );
+
+        // A reader holding the same events in other commits sees no changes
+        let reordered = clone();
+        let reordered_git = reordered.path().join(".git");
+        WalManager::open(&reordered_git)
+            .unwrap()
+            .append(&actor, &[closed, comment, created])
+            .unwrap();
+        let preview = super::SyncManager::open(&reordered_git)
+            .unwrap()
+            .preview("origin")
+            .unwrap();
+        assert_eq!(preview.event_count, 0);
+        assert!(preview.issues.is_empty());
+    }
 }
@@ -7,24 +7,44 @@
 
 use chrono::{DateTime, Datelike, Utc};
 use git2::{Oid, Repository, Signature};
+use libgrite_core::config::load_repo_config;
+use libgrite_core::hash::HashAlgo;
 use libgrite_core::types::event::Event;
 use libgrite_core::types::ids::ActorId;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
+use std::time::Duration;
 
-use crate::chunk::{chunk_hash, decode_chunk, encode_chunk};
+use crate::chunk::{
+    chunk_hash, decode_chunk_with_limits, encode_chunk_with_codec, read_chunk_header, ChunkCodec,
+    ChunkHeader, ChunkLimits,
+};
+use crate::snapshot::SnapshotManager;
 use crate::GitError;
 
 /// WAL reference name
 pub const WAL_REF: &str = "refs/grite/wal";
 
+/// Commit message trailer recording the hash of the commit's chunk
+pub const CHUNK_HASH_TRAILER: &str = "Chunk-Hash";
+
+/// How often [`WalTail`] checks the WAL ref for new commits
+pub const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
 /// Metadata stored in each WAL commit
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WalMeta {
     pub schema_version: u32,
     pub actor_id: String,
+    #[serde(default)]
     pub chunk_hash: String,
     pub prev_wal: Option<String>,
+    /// Set on the chunk-less marker commit written by
+    /// [`WalManager::prune_before`]: the commit named here is the oldest one
+    /// readers still visit, since a snapshot covers everything up to it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned_before: Option<String>,
 }
 
 /// Information about a WAL commit
@@ -36,18 +56,91 @@ pub struct WalCommit {
     pub prev_wal: Option<Oid>,
 }
 
+/// Header of a chunk stored in a WAL commit
+#[derive(Debug, Clone)]
+pub struct WalChunk {
+    pub commit: Oid,
+    /// Path of the chunk within the commit tree
+    pub path: String,
+    pub header: ChunkHeader,
+}
+
+/// Statistics from pruning WAL history
+#[derive(Debug)]
+pub struct PruneStats {
+    /// Commits dropped from the WAL history
+    pub pruned: usize,
+    /// Commits still read, from the new root up to the head
+    pub kept: usize,
+    /// WAL head after pruning
+    pub new_head: Oid,
+}
+
+/// Outcome of [`WalManager::verify_chunks`]
+#[derive(Debug, Default)]
+pub struct ChunkVerification {
+    /// Chunks whose hash matched the one recorded in their commit
+    pub verified: usize,
+    /// Commits recording no chunk hash, in neither their message nor their
+    /// `meta.json`; their chunks are accepted unchecked
+    pub unrecorded: Vec<Oid>,
+}
+
 /// Manager for WAL operations
 pub struct WalManager {
     repo: Repository,
+    chunk_limits: ChunkLimits,
+    max_events_per_chunk: Option<usize>,
+    chunk_codec: ChunkCodec,
 }
 
 impl WalManager {
-    /// Open a WAL manager for the repository at the given path
+    /// Open a WAL manager for the repository at the given path.
+    ///
+    /// Appends are split per the repo config's `max_events_per_chunk` and
+    /// compressed when it sets `compress_chunks`.
     pub fn open(git_dir: &Path) -> Result<Self, GitError> {
         // git_dir is .git, so parent is the repo root
         let repo_path = git_dir.parent().ok_or(GitError::NotARepo)?;
         let repo = Repository::open(repo_path)?;
-        Ok(Self { repo })
+        // An unreadable config leaves appends unsplit and uncompressed;
+        // commands that need the config report its errors themselves
+        let config = load_repo_config(git_dir).ok().flatten().unwrap_or_default();
+        let mut wal = Self {
+            repo,
+            chunk_limits: ChunkLimits::default(),
+            max_events_per_chunk: None,
+            chunk_codec: ChunkCodec::Cbor,
+        };
+        wal.set_max_events_per_chunk(config.max_events_per_chunk);
+        if config.compress_chunks {
+            wal.set_chunk_codec(ChunkCodec::CborZstd);
+        }
+        Ok(wal)
+    }
+
+    /// Limits applied when decoding WAL chunks; raise them to read
+    /// legitimately larger chunks
+    pub fn set_chunk_limits(&mut self, limits: ChunkLimits) {
+        self.chunk_limits = limits;
+    }
+
+    /// Split each append into chunks of at most `max` events, one commit per
+    /// chunk. Unset writes every append as a single chunk; values below 1 are
+    /// treated as 1.
+    pub fn set_max_events_per_chunk(&mut self, max: Option<usize>) {
+        self.max_events_per_chunk = max.map(|n| n.max(1));
+    }
+
+    /// Codec for chunks written by [`append`](Self::append). Chunks already
+    /// in the WAL keep their codec; reads accept every supported codec.
+    pub fn set_chunk_codec(&mut self, codec: ChunkCodec) {
+        self.chunk_codec = codec;
+    }
+
+    /// Path of the repository's `.git` directory
+    pub fn git_dir(&self) -> &Path {
+        self.repo.path()
     }
 
     /// Get the current WAL head commit OID, if any
@@ -64,15 +157,36 @@ impl WalManager {
         }
     }
 
-    /// Append events to the WAL, creating a new commit
+    /// Append events to the WAL, creating a new commit.
+    ///
+    /// With [`set_max_events_per_chunk`](Self::set_max_events_per_chunk), a
+    /// larger batch is split into several chunks, each in its own commit.
+    /// Returns the last commit.
     pub fn append(&self, actor_id: &ActorId, events: &[Event]) -> Result<Oid, GitError> {
         if events.is_empty() {
             return Err(GitError::Wal("Cannot append empty events".to_string()));
         }
 
-        // Encode events to chunk
-        let chunk_data = encode_chunk(events)?;
-        let hash = chunk_hash(&chunk_data);
+        let chunk_size = self.max_events_per_chunk.unwrap_or(events.len());
+        let mut head = None;
+        for chunk in events.chunks(chunk_size) {
+            let chunk_data = encode_chunk_with_codec(chunk, HashAlgo::default(), self.chunk_codec)?;
+            head =
+                Some(self.append_chunk(actor_id, &chunk_data, chunk[0].ts_unix_ms, chunk.len())?);
+        }
+        Ok(head.expect("events is not empty"))
+    }
+
+    /// Commit an already encoded chunk of `event_count` events to the WAL,
+    /// filed under the date of `ts`
+    pub(crate) fn append_chunk(
+        &self,
+        actor_id: &ActorId,
+        chunk_data: &[u8],
+        ts: u64,
+        event_count: usize,
+    ) -> Result<Oid, GitError> {
+        let hash = chunk_hash(chunk_data);
         let hash_hex = hex::encode(hash);
 
         // Get current head (will be parent)
@@ -83,7 +197,6 @@ impl WalManager {
         let prev_wal = parent_commit.as_ref().map(|c| c.id());
 
         // Determine chunk path based on timestamp of first event
-        let ts = events[0].ts_unix_ms;
         let dt: DateTime<Utc> = DateTime::from_timestamp_millis(ts as i64).unwrap_or_else(Utc::now);
         let chunk_path = format!(
             "events/{:04}/{:02}/{:02}/{}.bin",
@@ -100,6 +213,7 @@ impl WalManager {
             actor_id: actor_id_hex.clone(),
             chunk_hash: hash_hex.clone(),
             prev_wal: prev_wal.map(|oid| oid.to_string()),
+            pruned_before: None,
         };
         let meta_json = serde_json::to_string_pretty(&meta)?;
 
@@ -112,13 +226,19 @@ impl WalManager {
 
         // Add chunk blob at the nested path
         // We need to create the nested directory structure
-        let chunk_blob = self.repo.blob(&chunk_data)?;
+        let chunk_blob = self.repo.blob(chunk_data)?;
         let tree_oid = self.insert_nested_blob(&mut tree_builder, &chunk_path, chunk_blob)?;
 
         // Create commit
         let tree = self.repo.find_tree(tree_oid)?;
         let sig = Signature::now("grite", "grit@local")?;
-        let message = format!("WAL: {} events from {}", events.len(), &actor_id_hex[..8]);
+        let message = format!(
+            "WAL: {} events from {}\n\n{}: {}\n",
+            event_count,
+            &actor_id_hex[..8],
+            CHUNK_HASH_TRAILER,
+            hash_hex
+        );
 
         let parents: Vec<&git2::Commit> =
             parent_commit.as_ref().map(|c| vec![c]).unwrap_or_default();
@@ -147,6 +267,211 @@ impl WalManager {
         self.read_since_impl(head, Some(since_oid))
     }
 
+    /// Follow the WAL, yielding events from new commits as they appear.
+    ///
+    /// Starts after `from` (exclusive), or at the beginning of the WAL when
+    /// `None`. Once caught up it polls the WAL ref every
+    /// [`TAIL_POLL_INTERVAL`] and blocks until another commit lands. The
+    /// iterator only ends after yielding an error; resume with
+    /// `tail(tail.position())`.
+    ///
+    /// If the WAL is rewritten so the position is no longer in its history,
+    /// events already read up to the position are skipped rather than
+    /// yielded again.
+    pub fn tail(&self, from: Option<Oid>) -> WalTail<'_> {
+        WalTail {
+            wal: self,
+            position: from,
+            pending: VecDeque::new(),
+            failed: false,
+        }
+    }
+
+    /// Count the commits in the WAL history, not counting prune markers
+    pub fn commit_count(&self) -> Result<usize, GitError> {
+        let mut count = 0;
+        let mut root = None;
+        let mut current_oid = self.head()?;
+        while let Some(oid) = current_oid {
+            let meta = self.commit_meta(oid)?;
+            if meta.pruned_before.is_none() {
+                count += 1;
+            }
+            current_oid = prev_in_history(oid, &meta, &mut root)?;
+        }
+        Ok(count)
+    }
+
+    /// Read the header of every chunk in the WAL, newest first.
+    ///
+    /// Headers are read without decoding payloads, so chunks this build
+    /// cannot decode are still listed.
+    pub fn chunk_headers(&self) -> Result<Vec<WalChunk>, GitError> {
+        let mut chunks = Vec::new();
+        let mut root = None;
+        let mut current_oid = self.head()?;
+        while let Some(oid) = current_oid {
+            let tree = self.repo.find_commit(oid)?.tree()?;
+            self.walk_tree_for_headers(oid, &tree, "", &mut chunks)?;
+            current_oid = prev_in_history(oid, &self.read_meta(&tree)?, &mut root)?;
+        }
+        Ok(chunks)
+    }
+
+    /// Stop reading WAL commits older than `oid`, which becomes the new root.
+    ///
+    /// `oid` must be the latest snapshot's WAL head or one of its ancestors,
+    /// so only history already captured by a snapshot is dropped. Nothing is
+    /// rewritten: a chunk-less marker commit naming `oid` is appended, and
+    /// readers walking back from a later head stop at `oid`. The WAL ref
+    /// therefore only moves forward, pushes stay fast-forward, and every
+    /// snapshot's `wal_head` stays in the history.
+    pub fn prune_before(&self, oid: Oid) -> Result<PruneStats, GitError> {
+        let snapshots = SnapshotManager::open(self.repo.path())?;
+        let latest = snapshots
+            .latest()?
+            .ok_or_else(|| GitError::Wal("Cannot prune WAL without a snapshot".to_string()))?;
+        let boundary = Oid::from_str(&snapshots.meta(latest.oid)?.wal_head)?;
+        if oid != boundary && !self.repo.graph_descendant_of(boundary, oid)? {
+            return Err(GitError::Wal(format!(
+                "Cannot prune before {}: it is newer than the latest snapshot",
+                oid
+            )));
+        }
+
+        let head = self
+            .head()?
+            .ok_or_else(|| GitError::Wal("WAL is empty".to_string()))?;
+
+        // Count commits to keep and to drop, skipping earlier prune markers
+        let (mut kept, mut pruned) = (0, 0);
+        let mut reached = false;
+        let mut root = None;
+        let mut current_oid = Some(head);
+        while let Some(current) = current_oid {
+            let meta = self.commit_meta(current)?;
+            if meta.pruned_before.is_none() {
+                if reached {
+                    pruned += 1;
+                } else {
+                    kept += 1;
+                }
+            }
+            reached |= current == oid;
+            current_oid = prev_in_history(current, &meta, &mut root)?;
+        }
+        if !reached {
+            return Err(GitError::Wal(format!(
+                "{} is not part of the WAL history",
+                oid
+            )));
+        }
+        if pruned == 0 {
+            return Ok(PruneStats {
+                pruned,
+                kept,
+                new_head: head,
+            });
+        }
+
+        let meta = WalMeta {
+            schema_version: 1,
+            actor_id: String::new(),
+            chunk_hash: String::new(),
+            prev_wal: Some(head.to_string()),
+            pruned_before: Some(oid.to_string()),
+        };
+        let mut tree_builder = self.repo.treebuilder(None)?;
+        let meta_blob = self
+            .repo
+            .blob(serde_json::to_string_pretty(&meta)?.as_bytes())?;
+        tree_builder.insert("meta.json", meta_blob, 0o100644)?;
+        let tree = self.repo.find_tree(tree_builder.write()?)?;
+        let sig = Signature::now("grite", "grit@local")?;
+        let new_head = self.repo.commit(
+            None,
+            &sig,
+            &sig,
+            &format!("WAL: prune history before {}\n", oid),
+            &tree,
+            &[&self.repo.find_commit(head)?],
+        )?;
+        // Fails if the WAL moved while we were writing the marker
+        self.repo
+            .reference_matching(WAL_REF, new_head, true, head, "grite: prune WAL")?;
+
+        Ok(PruneStats {
+            pruned,
+            kept,
+            new_head,
+        })
+    }
+
+    /// Snapshot the WAL and prune it once it exceeds `max_commits`.
+    ///
+    /// Returns `None` when the WAL is within the limit.
+    pub fn compact(&self, max_commits: usize) -> Result<Option<PruneStats>, GitError> {
+        if self.commit_count()? <= max_commits {
+            return Ok(None);
+        }
+        let head = self
+            .head()?
+            .ok_or_else(|| GitError::Wal("WAL is empty".to_string()))?;
+        let events = self.read_from_oid(head)?;
+        SnapshotManager::open(self.repo.path())?.create(head, &events)?;
+        self.prune_before(head).map(Some)
+    }
+
+    /// Check every chunk from `head` back to `stop_at` (exclusive) against the
+    /// hash recorded in its commit message, or in its `meta.json` for commits
+    /// written before the message trailer existed.
+    ///
+    /// A chunk whose content no longer matches is an error. Commits that
+    /// record no hash in either place are accepted and listed in `unrecorded`.
+    pub fn verify_chunks(
+        &self,
+        head: Oid,
+        stop_at: Option<Oid>,
+    ) -> Result<ChunkVerification, GitError> {
+        let mut verification = ChunkVerification::default();
+        let mut root = None;
+        let mut current_oid = Some(head);
+        while let Some(oid) = current_oid {
+            if Some(oid) == stop_at {
+                break;
+            }
+            let commit = self.repo.find_commit(oid)?;
+            let meta = self.read_meta(&commit.tree()?)?;
+            current_oid = prev_in_history(oid, &meta, &mut root)?;
+            if meta.pruned_before.is_some() {
+                continue;
+            }
+            let recorded = match recorded_chunk_hash(&commit) {
+                Some(recorded) => Some(recorded),
+                None => Some(meta.chunk_hash).filter(|hash| !hash.is_empty()),
+            };
+            match recorded {
+                Some(recorded) => {
+                    let mut blobs = Vec::new();
+                    self.walk_tree_for_chunk_blobs(&commit.tree()?, &mut blobs)?;
+                    for blob_oid in blobs {
+                        let blob = self.repo.find_blob(blob_oid)?;
+                        let actual = hex::encode(chunk_hash(blob.content()));
+                        if actual != recorded {
+                            return Err(GitError::Wal(format!(
+                                "Chunk hash mismatch in WAL commit {}: recorded {}, found {}",
+                                oid, recorded, actual
+                            )));
+                        }
+                        verification.verified += 1;
+                    }
+                }
+                None => verification.unrecorded.push(oid),
+            }
+        }
+        Ok(verification)
+    }
+
     /// Read all events from a specific commit OID (useful for reading orphaned commits)
     pub fn read_from_oid(&self, oid: Oid) -> Result<Vec<Event>, GitError> {
         self.read_since_impl(oid, None)
@@ -154,7 +479,8 @@ impl WalManager {
 
     /// Internal implementation for reading events
     fn read_since_impl(&self, head: Oid, stop_at: Option<Oid>) -> Result<Vec<Event>, GitError> {
-        let mut all_events = Vec::new();
+        let mut batches = Vec::new();
+        let mut root = None;
         let mut current_oid = Some(head);
 
         // Walk backwards through commits
@@ -167,27 +493,34 @@ impl WalManager {
             let tree = commit.tree()?;
 
             // Read meta.json to get chunk path
-            let meta_entry = tree
-                .get_name("meta.json")
-                .ok_or_else(|| GitError::Wal("Missing meta.json in WAL commit".to_string()))?;
-            let meta_blob = self.repo.find_blob(meta_entry.id())?;
-            let meta: WalMeta = serde_json::from_slice(meta_blob.content())?;
+            let meta = self.read_meta(&tree)?;
 
             // Find and decode the chunk
             let events = self.find_chunk_in_tree(&tree)?;
-            all_events.extend(events);
+            batches.push(events);
 
             // Move to parent
-            current_oid = meta
-                .prev_wal
-                .as_ref()
-                .map(|s| Oid::from_str(s))
-                .transpose()?;
+            current_oid = prev_in_history(oid, &meta, &mut root)?;
         }
 
-        // Events are in reverse order (newest first), reverse to get chronological
-        all_events.reverse();
-        Ok(all_events)
+        // Commits are in reverse order (newest first); reverse them to get
+        // chronological order while keeping each chunk's own ordering
+        batches.reverse();
+        Ok(batches.into_iter().flatten().collect())
+    }
+
+    /// Read meta.json from a WAL commit tree
+    fn read_meta(&self, tree: &git2::Tree) -> Result<WalMeta, GitError> {
+        let meta_entry = tree
+            .get_name("meta.json")
+            .ok_or_else(|| GitError::Wal("Missing meta.json in WAL commit".to_string()))?;
+        let meta_blob = self.repo.find_blob(meta_entry.id())?;
+        Ok(serde_json::from_slice(meta_blob.content())?)
+    }
+
+    /// Read meta.json from a WAL commit
+    fn commit_meta(&self, oid: Oid) -> Result<WalMeta, GitError> {
+        self.read_meta(&self.repo.find_commit(oid)?.tree()?)
     }
 
     /// Find and decode chunk from tree
@@ -209,7 +542,8 @@ impl WalManager {
             match entry.kind() {
                 Some(git2::ObjectType::Blob) if name.ends_with(".bin") => {
                     let blob = self.repo.find_blob(entry.id())?;
-                    let chunk_events = decode_chunk(blob.content())?;
+                    let (_, chunk_events) =
+                        decode_chunk_with_limits(blob.content(), &self.chunk_limits)?;
                     events.extend(chunk_events);
                 }
                 Some(git2::ObjectType::Tree) => {
@@ -222,6 +556,56 @@ impl WalManager {
         Ok(())
     }
 
+    /// Recursively walk tree collecting the blob IDs of .bin chunks
+    fn walk_tree_for_chunk_blobs(
+        &self,
+        tree: &git2::Tree,
+        blobs: &mut Vec<Oid>,
+    ) -> Result<(), GitError> {
+        for entry in tree.iter() {
+            let name = entry.name().unwrap_or("");
+            match entry.kind() {
+                Some(git2::ObjectType::Blob) if name.ends_with(".bin") => blobs.push(entry.id()),
+                Some(git2::ObjectType::Tree) => {
+                    let subtree = self.repo.find_tree(entry.id())?;
+                    self.walk_tree_for_chunk_blobs(&subtree, blobs)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Recursively walk tree collecting the headers of .bin chunks
+    fn walk_tree_for_headers(
+        &self,
+        commit: Oid,
+        tree: &git2::Tree,
+        prefix: &str,
+        chunks: &mut Vec<WalChunk>,
+    ) -> Result<(), GitError> {
+        for entry in tree.iter() {
+            let name = entry.name().unwrap_or("");
+            let path = format!("{}{}", prefix, name);
+            match entry.kind() {
+                Some(git2::ObjectType::Blob) if name.ends_with(".bin") => {
+                    let blob = self.repo.find_blob(entry.id())?;
+                    chunks.push(WalChunk {
+                        commit,
+                        path,
+                        header: read_chunk_header(blob.content())?,
+                    });
+                }
+                Some(git2::ObjectType::Tree) => {
+                    let subtree = self.repo.find_tree(entry.id())?;
+                    self.walk_tree_for_headers(commit, &subtree, &format!("{}/", path), chunks)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
     /// Insert a blob at a nested path, creating intermediate trees
     fn insert_nested_blob(
         &self,
@@ -272,6 +656,105 @@ impl WalManager {
     }
 }
 
+/// Previous commit in the WAL history walked back from a head, or `None` at
+/// its start
+///
+/// `root` carries the boundary set by the newest prune marker seen on the
+/// walk so far; the walk ends at that commit.
+fn prev_in_history(
+    oid: Oid,
+    meta: &WalMeta,
+    root: &mut Option<Oid>,
+) -> Result<Option<Oid>, GitError> {
+    if root.is_none() {
+        if let Some(boundary) = &meta.pruned_before {
+            *root = Some(Oid::from_str(boundary)?);
+        }
+    }
+    if Some(oid) == *root {
+        return Ok(None);
+    }
+    Ok(meta.prev_wal.as_deref().map(Oid::from_str).transpose()?)
+}
+
+/// Chunk hash recorded in a WAL commit's message, if any
+fn recorded_chunk_hash(commit: &git2::Commit) -> Option<String> {
+    let prefix = format!("{}: ", CHUNK_HASH_TRAILER);
+    commit
+        .message()?
+        .lines()
+        .find_map(|line| line.strip_prefix(prefix.as_str()))
+        .map(|hash| hash.trim().to_string())
+}
+
+/// Blocking iterator over WAL events, created by [`WalManager::tail`]
+pub struct WalTail<'a> {
+    wal: &'a WalManager,
+    /// Last commit whose events have been queued
+    position: Option<Oid>,
+    pending: VecDeque<Event>,
+    /// Set once an error has been yielded, which ends the iteration
+    failed: bool,
+}
+
+impl WalTail<'_> {
+    /// The last WAL commit whose events have been read
+    pub fn position(&self) -> Option<Oid> {
+        self.position
+    }
+
+    /// Queue events from commits past the current position, if any
+    fn poll(&mut self) -> Result<(), GitError> {
+        let head = match self.wal.head()? {
+            Some(oid) => oid,
+            None => return Ok(()),
+        };
+        if Some(head) == self.position {
+            return Ok(());
+        }
+        // Re-read the ref each poll so updates made mid-iteration are picked
+        // up from where the previous batch stopped
+        let mut events = self.wal.read_since_impl(head, self.position)?;
+        if let Some(position) = self.position {
+            if !self.wal.repo.graph_descendant_of(head, position)? {
+                // The WAL was rewritten and the walk never met the position
+                let seen: HashSet<_> = self
+                    .wal
+                    .read_from_oid(position)?
+                    .iter()
+                    .map(|e| e.event_id)
+                    .collect();
+                events.retain(|e| !seen.contains(&e.event_id));
+            }
+        }
+        self.pending.extend(events);
+        self.position = Some(head);
+        Ok(())
+    }
+}
+
+impl Iterator for WalTail<'_> {
+    type Item = Result<Event, GitError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.failed {
+                return None;
+            }
+            if let Err(e) = self.poll() {
+                self.failed = true;
+                return Some(Err(e));
+            }
+            if self.pending.is_empty() {
+                std::thread::sleep(TAIL_POLL_INTERVAL);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,6 +814,108 @@ mod tests {
         assert_eq!(events[0].event_id, event.event_id);
     }
 
+    #[test]
+    fn test_wal_append_compresses_when_configured() {
+        use libgrite_core::config::{save_repo_config, RepoConfig};
+
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+        let actor = [1u8; 16];
+        let event = |title: &str| {
+            make_test_event(EventKind::IssueCreated {
+                title: title.to_string(),
+                body: String::new(),
+                labels: vec![],
+            })
+        };
+
+        // Written before compression is enabled, so stays raw
+        let first = event("Raw");
+        WalManager::open(&git_dir)
+            .unwrap()
+            .append(&actor, std::slice::from_ref(&first))
+            .unwrap();
+
+        let config = RepoConfig {
+            compress_chunks: true,
+            ..Default::default()
+        };
+        save_repo_config(&git_dir, &config).unwrap();
+        let wal = WalManager::open(&git_dir).unwrap();
+        let second = event("Compressed");
+        wal.append(&actor, std::slice::from_ref(&second)).unwrap();
+
+        // Newest first
+        let codecs: Vec<String> = wal
+            .chunk_headers()
+            .unwrap()
+            .into_iter()
+            .map(|c| c.header.codec)
+            .collect();
+        assert_eq!(codecs, vec!["cbor-zstd-v1", "cbor-v1"]);
+        assert_eq!(wal.read_all().unwrap(), vec![first, second]);
+        assert_eq!(
+            wal.verify_chunks(wal.head().unwrap().unwrap(), None)
+                .unwrap()
+                .verified,
+            2
+        );
+    }
+
+    #[test]
+    fn test_wal_append_splits_at_max_events_per_chunk() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let mut wal = WalManager::open(&git_dir).unwrap();
+        wal.set_max_events_per_chunk(Some(1000));
+        let actor = [1u8; 16];
+
+        // A batch under the cap stays one chunk
+        let small: Vec<Event> = (0..10)
+            .map(|i| {
+                make_test_event(EventKind::IssueCreated {
+                    title: format!("Small {}", i),
+                    body: String::new(),
+                    labels: vec![],
+                })
+            })
+            .collect();
+        wal.append(&actor, &small).unwrap();
+        assert_eq!(wal.chunk_headers().unwrap().len(), 1);
+
+        let events: Vec<Event> = (0..2500)
+            .map(|i| {
+                make_test_event(EventKind::IssueCreated {
+                    title: format!("Issue {}", i),
+                    body: String::new(),
+                    labels: vec![],
+                })
+            })
+            .collect();
+        let oid = wal.append(&actor, &events).unwrap();
+        assert_eq!(wal.head().unwrap(), Some(oid));
+
+        // Three more commits, each with an independently decodable chunk
+        assert_eq!(wal.commit_count().unwrap(), 4);
+        let counts: Vec<usize> = wal
+            .chunk_headers()
+            .unwrap()
+            .iter()
+            .take(3)
+            .map(|c| {
+                let tree = wal.repo.find_commit(c.commit).unwrap().tree().unwrap();
+                wal.find_chunk_in_tree(&tree).unwrap().len()
+            })
+            .collect();
+        // Newest first
+        assert_eq!(counts, vec![500, 1000, 1000]);
+
+        let read: Vec<_> = wal.read_all().unwrap().iter().map(|e| e.event_id).collect();
+        let expected: Vec<_> = small.iter().chain(&events).map(|e| e.event_id).collect();
+        assert_eq!(read, expected);
+    }
+
     #[test]
     fn test_wal_multiple_appends() {
         let (temp, _repo) = setup_test_repo();
@@ -350,6 +935,7 @@ mod tests {
         // Append second event
         let event2 = make_test_event(EventKind::CommentAdded {
             body: "A comment".to_string(),
+            parent_event: None,
         });
         let _oid2 = wal.append(&actor, std::slice::from_ref(&event2)).unwrap();
 
@@ -364,4 +950,271 @@ mod tests {
         assert_eq!(events_since.len(), 1);
         assert_eq!(events_since[0].event_id, event2.event_id);
     }
+
+    #[test]
+    fn test_wal_tail_follows_new_commits() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let wal = WalManager::open(&git_dir).unwrap();
+        let actor = [1u8; 16];
+
+        let event1 = make_test_event(EventKind::IssueCreated {
+            title: "First".to_string(),
+            body: "Body 1".to_string(),
+            labels: vec![],
+        });
+        let event2 = make_test_event(EventKind::CommentAdded {
+            body: "Same commit".to_string(),
+            parent_event: None,
+        });
+        wal.append(&actor, &[event1.clone(), event2.clone()])
+            .unwrap();
+
+        let mut tail = wal.tail(None);
+        assert_eq!(tail.next().unwrap().unwrap().event_id, event1.event_id);
+
+        // The ref moves while the first batch is still being drained
+        let event3 = make_test_event(EventKind::CommentAdded {
+            body: "Appended after tail started".to_string(),
+            parent_event: None,
+        });
+        let oid3 = wal.append(&actor, std::slice::from_ref(&event3)).unwrap();
+
+        assert_eq!(tail.next().unwrap().unwrap().event_id, event2.event_id);
+        assert_eq!(tail.next().unwrap().unwrap().event_id, event3.event_id);
+        assert_eq!(tail.position(), Some(oid3));
+
+        // Resuming from a known commit only yields later events
+        let event4 = make_test_event(EventKind::CommentAdded {
+            body: "Fourth".to_string(),
+            parent_event: None,
+        });
+        wal.append(&actor, std::slice::from_ref(&event4)).unwrap();
+        let mut resumed = wal.tail(Some(oid3));
+        assert_eq!(resumed.next().unwrap().unwrap().event_id, event4.event_id);
+    }
+
+    #[test]
+    fn test_wal_tail_after_rewrite_and_error() {
+        let (temp, repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let wal = WalManager::open(&git_dir).unwrap();
+        let actor = [1u8; 16];
+        let comment = |body: &str| {
+            make_test_event(EventKind::CommentAdded {
+                body: body.to_string(),
+                parent_event: None,
+            })
+        };
+
+        let (event1, event2) = (comment("One"), comment("Two"));
+        wal.append(&actor, std::slice::from_ref(&event1)).unwrap();
+        wal.append(&actor, std::slice::from_ref(&event2)).unwrap();
+        let mut tail = wal.tail(None);
+        assert_eq!(tail.next().unwrap().unwrap().event_id, event1.event_id);
+        assert_eq!(tail.next().unwrap().unwrap().event_id, event2.event_id);
+
+        // Rewrite the WAL with the same events in one commit, then add one
+        repo.find_reference(WAL_REF).unwrap().delete().unwrap();
+        wal.append(&actor, &[event1.clone(), event2.clone()])
+            .unwrap();
+        let event3 = comment("Three");
+        wal.append(&actor, std::slice::from_ref(&event3)).unwrap();
+        assert_eq!(tail.next().unwrap().unwrap().event_id, event3.event_id);
+
+        // A WAL ref pointing at something unreadable ends the iteration
+        let tree = repo.find_tree(repo.treebuilder(None).unwrap().write().unwrap());
+        let sig = Signature::now("test", "test@local").unwrap();
+        let broken = repo
+            .commit(None, &sig, &sig, "not a WAL commit", &tree.unwrap(), &[])
+            .unwrap();
+        repo.reference(WAL_REF, broken, true, "test").unwrap();
+        assert!(tail.next().unwrap().is_err());
+        assert!(tail.next().is_none());
+    }
+
+    #[test]
+    fn test_wal_prune_before_snapshot() {
+        let (temp, repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let wal = WalManager::open(&git_dir).unwrap();
+        let actor = [1u8; 16];
+
+        let mut oids = Vec::new();
+        let mut all_events = Vec::new();
+        for i in 0..5 {
+            let event = make_test_event(EventKind::CommentAdded {
+                body: format!("Comment {}", i),
+                parent_event: None,
+            });
+            oids.push(wal.append(&actor, std::slice::from_ref(&event)).unwrap());
+            all_events.push(event);
+        }
+
+        // No snapshot yet
+        assert!(wal.prune_before(oids[2]).is_err());
+
+        let snapshots = SnapshotManager::open(&git_dir).unwrap();
+        let snapshot_oid = snapshots
+            .create(oids[2], &wal.read_from_oid(oids[2]).unwrap())
+            .unwrap();
+
+        // Commits past the snapshot boundary are never pruned
+        assert!(wal.prune_before(oids[3]).is_err());
+        assert_eq!(wal.commit_count().unwrap(), 5);
+
+        let stats = wal.prune_before(oids[2]).unwrap();
+        assert_eq!(stats.pruned, 2);
+        assert_eq!(stats.kept, 3);
+        assert_eq!(wal.head().unwrap(), Some(stats.new_head));
+        assert_eq!(wal.commit_count().unwrap(), 3);
+
+        // The ref only moved forward, so pushes stay fast-forward and the
+        // snapshot's WAL head is still in the history
+        assert!(repo.graph_descendant_of(stats.new_head, oids[4]).unwrap());
+        assert!(repo.graph_descendant_of(stats.new_head, oids[2]).unwrap());
+
+        // Appending after the prune keeps the boundary
+        let later = make_test_event(EventKind::CommentAdded {
+            body: "Later".to_string(),
+            parent_event: None,
+        });
+        wal.append(&actor, std::slice::from_ref(&later)).unwrap();
+        assert_eq!(wal.commit_count().unwrap(), 4);
+        all_events.push(later);
+
+        let remaining: Vec<_> = wal.read_all().unwrap().iter().map(|e| e.event_id).collect();
+        let expected: Vec<_> = all_events[2..].iter().map(|e| e.event_id).collect();
+        assert_eq!(remaining, expected);
+
+        // Snapshot + WAL still reconstructs the full history
+        let mut reconstructed: Vec<_> = snapshots
+            .read(snapshot_oid)
+            .unwrap()
+            .iter()
+            .map(|e| e.event_id)
+            .collect();
+        for event_id in remaining {
+            if !reconstructed.contains(&event_id) {
+                reconstructed.push(event_id);
+            }
+        }
+        let original: Vec<_> = all_events.iter().map(|e| e.event_id).collect();
+        assert_eq!(reconstructed, original);
+    }
+
+    #[test]
+    fn test_wal_compact_over_limit() {
+        let (temp, _repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let wal = WalManager::open(&git_dir).unwrap();
+        let actor = [1u8; 16];
+        for i in 0..4 {
+            let event = make_test_event(EventKind::CommentAdded {
+                body: format!("Comment {}", i),
+                parent_event: None,
+            });
+            wal.append(&actor, std::slice::from_ref(&event)).unwrap();
+        }
+
+        assert!(wal.compact(4).unwrap().is_none());
+
+        let stats = wal.compact(3).unwrap().unwrap();
+        assert_eq!(stats.pruned, 3);
+        assert_eq!(wal.commit_count().unwrap(), 1);
+
+        let snapshots = SnapshotManager::open(&git_dir).unwrap();
+        let latest = snapshots.latest().unwrap().unwrap();
+        assert_eq!(snapshots.read(latest.oid).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_verify_chunks_detects_corrupted_chunk() {
+        let (temp, repo) = setup_test_repo();
+        let git_dir = temp.path().join(".git");
+
+        let wal = WalManager::open(&git_dir).unwrap();
+        let actor = [1u8; 16];
+        let comment = |body: &str| {
+            make_test_event(EventKind::CommentAdded {
+                body: body.to_string(),
+                parent_event: None,
+            })
+        };
+
+        // A commit recording no hash, in neither its message nor meta.json
+        let first = wal.append(&actor, &[comment("Old")]).unwrap();
+        let first = repo.find_commit(first).unwrap();
+        let mut builder = repo.treebuilder(Some(&first.tree().unwrap())).unwrap();
+        let meta = repo
+            .blob(br#"{"schema_version":1,"actor_id":"01010101","prev_wal":null}"#)
+            .unwrap();
+        builder.insert("meta.json", meta, 0o100644).unwrap();
+        let legacy = repo
+            .commit(
+                None,
+                &first.author(),
+                &first.committer(),
+                "WAL: 1 events from 01010101",
+                &repo.find_tree(builder.write().unwrap()).unwrap(),
+                &[],
+            )
+            .unwrap();
+        repo.reference(WAL_REF, legacy, true, "test").unwrap();
+
+        let head = wal.append(&actor, &[comment("New")]).unwrap();
+        let verification = wal.verify_chunks(head, None).unwrap();
+        assert_eq!(verification.verified, 1);
+        assert_eq!(verification.unrecorded, vec![legacy]);
+
+        // Without the message trailer, the hash in meta.json is checked
+        let commit = repo.find_commit(head).unwrap();
+        let no_trailer = repo
+            .commit(
+                None,
+                &commit.author(),
+                &commit.committer(),
+                "WAL: 1 events from 01010101",
+                &commit.tree().unwrap(),
+                &[&repo.find_commit(legacy).unwrap()],
+            )
+            .unwrap();
+        let verification = wal.verify_chunks(no_trailer, None).unwrap();
+        assert_eq!(verification.verified, 1);
+        assert_eq!(verification.unrecorded, vec![legacy]);
+
+        // Rewrite the newest chunk's blob, keeping the commit message
+        let chunk = &wal.chunk_headers().unwrap()[0];
+        let commit = repo.find_commit(head).unwrap();
+        let tree = commit.tree().unwrap();
+        let original = repo
+            .find_blob(tree.get_path(Path::new(&chunk.path)).unwrap().id())
+            .unwrap();
+        let mut corrupted = original.content().to_vec();
+        *corrupted.last_mut().unwrap() ^= 0xff;
+        let blob = repo.blob(&corrupted).unwrap();
+        let mut builder = repo.treebuilder(Some(&tree)).unwrap();
+        let tree_oid = wal
+            .insert_nested_blob(&mut builder, &chunk.path, blob)
+            .unwrap();
+        let parent = repo.find_commit(legacy).unwrap();
+        let tampered = repo
+            .commit(
+                None,
+                &commit.author(),
+                &commit.committer(),
+                commit.message().unwrap(),
+                &repo.find_tree(tree_oid).unwrap(),
+                &[&parent],
+            )
+            .unwrap();
+
+        let err = wal.verify_chunks(tampered, None).unwrap_err();
+        assert!(err.to_string().contains("Chunk hash mismatch"), "{}", err);
+        assert!(err.to_string().contains(&tampered.to_string()), "{}", err);
+    }
 }
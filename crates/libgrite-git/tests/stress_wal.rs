@@ -18,6 +18,7 @@ fn create_test_event(actor: &[u8; 16], issue_id: &[u8; 16], index: u64) -> Event
     let ts = 1700000000000 + index;
     let kind = EventKind::CommentAdded {
         body: format!("Comment {}", index),
+        parent_event: None,
     };
     let event_id = compute_event_id(issue_id, actor, ts, None, &kind);
     Event::new(event_id, *issue_id, *actor, ts, None, kind)
@@ -98,4 +98,7 @@ pub mod codes {
     pub const IO_ERROR: &str = "io_error";
     pub const GIT_ERROR: &str = "git_error";
     pub const IPC_ERROR: &str = "ipc_error";
+    pub const CONFLICT: &str = "conflict";
+    pub const AMBIGUOUS: &str = "ambiguous";
+    pub const RATE_LIMITED: &str = "rate_limited";
 }
@@ -2,8 +2,10 @@
 //!
 //! This crate provides:
 //! - Message types for daemon communication (IpcRequest, IpcResponse, IpcCommand)
+//! - Optional MessagePack encoding of response data (DataEncoding)
 //! - Notification types for pub/sub (EventApplied, WalSynced, etc.)
 //! - Daemon lock management (DaemonLock)
+//! - Per-user daemon discovery registry (DaemonRegistry)
 //! - IPC client for connecting to the daemon
 
 pub mod client;
@@ -12,15 +14,17 @@ pub mod framing;
 pub mod lock;
 pub mod messages;
 pub mod notifications;
+pub mod registry;
 
 pub use client::IpcClient;
 pub use error::IpcError;
 pub use lock::DaemonLock;
-pub use messages::{IpcCommand, IpcErrorPayload, IpcRequest, IpcResponse};
+pub use messages::{DataEncoding, IpcCommand, IpcErrorPayload, IpcRequest, IpcResponse};
 pub use notifications::Notification;
+pub use registry::{DaemonRegistry, RegistryEntry};
 
 /// Current IPC schema version
-pub const IPC_SCHEMA_VERSION: u32 = 1;
+pub const IPC_SCHEMA_VERSION: u32 = 2;
 
 /// Default request timeout in milliseconds
 pub const DEFAULT_TIMEOUT_MS: u64 = 10_000;
@@ -77,6 +77,16 @@ impl DaemonLock {
         self.pid == std::process::id()
     }
 
+    /// Check if the lock is held by another running process on `host_id`
+    ///
+    /// A lock taken on a different host cannot be checked and is not
+    /// considered live.
+    pub fn is_held_by_other_live_process(&self, host_id: &str) -> bool {
+        self.host_id == host_id
+            && !self.is_owned_by_current_process()
+            && crate::registry::process_alive(self.pid)
+    }
+
     /// Remaining time until expiration in milliseconds
     pub fn time_remaining_ms(&self) -> u64 {
         self.expires_ts.saturating_sub(current_time_ms())
@@ -6,6 +6,7 @@
 use rkyv::{Archive, Deserialize, Serialize};
 use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
 
+use crate::error::IpcError;
 use crate::IPC_SCHEMA_VERSION;
 
 /// IPC request envelope
@@ -18,12 +19,15 @@ pub struct IpcRequest {
     pub request_id: String,
     /// Repository root path
     pub repo_root: String,
-    /// Actor ID (hex-encoded 16 bytes)
+    /// Actor ID (hex-encoded 16 bytes); events the request writes are
+    /// attributed to this actor
     pub actor_id: String,
     /// Data directory path
     pub data_dir: String,
     /// The command to execute
     pub command: IpcCommand,
+    /// Encoding the client would like for response data
+    pub accept_encoding: DataEncoding,
 }
 
 impl IpcRequest {
@@ -42,8 +46,28 @@ impl IpcRequest {
             actor_id,
             data_dir,
             command,
+            accept_encoding: DataEncoding::Json,
         }
     }
+
+    /// Ask the daemon to encode response data with `encoding`
+    pub fn with_encoding(mut self, encoding: DataEncoding) -> Self {
+        self.accept_encoding = encoding;
+        self
+    }
+}
+
+/// Encoding of response data
+///
+/// JSON is always understood. Responses that are plain text rather than
+/// structured data (e.g. a markdown export) stay in `data` whatever the
+/// request asked for.
+#[derive(Archive, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[rkyv(derive(Debug))]
+pub enum DataEncoding {
+    #[default]
+    Json,
+    MsgPack,
 }
 
 /// IPC response envelope
@@ -58,6 +82,8 @@ pub struct IpcResponse {
     pub ok: bool,
     /// Response data (JSON-encoded for flexibility)
     pub data: Option<String>,
+    /// Response data as MessagePack, replacing `data` when the request asked for it
+    pub data_msgpack: Option<Vec<u8>>,
     /// Error details if ok=false
     pub error: Option<IpcErrorPayload>,
 }
@@ -70,6 +96,7 @@ impl IpcResponse {
             request_id,
             ok: true,
             data,
+            data_msgpack: None,
             error: None,
         }
     }
@@ -81,6 +108,7 @@ impl IpcResponse {
             request_id,
             ok: false,
             data: None,
+            data_msgpack: None,
             error: Some(IpcErrorPayload {
                 code,
                 message,
@@ -88,6 +116,43 @@ impl IpcResponse {
             }),
         }
     }
+
+    /// Create a successful response with `data` serialized in `encoding`
+    ///
+    /// JSON goes into `data` and MessagePack into `data_msgpack`.
+    pub fn success_encoded<T: SerdeSerialize>(
+        request_id: String,
+        data: Option<&T>,
+        encoding: DataEncoding,
+    ) -> Result<Self, IpcError> {
+        let mut response = Self::success(request_id, None);
+        match (data, encoding) {
+            (None, _) => {}
+            (Some(data), DataEncoding::Json) => response.data = Some(serde_json::to_string(data)?),
+            (Some(data), DataEncoding::MsgPack) => {
+                response.data_msgpack = Some(
+                    rmp_serde::to_vec_named(data)
+                        .map_err(|e| IpcError::Serialization(e.to_string()))?,
+                );
+            }
+        }
+        Ok(response)
+    }
+
+    /// Decode the response data, whichever encoding it arrived in
+    ///
+    /// MessagePack data takes precedence; otherwise `data` is parsed as JSON.
+    pub fn decode_data(&self) -> Result<Option<serde_json::Value>, IpcError> {
+        if let Some(bytes) = &self.data_msgpack {
+            return rmp_serde::from_slice(bytes)
+                .map(Some)
+                .map_err(|e| IpcError::Deserialization(e.to_string()));
+        }
+        match &self.data {
+            Some(json) => Ok(Some(serde_json::from_str(json)?)),
+            None => Ok(None),
+        }
+    }
 }
 
 /// Error payload in responses
@@ -117,10 +182,28 @@ pub enum IpcCommand {
     IssueList {
         state: Option<String>,
         label: Option<String>,
+        assignee: Option<String>,
+        unassigned: bool,
+        /// Custom field filter as `key=value`
+        field: Option<String>,
+        /// Only issues in this milestone
+        milestone: Option<String>,
+        /// Only issues with this severity ("sev1".."sev4", or "none")
+        severity: Option<String>,
+        /// Also list soft-deleted issues
+        include_deleted: bool,
+        /// Sort order: "created" or "most-blocking"
+        sort: String,
     },
     IssueShow {
         issue_id: String,
     },
+    /// A page of an issue's events, for fetching busy issues incrementally
+    IssueEvents {
+        issue_id: String,
+        offset: u32,
+        limit: u32,
+    },
     IssueUpdate {
         issue_id: String,
         title: Option<String>,
@@ -129,14 +212,18 @@ pub enum IpcCommand {
     IssueComment {
         issue_id: String,
         body: String,
+        /// Event ID (or unique prefix) of the comment being replied to
+        reply_to: Option<String>,
     },
+    /// Applied to every issue; fails without writing if any ID is unknown
     IssueLabel {
-        issue_id: String,
+        issue_ids: Vec<String>,
         add: Vec<String>,
         remove: Vec<String>,
     },
+    /// Applied to every issue; fails without writing if any ID is unknown
     IssueAssign {
-        issue_id: String,
+        issue_ids: Vec<String>,
         add: Vec<String>,
         remove: Vec<String>,
     },
@@ -155,6 +242,57 @@ pub enum IpcCommand {
         issue_id: String,
         file_path: String,
     },
+    IssueField {
+        issue_id: String,
+        key: String,
+        /// New value, or `None` to clear the field
+        value: Option<String>,
+    },
+    /// Add or remove a typed link to another issue
+    IssueLinkIssue {
+        issue_id: String,
+        target_id: String,
+        relation: String,
+        remove: bool,
+    },
+    /// Link an issue as `duplicate-of` another and close it, in one batch
+    IssueDup {
+        issue_id: String,
+        target_id: String,
+    },
+    /// Subscribe or unsubscribe a user; `None` means the requesting actor
+    IssueSubscribe {
+        issue_id: String,
+        user: Option<String>,
+        remove: bool,
+    },
+    IssueEstimate {
+        issue_id: String,
+        minutes: u32,
+    },
+    IssueLogTime {
+        issue_id: String,
+        minutes: u32,
+        note: Option<String>,
+    },
+    /// Set the issue's milestone, or clear it with `None`
+    IssueMilestone {
+        issue_id: String,
+        milestone: Option<String>,
+    },
+    /// Set the issue's severity: "sev1".."sev4", or "none" to clear it
+    IssueSeverity {
+        issue_id: String,
+        severity: String,
+    },
+    /// Soft-delete the issue
+    IssueDelete {
+        issue_id: String,
+    },
+    /// Reverse a soft delete
+    IssueUndelete {
+        issue_id: String,
+    },
     IssueDepAdd {
         issue_id: String,
         target_id: String,
@@ -173,6 +311,13 @@ pub enum IpcCommand {
         state: Option<String>,
         label: Option<String>,
     },
+    IssueWhyBlocked {
+        issue_id: String,
+    },
+    IssueTree {
+        issue_id: String,
+        depth: u32,
+    },
 
     // Database commands
     DbStats,
@@ -181,10 +326,31 @@ pub enum IpcCommand {
     Export {
         format: String,
         since: Option<String>,
+        /// Event ordering: "by-issue" or "chronological"
+        order: String,
+        /// Export only this issue (ID or prefix)
+        issue: Option<String>,
+        /// Comma-separated event kinds to export; `None` exports every kind
+        kinds: Option<String>,
+        /// Reply with `{ "events": n, "issues": m }` instead of the export
+        count_only: bool,
+        /// Embed referenced attachment bytes in JSON exports
+        include_attachments: bool,
+        /// Replace actor IDs and user names with pseudonyms
+        anonymize: bool,
+        /// Absolute path to stream the export into; the reply is then a
+        /// summary (`format`, `output_path`, `event_count`) instead of the
+        /// export itself
+        out: Option<String>,
     },
 
     // Rebuild command
-    Rebuild,
+    Rebuild {
+        /// Skip events that fail to apply instead of aborting
+        skip_errors: bool,
+    },
+    /// Report what a rebuild would process, without writing
+    RebuildPlan,
 
     // Sync command
     Sync {
@@ -219,6 +385,13 @@ mod tests {
             IpcCommand::IssueList {
                 state: Some("open".to_string()),
                 label: None,
+                assignee: None,
+                unassigned: false,
+                field: None,
+                milestone: None,
+                severity: None,
+                include_deleted: false,
+                sort: "created".to_string(),
             },
         );
 
@@ -274,4 +447,56 @@ mod tests {
         assert_eq!(archived.request_id, "test-456");
         assert_eq!(archived.ipc_schema_version, IPC_SCHEMA_VERSION);
     }
+
+    fn large_issue_list() -> String {
+        let issues: Vec<serde_json::Value> = (0..2000)
+            .map(|i| {
+                serde_json::json!({
+                    "issue_id": format!("{:032x}", i),
+                    "title": format!("Issue number {}", i),
+                    "state": if i % 3 == 0 { "closed" } else { "open" },
+                    "labels": ["bug", format!("area-{}", i % 7)],
+                    "assignees": [],
+                    "updated_ts": 1_700_000_000_000u64 + i,
+                    "comment_count": i % 11,
+                })
+            })
+            .collect();
+        serde_json::json!({ "issues": issues, "total": 2000 }).to_string()
+    }
+
+    #[test]
+    fn test_msgpack_roundtrip_matches_json() {
+        let data: serde_json::Value = serde_json::from_str(&large_issue_list()).unwrap();
+        let json_resp =
+            IpcResponse::success_encoded("req".to_string(), Some(&data), DataEncoding::Json)
+                .unwrap();
+        let msgpack_resp =
+            IpcResponse::success_encoded("req".to_string(), Some(&data), DataEncoding::MsgPack)
+                .unwrap();
+        assert!(msgpack_resp.data.is_none());
+        assert!(msgpack_resp.data_msgpack.is_some());
+
+        // Through the rkyv wire format, as the client receives it
+        let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&msgpack_resp).unwrap();
+        let archived = rkyv::access::<ArchivedIpcResponse, rkyv::rancor::Error>(&bytes).unwrap();
+        let received: IpcResponse =
+            rkyv::deserialize::<IpcResponse, rkyv::rancor::Error>(archived).unwrap();
+
+        assert_eq!(received.decode_data().unwrap(), Some(data.clone()));
+        assert_eq!(json_resp.decode_data().unwrap(), Some(data));
+    }
+
+    #[test]
+    fn test_success_encoded_without_data() {
+        let resp = IpcResponse::success_encoded::<serde_json::Value>(
+            "req".to_string(),
+            None,
+            DataEncoding::MsgPack,
+        )
+        .unwrap();
+        assert!(resp.ok);
+        assert!(resp.data.is_none());
+        assert!(resp.data_msgpack.is_none());
+    }
 }
@@ -17,6 +17,8 @@ pub enum Notification {
         event_id: String,
         /// Timestamp in milliseconds since Unix epoch
         ts_unix_ms: u64,
+        /// Users subscribed to the issue, so clients can filter
+        subscribers: Vec<String>,
     },
 
     /// WAL was synced with a remote
@@ -43,6 +45,14 @@ pub enum Notification {
         snapshot_ref: String,
     },
 
+    /// The local database was rebuilt
+    Rebuilt {
+        /// Number of events replayed
+        event_count: u64,
+        /// Number of issues after the rebuild
+        issue_count: u64,
+    },
+
     /// Worker started for a repository
     WorkerStarted {
         /// Repository root path
@@ -70,17 +80,24 @@ impl Notification {
             Notification::WalSynced { .. } => "WalSynced",
             Notification::LockChanged { .. } => "LockChanged",
             Notification::SnapshotCreated { .. } => "SnapshotCreated",
+            Notification::Rebuilt { .. } => "Rebuilt",
             Notification::WorkerStarted { .. } => "WorkerStarted",
             Notification::WorkerStopped { .. } => "WorkerStopped",
         }
     }
 
     /// Create an EventApplied notification
-    pub fn event_applied(issue_id: String, event_id: String, ts_unix_ms: u64) -> Self {
+    pub fn event_applied(
+        issue_id: String,
+        event_id: String,
+        ts_unix_ms: u64,
+        subscribers: Vec<String>,
+    ) -> Self {
         Notification::EventApplied {
             issue_id,
             event_id,
             ts_unix_ms,
+            subscribers,
         }
     }
 
@@ -102,6 +119,14 @@ impl Notification {
     pub fn snapshot_created(snapshot_ref: String) -> Self {
         Notification::SnapshotCreated { snapshot_ref }
     }
+
+    /// Create a Rebuilt notification
+    pub fn rebuilt(event_count: u64, issue_count: u64) -> Self {
+        Notification::Rebuilt {
+            event_count,
+            issue_count,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -110,11 +135,19 @@ mod tests {
 
     #[test]
     fn test_notification_type() {
-        let n = Notification::event_applied("issue1".to_string(), "event1".to_string(), 1000);
+        let n = Notification::event_applied(
+            "issue1".to_string(),
+            "event1".to_string(),
+            1000,
+            vec!["alice".to_string()],
+        );
         assert_eq!(n.notification_type(), "EventApplied");
 
         let n = Notification::wal_synced("abc123".to_string(), "origin".to_string());
         assert_eq!(n.notification_type(), "WalSynced");
+
+        let n = Notification::rebuilt(10, 2);
+        assert_eq!(n.notification_type(), "Rebuilt");
     }
 
     #[test]
@@ -123,6 +156,7 @@ mod tests {
             issue_id: "issue123".to_string(),
             event_id: "event456".to_string(),
             ts_unix_ms: 1700000000000,
+            subscribers: vec!["alice".to_string()],
         };
 
         let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&notification).unwrap();
@@ -133,10 +167,13 @@ mod tests {
                 issue_id,
                 event_id,
                 ts_unix_ms,
+                subscribers,
             } => {
                 assert_eq!(issue_id.as_str(), "issue123");
                 assert_eq!(event_id.as_str(), "event456");
                 assert_eq!(*ts_unix_ms, 1700000000000);
+                assert_eq!(subscribers.len(), 1);
+                assert_eq!(subscribers[0].as_str(), "alice");
             }
             _ => panic!("Wrong variant"),
         }
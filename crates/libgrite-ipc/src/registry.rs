@@ -0,0 +1,280 @@
+//! Per-user daemon discovery registry
+//!
+//! Daemons record which endpoint serves each `(repo_root, actor_id)` pair so
+//! clients can find the right daemon when several are running on one host
+//! (for example one per container sharing `/tmp`). Entries whose process has
+//! exited are pruned whenever the registry is read. Every rewrite happens
+//! under an exclusive lock on a sibling `.lock` file, so daemons starting at
+//! the same time never drop each other's entries.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::IpcError;
+
+/// A single daemon registration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegistryEntry {
+    /// Repository root path
+    pub repo_root: String,
+    /// Actor ID (hex-encoded)
+    pub actor_id: String,
+    /// Unix socket path the daemon listens on
+    pub endpoint: String,
+    /// Process ID of the daemon
+    pub pid: u32,
+}
+
+/// Registry file mapping `(repo_root, actor_id)` to daemon endpoints
+#[derive(Debug, Clone)]
+pub struct DaemonRegistry {
+    path: PathBuf,
+}
+
+impl Default for DaemonRegistry {
+    fn default() -> Self {
+        Self::new(default_registry_path())
+    }
+}
+
+impl DaemonRegistry {
+    /// Use the registry file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Path of the registry file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read all live entries, pruning those whose process has exited
+    pub fn entries(&self) -> Result<Vec<RegistryEntry>, IpcError> {
+        let entries = self.read_raw()?;
+        if entries.iter().all(|e| process_alive(e.pid)) {
+            return Ok(entries);
+        }
+        self.update(|_| {})
+    }
+
+    /// Record a daemon, replacing any entry for the same repo and actor
+    pub fn register(&self, entry: RegistryEntry) -> Result<(), IpcError> {
+        self.update(|entries| {
+            entries.retain(|e| !same_key(e, &entry.repo_root, &entry.actor_id));
+            entries.push(entry);
+        })?;
+        Ok(())
+    }
+
+    /// Remove the entry for a repo and actor if it belongs to this process
+    pub fn unregister(&self, repo_root: &str, actor_id: &str) -> Result<(), IpcError> {
+        self.update(|entries| {
+            entries.retain(|e| !(same_key(e, repo_root, actor_id) && e.pid == std::process::id()));
+        })?;
+        Ok(())
+    }
+
+    /// Read, prune, modify and write the entries under the registry lock
+    ///
+    /// The file is only rewritten when the entries change. Returns the
+    /// entries as written.
+    fn update<F>(&self, update: F) -> Result<Vec<RegistryEntry>, IpcError>
+    where
+        F: FnOnce(&mut Vec<RegistryEntry>),
+    {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let lock_file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.path.with_extension("lock"))?;
+        lock_file.lock_exclusive()?;
+
+        let before = self.read_raw()?;
+        let mut entries: Vec<RegistryEntry> = before
+            .iter()
+            .filter(|e| process_alive(e.pid))
+            .cloned()
+            .collect();
+        update(&mut entries);
+        if entries != before {
+            self.write_raw(&entries)?;
+        }
+
+        // Lock released when `lock_file` is dropped
+        Ok(entries)
+    }
+
+    /// Find the live daemon serving a repo and actor
+    pub fn lookup(
+        &self,
+        repo_root: &str,
+        actor_id: &str,
+    ) -> Result<Option<RegistryEntry>, IpcError> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .find(|e| same_key(e, repo_root, actor_id)))
+    }
+
+    fn read_raw(&self) -> Result<Vec<RegistryEntry>, IpcError> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) if contents.trim().is_empty() => Ok(Vec::new()),
+            Ok(contents) => Ok(serde_json::from_str(&contents)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write via a temp file and rename so readers never see a partial file
+    fn write_raw(&self, entries: &[RegistryEntry]) -> Result<(), IpcError> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self
+            .path
+            .with_extension(format!("tmp.{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string_pretty(entries)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Get the default per-user registry path.
+///
+/// Lives next to the default socket:
+/// - `$XDG_RUNTIME_DIR/grite-daemons.json` if available
+/// - `/tmp/grite-daemons-<uid>.json` as fallback on Unix
+/// - `/tmp/grite-daemons.json` on non-Unix platforms
+pub fn default_registry_path() -> PathBuf {
+    if let Ok(runtime_dir) = std::env::var("XDG_RUNTIME_DIR") {
+        return Path::new(&runtime_dir).join("grite-daemons.json");
+    }
+
+    #[cfg(unix)]
+    {
+        let uid = unsafe { libc::getuid() };
+        PathBuf::from(format!("/tmp/grite-daemons-{}.json", uid))
+    }
+
+    #[cfg(not(unix))]
+    {
+        PathBuf::from("/tmp/grite-daemons.json")
+    }
+}
+
+fn same_key(entry: &RegistryEntry, repo_root: &str, actor_id: &str) -> bool {
+    entry.repo_root == repo_root && entry.actor_id == actor_id
+}
+
+/// Whether a process with this pid exists
+#[cfg(unix)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // Signal 0 performs the permission and existence checks only
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return true;
+    }
+    std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub(crate) fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(repo_root: &str, endpoint: &str, pid: u32) -> RegistryEntry {
+        RegistryEntry {
+            repo_root: repo_root.to_string(),
+            actor_id: "actor123".to_string(),
+            endpoint: endpoint.to_string(),
+            pid,
+        }
+    }
+
+    #[test]
+    fn test_registry_resolves_endpoint_per_repo() {
+        let temp = TempDir::new().unwrap();
+        let registry = DaemonRegistry::new(temp.path().join("daemons.json"));
+        let pid = std::process::id();
+
+        registry
+            .register(entry("/repo/a", "/tmp/a.sock", pid))
+            .unwrap();
+        registry
+            .register(entry("/repo/b", "/tmp/b.sock", pid))
+            .unwrap();
+
+        let a = registry.lookup("/repo/a", "actor123").unwrap().unwrap();
+        let b = registry.lookup("/repo/b", "actor123").unwrap().unwrap();
+        assert_eq!(a.endpoint, "/tmp/a.sock");
+        assert_eq!(b.endpoint, "/tmp/b.sock");
+        assert!(registry.lookup("/repo/c", "actor123").unwrap().is_none());
+        assert!(registry.lookup("/repo/a", "other").unwrap().is_none());
+
+        // Re-registering a repo replaces its endpoint
+        registry
+            .register(entry("/repo/a", "/tmp/a2.sock", pid))
+            .unwrap();
+        assert_eq!(registry.entries().unwrap().len(), 2);
+        let a = registry.lookup("/repo/a", "actor123").unwrap().unwrap();
+        assert_eq!(a.endpoint, "/tmp/a2.sock");
+
+        registry.unregister("/repo/a", "actor123").unwrap();
+        assert!(registry.lookup("/repo/a", "actor123").unwrap().is_none());
+        assert!(registry.lookup("/repo/b", "actor123").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_registry_prunes_dead_pids() {
+        let temp = TempDir::new().unwrap();
+        let registry = DaemonRegistry::new(temp.path().join("daemons.json"));
+
+        // pid_max on Linux is at most 2^22, so this pid cannot exist
+        let dead = entry("/repo/dead", "/tmp/dead.sock", i32::MAX as u32);
+        let live = entry("/repo/live", "/tmp/live.sock", std::process::id());
+        registry.write_raw(&[dead, live.clone()]).unwrap();
+
+        assert_eq!(registry.entries().unwrap(), vec![live]);
+        assert_eq!(registry.read_raw().unwrap().len(), 1, "pruned on read");
+    }
+
+    #[test]
+    fn test_concurrent_registrations_are_all_kept() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("daemons.json");
+
+        let handles: Vec<_> = (0..16)
+            .map(|i| {
+                let registry = DaemonRegistry::new(&path);
+                std::thread::spawn(move || {
+                    let repo = format!("/repo/{}", i);
+                    let endpoint = format!("/tmp/{}.sock", i);
+                    registry
+                        .register(entry(&repo, &endpoint, std::process::id()))
+                        .unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(DaemonRegistry::new(&path).entries().unwrap().len(), 16);
+    }
+}